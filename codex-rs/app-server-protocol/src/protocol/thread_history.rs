@@ -6,6 +6,7 @@ use crate::protocol::v2::UserInput;
 use codex_protocol::protocol::AgentReasoningEvent;
 use codex_protocol::protocol::AgentReasoningRawContentEvent;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ThreadRolledBackEvent;
 use codex_protocol::protocol::TurnAbortedEvent;
 use codex_protocol::protocol::UserMessageEvent;
 
@@ -59,6 +60,7 @@ impl ThreadHistoryBuilder {
             EventMsg::ExitedReviewMode(_) => {}
             EventMsg::UndoCompleted(_) => {}
             EventMsg::TurnAborted(payload) => self.handle_turn_aborted(payload),
+            EventMsg::ThreadRolledBack(payload) => self.handle_thread_rolled_back(payload),
             _ => {}
         }
     }
@@ -130,6 +132,12 @@ impl ThreadHistoryBuilder {
         turn.status = TurnStatus::Interrupted;
     }
 
+    fn handle_thread_rolled_back(&mut self, payload: &ThreadRolledBackEvent) {
+        self.finish_current_turn();
+        let keep = self.turns.len().saturating_sub(payload.num_turns as usize);
+        self.turns.truncate(keep);
+    }
+
     fn finish_current_turn(&mut self) {
         if let Some(turn) = self.current_turn.take() {
             if turn.items.is_empty() {
@@ -410,4 +418,53 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn rollback_drops_trailing_turns() {
+        let events = vec![
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "First".into(),
+                images: None,
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "One".into(),
+            }),
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "Second".into(),
+                images: None,
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Two".into(),
+            }),
+            EventMsg::ThreadRolledBack(ThreadRolledBackEvent {
+                num_turns: 1,
+                worktree_restored: false,
+            }),
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "Second, again".into(),
+                images: None,
+            }),
+        ];
+
+        let turns = build_turns_from_event_msgs(&events);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(
+            turns[0].items[0],
+            ThreadItem::UserMessage {
+                id: "item-1".into(),
+                content: vec![UserInput::Text {
+                    text: "First".into()
+                }],
+            }
+        );
+        assert_eq!(
+            turns[1].items[0],
+            ThreadItem::UserMessage {
+                id: "item-5".into(),
+                content: vec![UserInput::Text {
+                    text: "Second, again".into()
+                }],
+            }
+        );
+    }
 }
@@ -25,6 +25,8 @@ use codex_protocol::protocol::SessionSource as CoreSessionSource;
 use codex_protocol::protocol::SkillErrorInfo as CoreSkillErrorInfo;
 use codex_protocol::protocol::SkillMetadata as CoreSkillMetadata;
 use codex_protocol::protocol::SkillScope as CoreSkillScope;
+use codex_protocol::protocol::SubAgentStatus as CoreSubAgentStatus;
+use codex_protocol::protocol::TokenCost as CoreTokenCost;
 use codex_protocol::protocol::TokenUsage as CoreTokenUsage;
 use codex_protocol::protocol::TokenUsageInfo as CoreTokenUsageInfo;
 use codex_protocol::user_input::UserInput as CoreUserInput;
@@ -102,6 +104,7 @@ pub enum CodexErrorInfo {
         #[ts(rename = "httpStatusCode")]
         http_status_code: Option<u16>,
     },
+    ThreadRollbackFailed,
     Other,
 }
 
@@ -126,6 +129,7 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
             CoreCodexErrorInfo::ResponseTooManyFailedAttempts { http_status_code } => {
                 CodexErrorInfo::ResponseTooManyFailedAttempts { http_status_code }
             }
+            CoreCodexErrorInfo::ThreadRollbackFailed => CodexErrorInfo::ThreadRollbackFailed,
             CoreCodexErrorInfo::Other => CodexErrorInfo::Other,
         }
     }
@@ -1045,6 +1049,27 @@ pub struct ThreadArchiveParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadArchiveResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRollbackParams {
+    pub thread_id: String,
+    /// Number of most recent turns to drop. Must be at least 1.
+    pub num_turns: u32,
+    /// Also restore the working tree from the ghost snapshot taken at the
+    /// start of the earliest dropped turn.
+    #[serde(default)]
+    pub restore_worktree: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRollbackResponse {
+    pub num_turns: u32,
+    pub worktree_restored: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1213,6 +1238,8 @@ pub struct ThreadTokenUsage {
     pub last: TokenUsageBreakdown,
     #[ts(type = "number | null")]
     pub model_context_window: Option<i64>,
+    /// Estimated cost, present when pricing is known for the model.
+    pub cost: Option<TokenCost>,
 }
 
 impl From<CoreTokenUsageInfo> for ThreadTokenUsage {
@@ -1221,6 +1248,26 @@ impl From<CoreTokenUsageInfo> for ThreadTokenUsage {
             total: value.total_token_usage.into(),
             last: value.last_token_usage.into(),
             model_context_window: value.model_context_window,
+            cost: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TokenCost {
+    /// Estimated USD spent by the thread so far.
+    pub session_usd: f64,
+    /// Estimated USD spent by the current turn.
+    pub turn_usd: f64,
+}
+
+impl From<CoreTokenCost> for TokenCost {
+    fn from(value: CoreTokenCost) -> Self {
+        Self {
+            session_usd: value.session_usd,
+            turn_usd: value.turn_usd,
         }
     }
 }
@@ -1319,6 +1366,11 @@ pub struct TurnStartParams {
     pub effort: Option<ReasoningEffort>,
     /// Override the reasoning summary for this turn and subsequent turns.
     pub summary: Option<ReasoningSummary>,
+    /// Turn plan mode on or off for this turn and subsequent turns. In plan
+    /// mode the agent explores read-only and proposes a plan; writes are
+    /// rejected until the plan is approved with `turn/plan/approve` or a turn
+    /// starts with `planMode: false`.
+    pub plan_mode: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1397,6 +1449,21 @@ pub struct TurnInterruptParams {
 #[ts(export_to = "v2/")]
 pub struct TurnInterruptResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnPlanApproveParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnPlanApproveResponse {
+    /// The turn that implements the approved plan.
+    pub turn: Turn,
+}
+
 // User input types
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -1498,9 +1565,41 @@ pub enum ThreadItem {
     WebSearch { id: String, query: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
+    FetchUrl {
+        id: String,
+        /// URL the agent asked for.
+        url: String,
+        status: FetchUrlStatus,
+        /// URL the page was served from after redirects.
+        final_url: Option<String>,
+        /// HTTP status of the final response.
+        http_status: Option<u16>,
+        title: Option<String>,
+        /// 1-based page returned to the agent, with the page count.
+        page: Option<u32>,
+        total_pages: Option<u32>,
+        /// Whether the page was served from the thread's fetch cache.
+        cached: bool,
+        error: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
     ImageView { id: String, path: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
+    SubAgent {
+        id: String,
+        /// Role the parent agent gave the sub-agent, if any.
+        role: Option<String>,
+        model: String,
+        prompt: String,
+        read_only: bool,
+        status: SubAgentStatus,
+        /// Final message from the sub-agent, or the failure reason.
+        result: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
     EnteredReviewMode { id: String, review: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -1585,6 +1684,35 @@ pub enum McpToolCallStatus {
     Failed,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum FetchUrlStatus {
+    InProgress,
+    Completed,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum SubAgentStatus {
+    InProgress,
+    Completed,
+    Failed,
+    Interrupted,
+}
+
+impl From<CoreSubAgentStatus> for SubAgentStatus {
+    fn from(value: CoreSubAgentStatus) -> Self {
+        match value {
+            CoreSubAgentStatus::Completed => SubAgentStatus::Completed,
+            CoreSubAgentStatus::Failed => SubAgentStatus::Failed,
+            CoreSubAgentStatus::Interrupted => SubAgentStatus::Interrupted,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1655,6 +1783,18 @@ pub struct TurnPlanUpdatedNotification {
     pub plan: Vec<TurnPlanStep>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+/// Notification that a plan mode turn finished with a plan that is waiting
+/// for approval via `turn/plan/approve`.
+pub struct TurnPlanApprovalRequestedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub explanation: Option<String>,
+    pub plan: Vec<TurnPlanStep>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
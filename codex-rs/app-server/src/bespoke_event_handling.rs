@@ -1,7 +1,9 @@
 use crate::codex_message_processor::ApiVersion;
 use crate::codex_message_processor::PendingInterrupts;
+use crate::codex_message_processor::PendingRollbacks;
 use crate::codex_message_processor::TurnSummary;
 use crate::codex_message_processor::TurnSummaryStore;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
 use codex_app_server_protocol::AgentMessageDeltaNotification;
@@ -20,6 +22,7 @@ use codex_app_server_protocol::ErrorNotification;
 use codex_app_server_protocol::ExecCommandApprovalParams;
 use codex_app_server_protocol::ExecCommandApprovalResponse;
 use codex_app_server_protocol::ExecPolicyAmendment as V2ExecPolicyAmendment;
+use codex_app_server_protocol::FetchUrlStatus;
 use codex_app_server_protocol::FileChangeOutputDeltaNotification;
 use codex_app_server_protocol::FileChangeRequestApprovalParams;
 use codex_app_server_protocol::FileChangeRequestApprovalResponse;
@@ -27,6 +30,7 @@ use codex_app_server_protocol::FileUpdateChange;
 use codex_app_server_protocol::InterruptConversationResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::ItemStartedNotification;
+use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::McpToolCallError;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
@@ -38,8 +42,10 @@ use codex_app_server_protocol::ReasoningSummaryTextDeltaNotification;
 use codex_app_server_protocol::ReasoningTextDeltaNotification;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
+use codex_app_server_protocol::SubAgentStatus;
 use codex_app_server_protocol::TerminalInteractionNotification;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadRollbackResponse;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
 use codex_app_server_protocol::Turn;
@@ -47,12 +53,15 @@ use codex_app_server_protocol::TurnCompletedNotification;
 use codex_app_server_protocol::TurnDiffUpdatedNotification;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptResponse;
+use codex_app_server_protocol::TurnPlanApprovalRequestedNotification;
 use codex_app_server_protocol::TurnPlanStep;
 use codex_app_server_protocol::TurnPlanUpdatedNotification;
 use codex_app_server_protocol::TurnStatus;
 use codex_core::CodexConversation;
 use codex_core::parse_command::shlex_join;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::CodexErrorInfo as CoreCodexErrorInfo;
+use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -69,6 +78,7 @@ use codex_core::review_prompts;
 use codex_protocol::ConversationId;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::SubAgentSource;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::path::PathBuf;
@@ -78,12 +88,14 @@ use tracing::error;
 
 type JsonValue = serde_json::Value;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn apply_bespoke_event_handling(
     event: Event,
     conversation_id: ConversationId,
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
+    pending_rollbacks: PendingRollbacks,
     turn_summary_store: TurnSummaryStore,
     api_version: ApiVersion,
 ) {
@@ -336,6 +348,38 @@ pub(crate) async fn apply_bespoke_event_handling(
             handle_token_count_event(conversation_id, event_turn_id, token_count_event, &outgoing)
                 .await;
         }
+        EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: Some(CoreCodexErrorInfo::ThreadRollbackFailed),
+        }) => {
+            // Rollback failures happen between turns, so they answer the pending
+            // `thread/rollback` request instead of being attributed to a turn.
+            let pending = {
+                let mut map = pending_rollbacks.lock().await;
+                map.remove(&conversation_id).unwrap_or_default()
+            };
+            for request_id in pending {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: message.clone(),
+                    data: None,
+                };
+                outgoing.send_error(request_id, error).await;
+            }
+        }
+        EventMsg::ThreadRolledBack(rollback) => {
+            let pending = {
+                let mut map = pending_rollbacks.lock().await;
+                map.remove(&conversation_id).unwrap_or_default()
+            };
+            for request_id in pending {
+                let response = ThreadRollbackResponse {
+                    num_turns: rollback.num_turns,
+                    worktree_restored: rollback.worktree_restored,
+                };
+                outgoing.send_response(request_id, response).await;
+            }
+        }
         EventMsg::Error(ev) => {
             let turn_error = TurnError {
                 message: ev.message,
@@ -391,6 +435,96 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ItemCompleted(completed))
                 .await;
         }
+        EventMsg::FetchUrlBegin(begin) => {
+            let item = ThreadItem::FetchUrl {
+                id: begin.call_id,
+                url: begin.url,
+                status: FetchUrlStatus::InProgress,
+                final_url: None,
+                http_status: None,
+                title: None,
+                page: None,
+                total_pages: None,
+                cached: false,
+                error: None,
+            };
+            let started = ItemStartedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemStarted(started))
+                .await;
+        }
+        EventMsg::FetchUrlEnd(end) => {
+            let (status, page, total_pages) = match end.error {
+                Some(_) => (FetchUrlStatus::Failed, None, None),
+                None => (
+                    FetchUrlStatus::Completed,
+                    Some(end.page),
+                    Some(end.total_pages),
+                ),
+            };
+            let item = ThreadItem::FetchUrl {
+                id: end.call_id,
+                url: end.url,
+                status,
+                final_url: end.final_url,
+                http_status: end.status,
+                title: end.title,
+                page,
+                total_pages,
+                cached: end.cached,
+                error: end.error,
+            };
+            let completed = ItemCompletedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemCompleted(completed))
+                .await;
+        }
+        EventMsg::SubAgentBegin(begin) => {
+            let item = ThreadItem::SubAgent {
+                id: begin.call_id,
+                role: sub_agent_role(&begin.source),
+                model: begin.model,
+                prompt: begin.prompt,
+                read_only: begin.read_only,
+                status: SubAgentStatus::InProgress,
+                result: None,
+            };
+            let started = ItemStartedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemStarted(started))
+                .await;
+        }
+        EventMsg::SubAgentEnd(end) => {
+            let item = ThreadItem::SubAgent {
+                id: end.call_id,
+                role: sub_agent_role(&end.source),
+                model: end.model,
+                prompt: end.prompt,
+                read_only: end.read_only,
+                status: end.status.into(),
+                result: end.message,
+            };
+            let completed = ItemCompletedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemCompleted(completed))
+                .await;
+        }
         EventMsg::EnteredReviewMode(review_request) => {
             let review = review_request
                 .user_facing_hint
@@ -710,6 +844,16 @@ pub(crate) async fn apply_bespoke_event_handling(
             )
             .await;
         }
+        EventMsg::PlanApprovalRequest(request) => {
+            handle_plan_approval_request(
+                conversation_id,
+                &event_turn_id,
+                request.plan,
+                api_version,
+                outgoing.as_ref(),
+            )
+            .await;
+        }
 
         _ => {}
     }
@@ -758,6 +902,26 @@ async fn handle_turn_plan_update(
     }
 }
 
+async fn handle_plan_approval_request(
+    conversation_id: ConversationId,
+    event_turn_id: &str,
+    plan: UpdatePlanArgs,
+    api_version: ApiVersion,
+    outgoing: &OutgoingMessageSender,
+) {
+    if let ApiVersion::V2 = api_version {
+        let notification = TurnPlanApprovalRequestedNotification {
+            thread_id: conversation_id.to_string(),
+            turn_id: event_turn_id.to_string(),
+            explanation: plan.explanation,
+            plan: plan.plan.into_iter().map(TurnPlanStep::from).collect(),
+        };
+        outgoing
+            .send_server_notification(ServerNotification::TurnPlanApprovalRequested(notification))
+            .await;
+    }
+}
+
 async fn emit_turn_completed_with_status(
     conversation_id: ConversationId,
     event_turn_id: String,
@@ -912,8 +1076,13 @@ async fn handle_token_count_event(
     token_count_event: TokenCountEvent,
     outgoing: &OutgoingMessageSender,
 ) {
-    let TokenCountEvent { info, rate_limits } = token_count_event;
-    if let Some(token_usage) = info.map(ThreadTokenUsage::from) {
+    let TokenCountEvent {
+        info,
+        rate_limits,
+        cost,
+    } = token_count_event;
+    if let Some(mut token_usage) = info.map(ThreadTokenUsage::from) {
+        token_usage.cost = cost.map(Into::into);
         let notification = ThreadTokenUsageUpdatedNotification {
             thread_id: conversation_id.to_string(),
             turn_id,
@@ -1023,6 +1192,13 @@ async fn on_exec_approval_response(
 
 const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 
+fn sub_agent_role(source: &SubAgentSource) -> Option<String> {
+    match source {
+        SubAgentSource::SpawnAgent { role } => role.clone(),
+        _ => None,
+    }
+}
+
 fn render_review_output_text(output: &ReviewOutputEvent) -> String {
     let mut sections = Vec::new();
     let explanation = output.overall_explanation.trim();
@@ -1309,11 +1485,13 @@ mod tests {
     use anyhow::Result;
     use anyhow::anyhow;
     use anyhow::bail;
+    use codex_app_server_protocol::TokenCost;
     use codex_app_server_protocol::TurnPlanStepStatus;
     use codex_core::protocol::CreditsSnapshot;
     use codex_core::protocol::McpInvocation;
     use codex_core::protocol::RateLimitSnapshot;
     use codex_core::protocol::RateLimitWindow;
+    use codex_core::protocol::TokenCost as CoreTokenCost;
     use codex_core::protocol::TokenUsage;
     use codex_core::protocol::TokenUsageInfo;
     use codex_protocol::plan_tool::PlanItemArg;
@@ -1533,6 +1711,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_handle_plan_approval_request_emits_notification_for_v2() -> Result<()> {
+        let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let outgoing = OutgoingMessageSender::new(tx);
+        let plan = UpdatePlanArgs {
+            explanation: None,
+            plan: vec![PlanItemArg {
+                step: "migrate config".to_string(),
+                status: StepStatus::Pending,
+            }],
+        };
+        let conversation_id = ConversationId::new();
+
+        handle_plan_approval_request(
+            conversation_id,
+            "turn-123",
+            plan.clone(),
+            ApiVersion::V1,
+            &outgoing,
+        )
+        .await;
+        assert!(rx.try_recv().is_err(), "v1 clients get no notification");
+
+        handle_plan_approval_request(conversation_id, "turn-123", plan, ApiVersion::V2, &outgoing)
+            .await;
+        let msg = rx
+            .recv()
+            .await
+            .ok_or_else(|| anyhow!("should send one notification"))?;
+        match msg {
+            OutgoingMessage::AppServerNotification(
+                ServerNotification::TurnPlanApprovalRequested(n),
+            ) => {
+                assert_eq!(n.thread_id, conversation_id.to_string());
+                assert_eq!(n.turn_id, "turn-123");
+                assert_eq!(n.explanation, None);
+                assert_eq!(
+                    n.plan,
+                    vec![TurnPlanStep {
+                        step: "migrate config".to_string(),
+                        status: TurnPlanStepStatus::Pending,
+                    }]
+                );
+            }
+            other => bail!("unexpected message: {other:?}"),
+        }
+        assert!(rx.try_recv().is_err(), "no extra messages expected");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_handle_token_count_event_emits_usage_and_rate_limits() -> Result<()> {
         let conversation_id = ConversationId::new();
@@ -1578,6 +1806,10 @@ mod tests {
             TokenCountEvent {
                 info: Some(info),
                 rate_limits: Some(rate_limits),
+                cost: Some(CoreTokenCost {
+                    session_usd: 0.5,
+                    turn_usd: 0.25,
+                }),
             },
             &outgoing,
         )
@@ -1598,6 +1830,13 @@ mod tests {
                 assert_eq!(usage.total.cached_input_tokens, 25);
                 assert_eq!(usage.last.output_tokens, 7);
                 assert_eq!(usage.model_context_window, Some(4096));
+                assert_eq!(
+                    usage.cost,
+                    Some(TokenCost {
+                        session_usd: 0.5,
+                        turn_usd: 0.25,
+                    })
+                );
             }
             other => bail!("unexpected notification: {other:?}"),
         }
@@ -1631,6 +1870,7 @@ mod tests {
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                cost: None,
             },
             &outgoing,
         )
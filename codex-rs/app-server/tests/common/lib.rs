@@ -26,6 +26,7 @@ pub use responses::create_apply_patch_sse_response;
 pub use responses::create_exec_command_sse_response;
 pub use responses::create_final_assistant_message_sse_response;
 pub use responses::create_shell_command_sse_response;
+pub use responses::create_update_plan_sse_response;
 pub use rollout::create_fake_rollout;
 use serde::de::DeserializeOwned;
 
@@ -45,8 +45,10 @@ use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadResumeParams;
+use codex_app_server_protocol::ThreadRollbackParams;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::TurnInterruptParams;
+use codex_app_server_protocol::TurnPlanApproveParams;
 use codex_app_server_protocol::TurnStartParams;
 use tokio::process::Command;
 
@@ -316,6 +318,15 @@ impl McpProcess {
         self.send_request("thread/archive", params).await
     }
 
+    /// Send a `thread/rollback` JSON-RPC request.
+    pub async fn send_thread_rollback_request(
+        &mut self,
+        params: ThreadRollbackParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/rollback", params).await
+    }
+
     /// Send a `thread/list` JSON-RPC request.
     pub async fn send_thread_list_request(
         &mut self,
@@ -375,6 +386,15 @@ impl McpProcess {
         self.send_request("turn/interrupt", params).await
     }
 
+    /// Send a `turn/plan/approve` JSON-RPC request (v2).
+    pub async fn send_turn_plan_approve_request(
+        &mut self,
+        params: TurnPlanApproveParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("turn/plan/approve", params).await
+    }
+
     /// Send a `review/start` JSON-RPC request (v2).
     pub async fn send_review_start_request(
         &mut self,
@@ -59,6 +59,38 @@ pub fn create_final_assistant_message_sse_response(message: &str) -> anyhow::Res
     Ok(sse)
 }
 
+pub fn create_update_plan_sse_response(steps: &[&str], call_id: &str) -> anyhow::Result<String> {
+    let plan = steps
+        .iter()
+        .map(|step| json!({ "step": step, "status": "pending" }))
+        .collect::<Vec<_>>();
+    let tool_call_arguments = serde_json::to_string(&json!({ "plan": plan }))?;
+    let tool_call = json!({
+        "choices": [
+            {
+                "delta": {
+                    "tool_calls": [
+                        {
+                            "id": call_id,
+                            "function": {
+                                "name": "update_plan",
+                                "arguments": tool_call_arguments
+                            }
+                        }
+                    ]
+                },
+                "finish_reason": "tool_calls"
+            }
+        ]
+    });
+
+    let sse = format!(
+        "data: {}\n\ndata: DONE\n\n",
+        serde_json::to_string(&tool_call)?
+    );
+    Ok(sse)
+}
+
 pub fn create_apply_patch_sse_response(
     patch_content: &str,
     call_id: &str,
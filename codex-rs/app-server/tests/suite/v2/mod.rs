@@ -6,6 +6,8 @@ mod review;
 mod thread_archive;
 mod thread_list;
 mod thread_resume;
+mod thread_rollback;
 mod thread_start;
 mod turn_interrupt;
+mod turn_plan_approve;
 mod turn_start;
@@ -361,7 +361,8 @@ fn derive_new_contents_from_chunks(
     let replacements = compute_replacements(&original_lines, path, chunks)?;
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
-    if !new_lines.last().is_some_and(String::is_empty) {
+    let keep_final_newline = !chunks.iter().any(|chunk| chunk.no_newline_at_end_of_file);
+    if keep_final_newline && !new_lines.last().is_some_and(String::is_empty) {
         new_lines.push(String::new());
     }
     let new_contents = new_lines.join("\n");
@@ -971,6 +972,33 @@ mod tests {
         assert_eq!(expected, diff);
     }
 
+    #[test]
+    fn test_update_without_newline_at_eof() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("no_newline.txt");
+        fs::write(&path, "foo\nbar").unwrap();
+
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+ foo
+-bar
++BAR
+*** No newline at end of file
+"#,
+            path.display()
+        ));
+
+        let patch = parse_patch(&patch).unwrap();
+        let chunks = match patch.hunks.as_slice() {
+            [Hunk::UpdateFile { chunks, .. }] => chunks,
+            _ => panic!("Expected a single UpdateFile hunk"),
+        };
+
+        let diff = unified_diff_from_chunks(&path, chunks).unwrap();
+        assert_eq!(diff.content, "foo\nBAR");
+    }
+
     #[test]
     fn test_unified_diff_interleaved_changes() {
         // Original file with six lines.
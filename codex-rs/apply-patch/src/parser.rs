@@ -18,7 +18,7 @@
 //! change: (change_context | change_line)+ eof_line?
 //! change_context: ("@@" | "@@ " /(.+)/) LF
 //! change_line: ("+" | "-" | " ") /(.+)/ LF
-//! eof_line: "*** End of File" LF
+//! eof_line: ("*** End of File" | "*** No newline at end of file") LF
 //!
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
@@ -35,6 +35,8 @@ const DELETE_FILE_MARKER: &str = "*** Delete File: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
 const EOF_MARKER: &str = "*** End of File";
+/// Like [`EOF_MARKER`], and the updated file does not end with a newline.
+const NO_NEWLINE_EOF_MARKER: &str = "*** No newline at end of file";
 const CHANGE_CONTEXT_MARKER: &str = "@@ ";
 const EMPTY_CHANGE_CONTEXT_MARKER: &str = "@@";
 
@@ -101,6 +103,10 @@ pub struct UpdateFileChunk {
     /// If set to true, `old_lines` must occur at the end of the source file.
     /// (Tolerance around trailing newlines should be encouraged.)
     pub is_end_of_file: bool,
+
+    /// If set to true, the updated file ends after `new_lines` without a
+    /// final newline. Implies `is_end_of_file`.
+    pub no_newline_at_end_of_file: bool,
 }
 
 pub fn parse_patch(patch: &str) -> Result<ApplyPatchArgs, ParseError> {
@@ -380,11 +386,12 @@ fn parse_update_file_chunk(
         old_lines: Vec::new(),
         new_lines: Vec::new(),
         is_end_of_file: false,
+        no_newline_at_end_of_file: false,
     };
     let mut parsed_lines = 0;
     for line in &lines[start_index..] {
         match *line {
-            EOF_MARKER => {
+            EOF_MARKER | NO_NEWLINE_EOF_MARKER => {
                 if parsed_lines == 0 {
                     return Err(InvalidHunkError {
                         message: "Update hunk does not contain any lines".to_string(),
@@ -392,6 +399,7 @@ fn parse_update_file_chunk(
                     });
                 }
                 chunk.is_end_of_file = true;
+                chunk.no_newline_at_end_of_file = *line == NO_NEWLINE_EOF_MARKER;
                 parsed_lines += 1;
                 break;
             }
@@ -520,7 +528,8 @@ fn test_parse_patch() {
                     change_context: Some("def f():".to_string()),
                     old_lines: vec!["    pass".to_string()],
                     new_lines: vec!["    return 123".to_string()],
-                    is_end_of_file: false
+                    is_end_of_file: false,
+                    no_newline_at_end_of_file: false
                 }]
             }
         ]
@@ -547,7 +556,8 @@ fn test_parse_patch() {
                     change_context: None,
                     old_lines: vec![],
                     new_lines: vec!["line".to_string()],
-                    is_end_of_file: false
+                    is_end_of_file: false,
+                    no_newline_at_end_of_file: false
                 }],
             },
             AddFile {
@@ -578,6 +588,7 @@ fn test_parse_patch() {
                 old_lines: vec!["import foo".to_string()],
                 new_lines: vec!["import foo".to_string(), "bar".to_string()],
                 is_end_of_file: false,
+                no_newline_at_end_of_file: false,
             }],
         }]
     );
@@ -598,6 +609,7 @@ fn test_parse_patch_lenient() {
             old_lines: vec!["import foo".to_string()],
             new_lines: vec!["import foo".to_string(), "bar".to_string()],
             is_end_of_file: false,
+            no_newline_at_end_of_file: false,
         }],
     }];
     let expected_error =
@@ -743,7 +755,8 @@ fn test_update_file_chunk() {
                     "add".to_string(),
                     "context2".to_string()
                 ],
-                is_end_of_file: false
+                is_end_of_file: false,
+                no_newline_at_end_of_file: false
             }),
             6
         ))
@@ -755,9 +768,27 @@ fn test_update_file_chunk() {
                 change_context: None,
                 old_lines: vec![],
                 new_lines: vec!["line".to_string()],
-                is_end_of_file: true
+                is_end_of_file: true,
+                no_newline_at_end_of_file: false
             }),
             3
         ))
     );
+    assert_eq!(
+        parse_update_file_chunk(
+            &["@@", "-line", "+line", "*** No newline at end of file"],
+            123,
+            false
+        ),
+        Ok((
+            (UpdateFileChunk {
+                change_context: None,
+                old_lines: vec!["line".to_string()],
+                new_lines: vec!["line".to_string()],
+                is_end_of_file: true,
+                no_newline_at_end_of_file: true
+            }),
+            4
+        ))
+    );
 }
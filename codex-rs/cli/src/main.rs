@@ -734,6 +734,14 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
     if resume_cli.web_search {
         interactive.web_search = true;
     }
+    let budget = resume_cli.budget;
+    let merged = &mut interactive.budget;
+    merged.max_input_tokens = budget.max_input_tokens.or(merged.max_input_tokens);
+    merged.max_output_tokens = budget.max_output_tokens.or(merged.max_output_tokens);
+    merged.max_cost_usd = budget.max_cost_usd.or(merged.max_cost_usd);
+    merged.max_tool_calls = budget.max_tool_calls.or(merged.max_tool_calls);
+    merged.max_turns = budget.max_turns.or(merged.max_turns);
+    merged.max_duration_secs = budget.max_duration_secs.or(merged.max_duration_secs);
     if !resume_cli.images.is_empty() {
         interactive.images = resume_cli.images;
     }
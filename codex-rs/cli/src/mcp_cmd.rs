@@ -264,7 +264,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         env_http_headers,
     } = transport
     {
-        match supports_oauth_login(&url).await {
+        match supports_oauth_login(&url, &config.network).await {
             Ok(true) => {
                 println!("Detected OAuth support. Starting OAuth flow…");
                 perform_oauth_login(
@@ -273,6 +273,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
                     config.mcp_oauth_credentials_store_mode,
                     http_headers.clone(),
                     env_http_headers.clone(),
+                    &config.network,
                     &Vec::new(),
                 )
                 .await?;
@@ -351,6 +352,7 @@ async fn run_login(config_overrides: &CliConfigOverrides, login_args: LoginArgs)
         config.mcp_oauth_credentials_store_mode,
         http_headers,
         env_http_headers,
+        &config.network,
         &scopes,
     )
     .await?;
@@ -400,6 +402,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
     let auth_statuses = compute_auth_statuses(
         config.mcp_servers.iter(),
         config.mcp_oauth_credentials_store_mode,
+        &config.network,
     )
     .await;
 
@@ -1,4 +1,5 @@
 use codex_client::Request;
+use http::HeaderMap;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt;
@@ -62,6 +63,12 @@ pub trait AuthProvider: Send + Sync {
     fn is_azure(&self) -> bool {
         false
     }
+
+    /// Additional credential headers sent alongside the primary one, e.g. an
+    /// APIM `Ocp-Apim-Subscription-Key` next to an Entra ID bearer token.
+    fn extra_headers(&self) -> HeaderMap {
+        HeaderMap::new()
+    }
 }
 
 /// Adds authentication headers to a request based on the auth provider's configuration.
@@ -91,6 +98,10 @@ pub(crate) fn add_auth_headers<A: AuthProvider>(auth: &A, mut req: Request) -> R
         }
     }
 
+    for (name, value) in auth.extra_headers().iter() {
+        let _ = req.headers.insert(name.clone(), value.clone());
+    }
+
     if let Some(account_id) = auth.account_id()
         && let Ok(header) = account_id.parse()
     {
@@ -102,13 +113,13 @@ pub(crate) fn add_auth_headers<A: AuthProvider>(auth: &A, mut req: Request) -> R
 #[cfg(test)]
 mod tests {
     use super::*;
-    use http::HeaderMap;
 
     struct TestAuthProvider {
         token: Option<String>,
         api_key: Option<String>,
         header_type: AuthHeaderType,
         is_azure: bool,
+        extra_headers: HeaderMap,
     }
 
     impl AuthProvider for TestAuthProvider {
@@ -127,6 +138,10 @@ mod tests {
         fn is_azure(&self) -> bool {
             self.is_azure
         }
+
+        fn extra_headers(&self) -> HeaderMap {
+            self.extra_headers.clone()
+        }
     }
 
     fn create_test_request() -> Request {
@@ -146,6 +161,7 @@ mod tests {
             api_key: None,
             header_type: AuthHeaderType::Bearer,
             is_azure: false,
+            extra_headers: HeaderMap::new(),
         };
 
         let req = add_auth_headers(&auth, create_test_request());
@@ -164,6 +180,7 @@ mod tests {
             api_key: Some("my-api-key".to_string()),
             header_type: AuthHeaderType::ApiKey,
             is_azure: true,
+            extra_headers: HeaderMap::new(),
         };
 
         let req = add_auth_headers(&auth, create_test_request());
@@ -179,6 +196,7 @@ mod tests {
             api_key: Some("subscription-key".to_string()),
             header_type: AuthHeaderType::Custom("Ocp-Apim-Subscription-Key".to_string()),
             is_azure: true,
+            extra_headers: HeaderMap::new(),
         };
 
         let req = add_auth_headers(&auth, create_test_request());
@@ -198,10 +216,35 @@ mod tests {
             api_key: None,
             header_type: AuthHeaderType::ApiKey,
             is_azure: true,
+            extra_headers: HeaderMap::new(),
         };
 
         let req = add_auth_headers(&auth, create_test_request());
 
         assert_eq!(req.headers.get("api-key").unwrap(), "fallback-token");
     }
+
+    #[test]
+    fn test_extra_headers_sent_with_bearer_token() {
+        let mut extra_headers = HeaderMap::new();
+        extra_headers.insert("Ocp-Apim-Subscription-Key", "apim-key".parse().unwrap());
+        let auth = TestAuthProvider {
+            token: Some("entra-token".to_string()),
+            api_key: None,
+            header_type: AuthHeaderType::Bearer,
+            is_azure: true,
+            extra_headers,
+        };
+
+        let req = add_auth_headers(&auth, create_test_request());
+
+        assert_eq!(
+            req.headers.get(http::header::AUTHORIZATION).unwrap(),
+            "Bearer entra-token"
+        );
+        assert_eq!(
+            req.headers.get("Ocp-Apim-Subscription-Key").unwrap(),
+            "apim-key"
+        );
+    }
 }
@@ -52,6 +52,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
     pub async fn stream_prompt(
         &self,
         model: &str,
+        underlying_model: Option<&str>,
         prompt: &ApiPrompt,
         conversation_id: Option<String>,
         session_source: Option<SessionSource>,
@@ -60,6 +61,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
 
         let request =
             ChatRequestBuilder::new(model, &prompt.instructions, &prompt.input, &prompt.tools)
+                .underlying_model(underlying_model)
                 .conversation_id(conversation_id)
                 .session_source(session_source)
                 .build(self.streaming.provider())?;
@@ -69,7 +71,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
 
     fn path(&self) -> &'static str {
         match self.streaming.provider().wire {
-            WireApi::Chat => "chat/completions",
+            WireApi::Chat | WireApi::AzureInference => "chat/completions",
             _ => "responses",
         }
     }
@@ -147,6 +149,12 @@ impl Stream for AggregatedStream {
                         }
                     }
 
+                    // The SSE parser already emitted the complete reasoning item;
+                    // do not synthesize a second one on `Completed`.
+                    if matches!(item, ResponseItem::Reasoning { .. }) {
+                        this.cumulative_reasoning.clear();
+                    }
+
                     return Poll::Ready(Some(Ok(ResponseEvent::OutputItemDone(item))));
                 }
                 Poll::Ready(Some(Ok(ResponseEvent::RateLimits(snapshot)))) => {
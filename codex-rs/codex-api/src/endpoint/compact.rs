@@ -39,7 +39,7 @@ impl<T: HttpTransport, A: AuthProvider> CompactClient<T, A> {
     fn path(&self) -> Result<&'static str, ApiError> {
         match self.provider.wire {
             WireApi::Compact | WireApi::Responses => Ok("responses/compact"),
-            WireApi::Chat | WireApi::Anthropic => Err(ApiError::Stream(
+            WireApi::Chat | WireApi::Anthropic | WireApi::AzureInference => Err(ApiError::Stream(
                 "compact endpoint requires responses wire api".to_string(),
             )),
         }
@@ -136,6 +136,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            is_azure: false,
         }
     }
 
@@ -159,6 +159,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            is_azure: false,
         }
     }
 
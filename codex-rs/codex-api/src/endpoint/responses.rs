@@ -96,7 +96,7 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
     fn path(&self) -> &'static str {
         match self.streaming.provider().wire {
             WireApi::Responses | WireApi::Compact => "responses",
-            WireApi::Chat => "chat/completions",
+            WireApi::Chat | WireApi::AzureInference => "chat/completions",
             WireApi::Anthropic => "messages", // Anthropic Messages API path
         }
     }
@@ -31,11 +31,13 @@ pub use crate::endpoint::responses::ResponsesOptions;
 pub use crate::error::ApiError;
 pub use crate::provider::Provider;
 pub use crate::provider::WireApi;
+pub use crate::requests::AZURE_INFERENCE_API_VERSION;
 pub use crate::requests::AnthropicRequest;
 pub use crate::requests::AnthropicRequestBuilder;
 pub use crate::requests::ChatRequest;
 pub use crate::requests::ChatRequestBuilder;
 pub use crate::requests::ResponsesRequest;
 pub use crate::requests::ResponsesRequestBuilder;
+pub use crate::requests::model_name_matches;
 pub use crate::sse::stream_from_fixture;
 pub use crate::telemetry::SseTelemetry;
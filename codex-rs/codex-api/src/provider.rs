@@ -14,6 +14,10 @@ pub enum WireApi {
     Compact,
     /// Anthropic Messages API (used for Claude models on Azure AI Services)
     Anthropic,
+    /// Azure AI Model Inference API (`/models/chat/completions` with a `model`
+    /// body parameter), used for Mistral, Llama, DeepSeek, Phi and other
+    /// non-OpenAI deployments on Azure AI Foundry and serverless endpoints.
+    AzureInference,
 }
 
 /// High-level retry configuration for a provider.
@@ -56,6 +60,10 @@ pub struct Provider {
     pub headers: HeaderMap,
     pub retry: RetryConfig,
     pub stream_idle_timeout: Duration,
+    /// Whether the caller's provider config marks the endpoint as Azure
+    /// OpenAI (directly or behind API Management). Endpoints named `azure`
+    /// or hosted on a known Azure domain are detected without it.
+    pub is_azure: bool,
 }
 
 impl Provider {
@@ -98,7 +106,7 @@ impl Provider {
             return false;
         }
 
-        if self.name.eq_ignore_ascii_case("azure") {
+        if self.is_azure || self.name.eq_ignore_ascii_case("azure") {
             return true;
         }
 
@@ -117,6 +125,11 @@ impl Provider {
             || lower.contains("models.ai.azure.com")
             || lower.contains("/anthropic/")
     }
+
+    /// Returns true if this provider targets the Azure AI Model Inference API.
+    pub fn is_azure_inference_endpoint(&self) -> bool {
+        self.wire == WireApi::AzureInference
+    }
 }
 
 fn matches_azure_responses_base_url(base_url: &str) -> bool {
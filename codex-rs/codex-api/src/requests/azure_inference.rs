@@ -0,0 +1,313 @@
+//! Request adjustments for the Azure AI Model Inference API.
+//!
+//! The inference API speaks the Chat Completions schema, but each model family
+//! behind it has its own constraints around tool calling. The helpers here
+//! rewrite an already-built Chat Completions payload so it is accepted by the
+//! deployment's model.
+
+use crate::requests::headers::insert_header;
+use http::HeaderMap;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Default `api-version` for the Azure AI Model Inference API.
+pub const AZURE_INFERENCE_API_VERSION: &str = "2024-05-01-preview";
+
+/// Model families served through the Azure AI Model Inference API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferenceModelFamily {
+    Mistral,
+    Llama,
+    DeepSeek,
+    Phi,
+    Other,
+}
+
+impl InferenceModelFamily {
+    /// Classifies a model name (e.g. `Mistral-Large-2411`,
+    /// `Meta-Llama-3.1-405B-Instruct`, `DeepSeek-R1`, `Phi-4-mini-instruct`).
+    pub fn from_model(model: &str) -> Self {
+        let matches = |pattern| model_name_matches(model, pattern);
+        if matches("mistral") || matches("codestral") || matches("ministral") {
+            Self::Mistral
+        } else if matches("llama") {
+            Self::Llama
+        } else if matches("deepseek") {
+            Self::DeepSeek
+        } else if matches("phi") {
+            Self::Phi
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Whether `pattern` appears in `name` as a run of whole tokens, ignoring
+/// case. Tokens are separated by `-`, `_`, spaces or `/`, so `deepseek-r1`
+/// matches `DeepSeek-R1-0528` but not `deepseek-v3-r10`, and `llama-3`
+/// matches `Meta-Llama-3-70B` but not `Llama-3.1-8B`.
+pub fn model_name_matches(name: &str, pattern: &str) -> bool {
+    let tokens: Vec<&str> = name
+        .split(['-', '_', ' ', '/'])
+        .filter(|token| !token.is_empty())
+        .collect();
+    let pattern: Vec<&str> = pattern.split('-').collect();
+    tokens.windows(pattern.len()).any(|window| {
+        window
+            .iter()
+            .zip(&pattern)
+            .all(|(token, expected)| token.eq_ignore_ascii_case(expected))
+    })
+}
+
+/// Per-model quirks that shape the Chat Completions payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InferenceQuirks {
+    /// Whether the model accepts `tools` at all.
+    pub supports_tools: bool,
+    /// Whether `parallel_tool_calls` may be sent.
+    pub supports_parallel_tool_calls: bool,
+    /// Mistral only accepts tool call ids made of exactly nine ASCII
+    /// alphanumeric characters.
+    pub short_tool_call_ids: bool,
+    /// Tool results must be plain strings rather than content part arrays.
+    pub string_tool_content: bool,
+}
+
+impl InferenceQuirks {
+    /// Quirks of `model`, which should be the deployment's underlying model
+    /// name when discovery knows it; custom deployment names say little
+    /// about the model behind them.
+    pub fn for_model(model: &str) -> Self {
+        let matches = |pattern| model_name_matches(model, pattern);
+        match InferenceModelFamily::from_model(model) {
+            InferenceModelFamily::Mistral => Self {
+                supports_tools: true,
+                supports_parallel_tool_calls: true,
+                short_tool_call_ids: true,
+                string_tool_content: true,
+            },
+            InferenceModelFamily::Llama => Self {
+                // Function calling arrived with Llama 3.1; Llama 2 and 3.0
+                // reject requests that carry tools.
+                supports_tools: !(matches("llama-2") || matches("llama-3")),
+                supports_parallel_tool_calls: false,
+                short_tool_call_ids: false,
+                string_tool_content: true,
+            },
+            InferenceModelFamily::DeepSeek => Self {
+                // DeepSeek-R1 has no function calling on Azure; V3 does.
+                supports_tools: !matches("deepseek-r1"),
+                supports_parallel_tool_calls: false,
+                short_tool_call_ids: false,
+                string_tool_content: true,
+            },
+            InferenceModelFamily::Phi => Self {
+                supports_tools: matches("phi-4-mini") || matches("phi-4-multimodal"),
+                supports_parallel_tool_calls: false,
+                short_tool_call_ids: false,
+                string_tool_content: true,
+            },
+            InferenceModelFamily::Other => Self {
+                supports_tools: true,
+                supports_parallel_tool_calls: true,
+                short_tool_call_ids: false,
+                string_tool_content: false,
+            },
+        }
+    }
+}
+
+/// Rewrites a Chat Completions payload for the Azure AI Model Inference API.
+/// `model` picks the quirks; see [`InferenceQuirks::for_model`].
+pub(crate) fn apply_azure_inference_quirks(
+    model: &str,
+    payload: &mut Value,
+    headers: &mut HeaderMap,
+) {
+    let quirks = InferenceQuirks::for_model(model);
+
+    // Ask the service to drop parameters the model does not understand
+    // instead of failing the request with a 422.
+    insert_header(headers, "extra-parameters", "drop");
+
+    let Some(obj) = payload.as_object_mut() else {
+        return;
+    };
+
+    let has_tools = obj
+        .get("tools")
+        .and_then(Value::as_array)
+        .is_some_and(|tools| !tools.is_empty());
+    if !quirks.supports_tools || !has_tools {
+        obj.remove("tools");
+        obj.remove("parallel_tool_calls");
+    } else if !quirks.supports_parallel_tool_calls {
+        obj.insert("parallel_tool_calls".to_string(), Value::Bool(false));
+    }
+
+    let Some(messages) = obj.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+
+    let mut id_map: HashMap<String, String> = HashMap::new();
+    for message in messages.iter_mut() {
+        let Some(message) = message.as_object_mut() else {
+            continue;
+        };
+        // The inference API rejects unknown message fields.
+        message.remove("reasoning");
+
+        if quirks.short_tool_call_ids {
+            if let Some(tool_calls) = message.get_mut("tool_calls").and_then(Value::as_array_mut) {
+                for call in tool_calls {
+                    if let Some(Value::String(id)) = call.get_mut("id") {
+                        *id = short_tool_call_id(id, &mut id_map);
+                    }
+                }
+            }
+            if let Some(Value::String(id)) = message.get_mut("tool_call_id") {
+                *id = short_tool_call_id(id, &mut id_map);
+            }
+        }
+
+        if quirks.string_tool_content
+            && message.get("role").and_then(Value::as_str) == Some("tool")
+            && let Some(parts) = message.get("content").and_then(Value::as_array)
+        {
+            let text = parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n");
+            message.insert("content".to_string(), Value::String(text));
+        }
+    }
+}
+
+/// Maps an arbitrary tool call id onto the nine-character alphanumeric form
+/// Mistral requires. Ids that already conform are kept as-is so ids minted by
+/// Mistral itself round-trip unchanged.
+fn short_tool_call_id(id: &str, id_map: &mut HashMap<String, String>) -> String {
+    if id.len() == 9 && id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return id.to_string();
+    }
+    if let Some(mapped) = id_map.get(id) {
+        return mapped.clone();
+    }
+
+    const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    // FNV-1a keeps the mapping stable across requests of the same session.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in id.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let mut short = String::with_capacity(9);
+    for _ in 0..9 {
+        short.push(ALPHABET[(hash % ALPHABET.len() as u64) as usize] as char);
+        hash /= ALPHABET.len() as u64;
+    }
+    id_map.insert(id.to_string(), short.clone());
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn classifies_model_families() {
+        assert_eq!(
+            InferenceModelFamily::from_model("Mistral-Large-2411"),
+            InferenceModelFamily::Mistral
+        );
+        assert_eq!(
+            InferenceModelFamily::from_model("Meta-Llama-3.1-405B-Instruct"),
+            InferenceModelFamily::Llama
+        );
+        assert_eq!(
+            InferenceModelFamily::from_model("DeepSeek-R1"),
+            InferenceModelFamily::DeepSeek
+        );
+        assert_eq!(
+            InferenceModelFamily::from_model("Phi-4-mini-instruct"),
+            InferenceModelFamily::Phi
+        );
+        assert_eq!(
+            InferenceModelFamily::from_model("cohere-command-a"),
+            InferenceModelFamily::Other
+        );
+    }
+
+    #[test]
+    fn matches_whole_name_tokens() {
+        assert!(model_name_matches("DeepSeek-R1-0528", "deepseek-r1"));
+        assert!(model_name_matches("Meta-Llama-3-70B-Instruct", "llama-3"));
+        assert!(!model_name_matches("Llama-3.1-8B-Instruct", "llama-3"));
+        assert!(!model_name_matches("deepseek-v3-r10", "deepseek-r1"));
+        assert!(!model_name_matches("alphi-4-mini", "phi"));
+
+        // Names that merely contain "r1" keep their tools.
+        assert!(InferenceQuirks::for_model("DeepSeek-V3-0324-fr1").supports_tools);
+        assert!(!InferenceQuirks::for_model("DeepSeek-R1").supports_tools);
+    }
+
+    #[test]
+    fn drops_tools_for_models_without_function_calling() {
+        let mut payload = json!({
+            "model": "DeepSeek-R1",
+            "messages": [{"role": "user", "content": "hi"}],
+            "tools": [{"type": "function", "function": {"name": "shell"}}],
+        });
+        let mut headers = HeaderMap::new();
+        apply_azure_inference_quirks("DeepSeek-R1", &mut payload, &mut headers);
+
+        assert!(payload.get("tools").is_none());
+        assert_eq!(
+            headers.get("extra-parameters").map(|v| v.to_str().unwrap()),
+            Some("drop")
+        );
+    }
+
+    #[test]
+    fn rewrites_tool_call_ids_for_mistral() {
+        let mut payload = json!({
+            "model": "Mistral-Large-2411",
+            "messages": [
+                {"role": "assistant", "content": null, "reasoning": "r", "tool_calls": [
+                    {"id": "call_abcdefghijklmnop", "type": "function", "function": {"name": "shell", "arguments": "{}"}}
+                ]},
+                {"role": "tool", "tool_call_id": "call_abcdefghijklmnop", "content": [{"type": "text", "text": "ok"}]},
+            ],
+            "tools": [{"type": "function", "function": {"name": "shell"}}],
+        });
+        apply_azure_inference_quirks("Mistral-Large-2411", &mut payload, &mut HeaderMap::new());
+
+        let messages = payload["messages"].as_array().expect("messages");
+        let call_id = messages[0]["tool_calls"][0]["id"].as_str().expect("id");
+        assert_eq!(call_id.len(), 9);
+        assert!(call_id.bytes().all(|b| b.is_ascii_alphanumeric()));
+        assert_eq!(messages[1]["tool_call_id"], call_id);
+        assert_eq!(messages[1]["content"], "ok");
+        assert!(messages[0].get("reasoning").is_none());
+    }
+
+    #[test]
+    fn disables_parallel_tool_calls_for_llama() {
+        let mut payload = json!({
+            "model": "Llama-3.3-70B-Instruct",
+            "messages": [],
+            "tools": [{"type": "function", "function": {"name": "shell"}}],
+        });
+        apply_azure_inference_quirks(
+            "Llama-3.3-70B-Instruct",
+            &mut payload,
+            &mut HeaderMap::new(),
+        );
+        assert_eq!(payload["parallel_tool_calls"], json!(false));
+        assert!(payload.get("tools").is_some());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::ApiError;
 use crate::provider::Provider;
+use crate::requests::azure_inference::apply_azure_inference_quirks;
 use crate::requests::headers::build_conversation_headers;
 use crate::requests::headers::insert_header;
 use crate::requests::headers::subagent_header;
@@ -21,6 +22,7 @@ pub struct ChatRequest {
 
 pub struct ChatRequestBuilder<'a> {
     model: &'a str,
+    underlying_model: Option<&'a str>,
     instructions: &'a str,
     input: &'a [ResponseItem],
     tools: &'a [Value],
@@ -37,6 +39,7 @@ impl<'a> ChatRequestBuilder<'a> {
     ) -> Self {
         Self {
             model,
+            underlying_model: None,
             instructions,
             input,
             tools,
@@ -45,6 +48,14 @@ impl<'a> ChatRequestBuilder<'a> {
         }
     }
 
+    /// Model served by the deployment named `model`, when discovery knows
+    /// it. Azure AI inference quirks follow this name rather than the
+    /// deployment's.
+    pub fn underlying_model(mut self, model: Option<&'a str>) -> Self {
+        self.underlying_model = model;
+        self
+    }
+
     pub fn conversation_id(mut self, id: Option<String>) -> Self {
         self.conversation_id = id;
         self
@@ -55,7 +66,7 @@ impl<'a> ChatRequestBuilder<'a> {
         self
     }
 
-    pub fn build(self, _provider: &Provider) -> Result<ChatRequest, ApiError> {
+    pub fn build(self, provider: &Provider) -> Result<ChatRequest, ApiError> {
         let mut messages = Vec::<Value>::new();
         messages.push(json!({"role": "system", "content": self.instructions}));
 
@@ -291,7 +302,7 @@ impl<'a> ChatRequestBuilder<'a> {
             }
         }
 
-        let payload = json!({
+        let mut payload = json!({
             "model": self.model,
             "messages": messages,
             "stream": true,
@@ -302,6 +313,10 @@ impl<'a> ChatRequestBuilder<'a> {
         if let Some(subagent) = subagent_header(&self.session_source) {
             insert_header(&mut headers, "x-openai-subagent", &subagent);
         }
+        if provider.is_azure_inference_endpoint() {
+            let quirks_model = self.underlying_model.unwrap_or(self.model);
+            apply_azure_inference_quirks(quirks_model, &mut payload, &mut headers);
+        }
 
         Ok(ChatRequest {
             body: payload,
@@ -375,6 +390,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            is_azure: false,
         }
     }
 
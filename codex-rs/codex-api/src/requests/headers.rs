@@ -15,15 +15,7 @@ pub(crate) fn subagent_header(source: &Option<SessionSource>) -> Option<String>
     let SessionSource::SubAgent(sub) = source.as_ref()? else {
         return None;
     };
-    match sub {
-        codex_protocol::protocol::SubAgentSource::Other(label) => Some(label.clone()),
-        other => Some(
-            serde_json::to_value(other)
-                .ok()
-                .and_then(|v| v.as_str().map(std::string::ToString::to_string))
-                .unwrap_or_else(|| "other".to_string()),
-        ),
-    }
+    Some(sub.to_string())
 }
 
 pub(crate) fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) {
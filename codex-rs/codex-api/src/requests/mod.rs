@@ -1,10 +1,15 @@
 pub mod anthropic;
+pub mod azure_inference;
 pub mod chat;
 pub(crate) mod headers;
 pub mod responses;
 
 pub use anthropic::AnthropicRequest;
 pub use anthropic::AnthropicRequestBuilder;
+pub use azure_inference::AZURE_INFERENCE_API_VERSION;
+pub use azure_inference::InferenceModelFamily;
+pub use azure_inference::InferenceQuirks;
+pub use azure_inference::model_name_matches;
 pub use chat::ChatRequest;
 pub use chat::ChatRequestBuilder;
 pub use responses::ResponsesRequest;
@@ -194,6 +194,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(5),
+            is_azure: false,
         }
     }
 
@@ -30,6 +30,12 @@ pub(crate) fn spawn_chat_stream(
     ResponseStream { rx_event }
 }
 
+/// Parses a Chat Completions SSE stream into [`ResponseEvent`]s.
+///
+/// Providers expose chain of thought in different shapes (`reasoning`,
+/// `reasoning_content`, `reasoning_details`, or inline `<think>` blocks in
+/// the assistant content); all of them are normalized into reasoning deltas
+/// and a single reasoning item so the answer text never carries them.
 pub async fn process_chat_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
@@ -39,6 +45,7 @@ pub async fn process_chat_sse<S>(
     S: Stream<Item = Result<bytes::Bytes, codex_client::TransportError>> + Unpin,
 {
     let mut stream = stream.eventsource();
+    let mut think_splitter = ThinkTagSplitter::default();
 
     #[derive(Default, Debug)]
     struct ToolCallState {
@@ -55,6 +62,7 @@ pub async fn process_chat_sse<S>(
     let mut last_tool_call_index: Option<usize> = None;
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
+    let mut streamed_reasoning = false;
     let mut completed_sent = false;
 
     loop {
@@ -70,6 +78,10 @@ pub async fn process_chat_sse<S>(
                 return;
             }
             Ok(None) => {
+                for segment in think_splitter.finish() {
+                    emit_segment(&tx_event, &mut assistant_item, &mut reasoning_item, segment)
+                        .await;
+                }
                 if let Some(reasoning) = reasoning_item {
                     let _ = tx_event
                         .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
@@ -122,34 +134,33 @@ pub async fn process_chat_sse<S>(
 
         for choice in choices {
             if let Some(delta) = choice.get("delta") {
-                if let Some(reasoning) = delta.get("reasoning") {
-                    if let Some(text) = reasoning.as_str() {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
-                    } else if let Some(text) = reasoning.get("text").and_then(|v| v.as_str()) {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
-                    } else if let Some(text) = reasoning.get("content").and_then(|v| v.as_str()) {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
-                    }
+                if let Some(text) = reasoning_text(delta) {
+                    streamed_reasoning = true;
+                    append_reasoning_text(&tx_event, &mut reasoning_item, text).await;
                 }
 
                 if let Some(content) = delta.get("content") {
-                    if content.is_array() {
-                        for item in content.as_array().unwrap_or(&vec![]) {
-                            if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                                append_assistant_text(
-                                    &tx_event,
-                                    &mut assistant_item,
-                                    text.to_string(),
-                                )
-                                .await;
-                            }
-                        }
+                    let mut texts = Vec::new();
+                    if let Some(items) = content.as_array() {
+                        texts.extend(
+                            items
+                                .iter()
+                                .filter_map(|item| item.get("text").and_then(|t| t.as_str())),
+                        );
                     } else if let Some(text) = content.as_str() {
-                        append_assistant_text(&tx_event, &mut assistant_item, text.to_string())
+                        texts.push(text);
+                    }
+
+                    for text in texts {
+                        for segment in think_splitter.push(text) {
+                            emit_segment(
+                                &tx_event,
+                                &mut assistant_item,
+                                &mut reasoning_item,
+                                segment,
+                            )
                             .await;
+                        }
                     }
                 }
 
@@ -208,19 +219,22 @@ pub async fn process_chat_sse<S>(
                 }
             }
 
-            if let Some(message) = choice.get("message")
-                && let Some(reasoning) = message.get("reasoning")
+            // Non-streaming style payloads carry the full reasoning on the final
+            // message; skip it when the same text was already streamed as deltas.
+            if !streamed_reasoning
+                && let Some(message) = choice.get("message")
+                && let Some(text) = reasoning_text(message)
             {
-                if let Some(text) = reasoning.as_str() {
-                    append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string()).await;
-                } else if let Some(text) = reasoning.get("text").and_then(|v| v.as_str()) {
-                    append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string()).await;
-                } else if let Some(text) = reasoning.get("content").and_then(|v| v.as_str()) {
-                    append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string()).await;
-                }
+                append_reasoning_text(&tx_event, &mut reasoning_item, text).await;
             }
 
             let finish_reason = choice.get("finish_reason").and_then(|r| r.as_str());
+            if finish_reason.is_some() {
+                for segment in think_splitter.finish() {
+                    emit_segment(&tx_event, &mut assistant_item, &mut reasoning_item, segment)
+                        .await;
+                }
+            }
             if finish_reason == Some("stop") {
                 if let Some(reasoning) = reasoning_item.take() {
                     let _ = tx_event
@@ -284,6 +298,150 @@ pub async fn process_chat_sse<S>(
     }
 }
 
+/// A piece of streamed assistant content after `<think>` tags have been split out.
+#[derive(Debug, PartialEq, Eq)]
+enum ContentSegment {
+    Reasoning(String),
+    Text(String),
+}
+
+/// Incrementally separates inline `<think>…</think>` blocks from visible
+/// assistant text. Tags may be split across SSE chunks, so a trailing partial
+/// tag is held back until the next chunk arrives.
+#[derive(Debug, Default)]
+struct ThinkTagSplitter {
+    in_think: bool,
+    pending: String,
+    /// Drop the blank lines models emit right after `</think>`.
+    trim_next_text: bool,
+}
+
+impl ThinkTagSplitter {
+    const OPEN: &'static str = "<think>";
+    const CLOSE: &'static str = "</think>";
+
+    fn push(&mut self, chunk: &str) -> Vec<ContentSegment> {
+        let mut buf = std::mem::take(&mut self.pending);
+        buf.push_str(chunk);
+        let mut segments = Vec::new();
+
+        loop {
+            let tag = if self.in_think {
+                Self::CLOSE
+            } else {
+                Self::OPEN
+            };
+            if let Some(pos) = buf.find(tag) {
+                self.emit(&buf[..pos], &mut segments);
+                buf.drain(..pos + tag.len());
+                self.in_think = !self.in_think;
+                if !self.in_think {
+                    self.trim_next_text = true;
+                }
+                continue;
+            }
+
+            let keep = partial_tag_suffix_len(&buf, tag);
+            let split_at = buf.len() - keep;
+            self.emit(&buf[..split_at], &mut segments);
+            self.pending = buf.split_off(split_at);
+            break;
+        }
+
+        segments
+    }
+
+    fn finish(&mut self) -> Vec<ContentSegment> {
+        let rest = std::mem::take(&mut self.pending);
+        let mut segments = Vec::new();
+        self.emit(&rest, &mut segments);
+        segments
+    }
+
+    fn emit(&mut self, text: &str, segments: &mut Vec<ContentSegment>) {
+        if self.in_think {
+            if !text.is_empty() {
+                segments.push(ContentSegment::Reasoning(text.to_string()));
+            }
+            return;
+        }
+
+        let text = if self.trim_next_text {
+            text.trim_start()
+        } else {
+            text
+        };
+        if !text.is_empty() {
+            self.trim_next_text = false;
+            segments.push(ContentSegment::Text(text.to_string()));
+        }
+    }
+}
+
+/// Length of the longest suffix of `buf` that is a proper prefix of `tag`.
+fn partial_tag_suffix_len(buf: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&len| buf.ends_with(&tag[..len]))
+        .unwrap_or(0)
+}
+
+/// Extracts reasoning text from a Chat Completions `delta` or `message`.
+///
+/// OpenRouter and vLLM use `reasoning` (a string or an object with `text` /
+/// `content`), DeepSeek and Azure AI inference use `reasoning_content`, and
+/// OpenRouter additionally sends structured `reasoning_details` whose
+/// encrypted entries carry no readable text.
+fn reasoning_text(value: &serde_json::Value) -> Option<String> {
+    if let Some(reasoning) = value.get("reasoning") {
+        let text = reasoning.as_str().or_else(|| {
+            reasoning
+                .get("text")
+                .or_else(|| reasoning.get("content"))
+                .and_then(|v| v.as_str())
+        });
+        if let Some(text) = text
+            && !text.is_empty()
+        {
+            return Some(text.to_string());
+        }
+    }
+
+    if let Some(text) = value.get("reasoning_content").and_then(|v| v.as_str())
+        && !text.is_empty()
+    {
+        return Some(text.to_string());
+    }
+
+    let details = value.get("reasoning_details")?.as_array()?;
+    let text: String = details
+        .iter()
+        .filter_map(|detail| {
+            detail
+                .get("text")
+                .or_else(|| detail.get("summary"))
+                .and_then(|v| v.as_str())
+        })
+        .collect();
+    (!text.is_empty()).then_some(text)
+}
+
+async fn emit_segment(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    assistant_item: &mut Option<ResponseItem>,
+    reasoning_item: &mut Option<ResponseItem>,
+    segment: ContentSegment,
+) {
+    match segment {
+        ContentSegment::Reasoning(text) => {
+            append_reasoning_text(tx_event, reasoning_item, text).await;
+        }
+        ContentSegment::Text(text) => {
+            append_assistant_text(tx_event, assistant_item, text).await;
+        }
+    }
+}
+
 async fn append_assistant_text(
     tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
     assistant_item: &mut Option<ResponseItem>,
@@ -382,6 +540,131 @@ mod tests {
         out
     }
 
+    #[test]
+    fn think_splitter_handles_tags_split_across_chunks() {
+        let mut splitter = ThinkTagSplitter::default();
+        let mut segments = Vec::new();
+        for chunk in ["<thi", "nk>plan", " it</th", "ink>\n\nAnswer", " <"] {
+            segments.extend(splitter.push(chunk));
+        }
+        segments.extend(splitter.finish());
+
+        assert_eq!(
+            segments,
+            vec![
+                ContentSegment::Reasoning("plan".to_string()),
+                ContentSegment::Reasoning(" it".to_string()),
+                ContentSegment::Text("Answer".to_string()),
+                ContentSegment::Text(" ".to_string()),
+                ContentSegment::Text("<".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn splits_think_blocks_into_reasoning() {
+        let think = json!({"choices": [{"delta": {"content": "<think>check the docs</think>"}}]});
+        let answer = json!({"choices": [{"delta": {"content": "\nDone."}}]});
+        let finish = json!({"choices": [{"finish_reason": "stop"}]});
+
+        let body = build_body(&[think, answer, finish]);
+        let events = collect_events(&body).await;
+
+        assert_matches!(
+            &events[..],
+            [
+                ResponseEvent::OutputItemAdded(ResponseItem::Reasoning { .. }),
+                ResponseEvent::ReasoningContentDelta { delta: reasoning, .. },
+                ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }),
+                ResponseEvent::OutputTextDelta(text),
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning { .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { .. }),
+                ResponseEvent::Completed { .. }
+            ] if reasoning == "check the docs" && text == "Done."
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_reasoning_content_deltas() {
+        let reasoning = json!({"choices": [{"delta": {"reasoning_content": "thinking"}}]});
+        let answer = json!({"choices": [{"delta": {"content": "hi"}}]});
+        let finish = json!({"choices": [{"finish_reason": "stop"}]});
+
+        let body = build_body(&[reasoning, answer, finish]);
+        let events = collect_events(&body).await;
+
+        assert_matches!(
+            &events[..],
+            [
+                ResponseEvent::OutputItemAdded(ResponseItem::Reasoning { .. }),
+                ResponseEvent::ReasoningContentDelta { delta: reasoning, .. },
+                ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }),
+                ResponseEvent::OutputTextDelta(text),
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning { .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { .. }),
+                ResponseEvent::Completed { .. }
+            ] if reasoning == "thinking" && text == "hi"
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_reasoning_details_and_skips_encrypted_entries() {
+        let reasoning = json!({"choices": [{"delta": {"reasoning_details": [
+            {"type": "reasoning.text", "text": "step one"},
+            {"type": "reasoning.encrypted", "data": "opaque"}
+        ]}}]});
+        let summary = json!({"choices": [{"delta": {"reasoning_details": [
+            {"type": "reasoning.summary", "summary": ", step two"}
+        ]}}]});
+        let answer = json!({"choices": [{"delta": {"content": "ok"}}]});
+        let finish = json!({"choices": [{"finish_reason": "stop"}]});
+
+        let body = build_body(&[reasoning, summary, answer, finish]);
+        let events = collect_events(&body).await;
+
+        let reasoning_item = events
+            .iter()
+            .find_map(|ev| match ev {
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning { content, .. }) => {
+                    content.clone()
+                }
+                _ => None,
+            })
+            .expect("reasoning item");
+        assert_eq!(
+            reasoning_item,
+            vec![
+                ReasoningItemContent::ReasoningText {
+                    text: "step one".to_string()
+                },
+                ReasoningItemContent::ReasoningText {
+                    text: ", step two".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_final_message_reasoning_already_streamed() {
+        let delta = json!({"choices": [{"delta": {"reasoning": "thinking"}}]});
+        let finish = json!({"choices": [{
+            "message": {"reasoning": "thinking"},
+            "finish_reason": "stop"
+        }]});
+
+        let body = build_body(&[delta, finish]);
+        let events = collect_events(&body).await;
+
+        let deltas: Vec<_> = events
+            .iter()
+            .filter_map(|ev| match ev {
+                ResponseEvent::ReasoningContentDelta { delta, .. } => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, vec!["thinking"]);
+    }
+
     #[tokio::test]
     async fn concatenates_tool_call_arguments_across_deltas() {
         let delta_name = json!({
@@ -5,6 +5,7 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use codex_api::AggregateStreamExt;
 use codex_api::AuthProvider;
+use codex_api::ChatClient;
 use codex_api::Provider;
 use codex_api::ResponseEvent;
 use codex_api::ResponsesClient;
@@ -75,6 +76,7 @@ fn provider(name: &str, wire: WireApi) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: Duration::from_millis(50),
+        is_azure: false,
     }
 }
 
@@ -227,3 +229,48 @@ async fn responses_stream_aggregates_output_text_deltas() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn chat_stream_aggregate_hides_reasoning_deltas() -> Result<()> {
+    let chunks = [
+        serde_json::json!({"choices": [{"delta": {"reasoning_content": "thinking"}}]}),
+        serde_json::json!({"choices": [{"delta": {"content": "hi"}}]}),
+        serde_json::json!({"choices": [{"finish_reason": "stop"}]}),
+    ];
+    let body: String = chunks
+        .iter()
+        .map(|chunk| format!("data: {chunk}\n\n"))
+        .collect();
+    let transport = FixtureSseTransport::new(body);
+    let client = ChatClient::new(transport, provider("chat", WireApi::Chat), NoAuth);
+
+    let stream = client
+        .stream(serde_json::json!({"echo": true}), HeaderMap::new())
+        .await?;
+
+    let mut stream = stream.aggregate();
+    let mut events = Vec::new();
+    while let Some(ev) = stream.next().await {
+        events.push(ev?);
+    }
+
+    assert!(
+        !events.iter().any(|ev| matches!(
+            ev,
+            ResponseEvent::ReasoningContentDelta { .. } | ResponseEvent::OutputTextDelta(_)
+        )),
+        "aggregated stream forwarded deltas: {events:?}"
+    );
+    let reasoning_items = events
+        .iter()
+        .filter(|ev| {
+            matches!(
+                ev,
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning { .. })
+            )
+        })
+        .count();
+    assert_eq!(reasoning_items, 1);
+
+    Ok(())
+}
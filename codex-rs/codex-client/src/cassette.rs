@@ -0,0 +1,575 @@
+//! On-disk recordings of HTTP sessions ("cassettes").
+//!
+//! A cassette holds every request/response pair a session exchanged with the
+//! model provider, including raw SSE bodies, so a session can later be served
+//! by [`crate::ReplayTransport`] without network access. Credentials are
+//! redacted before anything is written to disk.
+//!
+//! Cassettes are JSON Lines: a `{"version":2}` header followed by one
+//! interaction per line, so recording appends instead of rewriting the file.
+//! Version 1 cassettes, a single JSON document, are still read.
+
+use crate::error::TransportError;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+pub const CASSETTE_VERSION: u32 = 2;
+
+/// The single-document format written before cassettes became JSON Lines.
+const LEGACY_CASSETTE_VERSION: u32 = 1;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Header name fragments whose values are never written to a cassette.
+const SENSITIVE_HEADER_MARKERS: &[&str] =
+    &["auth", "key", "token", "secret", "cookie", "signature"];
+
+/// Query parameters that carry credentials (SAS signatures, OAuth codes, keys).
+const SENSITIVE_QUERY_PARAMS: &[&str] = &[
+    "key",
+    "api-key",
+    "api_key",
+    "code",
+    "sig",
+    "token",
+    "access_token",
+    "client_secret",
+];
+
+/// JSON fields that carry credentials. Matched exactly (case-insensitive) so
+/// fields like `max_output_tokens` or `prompt_cache_key` are kept.
+const SENSITIVE_JSON_FIELDS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "client_secret",
+    "password",
+    "secret",
+    "authorization",
+    "token",
+];
+
+/// Whether a session records its HTTP traffic or replays a previous recording.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CassetteMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Cassette {
+    pub version: u32,
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<RecordedResponse>,
+    /// Transport failure observed instead of, or part way through, the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RecordedError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Raw body text. For streamed responses this is the SSE payload as
+    /// received on the wire.
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub streamed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordedError {
+    Timeout,
+    Network { message: String },
+}
+
+impl RecordedError {
+    pub(crate) fn from_transport_error(err: &TransportError) -> Self {
+        match err {
+            TransportError::Timeout => Self::Timeout,
+            TransportError::Network(message) | TransportError::Build(message) => Self::Network {
+                message: message.clone(),
+            },
+            other => Self::Network {
+                message: other.to_string(),
+            },
+        }
+    }
+
+    pub(crate) fn to_transport_error(&self) -> TransportError {
+        match self {
+            Self::Timeout => TransportError::Timeout,
+            Self::Network { message } => TransportError::Network(message.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CassetteHeader {
+    version: u32,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut lines = contents.lines().enumerate();
+        let header = lines
+            .next()
+            .and_then(|(_, line)| serde_json::from_str::<CassetteHeader>(line).ok())
+            .filter(|header| header.version != LEGACY_CASSETTE_VERSION);
+        let Some(header) = header else {
+            return Self::load_legacy(&contents);
+        };
+        if header.version != CASSETTE_VERSION {
+            return Err(invalid(format!(
+                "unsupported cassette version {} (expected {CASSETTE_VERSION})",
+                header.version
+            )));
+        }
+        let line_count = contents.lines().count();
+        let mut interactions = Vec::new();
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(interaction) => interactions.push(interaction),
+                // A recording cut short mid-write loses only its last line.
+                Err(err) if index + 1 == line_count && !contents.ends_with('\n') => {
+                    warn!(
+                        "ignoring truncated last interaction in {}: {err}",
+                        path.display()
+                    );
+                }
+                Err(err) => return Err(invalid(format!("line {}: {err}", index + 1))),
+            }
+        }
+        Ok(Self {
+            version: CASSETTE_VERSION,
+            interactions,
+        })
+    }
+
+    fn load_legacy(contents: &str) -> io::Result<Self> {
+        let cassette: Self = serde_json::from_str(contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if cassette.version != LEGACY_CASSETTE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported cassette version {} (expected {CASSETTE_VERSION})",
+                    cassette.version
+                ),
+            ));
+        }
+        Ok(Self {
+            version: CASSETTE_VERSION,
+            interactions: cassette.interactions,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut file = create_cassette_file(path)?;
+        for interaction in &self.interactions {
+            file.write_all(&interaction_line(interaction)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates (or truncates) `path` and writes the header line.
+fn create_cassette_file(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = File::create(path)?;
+    let mut header = serde_json::to_vec(&CassetteHeader {
+        version: CASSETTE_VERSION,
+    })
+    .map_err(io::Error::other)?;
+    header.push(b'\n');
+    file.write_all(&header)?;
+    Ok(file)
+}
+
+fn interaction_line(interaction: &Interaction) -> io::Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(interaction).map_err(io::Error::other)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Appends interactions to a cassette file as they complete, one line each,
+/// so a crash still leaves every finished interaction on disk.
+#[derive(Debug)]
+pub struct CassetteRecorder {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl CassetteRecorder {
+    pub fn create(path: PathBuf) -> io::Result<Self> {
+        let file = create_cassette_file(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn record(&self, interaction: Interaction) {
+        let written = interaction_line(&interaction).and_then(|line| {
+            let mut file = self
+                .file
+                .lock()
+                .map_err(|_| io::Error::other("cassette lock poisoned"))?;
+            // One write per line keeps concurrent requests from interleaving.
+            file.write_all(&line)?;
+            file.flush()
+        });
+        if let Err(err) = written {
+            warn!("failed to write cassette {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Serves recorded interactions in order. Each request consumes the first
+/// unused interaction with the same method and URL path.
+#[derive(Debug)]
+pub struct CassettePlayer {
+    remaining: Mutex<VecDeque<Interaction>>,
+}
+
+impl CassettePlayer {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            remaining: Mutex::new(cassette.interactions.into()),
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Cassette::load(path).map(Self::new)
+    }
+
+    pub fn take(&self, method: &str, url: &str) -> Option<Interaction> {
+        let mut remaining = self.remaining.lock().ok()?;
+        let path = url_path(url);
+        let index = remaining.iter().position(|interaction| {
+            interaction.request.method.eq_ignore_ascii_case(method)
+                && url_path(&interaction.request.url) == path
+        })?;
+        remaining.remove(index)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.remaining.lock().map(|r| r.len()).unwrap_or_default()
+    }
+}
+
+/// The URL path without origin, query string, or fragment. Replay ignores the
+/// host so a cassette can be served behind a different base URL, and ignores
+/// query parameters because they are partly redacted.
+fn url_path(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |start| &rest[start..]),
+        None => url,
+    }
+}
+
+pub(crate) fn redact_headers(headers: &http::HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.as_str().to_ascii_lowercase();
+            let value = if SENSITIVE_HEADER_MARKERS
+                .iter()
+                .any(|marker| name.contains(marker))
+            {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+pub(crate) fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _))
+                if SENSITIVE_QUERY_PARAMS
+                    .iter()
+                    .any(|param| name.eq_ignore_ascii_case(param)) =>
+            {
+                format!("{name}={REDACTED}")
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{base}?{query}")
+}
+
+pub(crate) fn redact_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if SENSITIVE_JSON_FIELDS
+                        .iter()
+                        .any(|field| key.eq_ignore_ascii_case(field))
+                    {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_json(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_json).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Redacts credentials from a recorded body. JSON bodies (for example token
+/// endpoint responses) are redacted as a whole and SSE bodies line by line;
+/// other bodies are stored verbatim.
+pub(crate) fn redact_body_text(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => {
+            serde_json::to_string(&redact_json(&value)).unwrap_or_else(|_| body.to_string())
+        }
+        _ => body
+            .split_inclusive('\n')
+            .map(redact_sse_line)
+            .collect::<String>(),
+    }
+}
+
+/// Redacts the JSON payload of an SSE `data:` line. Lines without secrets are
+/// kept byte for byte so replayed streams match what was recorded.
+fn redact_sse_line(line: &str) -> String {
+    let content = line.trim_end_matches(['\r', '\n']);
+    let ending = &line[content.len()..];
+    let Some(payload) = content.strip_prefix("data:") else {
+        return line.to_string();
+    };
+    match serde_json::from_str::<Value>(payload.trim_start()) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => {
+            let redacted = redact_json(&value);
+            if redacted == value {
+                return line.to_string();
+            }
+            match serde_json::to_string(&redacted) {
+                Ok(json) => format!("data: {json}{ending}"),
+                Err(_) => line.to_string(),
+            }
+        }
+        _ => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderMap;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_credential_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer sk-123"));
+        headers.insert("api-key", HeaderValue::from_static("abc"));
+        headers.insert("ocp-apim-subscription-key", HeaderValue::from_static("def"));
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+        let redacted = redact_headers(&headers);
+        assert_eq!(redacted["authorization"], REDACTED);
+        assert_eq!(redacted["api-key"], REDACTED);
+        assert_eq!(redacted["ocp-apim-subscription-key"], REDACTED);
+        assert_eq!(redacted["content-type"], "application/json");
+    }
+
+    #[test]
+    fn redacts_credential_query_params() {
+        assert_eq!(
+            redact_url("https://example.com/v1/responses?api-version=2025-04-01&sig=abc"),
+            "https://example.com/v1/responses?api-version=2025-04-01&sig=[REDACTED]"
+        );
+        assert_eq!(
+            redact_url("https://example.com/v1/responses"),
+            "https://example.com/v1/responses"
+        );
+    }
+
+    #[test]
+    fn redacts_secret_json_fields_only() {
+        let body = json!({
+            "model": "gpt-5",
+            "max_output_tokens": 100,
+            "prompt_cache_key": "conv-1",
+            "nested": [{"access_token": "t", "client_secret": "s"}],
+        });
+
+        assert_eq!(
+            redact_json(&body),
+            json!({
+                "model": "gpt-5",
+                "max_output_tokens": 100,
+                "prompt_cache_key": "conv-1",
+                "nested": [{"access_token": REDACTED, "client_secret": REDACTED}],
+            })
+        );
+    }
+
+    #[test]
+    fn redacts_secrets_in_sse_data_lines() {
+        let body = "event: response.created\r\ndata: {\"type\":\"response.created\"}\r\n\r\n\
+event: token\ndata: {\"access_token\":\"secret\"}\n\ndata: [DONE]\n\n";
+
+        assert_eq!(
+            redact_body_text(body),
+            "event: response.created\r\ndata: {\"type\":\"response.created\"}\r\n\r\n\
+event: token\ndata: {\"access_token\":\"[REDACTED]\"}\n\ndata: [DONE]\n\n"
+        );
+    }
+
+    #[test]
+    fn player_serves_interactions_in_order_by_path() {
+        let interaction = |url: &str, body: &str| Interaction {
+            request: RecordedRequest {
+                method: "POST".to_string(),
+                url: url.to_string(),
+                headers: BTreeMap::new(),
+                body: None,
+            },
+            response: Some(RecordedResponse {
+                status: 200,
+                headers: BTreeMap::new(),
+                body: body.to_string(),
+                streamed: true,
+            }),
+            error: None,
+        };
+        let player = CassettePlayer::new(Cassette {
+            version: CASSETTE_VERSION,
+            interactions: vec![
+                interaction(
+                    "https://example.com/v1/responses?api-version=[REDACTED]",
+                    "one",
+                ),
+                interaction("https://example.com/v1/responses/compact", "compact"),
+                interaction("https://example.com/v1/responses", "two"),
+            ],
+        });
+
+        let body = |interaction: Option<Interaction>| {
+            interaction
+                .and_then(|i| i.response)
+                .map(|r| r.body)
+                .unwrap_or_default()
+        };
+        assert_eq!(
+            body(player.take("POST", "https://example.com/v1/responses?api-version=1")),
+            "one"
+        );
+        assert_eq!(
+            body(player.take("POST", "http://127.0.0.1:4000/v1/responses")),
+            "two"
+        );
+        assert_eq!(player.remaining(), 1);
+        assert!(
+            player
+                .take("GET", "https://example.com/v1/models")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn recorder_appends_lines_and_loader_tolerates_a_cut_off_tail() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("session.jsonl");
+        let interaction = |url: &str| Interaction {
+            request: RecordedRequest {
+                method: "POST".to_string(),
+                url: url.to_string(),
+                headers: BTreeMap::new(),
+                body: None,
+            },
+            response: None,
+            error: Some(RecordedError::Timeout),
+        };
+
+        let recorder = CassetteRecorder::create(path.clone()).expect("create");
+        recorder.record(interaction("https://example.com/v1/responses"));
+        recorder.record(interaction("https://example.com/v1/responses/compact"));
+        let contents = std::fs::read_to_string(&path).expect("read");
+        assert_eq!(contents.lines().count(), 3);
+        assert_eq!(contents.lines().next(), Some(r#"{"version":2}"#));
+
+        // A crash part way through the third interaction.
+        std::fs::write(&path, format!("{contents}{{\"request\":{{\"meth")).expect("write");
+        let cassette = Cassette::load(&path).expect("load");
+        assert_eq!(
+            cassette.interactions,
+            vec![
+                interaction("https://example.com/v1/responses"),
+                interaction("https://example.com/v1/responses/compact"),
+            ]
+        );
+
+        let legacy = json!({
+            "version": 1,
+            "interactions": [interaction("https://example.com/v1/responses")],
+        });
+        std::fs::write(&path, serde_json::to_vec_pretty(&legacy).expect("json")).expect("write");
+        assert_eq!(
+            Cassette::load(&path)
+                .expect("load legacy")
+                .interactions
+                .len(),
+            1
+        );
+    }
+}
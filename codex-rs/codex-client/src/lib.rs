@@ -1,15 +1,29 @@
+mod cassette;
 mod default_client;
 mod error;
+mod network;
 mod request;
 mod retry;
 mod sse;
 mod telemetry;
 mod transport;
 
+pub use crate::cassette::CASSETTE_VERSION;
+pub use crate::cassette::Cassette;
+pub use crate::cassette::CassetteMode;
+pub use crate::cassette::CassettePlayer;
+pub use crate::cassette::CassetteRecorder;
+pub use crate::cassette::Interaction;
+pub use crate::cassette::RecordedError;
+pub use crate::cassette::RecordedRequest;
+pub use crate::cassette::RecordedResponse;
 pub use crate::default_client::CodexHttpClient;
 pub use crate::default_client::CodexRequestBuilder;
 pub use crate::error::StreamError;
 pub use crate::error::TransportError;
+pub use crate::network::HttpNetwork;
+pub use crate::network::NetworkConfigError;
+pub use crate::network::NetworkSettings;
 pub use crate::request::Request;
 pub use crate::request::Response;
 pub use crate::retry::RetryOn;
@@ -19,6 +33,10 @@ pub use crate::retry::run_with_retry;
 pub use crate::sse::sse_stream;
 pub use crate::telemetry::RequestTelemetry;
 pub use crate::transport::ByteStream;
+pub use crate::transport::CassetteTransport;
+pub use crate::transport::HttpCassette;
 pub use crate::transport::HttpTransport;
+pub use crate::transport::RecordingTransport;
+pub use crate::transport::ReplayTransport;
 pub use crate::transport::ReqwestTransport;
 pub use crate::transport::StreamResponse;
@@ -0,0 +1,320 @@
+//! Proxy, trust store and client certificate settings shared by every HTTP
+//! client Codex builds.
+
+use reqwest::Certificate;
+use reqwest::ClientBuilder;
+use reqwest::Identity;
+use reqwest::NoProxy;
+use reqwest::Proxy;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// `[network]` settings from `config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSettings {
+    /// Proxy for all HTTP and HTTPS traffic, e.g. `http://proxy.corp:8080`.
+    /// Credentials may be embedded in the URL or given separately below.
+    pub proxy: Option<String>,
+
+    /// Username for proxy basic authentication.
+    pub proxy_username: Option<String>,
+
+    /// Environment variable holding the proxy password, so the secret does not
+    /// live in `config.toml`.
+    pub proxy_password_env_var: Option<String>,
+
+    /// Hosts that bypass `proxy`. Entries follow the `NO_PROXY` convention:
+    /// host names, `.suffix` domains, IP addresses and CIDR ranges.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+
+    /// PEM files with additional root certificates to trust, for example the
+    /// CA used by a TLS-inspecting proxy. System roots remain trusted.
+    #[serde(default)]
+    pub ca_certificates: Vec<PathBuf>,
+
+    /// PEM client certificate presented for mutual TLS. May also contain the
+    /// private key, in which case `client_key` can be omitted.
+    pub client_certificate: Option<PathBuf>,
+
+    /// PEM private key for `client_certificate`.
+    pub client_key: Option<PathBuf>,
+}
+
+#[derive(Debug, Error)]
+pub enum NetworkConfigError {
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid proxy URL `{url}`: {source}")]
+    InvalidProxy { url: String, source: reqwest::Error },
+    #[error("proxy_password_env_var `{0}` is not set")]
+    MissingProxyPassword(String),
+    #[error("invalid CA certificate in {path}: {source}")]
+    InvalidCaCertificate {
+        path: PathBuf,
+        source: reqwest::Error,
+    },
+    #[error("invalid client certificate or key: {0}")]
+    InvalidClientIdentity(reqwest::Error),
+    #[error("client_key is set but client_certificate is not")]
+    ClientKeyWithoutCertificate,
+    #[error("proxy_password_env_var is set but proxy_username is not")]
+    ProxyPasswordWithoutUsername,
+}
+
+/// [`NetworkSettings`] with every file read and parsed, ready to be applied to
+/// any number of client builders.
+#[derive(Debug, Clone, Default)]
+pub struct HttpNetwork {
+    proxy: Option<Proxy>,
+    root_certificates: Vec<Certificate>,
+    identity: Option<Identity>,
+}
+
+impl NetworkSettings {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn load(&self) -> Result<HttpNetwork, NetworkConfigError> {
+        if self.proxy_password_env_var.is_some() && self.proxy_username.is_none() {
+            return Err(NetworkConfigError::ProxyPasswordWithoutUsername);
+        }
+        let proxy = match &self.proxy {
+            Some(url) => {
+                let mut proxy =
+                    Proxy::all(url).map_err(|source| NetworkConfigError::InvalidProxy {
+                        url: url.clone(),
+                        source,
+                    })?;
+                if let Some(username) = &self.proxy_username {
+                    proxy = proxy.basic_auth(username, &self.proxy_password()?);
+                }
+                if !self.no_proxy.is_empty() {
+                    proxy = proxy.no_proxy(NoProxy::from_string(&self.no_proxy.join(",")));
+                }
+                Some(proxy)
+            }
+            None => None,
+        };
+
+        let mut root_certificates = Vec::new();
+        for path in &self.ca_certificates {
+            let pem = read(path)?;
+            let certificates = Certificate::from_pem_bundle(&pem).map_err(|source| {
+                NetworkConfigError::InvalidCaCertificate {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+            root_certificates.extend(certificates);
+        }
+
+        let identity = match (&self.client_certificate, &self.client_key) {
+            (Some(certificate), key) => {
+                let mut pem = read(certificate)?;
+                if let Some(key) = key {
+                    pem.push(b'\n');
+                    pem.extend(read(key)?);
+                }
+                Some(Identity::from_pem(&pem).map_err(NetworkConfigError::InvalidClientIdentity)?)
+            }
+            (None, Some(_)) => return Err(NetworkConfigError::ClientKeyWithoutCertificate),
+            (None, None) => None,
+        };
+
+        Ok(HttpNetwork {
+            proxy,
+            root_certificates,
+            identity,
+        })
+    }
+
+    /// Proxy variables for child processes that make their own HTTP calls
+    /// (for example the Azure CLI), mirroring `proxy` and `no_proxy`. Fails
+    /// like [`NetworkSettings::load`] when the proxy password is unavailable
+    /// rather than handing the child an unauthenticated proxy.
+    pub fn proxy_env_vars(&self) -> Result<Vec<(&'static str, String)>, NetworkConfigError> {
+        let Some(proxy) = &self.proxy else {
+            return Ok(Vec::new());
+        };
+        let proxy = match &self.proxy_username {
+            Some(username) => with_credentials(proxy, username, &self.proxy_password()?),
+            None => proxy.clone(),
+        };
+        let mut vars = vec![("HTTPS_PROXY", proxy.clone()), ("HTTP_PROXY", proxy)];
+        if !self.no_proxy.is_empty() {
+            vars.push(("NO_PROXY", self.no_proxy.join(",")));
+        }
+        Ok(vars)
+    }
+
+    fn proxy_password(&self) -> Result<String, NetworkConfigError> {
+        match &self.proxy_password_env_var {
+            Some(var) => std::env::var(var)
+                .map_err(|_| NetworkConfigError::MissingProxyPassword(var.clone())),
+            None => Ok(String::new()),
+        }
+    }
+}
+
+impl HttpNetwork {
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        if let Some(identity) = &self.identity {
+            // PEM identities are only understood by the rustls backend, which
+            // loads the platform roots through `rustls-tls-native-roots`.
+            builder = builder.use_rustls_tls().identity(identity.clone());
+        }
+        builder
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, NetworkConfigError> {
+    std::fs::read(path).map_err(|source| NetworkConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Inserts `username:password@` after the scheme of a proxy URL.
+fn with_credentials(proxy: &str, username: &str, password: &str) -> String {
+    let encode = |value: &str| {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{b:02X}"),
+            })
+            .collect::<String>()
+    };
+    let credentials = if password.is_empty() {
+        encode(username)
+    } else {
+        format!("{}:{}", encode(username), encode(password))
+    };
+    match proxy.split_once("://") {
+        Some((scheme, rest)) => format!("{scheme}://{credentials}@{rest}"),
+        None => format!("{credentials}@{proxy}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn empty_settings_load_to_a_no_op() {
+        let network = NetworkSettings::default().load().expect("load");
+        assert!(network.proxy.is_none());
+        assert!(network.root_certificates.is_empty());
+        assert!(network.identity.is_none());
+    }
+
+    #[test]
+    fn rejects_client_key_without_certificate() {
+        let settings = NetworkSettings {
+            client_key: Some(PathBuf::from("/tmp/key.pem")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.load(),
+            Err(NetworkConfigError::ClientKeyWithoutCertificate)
+        ));
+    }
+
+    #[test]
+    fn rejects_proxy_password_without_username() {
+        let settings = NetworkSettings {
+            proxy: Some("http://proxy.corp:8080".to_string()),
+            proxy_password_env_var: Some("CODEX_PROXY_PASSWORD".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.load(),
+            Err(NetworkConfigError::ProxyPasswordWithoutUsername)
+        ));
+    }
+
+    #[test]
+    fn reports_missing_ca_file() {
+        let settings = NetworkSettings {
+            ca_certificates: vec![PathBuf::from("/nonexistent/codex-ca.pem")],
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.load(),
+            Err(NetworkConfigError::Read { .. })
+        ));
+    }
+
+    #[test]
+    fn proxy_env_vars_embed_encoded_credentials() {
+        let settings = NetworkSettings {
+            proxy: Some("http://proxy.corp:8080".to_string()),
+            proxy_username: Some("svc codex".to_string()),
+            no_proxy: vec!["localhost".to_string(), ".corp.example.com".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.proxy_env_vars().expect("proxy env vars"),
+            vec![
+                (
+                    "HTTPS_PROXY",
+                    "http://svc%20codex@proxy.corp:8080".to_string()
+                ),
+                (
+                    "HTTP_PROXY",
+                    "http://svc%20codex@proxy.corp:8080".to_string()
+                ),
+                ("NO_PROXY", "localhost,.corp.example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn proxy_env_vars_require_the_proxy_password() {
+        let settings = NetworkSettings {
+            proxy: Some("http://proxy.corp:8080".to_string()),
+            proxy_username: Some("svc".to_string()),
+            proxy_password_env_var: Some("CODEX_TEST_UNSET_PROXY_PASSWORD".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.proxy_env_vars(),
+            Err(NetworkConfigError::MissingProxyPassword(var))
+                if var == "CODEX_TEST_UNSET_PROXY_PASSWORD"
+        ));
+    }
+
+    #[test]
+    fn parses_network_table() {
+        let settings: NetworkSettings = serde_json::from_value(serde_json::json!({
+            "proxy": "http://proxy.corp:8080",
+            "no_proxy": ["localhost"],
+            "ca_certificates": ["/etc/ssl/corp-root.pem"],
+        }))
+        .expect("parse");
+        assert_eq!(settings.proxy.as_deref(), Some("http://proxy.corp:8080"));
+        assert_eq!(
+            settings.ca_certificates,
+            vec![PathBuf::from("/etc/ssl/corp-root.pem")]
+        );
+        assert!(!settings.is_empty());
+    }
+}
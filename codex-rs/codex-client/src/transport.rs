@@ -1,3 +1,14 @@
+use crate::cassette::CassetteMode;
+use crate::cassette::CassettePlayer;
+use crate::cassette::CassetteRecorder;
+use crate::cassette::Interaction;
+use crate::cassette::RecordedError;
+use crate::cassette::RecordedRequest;
+use crate::cassette::RecordedResponse;
+use crate::cassette::redact_body_text;
+use crate::cassette::redact_headers;
+use crate::cassette::redact_json;
+use crate::cassette::redact_url;
 use crate::default_client::CodexHttpClient;
 use crate::default_client::CodexRequestBuilder;
 use crate::error::TransportError;
@@ -8,8 +19,12 @@ use bytes::Bytes;
 use futures::StreamExt;
 use futures::stream::BoxStream;
 use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
 use http::Method;
 use http::StatusCode;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use tracing::Level;
 use tracing::enabled;
 use tracing::trace;
@@ -121,3 +136,389 @@ impl HttpTransport for ReqwestTransport {
         })
     }
 }
+
+/// Wraps another transport and records every exchange into a cassette.
+#[derive(Clone, Debug)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    recorder: Arc<CassetteRecorder>,
+}
+
+impl<T: HttpTransport> RecordingTransport<T> {
+    pub fn new(inner: T, recorder: Arc<CassetteRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+
+    fn recorded_request(req: &Request) -> RecordedRequest {
+        RecordedRequest {
+            method: req.method.to_string(),
+            url: redact_url(&req.url),
+            headers: redact_headers(&req.headers),
+            body: req.body.as_ref().map(redact_json),
+        }
+    }
+
+    fn record_error(&self, request: RecordedRequest, err: &TransportError) {
+        let interaction = match err {
+            TransportError::Http {
+                status,
+                headers,
+                body,
+            } => Interaction {
+                request,
+                response: Some(RecordedResponse {
+                    status: status.as_u16(),
+                    headers: headers.as_ref().map(redact_headers).unwrap_or_default(),
+                    body: body.as_deref().map(redact_body_text).unwrap_or_default(),
+                    streamed: false,
+                }),
+                error: None,
+            },
+            other => Interaction {
+                request,
+                response: None,
+                error: Some(RecordedError::from_transport_error(other)),
+            },
+        };
+        self.recorder.record(interaction);
+    }
+}
+
+#[async_trait]
+impl<T: HttpTransport> HttpTransport for RecordingTransport<T> {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        let request = Self::recorded_request(&req);
+        match self.inner.execute(req).await {
+            Ok(resp) => {
+                self.recorder.record(Interaction {
+                    request,
+                    response: Some(RecordedResponse {
+                        status: resp.status.as_u16(),
+                        headers: redact_headers(&resp.headers),
+                        body: redact_body_text(&String::from_utf8_lossy(&resp.body)),
+                        streamed: false,
+                    }),
+                    error: None,
+                });
+                Ok(resp)
+            }
+            Err(err) => {
+                self.record_error(request, &err);
+                Err(err)
+            }
+        }
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        let request = Self::recorded_request(&req);
+        match self.inner.stream(req).await {
+            Ok(resp) => {
+                let headers = redact_headers(&resp.headers);
+                let status = resp.status.as_u16();
+                let mut tee = RecordingStream {
+                    recorder: Arc::clone(&self.recorder),
+                    request: Some(request),
+                    status,
+                    headers,
+                    body: Vec::new(),
+                    error: None,
+                };
+                let bytes = resp
+                    .bytes
+                    .map(move |chunk| {
+                        match &chunk {
+                            Ok(bytes) => tee.body.extend_from_slice(bytes),
+                            Err(err) => tee.error = Some(RecordedError::from_transport_error(err)),
+                        }
+                        chunk
+                    })
+                    .boxed();
+                Ok(StreamResponse {
+                    status: resp.status,
+                    headers: resp.headers,
+                    bytes,
+                })
+            }
+            Err(err) => {
+                self.record_error(request, &err);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Accumulates a streamed body and records it once the stream is dropped,
+/// whether it completed, failed, or was abandoned by the consumer.
+struct RecordingStream {
+    recorder: Arc<CassetteRecorder>,
+    request: Option<RecordedRequest>,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+    error: Option<RecordedError>,
+}
+
+impl Drop for RecordingStream {
+    fn drop(&mut self) {
+        let Some(request) = self.request.take() else {
+            return;
+        };
+        self.recorder.record(Interaction {
+            request,
+            response: Some(RecordedResponse {
+                status: self.status,
+                headers: std::mem::take(&mut self.headers),
+                body: redact_body_text(&String::from_utf8_lossy(&self.body)),
+                streamed: true,
+            }),
+            error: self.error.take(),
+        });
+    }
+}
+
+/// Serves responses from a cassette instead of the network.
+#[derive(Clone, Debug)]
+pub struct ReplayTransport {
+    player: Arc<CassettePlayer>,
+}
+
+impl ReplayTransport {
+    pub fn new(player: Arc<CassettePlayer>) -> Self {
+        Self { player }
+    }
+
+    /// Returns the next recorded response for `req`, plus any transport error
+    /// that interrupted it mid-stream.
+    fn next(&self, req: &Request) -> Result<(Response, Option<RecordedError>), TransportError> {
+        let method = req.method.as_str();
+        let Some(interaction) = self.player.take(method, &req.url) else {
+            return Err(TransportError::Network(format!(
+                "no recorded interaction left for {method} {}",
+                redact_url(&req.url)
+            )));
+        };
+        let Some(response) = interaction.response else {
+            return Err(interaction.error.map_or_else(
+                || TransportError::Network("recorded request has no response".to_string()),
+                |err| err.to_transport_error(),
+            ));
+        };
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|err| TransportError::Build(err.to_string()))?;
+        let headers = header_map(&response.headers);
+        if !status.is_success() {
+            return Err(TransportError::Http {
+                status,
+                headers: Some(headers),
+                body: Some(response.body),
+            });
+        }
+        let response = Response {
+            status,
+            headers,
+            body: Bytes::from(response.body),
+        };
+        Ok((response, interaction.error))
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReplayTransport {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        let (response, error) = self.next(&req)?;
+        match error {
+            Some(err) => Err(err.to_transport_error()),
+            None => Ok(response),
+        }
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        let (response, error) = self.next(&req)?;
+        // Re-chunk on SSE event boundaries so parsers see events arrive one at
+        // a time, as they did on the wire.
+        let mut chunks: Vec<Result<Bytes, TransportError>> =
+            String::from_utf8_lossy(&response.body)
+                .split_inclusive("\n\n")
+                .map(|event| Ok(Bytes::from(event.to_string())))
+                .collect();
+        if let Some(err) = error {
+            chunks.push(Err(err.to_transport_error()));
+        }
+        Ok(StreamResponse {
+            status: response.status,
+            headers: response.headers,
+            bytes: futures::stream::iter(chunks).boxed(),
+        })
+    }
+}
+
+fn header_map(headers: &BTreeMap<String, String>) -> HeaderMap {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((
+                HeaderName::from_bytes(name.as_bytes()).ok()?,
+                HeaderValue::from_str(value).ok()?,
+            ))
+        })
+        .collect()
+}
+
+/// Opens cassettes and hands out transports that share them, so every request
+/// of a session appends to (or replays from) the same recording.
+#[derive(Clone, Debug)]
+pub enum HttpCassette {
+    Record(Arc<CassetteRecorder>),
+    Replay(Arc<CassettePlayer>),
+}
+
+impl HttpCassette {
+    pub fn open(mode: &CassetteMode) -> std::io::Result<Self> {
+        match mode {
+            CassetteMode::Record(path) => {
+                CassetteRecorder::create(path.clone()).map(|r| Self::Record(Arc::new(r)))
+            }
+            CassetteMode::Replay(path) => {
+                CassettePlayer::load(path).map(|p| Self::Replay(Arc::new(p)))
+            }
+        }
+    }
+
+    pub fn wrap<T: HttpTransport>(&self, inner: T) -> CassetteTransport<T> {
+        match self {
+            Self::Record(recorder) => {
+                CassetteTransport::Record(RecordingTransport::new(inner, Arc::clone(recorder)))
+            }
+            Self::Replay(player) => {
+                CassetteTransport::Replay(ReplayTransport::new(Arc::clone(player)))
+            }
+        }
+    }
+}
+
+/// A transport that is live, recording, or replaying depending on how the
+/// session was started.
+#[derive(Clone, Debug)]
+pub enum CassetteTransport<T> {
+    Live(T),
+    Record(RecordingTransport<T>),
+    Replay(ReplayTransport),
+}
+
+#[async_trait]
+impl<T: HttpTransport> HttpTransport for CassetteTransport<T> {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        match self {
+            Self::Live(inner) => inner.execute(req).await,
+            Self::Record(inner) => inner.execute(req).await,
+            Self::Replay(inner) => inner.execute(req).await,
+        }
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        match self {
+            Self::Live(inner) => inner.stream(req).await,
+            Self::Record(inner) => inner.stream(req).await,
+            Self::Replay(inner) => inner.stream(req).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cassette::Cassette;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const SSE_BODY: &str =
+        "data: {\"type\":\"response.created\"}\n\ndata: {\"type\":\"response.completed\"}\n\n";
+
+    struct FakeTransport;
+
+    #[async_trait]
+    impl HttpTransport for FakeTransport {
+        async fn execute(&self, _req: Request) -> Result<Response, TransportError> {
+            Err(TransportError::Timeout)
+        }
+
+        async fn stream(&self, _req: Request) -> Result<StreamResponse, TransportError> {
+            let mut headers = HeaderMap::new();
+            headers.insert("set-cookie", HeaderValue::from_static("session=abc"));
+            headers.insert("x-request-id", HeaderValue::from_static("req-1"));
+            let chunks = SSE_BODY
+                .split_inclusive("\n\n")
+                .map(|event| Ok(Bytes::from(event.to_string())))
+                .collect::<Vec<_>>();
+            Ok(StreamResponse {
+                status: StatusCode::OK,
+                headers,
+                bytes: futures::stream::iter(chunks).boxed(),
+            })
+        }
+    }
+
+    fn request() -> Request {
+        let mut req = Request::new(
+            Method::POST,
+            "https://example.com/v1/responses?api-version=1&key=secret".to_string(),
+        )
+        .with_json(&json!({"model": "gpt-5", "api_key": "sk-123"}));
+        req.headers
+            .insert("authorization", HeaderValue::from_static("Bearer sk-123"));
+        req
+    }
+
+    async fn drain(resp: StreamResponse) -> Vec<Result<Bytes, String>> {
+        resp.bytes
+            .map(|chunk| chunk.map_err(|err| err.to_string()))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn records_redacted_session_and_replays_it() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("session.json");
+
+        let recorder = HttpCassette::open(&CassetteMode::Record(path.clone())).expect("record");
+        let transport = recorder.wrap(FakeTransport);
+        let live = drain(transport.stream(request()).await.expect("stream")).await;
+        assert!(matches!(
+            transport.execute(request()).await,
+            Err(TransportError::Timeout)
+        ));
+
+        let cassette = Cassette::load(&path).expect("load cassette");
+        assert_eq!(cassette.interactions.len(), 2);
+        let recorded = &cassette.interactions[0];
+        assert_eq!(
+            recorded.request.url,
+            "https://example.com/v1/responses?api-version=1&key=[REDACTED]"
+        );
+        assert_eq!(recorded.request.headers["authorization"], "[REDACTED]");
+        assert_eq!(
+            recorded.request.body,
+            Some(json!({"model": "gpt-5", "api_key": "[REDACTED]"}))
+        );
+        let response = recorded.response.as_ref().expect("response");
+        assert_eq!(response.body, SSE_BODY);
+        assert_eq!(response.headers["set-cookie"], "[REDACTED]");
+        assert_eq!(response.headers["x-request-id"], "req-1");
+        let serialized = std::fs::read_to_string(&path).expect("read cassette");
+        assert!(!serialized.contains("sk-123"));
+
+        let replay = HttpCassette::open(&CassetteMode::Replay(path)).expect("replay");
+        let transport = replay.wrap(FakeTransport);
+        let replayed = drain(transport.stream(request()).await.expect("stream")).await;
+        assert_eq!(replayed, live);
+        assert!(matches!(
+            transport.execute(request()).await,
+            Err(TransportError::Timeout)
+        ));
+        assert!(matches!(
+            transport.stream(request()).await,
+            Err(TransportError::Network(_))
+        ));
+    }
+}
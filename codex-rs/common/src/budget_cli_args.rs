@@ -0,0 +1,108 @@
+//! `--max-*` flags that set the `[budget]` config table from the command line.
+
+use clap::Args;
+
+/// Session budget flags shared by the interactive and non-interactive CLIs.
+/// Each flag becomes a `budget.*` config override.
+#[derive(Args, Debug, Default, Clone)]
+pub struct BudgetCliArgs {
+    /// Stop the session after this many input tokens.
+    #[arg(long = "max-input-tokens", value_name = "TOKENS")]
+    pub max_input_tokens: Option<u64>,
+
+    /// Stop the session after this many output tokens.
+    #[arg(long = "max-output-tokens", value_name = "TOKENS")]
+    pub max_output_tokens: Option<u64>,
+
+    /// Stop the session once its estimated cost reaches this many US dollars.
+    #[arg(long = "max-cost", value_name = "USD")]
+    pub max_cost_usd: Option<f64>,
+
+    /// Refuse tool calls beyond this many in the session.
+    #[arg(long = "max-tool-calls", value_name = "N")]
+    pub max_tool_calls: Option<u64>,
+
+    /// Refuse turns beyond this many in the session.
+    #[arg(long = "max-turns", value_name = "N")]
+    pub max_turns: Option<u64>,
+
+    /// Stop the session after this much wall-clock time, e.g. `90`, `30m` or
+    /// `2h` (plain numbers are seconds).
+    #[arg(long = "max-duration", value_name = "DURATION", value_parser = parse_duration_secs)]
+    pub max_duration_secs: Option<u64>,
+}
+
+impl BudgetCliArgs {
+    /// Returns the flags as raw `key=value` overrides suitable for
+    /// [`crate::CliConfigOverrides::raw_overrides`].
+    pub fn to_raw_overrides(&self) -> Vec<String> {
+        let mut overrides = Vec::new();
+        let integers = [
+            ("max_input_tokens", self.max_input_tokens),
+            ("max_output_tokens", self.max_output_tokens),
+            ("max_tool_calls", self.max_tool_calls),
+            ("max_turns", self.max_turns),
+            ("max_duration_secs", self.max_duration_secs),
+        ];
+        for (key, value) in integers {
+            if let Some(value) = value {
+                overrides.push(format!("budget.{key}={value}"));
+            }
+        }
+        if let Some(max_cost_usd) = self.max_cost_usd {
+            // Always include a decimal point so the value parses as a TOML float.
+            overrides.push(format!("budget.max_cost_usd={max_cost_usd:?}"));
+        }
+        overrides
+    }
+}
+
+fn parse_duration_secs(raw: &str) -> Result<u64, String> {
+    let raw = raw.trim();
+    let (number, multiplier) = match raw.char_indices().last() {
+        Some((idx, 's')) => (&raw[..idx], 1),
+        Some((idx, 'm')) => (&raw[..idx], 60),
+        Some((idx, 'h')) => (&raw[..idx], 60 * 60),
+        _ => (raw, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid duration `{raw}`; expected e.g. `90`, `30m` or `2h`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_duration_suffixes() {
+        assert_eq!(parse_duration_secs("90"), Ok(90));
+        assert_eq!(parse_duration_secs("45s"), Ok(45));
+        assert_eq!(parse_duration_secs("30m"), Ok(1800));
+        assert_eq!(parse_duration_secs("2h"), Ok(7200));
+        assert!(parse_duration_secs("soon").is_err());
+        assert!(parse_duration_secs("-5m").is_err());
+    }
+
+    #[test]
+    fn converts_flags_to_overrides() {
+        let args = BudgetCliArgs {
+            max_turns: Some(5),
+            max_cost_usd: Some(2.0),
+            max_duration_secs: Some(600),
+            ..Default::default()
+        };
+        assert_eq!(
+            args.to_raw_overrides(),
+            vec![
+                "budget.max_turns=5".to_string(),
+                "budget.max_duration_secs=600".to_string(),
+                "budget.max_cost_usd=2.0".to_string(),
+            ]
+        );
+    }
+}
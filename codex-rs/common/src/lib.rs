@@ -19,6 +19,12 @@ pub use sandbox_mode_cli_arg::SandboxModeCliArg;
 #[cfg(feature = "cli")]
 pub mod format_env_display;
 
+#[cfg(feature = "cli")]
+mod budget_cli_args;
+
+#[cfg(feature = "cli")]
+pub use budget_cli_args::BudgetCliArgs;
+
 #[cfg(any(feature = "cli", test))]
 mod config_override;
 
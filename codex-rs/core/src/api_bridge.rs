@@ -6,10 +6,13 @@ use codex_api::TransportError;
 use codex_api::error::ApiError;
 use codex_api::rate_limits::parse_rate_limit;
 use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
 use serde::Deserialize;
 
 use crate::auth::azure::AzureAuth;
 use crate::error::CodexErr;
+use crate::error::EnvVarError;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
@@ -109,10 +112,26 @@ fn extract_request_id(headers: Option<&HeaderMap>) -> Option<String> {
 /// 2. Experimental bearer token from provider config
 /// 3. Azure Entra ID authentication (DefaultAzureCredential, etc.)
 /// 4. CodexAuth API key (from AZURE_CODEX_API_KEY env var)
+///
+/// Headers from `auth_headers` / `apim` are sent in addition to whichever
+/// credential wins, e.g. an APIM subscription key next to an Entra ID token.
 pub(crate) async fn auth_provider_from_auth(
     azure_auth: Option<&AzureAuth>,
     provider: &ModelProviderInfo,
     codex_auth: Option<&crate::auth::CodexAuth>,
+) -> crate::error::Result<CoreAuthProvider> {
+    let extra_headers = resolve_extra_auth_headers(azure_auth, provider).await?;
+    let mut auth =
+        primary_auth_provider(azure_auth, provider, codex_auth, !extra_headers.is_empty()).await?;
+    auth.extra_headers = extra_headers;
+    Ok(auth)
+}
+
+async fn primary_auth_provider(
+    azure_auth: Option<&AzureAuth>,
+    provider: &ModelProviderInfo,
+    codex_auth: Option<&crate::auth::CodexAuth>,
+    has_extra_headers: bool,
 ) -> crate::error::Result<CoreAuthProvider> {
     // Determine the effective auth header type for this provider
     let auth_header_type = provider.effective_auth_header_type();
@@ -129,6 +148,7 @@ pub(crate) async fn auth_provider_from_auth(
             account_id: None,
             auth_header_type,
             is_azure: true,
+            extra_headers: HeaderMap::new(),
         });
     }
 
@@ -139,6 +159,7 @@ pub(crate) async fn auth_provider_from_auth(
             account_id: None,
             auth_header_type,
             is_azure: true,
+            extra_headers: HeaderMap::new(),
         });
     }
 
@@ -153,6 +174,7 @@ pub(crate) async fn auth_provider_from_auth(
                     // Azure OpenAI uses Bearer tokens for Entra ID auth
                     auth_header_type: AuthHeaderType::Bearer,
                     is_azure: true,
+                    extra_headers: HeaderMap::new(),
                 });
             }
             Err(e) => {
@@ -176,6 +198,7 @@ pub(crate) async fn auth_provider_from_auth(
                     account_id: None,
                     auth_header_type: AuthHeaderType::Bearer,
                     is_azure: false,
+                    extra_headers: HeaderMap::new(),
                 });
             }
             Err(e) => {
@@ -185,8 +208,9 @@ pub(crate) async fn auth_provider_from_auth(
         }
     }
 
-    // No authentication configured - return error for Azure endpoints
-    if is_azure {
+    // No authentication configured - return error for Azure endpoints, unless
+    // an extra header (e.g. an APIM subscription key) authenticates on its own.
+    if is_azure && !has_extra_headers {
         return Err(crate::error::CodexErr::Authentication(
             "Azure authentication required but not configured. \
              Set AZURE_OPENAI_API_KEY or configure azure_auth in config.toml"
@@ -199,10 +223,59 @@ pub(crate) async fn auth_provider_from_auth(
         token: None,
         account_id: None,
         auth_header_type,
-        is_azure: false,
+        is_azure,
+        extra_headers: HeaderMap::new(),
     })
 }
 
+/// Resolves `auth_headers` (and the APIM subscription key) from environment
+/// variables or Azure Key Vault.
+async fn resolve_extra_auth_headers(
+    azure_auth: Option<&AzureAuth>,
+    provider: &ModelProviderInfo,
+) -> crate::error::Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for source in provider.extra_auth_headers() {
+        let value = if let Some(value) = source.env_var.as_deref().and_then(env_var_value) {
+            value
+        } else if let Some(secret_url) = &source.key_vault_secret {
+            crate::auth::key_vault::get_secret(secret_url, azure_auth)
+                .await
+                .map_err(|e| {
+                    CodexErr::Authentication(format!(
+                        "failed to read `{}` from Key Vault: {e}",
+                        source.header
+                    ))
+                })?
+        } else if let Some(env_var) = &source.env_var {
+            return Err(CodexErr::EnvVar(EnvVarError {
+                var: env_var.clone(),
+                instructions: Some(format!(
+                    "Set {env_var} to the value of the `{}` header.",
+                    source.header
+                )),
+            }));
+        } else {
+            return Err(CodexErr::Authentication(format!(
+                "auth header `{}` needs `env_var` or `key_vault_secret`",
+                source.header
+            )));
+        };
+        let name = HeaderName::try_from(source.header.as_str()).map_err(|e| {
+            CodexErr::Authentication(format!("invalid auth header `{}`: {e}", source.header))
+        })?;
+        let value = HeaderValue::try_from(value).map_err(|e| {
+            CodexErr::Authentication(format!("invalid value for `{}`: {e}", source.header))
+        })?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+fn env_var_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
 #[derive(Debug, Deserialize)]
 struct UsageErrorResponse {
     error: UsageErrorBody,
@@ -222,6 +295,7 @@ pub(crate) struct CoreAuthProvider {
     account_id: Option<String>,
     auth_header_type: AuthHeaderType,
     is_azure: bool,
+    extra_headers: HeaderMap,
 }
 
 impl Default for CoreAuthProvider {
@@ -231,6 +305,7 @@ impl Default for CoreAuthProvider {
             account_id: None,
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
+            extra_headers: HeaderMap::new(),
         }
     }
 }
@@ -255,4 +330,8 @@ impl ApiAuthProvider for CoreAuthProvider {
     fn is_azure(&self) -> bool {
         self.is_azure
     }
+
+    fn extra_headers(&self) -> HeaderMap {
+        self.extra_headers.clone()
+    }
 }
@@ -5,6 +5,7 @@ use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use crate::tools::sandboxing::PLAN_MODE_REJECTION;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
 use std::collections::HashMap;
@@ -39,6 +40,11 @@ pub(crate) async fn apply_patch(
     call_id: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    if turn_context.plan_mode {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            PLAN_MODE_REJECTION.to_string(),
+        )));
+    }
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
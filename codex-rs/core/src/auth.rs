@@ -1,5 +1,6 @@
 pub mod azure;
 pub mod azure_config;
+pub mod key_vault;
 mod storage;
 
 // Re-export Azure auth types for convenience
@@ -52,6 +52,9 @@ pub enum AzureAuthError {
 
     #[error("Azure CLI authentication failed: {0}")]
     AzureCliError(String),
+
+    #[error("Key Vault request failed: {0}")]
+    KeyVault(String),
 }
 
 /// A cached token with its expiration time.
@@ -133,10 +136,11 @@ impl AzureAuth {
         Self {
             config,
             cached_token: Arc::new(RwLock::new(None)),
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(30))
-                .build()
-                .expect("Failed to create HTTP client"),
+            client: crate::default_client::apply_network_settings(
+                reqwest::Client::builder().timeout(Duration::from_secs(30)),
+            )
+            .build()
+            .expect("Failed to create HTTP client"),
         }
     }
 
@@ -351,6 +355,12 @@ impl AzureAuth {
     async fn acquire_token_azure_cli(&self) -> Result<String, AzureAuthError> {
         let scope = &self.config.scope;
 
+        let proxy_env = crate::default_client::network_settings()
+            .proxy_env_vars()
+            .map_err(|e| {
+                AzureAuthError::AzureCliError(format!("invalid [network] configuration: {e}"))
+            })?;
+
         // Use az account get-access-token
         // On Windows, we need to use cmd.exe to run az.cmd since it's a batch script
         #[cfg(windows)]
@@ -365,6 +375,7 @@ impl AzureAuth {
                 "--output",
                 "json",
             ])
+            .envs(proxy_env)
             .output()
             .await
             .map_err(|e| AzureAuthError::AzureCliError(format!("Failed to run az CLI: {e}")))?;
@@ -379,6 +390,7 @@ impl AzureAuth {
                 "--output",
                 "json",
             ])
+            .envs(proxy_env)
             .output()
             .await
             .map_err(|e| AzureAuthError::AzureCliError(format!("Failed to run az CLI: {e}")))?;
@@ -0,0 +1,133 @@
+//! Azure Key Vault secret lookup for provider credential headers.
+//!
+//! Secrets are read once per process with the configured Azure Entra ID
+//! credentials (re-scoped to Key Vault) and cached, since model clients are
+//! rebuilt for every turn.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use tracing::debug;
+use url::Url;
+
+use crate::auth::azure::AzureAuth;
+use crate::auth::azure::AzureAuthError;
+use crate::auth::azure_config::AzureAuthConfig;
+
+/// Key Vault REST API version used for `GET /secrets/{name}`.
+const KEY_VAULT_API_VERSION: &str = "7.4";
+
+static SECRETS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Deserialize)]
+struct SecretBundle {
+    value: String,
+}
+
+/// Reads a secret such as `https://myvault.vault.azure.net/secrets/apim-key`.
+pub async fn get_secret(
+    secret_url: &str,
+    azure_auth: Option<&AzureAuth>,
+) -> Result<String, AzureAuthError> {
+    if let Some(value) = cached_secret(secret_url) {
+        return Ok(value);
+    }
+
+    let (url, scope) = parse_secret_url(secret_url)?;
+    let config = AzureAuthConfig {
+        scope,
+        ..azure_auth
+            .map(|auth| auth.config().clone())
+            .unwrap_or_default()
+    };
+    let token = AzureAuth::new(config).get_token().await?;
+
+    debug!("Reading Key Vault secret {}", url.path());
+    let response = crate::default_client::build_reqwest_client()
+        .get(url)
+        .bearer_auth(token)
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(AzureAuthError::KeyVault(format!(
+            "reading {secret_url} returned {status}: {body}"
+        )));
+    }
+    let bundle: SecretBundle = response.json().await?;
+
+    let mut secrets = match SECRETS.lock() {
+        Ok(secrets) => secrets,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    secrets.insert(secret_url.to_string(), bundle.value.clone());
+    Ok(bundle.value)
+}
+
+fn cached_secret(secret_url: &str) -> Option<String> {
+    match SECRETS.lock() {
+        Ok(secrets) => secrets.get(secret_url).cloned(),
+        Err(poisoned) => poisoned.into_inner().get(secret_url).cloned(),
+    }
+}
+
+/// Returns the request URL (with `api-version`) and the token scope for the
+/// vault's cloud, e.g. `https://vault.azure.net/.default`.
+fn parse_secret_url(secret_url: &str) -> Result<(Url, String), AzureAuthError> {
+    let invalid = || {
+        AzureAuthError::InvalidConfiguration(format!(
+            "`{secret_url}` is not a Key Vault secret URL \
+             (expected https://<vault>.vault.azure.net/secrets/<name>)"
+        ))
+    };
+    let mut url = Url::parse(secret_url).map_err(|_| invalid())?;
+    let vault_domain = url
+        .host_str()
+        .and_then(|host| host.split_once('.'))
+        .map(|(_, domain)| domain.to_string())
+        .filter(|domain| domain.starts_with("vault."))
+        .ok_or_else(invalid)?;
+    if url.scheme() != "https" || !url.path().starts_with("/secrets/") {
+        return Err(invalid());
+    }
+    url.query_pairs_mut()
+        .append_pair("api-version", KEY_VAULT_API_VERSION);
+    Ok((url, format!("https://{vault_domain}/.default")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_public_and_sovereign_cloud_secret_urls() {
+        let (url, scope) =
+            parse_secret_url("https://contoso.vault.azure.net/secrets/apim-key").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://contoso.vault.azure.net/secrets/apim-key?api-version=7.4"
+        );
+        assert_eq!(scope, "https://vault.azure.net/.default");
+
+        let (_, scope) =
+            parse_secret_url("https://contoso.vault.azure.cn/secrets/apim-key/abc123").unwrap();
+        assert_eq!(scope, "https://vault.azure.cn/.default");
+    }
+
+    #[test]
+    fn rejects_non_secret_urls() {
+        for url in [
+            "not a url",
+            "http://contoso.vault.azure.net/secrets/apim-key",
+            "https://contoso.vault.azure.net/keys/apim-key",
+            "https://example.com/secrets/apim-key",
+        ] {
+            assert!(parse_secret_url(url).is_err(), "{url} should be rejected");
+        }
+    }
+}
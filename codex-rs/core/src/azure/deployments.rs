@@ -3,16 +3,70 @@
 //! This module provides functionality to discover and list Azure OpenAI
 //! deployments using the Azure CLI.
 
+use codex_api::model_name_matches;
 use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::openai_models::ReasoningEffortPreset;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::LazyLock;
 use tokio::sync::RwLock;
 use tracing::debug;
 use tracing::error;
 use tracing::warn;
 
+use crate::model_provider_info::WireApi;
+
+/// Preferred wire API of every deployment discovered in this process, keyed by
+/// lowercase deployment name. Lets `ModelClient` route custom-named
+/// deployments (e.g. `my-mistral`) to the right API.
+static DISCOVERED_WIRE_APIS: LazyLock<std::sync::RwLock<HashMap<String, WireApi>>> =
+    LazyLock::new(|| std::sync::RwLock::new(HashMap::new()));
+
+/// Returns the wire API recorded for a previously discovered deployment.
+pub(crate) fn discovered_wire_api(deployment: &str) -> Option<WireApi> {
+    DISCOVERED_WIRE_APIS
+        .read()
+        .ok()?
+        .get(&deployment.to_ascii_lowercase())
+        .copied()
+}
+
+/// Underlying model of every deployment discovered in this process, keyed by
+/// lowercase deployment name. Lets custom-named deployments be priced as the
+/// model they serve.
+static DISCOVERED_MODELS: LazyLock<std::sync::RwLock<HashMap<String, String>>> =
+    LazyLock::new(|| std::sync::RwLock::new(HashMap::new()));
+
+/// Returns the underlying model recorded for a previously discovered
+/// deployment.
+pub(crate) fn discovered_model(deployment: &str) -> Option<String> {
+    DISCOVERED_MODELS
+        .read()
+        .ok()?
+        .get(&deployment.to_ascii_lowercase())
+        .cloned()
+}
+
+pub(crate) fn record_discovered_deployments(deployments: &[AzureDeployment]) {
+    if let Ok(mut map) = DISCOVERED_WIRE_APIS.write() {
+        for deployment in deployments {
+            map.insert(
+                deployment.name.to_ascii_lowercase(),
+                deployment.preferred_wire_api(),
+            );
+        }
+    }
+    if let Ok(mut map) = DISCOVERED_MODELS.write() {
+        for deployment in deployments {
+            if let Some(model) = deployment.underlying_model_name() {
+                map.insert(deployment.name.to_ascii_lowercase(), model.to_string());
+            }
+        }
+    }
+}
+
 /// Information about an Azure OpenAI deployment.
 #[derive(Debug, Clone, Deserialize)]
 pub struct AzureDeployment {
@@ -131,6 +185,14 @@ impl AzureDeploymentsManager {
 
         debug!("Discovering resource group for account: {}", account_name);
 
+        let proxy_env = match crate::default_client::network_settings().proxy_env_vars() {
+            Ok(vars) => vars,
+            Err(e) => {
+                error!("Invalid [network] configuration: {}", e);
+                return None;
+            }
+        };
+
         // Use Azure CLI to find the resource group
         #[cfg(windows)]
         let output = tokio::process::Command::new("cmd")
@@ -145,6 +207,7 @@ impl AzureDeploymentsManager {
                 "-o",
                 "tsv",
             ])
+            .envs(proxy_env)
             .output()
             .await;
 
@@ -159,6 +222,7 @@ impl AzureDeploymentsManager {
                 "-o",
                 "tsv",
             ])
+            .envs(proxy_env)
             .output()
             .await;
 
@@ -194,6 +258,14 @@ impl AzureDeploymentsManager {
             account_name, resource_group
         );
 
+        let proxy_env = match crate::default_client::network_settings().proxy_env_vars() {
+            Ok(vars) => vars,
+            Err(e) => {
+                error!("Invalid [network] configuration: {}", e);
+                return Vec::new();
+            }
+        };
+
         #[cfg(windows)]
         let output = tokio::process::Command::new("cmd")
             .args([
@@ -210,6 +282,7 @@ impl AzureDeploymentsManager {
                 "-o",
                 "json",
             ])
+            .envs(proxy_env)
             .output()
             .await;
 
@@ -227,6 +300,7 @@ impl AzureDeploymentsManager {
                 "-o",
                 "json",
             ])
+            .envs(proxy_env)
             .output()
             .await;
 
@@ -290,6 +364,7 @@ impl AzureDeploymentsManager {
         let deployments = self
             .list_deployments_from_azure(&account_name, &resource_group)
             .await;
+        record_discovered_deployments(&deployments);
 
         *self.deployments.write().await = deployments.clone();
         deployments
@@ -304,7 +379,8 @@ impl AzureDeploymentsManager {
         self.refresh_deployments().await
     }
 
-    /// Get only GPT model deployments (filtered by name starting with "gpt").
+    /// Get chat-capable model deployments (GPT, Claude and Azure AI Model
+    /// Inference models such as Mistral, Llama, DeepSeek and Phi).
     pub async fn get_gpt_deployments(&self) -> Vec<AzureDeployment> {
         self.get_deployments()
             .await
@@ -328,10 +404,7 @@ impl AzureDeploymentsManager {
 
     /// Get the preferred wire API for a specific model/deployment.
     /// Returns None if deployment not found, in which case caller should use default.
-    pub async fn get_wire_api_for_model(
-        &self,
-        model_name: &str,
-    ) -> Option<crate::model_provider_info::WireApi> {
+    pub async fn get_wire_api_for_model(&self, model_name: &str) -> Option<WireApi> {
         self.find_deployment(model_name)
             .await
             .map(|d| d.preferred_wire_api())
@@ -393,14 +466,42 @@ impl AzureDeployment {
             .is_some_and(|v| v == "false")
     }
 
+    /// Returns the model format reported by Azure (e.g. "OpenAI", "Mistral AI",
+    /// "Meta", "DeepSeek", "Microsoft").
+    pub fn model_format(&self) -> Option<&str> {
+        self.properties
+            .model
+            .as_ref()
+            .and_then(|m| m.format.as_deref())
+    }
+
+    /// Returns true if this deployment is served through the Azure AI Model
+    /// Inference API rather than the Azure OpenAI or Anthropic APIs.
+    pub fn uses_azure_inference(&self) -> bool {
+        if is_claude_model(&self.name) || self.underlying_model_name().is_some_and(is_claude_model)
+        {
+            return false;
+        }
+        match self.model_format() {
+            Some(format) => {
+                !format.eq_ignore_ascii_case("OpenAI") && !format.eq_ignore_ascii_case("Anthropic")
+            }
+            None => is_azure_inference_model(self.underlying_model_name().unwrap_or(&self.name)),
+        }
+    }
+
     /// Determines the preferred wire API for this deployment.
     /// Priority:
-    /// 1. If only responses API is available (chatCompletion explicitly false), use Responses
-    /// 2. If responses API is available and chatCompletion is not explicitly false, use Responses
-    /// 3. If only chatCompletion is available, use Chat
-    /// 4. Default to Responses for unknown/missing capabilities (for backwards compatibility)
-    pub fn preferred_wire_api(&self) -> crate::model_provider_info::WireApi {
-        use crate::model_provider_info::WireApi;
+    /// 1. Non-OpenAI, non-Anthropic models (Mistral, Llama, DeepSeek, Phi, ...) use
+    ///    the Azure AI Model Inference API
+    /// 2. If only responses API is available (chatCompletion explicitly false), use Responses
+    /// 3. If responses API is available and chatCompletion is not explicitly false, use Responses
+    /// 4. If only chatCompletion is available, use Chat
+    /// 5. Default to Responses for unknown/missing capabilities (for backwards compatibility)
+    pub fn preferred_wire_api(&self) -> WireApi {
+        if self.uses_azure_inference() {
+            return WireApi::AzureInference;
+        }
 
         let supports_responses = self.supports_responses_api();
         let supports_chat = self.supports_chat_completions();
@@ -458,6 +559,11 @@ impl AzureDeployment {
             }
             None => "Azure OpenAI deployment".to_string(),
         };
+        let description = if self.uses_azure_inference() {
+            description.replacen("Azure deployment", "Azure AI inference deployment", 1)
+        } else {
+            description
+        };
 
         // Determine supported reasoning efforts based on the model
         let supported_reasoning_efforts =
@@ -484,10 +590,41 @@ impl AzureDeployment {
 /// Supported model prefixes for the model picker.
 /// Includes GPT models, Claude models, and other Azure AI deployments.
 const SUPPORTED_MODEL_PREFIXES: &[&str] = &[
-    "gpt", "claude", "o1", // OpenAI o1 models
+    "gpt",
+    "claude",
+    "o1", // OpenAI o1 models
     "o3", // OpenAI o3 models
+    // Azure AI Model Inference models
+    "mistral",
+    "codestral",
+    "ministral",
+    "llama",
+    "meta-llama",
+    "deepseek",
+    "phi-",
+];
+
+/// Name tokens of models served through the Azure AI Model Inference API.
+const AZURE_INFERENCE_MODEL_MARKERS: &[&str] = &[
+    "mistral",
+    "codestral",
+    "ministral",
+    "llama",
+    "deepseek",
+    "phi",
+    "cohere",
+    "jamba",
 ];
 
+/// Check if a model name belongs to a family served through the Azure AI
+/// Model Inference API. Pass the discovered underlying model when there is
+/// one; the deployment name is only a fallback.
+pub(crate) fn is_azure_inference_model(name: &str) -> bool {
+    AZURE_INFERENCE_MODEL_MARKERS
+        .iter()
+        .any(|marker| model_name_matches(name, marker))
+}
+
 /// Check if a deployment is a supported model for the picker.
 /// Checks both the deployment name and the underlying model name.
 fn is_supported_model(deployment_name: &str, underlying_model: Option<&str>) -> bool {
@@ -584,6 +721,16 @@ fn get_reasoning_efforts_for_model(
         };
     }
 
+    // Azure AI Model Inference models don't take a reasoning effort; DeepSeek-R1
+    // and Phi-4-reasoning always think, the rest never do. Offer no efforts so
+    // the picker doesn't suggest one the endpoint ignores.
+    if is_azure_inference_model(underlying_model.unwrap_or(deployment_name)) {
+        return ReasoningSupport {
+            default_effort: None,
+            presets: Vec::new(),
+        };
+    }
+
     // Specialized support maps based on model capability signals.
     let is_gpt5_pro = name_lower.contains("gpt-5-pro")
         || underlying_lower
@@ -878,8 +1025,97 @@ mod tests {
         ));
 
         // Non-supported models
-        assert!(!is_supported_model("llama-3", None));
-        assert!(!is_supported_model("mistral-7b", None));
+        assert!(!is_supported_model("text-embedding-3-large", None));
+        assert!(!is_supported_model("dall-e-3", None));
+    }
+
+    #[test]
+    fn test_is_supported_model_azure_inference() {
+        assert!(is_supported_model("Llama-3.3-70B-Instruct", None));
+        assert!(is_supported_model("mistral-large-2411", None));
+        assert!(is_supported_model("DeepSeek-R1", None));
+        assert!(is_supported_model("Phi-4-mini-instruct", None));
+        assert!(is_supported_model(
+            "my-deployment",
+            Some("Mistral-Large-2411")
+        ));
+    }
+
+    fn deployment(name: &str, model: &str, format: &str) -> AzureDeployment {
+        AzureDeployment {
+            name: name.to_string(),
+            resource_group: None,
+            properties: AzureDeploymentProperties {
+                model: Some(AzureModelInfo {
+                    name: Some(model.to_string()),
+                    version: None,
+                    format: Some(format.to_string()),
+                }),
+                provisioning_state: None,
+                capabilities: Some(AzureDeploymentCapabilities {
+                    chat_completion: Some("true".to_string()),
+                    ..Default::default()
+                }),
+            },
+        }
+    }
+
+    #[test]
+    fn test_preferred_wire_api_for_inference_deployments() {
+        assert_eq!(
+            deployment("my-mistral", "Mistral-Large-2411", "Mistral AI").preferred_wire_api(),
+            WireApi::AzureInference
+        );
+        assert_eq!(
+            deployment("DeepSeek-R1", "DeepSeek-R1", "DeepSeek").preferred_wire_api(),
+            WireApi::AzureInference
+        );
+        assert_eq!(
+            deployment("gpt-4o", "gpt-4o", "OpenAI").preferred_wire_api(),
+            WireApi::Chat
+        );
+    }
+
+    #[test]
+    fn test_inference_models_offer_no_reasoning_efforts() {
+        let efforts = get_reasoning_efforts_for_model("my-mistral", Some("Mistral-Large-2411"));
+        assert_eq!(efforts.default_effort, None);
+        assert!(efforts.presets.is_empty());
+    }
+
+    #[test]
+    fn test_inference_detection_prefers_the_discovered_model() {
+        assert!(is_azure_inference_model("Meta-Llama-3.1-405B-Instruct"));
+        assert!(is_azure_inference_model("team-phi-4"));
+        assert!(!is_azure_inference_model("gpt-4o-dolphin"));
+        assert!(!is_azure_inference_model("mistralish-gpt"));
+
+        // A deployment named after one family but serving another follows
+        // its model.
+        let efforts = get_reasoning_efforts_for_model("llama-router", Some("gpt-5"));
+        assert!(!efforts.presets.is_empty());
+        let mut renamed = deployment("llama-router", "gpt-4o", "OpenAI");
+        renamed.properties.model.as_mut().expect("model").format = None;
+        assert!(!renamed.uses_azure_inference());
+    }
+
+    #[test]
+    fn test_discovered_deployment_registry() {
+        record_discovered_deployments(&[deployment(
+            "Team-Llama",
+            "Llama-3.3-70B-Instruct",
+            "Meta",
+        )]);
+        assert_eq!(
+            discovered_wire_api("team-llama"),
+            Some(WireApi::AzureInference)
+        );
+        assert_eq!(discovered_wire_api("unknown-deployment"), None);
+        assert_eq!(
+            discovered_model("Team-Llama").as_deref(),
+            Some("Llama-3.3-70B-Instruct")
+        );
+        assert_eq!(discovered_model("unknown-deployment"), None);
     }
 
     #[test]
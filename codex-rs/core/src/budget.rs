@@ -0,0 +1,382 @@
+//! Session-wide limits configured under `[budget]`.
+//!
+//! Token, cost and duration limits are checked after every sampling request,
+//! so a turn may overshoot them by one response. Every limit is also checked
+//! before a turn or tool call starts; a tool call that is already running is
+//! left to finish. Turn and tool call limits are never exceeded.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::BudgetEntry;
+use codex_protocol::protocol::BudgetLimit;
+use codex_protocol::protocol::BudgetStatus;
+use codex_protocol::protocol::TokenUsage;
+
+use crate::config::types::BudgetConfig;
+
+/// Fraction of a limit at which a one-time warning is emitted.
+const BUDGET_WARNING_RATIO: f64 = 0.8;
+
+pub(crate) struct SessionBudget {
+    limits: BudgetConfig,
+    started_at: Instant,
+    state: Mutex<BudgetState>,
+}
+
+#[derive(Default)]
+struct BudgetState {
+    turns: u64,
+    tool_calls: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    /// Running session cost, when pricing is known for the model.
+    cost_usd: Option<f64>,
+    /// Cost of sub-agents spawned by this session, when pricing is known for
+    /// their models.
+    sub_agent_cost_usd: Option<f64>,
+    warned: HashSet<BudgetLimit>,
+    exceeded: Option<BudgetLimit>,
+}
+
+impl BudgetState {
+    fn total_cost_usd(&self) -> Option<f64> {
+        match (self.cost_usd, self.sub_agent_cost_usd) {
+            (None, None) => None,
+            (own, sub_agents) => Some(own.unwrap_or(0.0) + sub_agents.unwrap_or(0.0)),
+        }
+    }
+}
+
+/// Result of [`SessionBudget::check`].
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct BudgetCheck {
+    /// Warnings for limits that crossed the warning threshold since the last
+    /// check.
+    pub(crate) warnings: Vec<String>,
+    pub(crate) exceeded: Option<BudgetLimit>,
+}
+
+impl SessionBudget {
+    pub(crate) fn new(limits: BudgetConfig) -> Self {
+        Self {
+            limits,
+            started_at: Instant::now(),
+            state: Mutex::new(BudgetState::default()),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.limits.is_empty()
+    }
+
+    pub(crate) fn limits(&self) -> &BudgetConfig {
+        &self.limits
+    }
+
+    /// Adds the usage reported for one model response.
+    pub(crate) fn record_usage(&self, usage: &TokenUsage) {
+        let mut state = self.lock();
+        state.input_tokens = state
+            .input_tokens
+            .saturating_add(u64::try_from(usage.input_tokens).unwrap_or(0));
+        state.output_tokens = state
+            .output_tokens
+            .saturating_add(u64::try_from(usage.output_tokens).unwrap_or(0));
+    }
+
+    /// Replaces the running session cost.
+    pub(crate) fn set_cost_usd(&self, cost_usd: f64) {
+        self.lock().cost_usd = Some(cost_usd);
+    }
+
+    /// Charges cost incurred by a sub-agent on top of the session's own cost.
+    pub(crate) fn add_sub_agent_cost_usd(&self, cost_usd: f64) {
+        let mut state = self.lock();
+        state.sub_agent_cost_usd = Some(state.sub_agent_cost_usd.unwrap_or(0.0) + cost_usd);
+    }
+
+    /// Counts a new turn, or returns the limit that prevents it.
+    pub(crate) fn try_start_turn(&self) -> Result<(), BudgetLimit> {
+        let mut state = self.lock();
+        if let Some(limit) = state.exceeded {
+            return Err(limit);
+        }
+        if let Some(limit) = self.exceeded_limit(&state) {
+            state.exceeded = Some(limit);
+            return Err(limit);
+        }
+        if self.limits.max_turns.is_some_and(|max| state.turns >= max) {
+            state.exceeded = Some(BudgetLimit::Turns);
+            return Err(BudgetLimit::Turns);
+        }
+        state.turns += 1;
+        Ok(())
+    }
+
+    /// Counts a new tool call, or returns the limit that prevents it. A
+    /// refused call marks the budget as exceeded so the turn stops after the
+    /// current sampling round.
+    pub(crate) fn try_start_tool_call(&self) -> Result<(), BudgetLimit> {
+        let mut state = self.lock();
+        if let Some(limit) = state.exceeded {
+            return Err(limit);
+        }
+        if let Some(limit) = self.exceeded_limit(&state) {
+            state.exceeded = Some(limit);
+            return Err(limit);
+        }
+        if self
+            .limits
+            .max_tool_calls
+            .is_some_and(|max| state.tool_calls >= max)
+        {
+            state.exceeded = Some(BudgetLimit::ToolCalls);
+            return Err(BudgetLimit::ToolCalls);
+        }
+        state.tool_calls += 1;
+        Ok(())
+    }
+
+    /// Re-evaluates the token, cost and duration limits and returns any new
+    /// warnings along with the exceeded limit, if there is one.
+    pub(crate) fn check(&self) -> BudgetCheck {
+        let mut state = self.lock();
+        if state.exceeded.is_none() {
+            state.exceeded = self.exceeded_limit(&state);
+        }
+        let mut warnings = Vec::new();
+        for entry in self.entries(&state) {
+            if entry.max <= 0.0
+                || entry.used < entry.max * BUDGET_WARNING_RATIO
+                || Some(entry.limit) == state.exceeded
+            {
+                continue;
+            }
+            if state.warned.insert(entry.limit) {
+                warnings.push(format!(
+                    "Session budget: {} used of {} {}.",
+                    format_amount(entry.limit, entry.used),
+                    format_amount(entry.limit, entry.max),
+                    entry.limit,
+                ));
+            }
+        }
+        BudgetCheck {
+            warnings,
+            exceeded: state.exceeded,
+        }
+    }
+
+    pub(crate) fn status(&self) -> BudgetStatus {
+        let state = self.lock();
+        BudgetStatus {
+            entries: self.entries(&state),
+            exceeded: state.exceeded,
+        }
+    }
+
+    /// Human-readable reason for stopping on `limit`.
+    pub(crate) fn exceeded_message(&self, limit: BudgetLimit) -> String {
+        let max = self
+            .entries(&self.lock())
+            .into_iter()
+            .find(|entry| entry.limit == limit)
+            .map(|entry| format_amount(limit, entry.max));
+        match max {
+            Some(max) => format!("Session budget exceeded: reached the limit of {max} {limit}."),
+            None => format!("Session budget exceeded: {limit}."),
+        }
+    }
+
+    fn exceeded_limit(&self, state: &BudgetState) -> Option<BudgetLimit> {
+        self.entries(state)
+            .into_iter()
+            .find(|entry| match entry.limit {
+                // Turns and tool calls are refused up front rather than
+                // treated as exceeded once the last one has been used.
+                BudgetLimit::Turns | BudgetLimit::ToolCalls => false,
+                _ => entry.used >= entry.max,
+            })
+            .map(|entry| entry.limit)
+    }
+
+    fn entries(&self, state: &BudgetState) -> Vec<BudgetEntry> {
+        let elapsed = self.elapsed().as_secs_f64();
+        let limits = &self.limits;
+        [
+            (
+                BudgetLimit::InputTokens,
+                limits.max_input_tokens.map(|max| max as f64),
+                Some(state.input_tokens as f64),
+            ),
+            (
+                BudgetLimit::OutputTokens,
+                limits.max_output_tokens.map(|max| max as f64),
+                Some(state.output_tokens as f64),
+            ),
+            (
+                BudgetLimit::Cost,
+                limits.max_cost_usd,
+                state.total_cost_usd(),
+            ),
+            (
+                BudgetLimit::ToolCalls,
+                limits.max_tool_calls.map(|max| max as f64),
+                Some(state.tool_calls as f64),
+            ),
+            (
+                BudgetLimit::Turns,
+                limits.max_turns.map(|max| max as f64),
+                Some(state.turns as f64),
+            ),
+            (
+                BudgetLimit::Duration,
+                limits.max_duration_secs.map(|max| max as f64),
+                Some(elapsed),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(limit, max, used)| {
+            Some(BudgetEntry {
+                limit,
+                used: used?,
+                max: max?,
+            })
+        })
+        .collect()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn format_amount(limit: BudgetLimit, amount: f64) -> String {
+    match limit {
+        BudgetLimit::Cost => format!("${amount:.2}"),
+        BudgetLimit::Duration => format!("{}s", amount.round() as u64),
+        _ => format!("{}", amount.round() as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input_tokens: i64, output_tokens: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn turns_and_tool_calls_are_refused_once_used_up() {
+        let budget = SessionBudget::new(BudgetConfig {
+            max_turns: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(budget.try_start_turn(), Ok(()));
+        assert_eq!(budget.try_start_turn(), Ok(()));
+        assert_eq!(budget.try_start_turn(), Err(BudgetLimit::Turns));
+        assert_eq!(budget.status().exceeded, Some(BudgetLimit::Turns));
+
+        let budget = SessionBudget::new(BudgetConfig {
+            max_tool_calls: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(budget.try_start_tool_call(), Ok(()));
+        assert_eq!(budget.check().exceeded, None);
+        assert_eq!(budget.try_start_tool_call(), Err(BudgetLimit::ToolCalls));
+        assert_eq!(budget.check().exceeded, Some(BudgetLimit::ToolCalls));
+        assert_eq!(budget.try_start_turn(), Err(BudgetLimit::ToolCalls));
+    }
+
+    #[test]
+    fn tool_calls_are_refused_once_the_duration_is_used_up() {
+        let budget = SessionBudget::new(BudgetConfig {
+            max_duration_secs: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(budget.try_start_tool_call(), Err(BudgetLimit::Duration));
+        assert_eq!(budget.status().exceeded, Some(BudgetLimit::Duration));
+
+        let budget = SessionBudget::new(BudgetConfig::default());
+        assert_eq!(budget.try_start_tool_call(), Ok(()));
+    }
+
+    #[test]
+    fn token_limits_warn_once_then_exceed() {
+        let budget = SessionBudget::new(BudgetConfig {
+            max_output_tokens: Some(100),
+            ..Default::default()
+        });
+        budget.record_usage(&usage(500, 50));
+        assert_eq!(budget.check(), BudgetCheck::default());
+
+        budget.record_usage(&usage(500, 35));
+        assert_eq!(
+            budget.check(),
+            BudgetCheck {
+                warnings: vec!["Session budget: 85 used of 100 output tokens.".to_string()],
+                exceeded: None,
+            }
+        );
+        assert_eq!(budget.check(), BudgetCheck::default());
+
+        budget.record_usage(&usage(0, 15));
+        assert_eq!(budget.check().exceeded, Some(BudgetLimit::OutputTokens));
+        assert_eq!(
+            budget.exceeded_message(BudgetLimit::OutputTokens),
+            "Session budget exceeded: reached the limit of 100 output tokens."
+        );
+    }
+
+    #[test]
+    fn cost_is_only_enforced_once_known() {
+        let budget = SessionBudget::new(BudgetConfig {
+            max_cost_usd: Some(1.0),
+            ..Default::default()
+        });
+        assert!(budget.is_enabled());
+        assert_eq!(budget.status().entries, Vec::new());
+
+        budget.set_cost_usd(1.25);
+        assert_eq!(budget.check().exceeded, Some(BudgetLimit::Cost));
+        assert_eq!(
+            budget.status().entries,
+            vec![BudgetEntry {
+                limit: BudgetLimit::Cost,
+                used: 1.25,
+                max: 1.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn sub_agent_cost_survives_session_cost_updates() {
+        let budget = SessionBudget::new(BudgetConfig {
+            max_cost_usd: Some(1.0),
+            ..Default::default()
+        });
+        budget.add_sub_agent_cost_usd(0.25);
+        budget.add_sub_agent_cost_usd(0.25);
+        budget.set_cost_usd(0.25);
+        assert_eq!(budget.check().exceeded, None);
+        assert_eq!(budget.status().entries[0].used, 0.75);
+
+        budget.set_cost_usd(0.5);
+        assert_eq!(budget.check().exceeded, Some(BudgetLimit::Cost));
+    }
+}
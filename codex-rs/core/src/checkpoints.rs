@@ -0,0 +1,160 @@
+//! Undo checkpoints built from the ghost snapshots recorded at the start of
+//! each turn.
+
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostSnapshotConfig;
+use codex_git::GitToolingError;
+use codex_git::create_ghost_commit;
+use codex_git::diff_ghost_commits;
+use codex_protocol::protocol::Checkpoint;
+
+use crate::context_manager::TurnSnapshot;
+
+/// Lines of unified diff kept in [`Checkpoint::diff_preview`].
+const DIFF_PREVIEW_MAX_LINES: usize = 400;
+
+/// Writes this close to the start or end of a task are still attributed to
+/// it, which absorbs coarse filesystem timestamps.
+const ACTIVITY_SLACK: Duration = Duration::from_secs(2);
+
+/// Wall-clock span during which a session task was running.
+pub(crate) type TaskActivity = (SystemTime, SystemTime);
+
+/// Build the checkpoints for `snapshots` (newest first). Takes a snapshot of
+/// the current working tree so the latest turn and external edits can be
+/// diffed the same way as earlier turns. Blocking; run it off the runtime.
+pub(crate) fn list_checkpoints(
+    cwd: &Path,
+    ghost_snapshot: GhostSnapshotConfig,
+    snapshots: Vec<TurnSnapshot>,
+    activity: &[TaskActivity],
+) -> Result<Vec<Checkpoint>, GitToolingError> {
+    if snapshots.is_empty() {
+        return Ok(Vec::new());
+    }
+    let current = create_ghost_commit(
+        &CreateGhostCommitOptions::new(cwd)
+            .message("codex checkpoint listing")
+            .ghost_snapshot(ghost_snapshot),
+    )?;
+
+    let mut checkpoints = Vec::with_capacity(snapshots.len());
+    let mut next_commit = current.id().to_string();
+    for snapshot in snapshots {
+        let commit_id = snapshot.ghost_commit.id().to_string();
+        let turn_diff = diff_ghost_commits(cwd, &commit_id, &next_commit)?;
+        let changed_since = if next_commit == current.id() {
+            turn_diff.files.clone()
+        } else {
+            diff_ghost_commits(cwd, &commit_id, current.id())?.files
+        };
+        let external_changes = changed_since
+            .into_iter()
+            .filter(|path| {
+                std::fs::metadata(cwd.join(path))
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| is_external_change(modified, activity))
+            })
+            .collect();
+        let (diff_preview, diff_truncated) = diff_preview(&turn_diff.patch);
+        checkpoints.push(Checkpoint {
+            num_turns: u32::try_from(snapshot.num_turns).unwrap_or(u32::MAX),
+            commit_id: commit_id.clone(),
+            prompt: snapshot
+                .prompt
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            files: turn_diff.files,
+            diff_preview,
+            diff_truncated,
+            external_changes,
+        });
+        next_commit = commit_id;
+    }
+    Ok(checkpoints)
+}
+
+/// Whether a file last modified at `modified` was written while no task was
+/// running. Writes older than the first recorded task cannot be attributed and
+/// are not reported.
+fn is_external_change(modified: SystemTime, activity: &[TaskActivity]) -> bool {
+    let Some(first_start) = activity.iter().map(|(start, _)| *start).min() else {
+        return false;
+    };
+    if modified < first_start {
+        return false;
+    }
+    !activity.iter().any(|(start, end)| {
+        let start = start.checked_sub(ACTIVITY_SLACK).unwrap_or(*start);
+        modified >= start && modified <= *end + ACTIVITY_SLACK
+    })
+}
+
+fn diff_preview(patch: &str) -> (String, bool) {
+    let mut lines = patch.lines();
+    let preview: Vec<&str> = lines.by_ref().take(DIFF_PREVIEW_MAX_LINES).collect();
+    let truncated = lines.next().is_some();
+    let mut preview = preview.join("\n");
+    if !preview.is_empty() {
+        preview.push('\n');
+    }
+    (preview, truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn writes_between_tasks_are_external() {
+        let base = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let activity = vec![
+            (base, base + Duration::from_secs(60)),
+            (
+                base + Duration::from_secs(300),
+                base + Duration::from_secs(360),
+            ),
+        ];
+
+        assert!(!is_external_change(
+            base + Duration::from_secs(30),
+            &activity
+        ));
+        assert!(!is_external_change(
+            base + Duration::from_secs(61),
+            &activity
+        ));
+        assert!(is_external_change(
+            base + Duration::from_secs(120),
+            &activity
+        ));
+        assert!(is_external_change(
+            base + Duration::from_secs(400),
+            &activity
+        ));
+        assert!(!is_external_change(
+            base - Duration::from_secs(60),
+            &activity
+        ));
+        assert!(!is_external_change(base, &[]));
+    }
+
+    #[test]
+    fn diff_preview_cuts_long_patches() {
+        let patch: String = (0..DIFF_PREVIEW_MAX_LINES + 5)
+            .map(|i| format!("+line {i}\n"))
+            .collect();
+        let (preview, truncated) = diff_preview(&patch);
+        assert!(truncated);
+        assert_eq!(preview.lines().count(), DIFF_PREVIEW_MAX_LINES);
+
+        assert_eq!(diff_preview("+one\n"), ("+one\n".to_string(), false));
+    }
+}
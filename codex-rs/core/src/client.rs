@@ -1,8 +1,12 @@
 use std::sync::Arc;
 
+use crate::api_bridge::CoreAuthProvider;
 use crate::api_bridge::auth_provider_from_auth;
 use crate::api_bridge::map_api_error;
 use crate::auth::azure::AzureAuth;
+use crate::azure::deployments::discovered_model;
+use crate::azure::deployments::discovered_wire_api;
+use crate::azure::deployments::is_azure_inference_model;
 use codex_api::AggregateStreamExt;
 use codex_api::AnthropicClient as ApiAnthropicClient;
 use codex_api::AnthropicOptions as ApiAnthropicOptions;
@@ -11,7 +15,6 @@ use codex_api::CompactClient as ApiCompactClient;
 use codex_api::CompactionInput as ApiCompactionInput;
 use codex_api::Prompt as ApiPrompt;
 use codex_api::RequestTelemetry;
-use codex_api::ReqwestTransport;
 use codex_api::ResponseStream as ApiResponseStream;
 use codex_api::ResponsesClient as ApiResponsesClient;
 use codex_api::ResponsesOptions as ApiResponsesOptions;
@@ -21,6 +24,7 @@ use codex_api::common::Reasoning;
 use codex_api::create_text_param_for_request;
 use codex_api::error::ApiError;
 use codex_app_server_protocol::AuthMode;
+use codex_client::CassetteMode;
 use codex_otel::otel_manager::OtelManager;
 use codex_protocol::ConversationId;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
@@ -40,12 +44,13 @@ use tokio::sync::mpsc;
 use tracing::warn;
 
 use crate::AuthManager;
+use crate::auth::CodexAuth;
 use crate::auth::RefreshTokenError;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::config::Config;
-use crate::default_client::build_reqwest_client;
+use crate::default_client::build_http_transport;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::features::FEATURES;
@@ -88,23 +93,26 @@ impl ModelClient {
         session_source: SessionSource,
     ) -> Self {
         // Determine wire API and effective provider based on model capabilities
-        let (wire_api_override, effective_provider) = if provider.is_azure_endpoint() {
-            let model_slug = model_family.get_model_slug();
-            let effective_wire_api = determine_wire_api_for_azure_model(model_slug);
-
-            // If the effective wire API differs from the provider's default,
-            // we need to adjust the provider's base_url for Azure
-            let adjusted_provider = if effective_wire_api != provider.wire_api {
-                adjust_azure_provider_for_wire_api(&provider, effective_wire_api, model_slug)
+        // Providers explicitly configured for the Azure AI Model Inference API
+        // already point at `/models` and must not be re-routed by model name.
+        let (wire_api_override, effective_provider) =
+            if provider.is_azure_endpoint() && provider.wire_api != WireApi::AzureInference {
+                let model_slug = model_family.get_model_slug();
+                let effective_wire_api = determine_wire_api_for_azure_model(model_slug);
+
+                // If the effective wire API differs from the provider's default,
+                // we need to adjust the provider's base_url for Azure
+                let adjusted_provider = if effective_wire_api != provider.wire_api {
+                    adjust_azure_provider_for_wire_api(&provider, effective_wire_api, model_slug)
+                } else {
+                    provider
+                };
+
+                (Some(effective_wire_api), adjusted_provider)
             } else {
-                provider
+                (None, provider)
             };
 
-            (Some(effective_wire_api), adjusted_provider)
-        } else {
-            (None, provider)
-        };
-
         Self {
             config,
             auth_manager,
@@ -161,7 +169,7 @@ impl ModelClient {
 
         match wire_api {
             WireApi::Responses => self.stream_responses_api(prompt).await,
-            WireApi::Chat => {
+            WireApi::Chat | WireApi::AzureInference => {
                 let api_stream = self.stream_chat_completions(prompt).await?;
 
                 if self.config.show_raw_agent_reasoning {
@@ -196,8 +204,9 @@ impl ModelClient {
 
     /// Streams a turn via the OpenAI Chat Completions API.
     ///
-    /// This path is only used when the provider is configured with
-    /// `WireApi::Chat`; it does not support `output_schema` today.
+    /// This path is used for `WireApi::Chat` and for the Chat Completions
+    /// compatible Azure AI Model Inference API (`WireApi::AzureInference`);
+    /// it does not support `output_schema` today.
     async fn stream_chat_completions(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
         if prompt.output_schema.is_some() {
             return Err(CodexErr::UnsupportedOperation(
@@ -215,18 +224,14 @@ impl ModelClient {
 
         let mut refreshed = false;
         let model = self.get_model();
+        let underlying_model = discovered_model(&model);
         loop {
             let auth = auth_manager.as_ref().and_then(|m| m.auth());
             let api_provider = self
                 .provider
                 .to_api_provider_with_model(auth.as_ref().map(|a| a.mode), Some(&model))?;
-            let api_auth = auth_provider_from_auth(
-                self.azure_auth.as_ref().map(AsRef::as_ref),
-                &self.provider,
-                auth.as_ref(),
-            )
-            .await?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = build_http_transport(self.config.http_cassette.as_ref())?;
+            let api_auth = self.resolve_api_auth(auth.as_ref()).await?;
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
                 .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
@@ -234,6 +239,7 @@ impl ModelClient {
             let stream_result = client
                 .stream_prompt(
                     &model,
+                    underlying_model.as_deref(),
                     &api_prompt,
                     Some(conversation_id.clone()),
                     Some(session_source.clone()),
@@ -290,13 +296,8 @@ impl ModelClient {
             let api_provider = self
                 .provider
                 .to_api_provider_with_model(auth.as_ref().map(|a| a.mode), Some(&model))?;
-            let api_auth = auth_provider_from_auth(
-                self.azure_auth.as_ref().map(AsRef::as_ref),
-                &self.provider,
-                auth.as_ref(),
-            )
-            .await?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = build_http_transport(self.config.http_cassette.as_ref())?;
+            let api_auth = self.resolve_api_auth(auth.as_ref()).await?;
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiAnthropicClient::new(transport, api_provider, api_auth)
                 .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
@@ -391,13 +392,8 @@ impl ModelClient {
             let api_provider = self
                 .provider
                 .to_api_provider_with_model(auth.as_ref().map(|a| a.mode), Some(&model))?;
-            let api_auth = auth_provider_from_auth(
-                self.azure_auth.as_ref().map(AsRef::as_ref),
-                &self.provider,
-                auth.as_ref(),
-            )
-            .await?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = build_http_transport(self.config.http_cassette.as_ref())?;
+            let api_auth = self.resolve_api_auth(auth.as_ref()).await?;
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiResponsesClient::new(transport, api_provider, api_auth)
                 .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
@@ -487,13 +483,8 @@ impl ModelClient {
         let api_provider = self
             .provider
             .to_api_provider_with_model(auth.as_ref().map(|a| a.mode), Some(&model))?;
-        let api_auth = auth_provider_from_auth(
-            self.azure_auth.as_ref().map(AsRef::as_ref),
-            &self.provider,
-            auth.as_ref(),
-        )
-        .await?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport = build_http_transport(self.config.http_cassette.as_ref())?;
+        let api_auth = self.resolve_api_auth(auth.as_ref()).await?;
         let request_telemetry = self.build_request_telemetry();
         let client = ApiCompactClient::new(transport, api_provider, api_auth)
             .with_telemetry(Some(request_telemetry));
@@ -509,14 +500,7 @@ impl ModelClient {
 
         let mut extra_headers = ApiHeaderMap::new();
         if let SessionSource::SubAgent(sub) = &self.session_source {
-            let subagent = if let crate::protocol::SubAgentSource::Other(label) = sub {
-                label.clone()
-            } else {
-                serde_json::to_value(sub)
-                    .ok()
-                    .and_then(|v| v.as_str().map(std::string::ToString::to_string))
-                    .unwrap_or_else(|| "other".to_string())
-            };
+            let subagent = sub.to_string();
             if let Ok(val) = HeaderValue::from_str(&subagent) {
                 extra_headers.insert("x-openai-subagent", val);
             }
@@ -530,6 +514,21 @@ impl ModelClient {
 }
 
 impl ModelClient {
+    /// Resolves the credentials for a model request. Token and Azure Resource
+    /// Manager requests are not part of a cassette, so a replayed session
+    /// skips them and sends no credentials instead of reaching the network.
+    async fn resolve_api_auth(&self, auth: Option<&CodexAuth>) -> Result<CoreAuthProvider> {
+        if matches!(self.config.http_cassette, Some(CassetteMode::Replay(_))) {
+            return Ok(CoreAuthProvider::default());
+        }
+        auth_provider_from_auth(
+            self.azure_auth.as_ref().map(AsRef::as_ref),
+            &self.provider,
+            auth,
+        )
+        .await
+    }
+
     /// Builds request and SSE telemetry for streaming API calls (Chat/Responses).
     fn build_streaming_telemetry(&self) -> (Arc<dyn RequestTelemetry>, Arc<dyn SseTelemetry>) {
         let telemetry = Arc::new(ApiTelemetry::new(self.otel_manager.clone()));
@@ -744,9 +743,28 @@ fn adjust_azure_provider_for_wire_api(
     target_wire_api: WireApi,
     model_name: &str,
 ) -> ModelProviderInfo {
+    let provider = &provider.with_apim_path_prefix();
     let mut adjusted = provider.clone();
     adjusted.wire_api = target_wire_api;
 
+    if target_wire_api == WireApi::AzureInference {
+        // The api-version configured for Azure OpenAI doesn't apply to the
+        // inference API; `to_api_provider` fills in the inference default.
+        if let Some(params) = adjusted.query_params.as_mut() {
+            params.remove("api-version");
+        }
+        adjusted.base_url = provider
+            .base_url
+            .as_deref()
+            .map(adjust_url_for_azure_inference);
+        tracing::debug!(
+            model = %model_name,
+            adjusted_base_url = ?adjusted.base_url,
+            "Adjusted for Azure AI Model Inference endpoint"
+        );
+        return adjusted;
+    }
+
     // Adjust base_url based on target wire API
     if let Some(base_url) = &provider.base_url {
         // Check if this is a Claude model
@@ -790,6 +808,7 @@ fn adjust_azure_provider_for_wire_api(
                     // Try to construct an Anthropic-like URL
                     adjust_url_for_azure_ai_services(base_url)
                 }
+                WireApi::AzureInference => adjust_url_for_azure_inference(base_url),
             });
         } else {
             // Standard Azure OpenAI handling
@@ -818,6 +837,7 @@ fn adjust_azure_provider_for_wire_api(
                     // For Anthropic API on Azure AI Services
                     adjust_url_for_azure_ai_services(base_url)
                 }
+                WireApi::AzureInference => adjust_url_for_azure_inference(base_url),
             });
         }
     }
@@ -876,6 +896,30 @@ fn adjust_url_for_azure_ai_services(base_url: &str) -> String {
     format!("{url}/anthropic/v1")
 }
 
+/// Adjusts a URL for the Azure AI Model Inference API.
+///
+/// Foundry resources (`services.ai.azure.com`, `cognitiveservices.azure.com`)
+/// serve the inference API under `/models`, while serverless endpoints
+/// (`{name}.{region}.models.ai.azure.com`) serve it at the host root. The
+/// `chat/completions` path is appended by the Chat client.
+fn adjust_url_for_azure_inference(base_url: &str) -> String {
+    let url = base_url.trim_end_matches('/');
+    let host_end = url
+        .find("://")
+        .map(|scheme_end| {
+            let rest = &url[scheme_end + 3..];
+            scheme_end + 3 + rest.find('/').unwrap_or(rest.len())
+        })
+        .unwrap_or(url.len());
+    let root = &url[..host_end];
+
+    if root.to_lowercase().contains("models.ai.azure.com") {
+        root.to_string()
+    } else {
+        format!("{root}/models")
+    }
+}
+
 /// Determines the appropriate wire API for an Azure deployment based on model name.
 ///
 /// Azure OpenAI deployments have varying capabilities:
@@ -885,6 +929,12 @@ fn adjust_url_for_azure_ai_services(base_url: &str) -> String {
 ///
 /// This function uses pattern matching on model names to determine the correct API.
 fn determine_wire_api_for_azure_model(model_name: &str) -> WireApi {
+    // Deployments discovered via ARM know their model format, which also covers
+    // custom deployment names that don't reveal the underlying model.
+    if let Some(wire_api) = discovered_wire_api(model_name) {
+        return wire_api;
+    }
+
     let name_lower = model_name.to_lowercase();
 
     // Claude models on Azure AI Services use the Anthropic Messages API
@@ -896,6 +946,16 @@ fn determine_wire_api_for_azure_model(model_name: &str) -> WireApi {
         return WireApi::Anthropic;
     }
 
+    // Mistral, Llama, DeepSeek, Phi and other Foundry models are served by the
+    // Azure AI Model Inference API.
+    if is_azure_inference_model(model_name) {
+        tracing::debug!(
+            model = %model_name,
+            "Using Azure AI Model Inference API"
+        );
+        return WireApi::AzureInference;
+    }
+
     // Grok models on Azure only support Chat Completions API
     if name_lower.starts_with("grok-") {
         tracing::debug!(
@@ -0,0 +1,122 @@
+//! Tokenization and BM25 scoring for code chunks.
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+const MIN_TERM_LEN: usize = 2;
+
+/// Splits text into lowercase search terms. Each identifier yields itself
+/// plus its snake_case and camelCase parts, so `parseConfigFile` matches
+/// queries for `parse config` as well as the full name.
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for ident in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|ident| !ident.is_empty())
+    {
+        push_term(&mut terms, ident);
+        let parts = split_identifier(ident);
+        if parts.len() > 1 {
+            for part in parts {
+                push_term(&mut terms, part);
+            }
+        }
+    }
+    terms
+}
+
+/// Term counts for one chunk, sorted by term.
+pub(crate) fn term_counts(text: &str) -> (Vec<(String, u32)>, u32) {
+    let mut terms = tokenize(text);
+    let total = terms.len() as u32;
+    terms.sort_unstable();
+    let mut counts: Vec<(String, u32)> = Vec::new();
+    for term in terms {
+        match counts.last_mut() {
+            Some((last, count)) if *last == term => *count += 1,
+            _ => counts.push((term, 1)),
+        }
+    }
+    (counts, total)
+}
+
+pub(crate) fn idf(total_docs: usize, doc_freq: usize) -> f64 {
+    let total_docs = total_docs as f64;
+    let doc_freq = doc_freq as f64;
+    (1.0 + (total_docs - doc_freq + 0.5) / (doc_freq + 0.5)).ln()
+}
+
+pub(crate) fn score(term_freq: u32, doc_len: u32, avg_doc_len: f64, idf: f64) -> f64 {
+    if term_freq == 0 {
+        return 0.0;
+    }
+    let term_freq = f64::from(term_freq);
+    let norm = 1.0 - B + B * f64::from(doc_len) / avg_doc_len.max(1.0);
+    idf * term_freq * (K1 + 1.0) / (term_freq + K1 * norm)
+}
+
+fn push_term(terms: &mut Vec<String>, term: &str) {
+    let term = term.trim_matches('_');
+    if term.chars().count() >= MIN_TERM_LEN {
+        terms.push(term.to_lowercase());
+    }
+}
+
+fn split_identifier(ident: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    for word in ident.split('_').filter(|word| !word.is_empty()) {
+        let chars: Vec<(usize, char)> = word.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (index, c) = chars[i];
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars
+                .get(i + 1)
+                .is_some_and(|(_, next)| next.is_lowercase());
+            // `parseConfig` -> parse|Config, `HTTPServer` -> HTTP|Server.
+            if c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next_is_lower))
+            {
+                parts.push(&word[start..index]);
+                start = index;
+            }
+        }
+        parts.push(&word[start..]);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tokenize_splits_identifiers() {
+        assert_eq!(
+            tokenize("fn parse_config(HTTPServer) -> x"),
+            vec![
+                "fn",
+                "parse_config",
+                "parse",
+                "config",
+                "httpserver",
+                "http",
+                "server",
+            ]
+        );
+    }
+
+    #[test]
+    fn rarer_terms_score_higher() {
+        let (counts, len) = term_counts("retry retry backoff");
+        assert_eq!(
+            counts,
+            vec![("backoff".to_string(), 1), ("retry".to_string(), 2)]
+        );
+        let common = score(1, len, 3.0, idf(100, 90));
+        let rare = score(1, len, 3.0, idf(100, 2));
+        assert!(rare > common, "{rare} <= {common}");
+    }
+}
@@ -0,0 +1,998 @@
+//! Local code index behind the `search_code` tool.
+//!
+//! Files under the project root (respecting `.gitignore`) are split into
+//! chunks at symbol boundaries found with tree-sitter, and chunks are ranked
+//! with BM25 over an in-memory inverted index. Everything is computed
+//! locally. Each file's chunks are stored as a shard under
+//! `$CODEX_HOME/code_index/`, so a session starts from the last run's index
+//! and rewrites only what changed.
+//!
+//! The session builds the index in the background when it starts and keeps it
+//! current from file-system events: before a search only the directories that
+//! saw changes are re-listed, and files are re-read when their mtime or size
+//! differ. Without a working watcher every search falls back to a full walk.
+
+mod bm25;
+mod postings;
+mod storage;
+mod symbols;
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use codex_utils_string::take_bytes_at_char_boundary;
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use serde::Deserialize;
+use serde::Serialize;
+
+use postings::ChunkSpan;
+use postings::FileId;
+use postings::InvertedIndex;
+use postings::ScoredChunk;
+use storage::ShardStore;
+use symbols::SourceLanguage;
+pub(crate) use symbols::Symbol;
+use symbols::extract_symbols;
+
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Lockfiles, minified bundles and the like drown out real code.
+const SKIPPED_SUFFIXES: &[&str] = &[
+    ".lock",
+    "-lock.json",
+    "-lock.yaml",
+    ".min.js",
+    ".min.css",
+    ".map",
+    ".svg",
+];
+/// Changing one of these can hide or reveal any part of the tree.
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore"];
+const MIN_CHUNK_LINES: usize = 8;
+const MAX_CHUNK_LINES: usize = 60;
+const MAX_HITS_PER_FILE: usize = 3;
+/// Extra weight for a chunk that defines a symbol named exactly like a
+/// query term, relative to that term's idf.
+const SYMBOL_BOOST: f64 = 1.5;
+const SNIPPET_LINES: usize = 12;
+const MAX_SNIPPET_LINE_LENGTH: usize = 200;
+/// Past this many changed paths between searches, a full walk is cheaper
+/// than re-listing each directory.
+const MAX_PENDING_PATHS: usize = 10_000;
+
+/// One file's shard. Binary and non-UTF-8 files are kept with no chunks so
+/// later refreshes can skip them while their mtime and size stay the same.
+#[derive(Serialize, Deserialize)]
+struct FileEntry {
+    rel: String,
+    mtime_ns: u64,
+    len: u64,
+    symbols: Vec<Symbol>,
+    chunks: Vec<Chunk>,
+}
+
+/// Lines are 1-indexed and inclusive.
+#[derive(Serialize, Deserialize)]
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    len: u32,
+    terms: Vec<(String, u32)>,
+}
+
+/// One ranked result. `path` is relative to the index root.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SearchHit {
+    pub(crate) path: String,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+    pub(crate) score: f64,
+    /// Definitions that start inside the hit, e.g. `fn parse_config`.
+    pub(crate) symbols: Vec<String>,
+    /// A few `L<n>: <text>` lines around the first match.
+    pub(crate) snippet: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RefreshStats {
+    pub(crate) indexed_files: usize,
+    pub(crate) updated_files: usize,
+    pub(crate) removed_files: usize,
+}
+
+pub(crate) struct CodeIndex {
+    root: PathBuf,
+    store: ShardStore,
+    /// Index ids by path relative to the root.
+    paths: BTreeMap<String, FileId>,
+    /// Directories the last walks entered, relative to the root (`""` is the
+    /// root itself). Ignored directories are not listed.
+    dirs: BTreeSet<String>,
+    index: InvertedIndex,
+}
+
+impl CodeIndex {
+    /// Loads the stored shards for `root`, or starts an empty index when
+    /// there are none or they were written by an incompatible version.
+    pub(crate) fn open(codex_home: &Path, root: &Path) -> Self {
+        let (store, entries) = ShardStore::open(codex_home, root);
+        let mut index = Self {
+            root: root.to_path_buf(),
+            store,
+            paths: BTreeMap::new(),
+            dirs: BTreeSet::new(),
+            index: InvertedIndex::default(),
+        };
+        for entry in entries {
+            index.insert(entry);
+        }
+        index
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Walks the whole tree, re-indexes new and changed files and drops
+    /// deleted ones.
+    pub(crate) fn refresh(&mut self) -> RefreshStats {
+        let walked = self.walk("", None);
+        let seen: HashSet<&str> = walked.files.iter().map(|file| file.rel.as_str()).collect();
+        let removed: Vec<String> = self
+            .paths
+            .keys()
+            .filter(|rel| !seen.contains(rel.as_str()))
+            .cloned()
+            .collect();
+        for rel in &removed {
+            self.remove(rel);
+        }
+        self.dirs = std::iter::once(String::new()).chain(walked.dirs).collect();
+        let updated_files = self.update(walked.files);
+        self.stats(updated_files, removed.len())
+    }
+
+    /// Re-indexes after `paths` changed. Directories that contain a change are
+    /// re-listed one level deep and directories that appeared are walked in
+    /// full, so the cost follows the size of the change, not of the tree.
+    pub(crate) fn refresh_paths(&mut self, paths: &HashSet<PathBuf>) -> RefreshStats {
+        let mut dirty = BTreeSet::new();
+        for path in paths {
+            let Some(rel) = relative_path(&self.root, path) else {
+                continue;
+            };
+            let (parent, name) = rel.rsplit_once('/').unwrap_or(("", rel.as_str()));
+            if IGNORE_FILES.contains(&name) {
+                return self.refresh();
+            }
+            if !rel.is_empty() {
+                dirty.insert(parent.to_string());
+            }
+        }
+
+        let mut candidates = Vec::new();
+        let mut removed_files = 0;
+        for dir in dirty {
+            if !self.dirs.contains(&dir) {
+                // Ignored, or already handled with a removed or new parent.
+                continue;
+            }
+            if !self.root.join(&dir).is_dir() {
+                removed_files += self.remove_dir(&dir);
+                continue;
+            }
+            let listed = self.walk(&dir, Some(1));
+            let listed_files: HashSet<&str> =
+                listed.files.iter().map(|file| file.rel.as_str()).collect();
+            let listed_dirs: HashSet<&str> = listed.dirs.iter().map(String::as_str).collect();
+            let gone_files: Vec<String> = children(
+                self.paths.range(child_prefix(&dir)..).map(|(rel, _)| rel),
+                &dir,
+            )
+            .into_iter()
+            .filter(|rel| !listed_files.contains(rel))
+            .map(str::to_string)
+            .collect();
+            let gone_dirs: Vec<String> = children(self.dirs.range(child_prefix(&dir)..), &dir)
+                .into_iter()
+                .filter(|rel| !listed_dirs.contains(rel))
+                .map(str::to_string)
+                .collect();
+            for rel in &gone_files {
+                self.remove(rel);
+            }
+            removed_files += gone_files.len();
+            for rel in &gone_dirs {
+                removed_files += self.remove_dir(rel);
+            }
+            candidates.extend(listed.files);
+            for child in listed.dirs {
+                if self.dirs.insert(child.clone()) {
+                    let walked = self.walk(&child, None);
+                    self.dirs.extend(walked.dirs);
+                    candidates.extend(walked.files);
+                }
+            }
+        }
+        let updated_files = self.update(candidates);
+        self.stats(updated_files, removed_files)
+    }
+
+    /// Returns up to `limit` chunks ranked by BM25, optionally restricted to
+    /// paths under `prefix` (relative to the root).
+    pub(crate) fn search(&self, query: &str, limit: usize, prefix: Option<&str>) -> Vec<SearchHit> {
+        let mut terms = bm25::tokenize(query);
+        terms.sort_unstable();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut by_file: HashMap<FileId, Vec<ScoredChunk>> = HashMap::new();
+        for scored in self.index.search(&terms, |rel| {
+            prefix.is_none_or(|prefix| is_under(rel, prefix))
+        }) {
+            by_file.entry(scored.file).or_default().push(scored);
+        }
+        let mut hits = Vec::new();
+        for (file_id, mut file_hits) in by_file {
+            let Some(file) = self.index.file(file_id) else {
+                continue;
+            };
+            file_hits.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.chunk.cmp(&b.chunk)));
+            file_hits.truncate(MAX_HITS_PER_FILE);
+            hits.extend(file_hits.into_iter().map(|scored| (file, scored)));
+        }
+        hits.sort_by(|a, b| {
+            b.1.score
+                .total_cmp(&a.1.score)
+                .then_with(|| a.0.rel.cmp(&b.0.rel))
+                .then(a.1.chunk.cmp(&b.1.chunk))
+        });
+        hits.truncate(limit);
+
+        hits.into_iter()
+            .map(|(file, scored)| {
+                let chunk = file.chunks[scored.chunk];
+                SearchHit {
+                    path: file.rel.clone(),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    score: scored.score,
+                    symbols: file
+                        .symbols
+                        .iter()
+                        .filter(|symbol| {
+                            (chunk.start_line..=chunk.end_line).contains(&symbol.start_line)
+                        })
+                        .map(|symbol| format!("{} {}", symbol.kind.as_str(), symbol.name))
+                        .collect(),
+                    snippet: self.snippet(&file.rel, chunk, &terms),
+                }
+            })
+            .collect()
+    }
+
+    /// Lists files and directories under `start` (relative to the root),
+    /// honoring ignore files. `start` itself is not included.
+    fn walk(&self, start: &str, max_depth: Option<usize>) -> Walked {
+        let mut walked = Walked::default();
+        for entry in ignore::WalkBuilder::new(self.root.join(start))
+            .max_depth(max_depth)
+            .build()
+            .flatten()
+        {
+            if entry.depth() == 0 {
+                continue;
+            }
+            let Some(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            let Some(rel) = relative_path(&self.root, path) else {
+                continue;
+            };
+            if file_type.is_dir() {
+                walked.dirs.push(rel);
+                continue;
+            }
+            if !file_type.is_file() || SKIPPED_SUFFIXES.iter().any(|suffix| rel.ends_with(suffix)) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() > MAX_FILE_BYTES {
+                continue;
+            }
+            let mtime_ns = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos() as u64)
+                .unwrap_or(0);
+            walked.files.push(PendingFile {
+                rel,
+                path: path.to_path_buf(),
+                mtime_ns,
+                len: metadata.len(),
+            });
+        }
+        walked
+    }
+
+    /// Re-reads the candidates whose mtime or size changed and returns how
+    /// many that was.
+    fn update(&mut self, candidates: Vec<PendingFile>) -> usize {
+        let mut changed = BTreeMap::new();
+        for file in candidates {
+            let unchanged = self
+                .paths
+                .get(&file.rel)
+                .and_then(|id| self.index.file(*id))
+                .is_some_and(|indexed| {
+                    indexed.mtime_ns == file.mtime_ns && indexed.len == file.len
+                });
+            if !unchanged {
+                changed.insert(file.rel.clone(), file);
+            }
+        }
+        let updated_files = changed.len();
+        for (rel, entry) in index_files(changed.into_values().collect(), &self.store) {
+            match entry {
+                Some(entry) => self.insert(entry),
+                // Unreadable: keep it out of the index.
+                None => self.remove(&rel),
+            }
+        }
+        updated_files
+    }
+
+    fn insert(&mut self, entry: FileEntry) {
+        let rel = entry.rel.clone();
+        let id = self.index.insert(entry);
+        if let Some(old) = self.paths.insert(rel, id) {
+            self.index.remove(old);
+        }
+    }
+
+    fn remove(&mut self, rel: &str) {
+        if let Some(id) = self.paths.remove(rel) {
+            self.index.remove(id);
+        }
+        if let Err(err) = self.store.remove(rel) {
+            tracing::warn!("failed to remove code index entry for {rel}: {err}");
+        }
+    }
+
+    /// Drops `dir` and everything below it; returns the number of files.
+    fn remove_dir(&mut self, dir: &str) -> usize {
+        let prefix = child_prefix(dir);
+        let files: Vec<String> = self
+            .paths
+            .range(prefix.clone()..)
+            .map(|(rel, _)| rel)
+            .take_while(|rel| rel.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for rel in &files {
+            self.remove(rel);
+        }
+        self.dirs
+            .retain(|rel| rel != dir && !rel.starts_with(&prefix));
+        files.len()
+    }
+
+    fn stats(&self, updated_files: usize, removed_files: usize) -> RefreshStats {
+        RefreshStats {
+            indexed_files: self.index.indexed_files(),
+            updated_files,
+            removed_files,
+        }
+    }
+
+    /// Reads the chunk back from disk and shows the lines around the first
+    /// one that mentions a query term.
+    fn snippet(&self, rel: &str, chunk: ChunkSpan, terms: &[String]) -> String {
+        let Ok(text) = std::fs::read_to_string(self.root.join(rel)) else {
+            return String::new();
+        };
+        let lines: Vec<&str> = text
+            .lines()
+            .skip(chunk.start_line - 1)
+            .take(chunk.end_line + 1 - chunk.start_line)
+            .collect();
+        let first_match = lines
+            .iter()
+            .position(|line| {
+                bm25::tokenize(line)
+                    .iter()
+                    .any(|token| terms.binary_search(token).is_ok())
+            })
+            .unwrap_or(0);
+        let start = first_match.saturating_sub(2);
+        lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(SNIPPET_LINES)
+            .map(|(offset, line)| {
+                let line = if line.len() > MAX_SNIPPET_LINE_LENGTH {
+                    take_bytes_at_char_boundary(line, MAX_SNIPPET_LINE_LENGTH)
+                } else {
+                    line
+                };
+                format!("L{}: {line}", chunk.start_line + offset)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Files and directories found by a walk, relative to the root.
+#[derive(Default)]
+struct Walked {
+    files: Vec<PendingFile>,
+    dirs: Vec<String>,
+}
+
+/// Keys directly inside `dir` (relative to the root), from a sorted key set
+/// that starts at [`child_prefix`]`(dir)`.
+fn children<'a>(keys: impl Iterator<Item = &'a String>, dir: &str) -> Vec<&'a str> {
+    let prefix = child_prefix(dir);
+    keys.map(String::as_str)
+        .take_while(|rel| rel.starts_with(&prefix))
+        .filter(|rel| !rel.is_empty() && !rel[prefix.len()..].contains('/'))
+        .collect()
+}
+
+fn child_prefix(dir: &str) -> String {
+    if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{dir}/")
+    }
+}
+
+/// Result of [`CodeIndexManager::search`].
+pub(crate) enum CodeSearch {
+    Ready {
+        hits: Vec<SearchHit>,
+        stats: RefreshStats,
+    },
+    /// The index for the root is still being built in the background.
+    Building,
+}
+
+/// Owns the session's index: builds it in the background, keeps a watcher on
+/// the root and applies the changes it reports before each search.
+pub(crate) struct CodeIndexManager {
+    codex_home: PathBuf,
+    state: Arc<Mutex<Option<ManagedIndex>>>,
+}
+
+enum ManagedIndex {
+    Building { root: PathBuf },
+    Ready(Box<LiveIndex>),
+}
+
+impl ManagedIndex {
+    fn root(&self) -> &Path {
+        match self {
+            ManagedIndex::Building { root } => root,
+            ManagedIndex::Ready(live) => live.index.root(),
+        }
+    }
+}
+
+struct LiveIndex {
+    index: CodeIndex,
+    changes: Arc<Mutex<PendingChanges>>,
+    /// Keeps the watch alive. `None` when the platform watcher could not be
+    /// started; every search then walks the tree.
+    watcher: Option<RecommendedWatcher>,
+}
+
+/// Paths reported by the watcher since the last search.
+#[derive(Default)]
+struct PendingChanges {
+    paths: HashSet<PathBuf>,
+    /// Events were dropped or too many piled up; walk the whole tree.
+    rescan: bool,
+}
+
+impl CodeIndexManager {
+    pub(crate) fn new(codex_home: &Path) -> Self {
+        Self {
+            codex_home: codex_home.to_path_buf(),
+            state: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Starts building the index for `root` on a blocking thread, unless it
+    /// is already built or being built. Replaces the index of another root.
+    pub(crate) fn start(&self, root: PathBuf) {
+        {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            if state.as_ref().is_some_and(|state| state.root() == root) {
+                return;
+            }
+            *state = Some(ManagedIndex::Building { root: root.clone() });
+        }
+        let codex_home = self.codex_home.clone();
+        let state = Arc::downgrade(&self.state);
+        tokio::task::spawn_blocking(move || {
+            let live = LiveIndex::build(&codex_home, root.clone());
+            // The session may have ended, or moved on to another root.
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+            if matches!(&*state, Some(ManagedIndex::Building { root: building }) if *building == root)
+            {
+                *state = Some(ManagedIndex::Ready(Box::new(live)));
+            }
+        });
+    }
+
+    /// Applies pending changes to the index for `root` and searches it. While
+    /// the index is being built this returns [`CodeSearch::Building`] instead
+    /// of waiting, and starts the build if nothing has yet.
+    pub(crate) async fn search(
+        &self,
+        root: PathBuf,
+        query: String,
+        limit: usize,
+        prefix: Option<String>,
+    ) -> io::Result<CodeSearch> {
+        let state = Arc::clone(&self.state);
+        let search_root = root.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut guard = state
+                .lock()
+                .map_err(|_| io::Error::other("code index lock poisoned"))?;
+            Ok::<_, io::Error>(match guard.as_mut() {
+                Some(ManagedIndex::Ready(live)) if live.index.root() == search_root => {
+                    let stats = live.refresh();
+                    let hits = live.index.search(&query, limit, prefix.as_deref());
+                    Some(CodeSearch::Ready { hits, stats })
+                }
+                Some(ManagedIndex::Building { root }) if *root == search_root => {
+                    Some(CodeSearch::Building)
+                }
+                _ => None,
+            })
+        })
+        .await
+        .map_err(io::Error::other)??;
+        match result {
+            Some(result) => Ok(result),
+            None => {
+                self.start(root);
+                Ok(CodeSearch::Building)
+            }
+        }
+    }
+}
+
+impl LiveIndex {
+    fn build(codex_home: &Path, root: PathBuf) -> Self {
+        let changes = Arc::new(Mutex::new(PendingChanges::default()));
+        // Watch before walking so nothing that changes during the walk is
+        // missed.
+        let watcher = watch(&root, Arc::clone(&changes));
+        let mut index = CodeIndex::open(codex_home, &root);
+        let stats = index.refresh();
+        tracing::debug!(
+            "code index for {} ready: {} files, {} updated, {} removed",
+            root.display(),
+            stats.indexed_files,
+            stats.updated_files,
+            stats.removed_files
+        );
+        Self {
+            index,
+            changes,
+            watcher,
+        }
+    }
+
+    fn refresh(&mut self) -> RefreshStats {
+        let changes = match self.changes.lock() {
+            Ok(mut changes) => std::mem::take(&mut *changes),
+            Err(_) => PendingChanges {
+                rescan: true,
+                ..Default::default()
+            },
+        };
+        if self.watcher.is_none() || changes.rescan {
+            self.index.refresh()
+        } else {
+            self.index.refresh_paths(&changes.paths)
+        }
+    }
+}
+
+impl PendingChanges {
+    fn record(&mut self, event: notify::Result<notify::Event>) {
+        if self.rescan {
+            return;
+        }
+        match event {
+            Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+            Ok(event) => {
+                if event.need_rescan() {
+                    self.rescan = true;
+                }
+                // Git rewrites its own files on every command.
+                self.paths.extend(event.paths.into_iter().filter(|path| {
+                    !path
+                        .components()
+                        .any(|component| component.as_os_str() == ".git")
+                }));
+                if self.paths.len() > MAX_PENDING_PATHS {
+                    self.rescan = true;
+                }
+            }
+            Err(err) => {
+                tracing::warn!("code index watcher error: {err}");
+                self.rescan = true;
+            }
+        }
+        if self.rescan {
+            self.paths.clear();
+        }
+    }
+}
+
+fn watch(root: &Path, changes: Arc<Mutex<PendingChanges>>) -> Option<RecommendedWatcher> {
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(mut changes) = changes.lock() {
+            changes.record(event);
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::warn!(
+                "cannot watch {} for code index updates: {err}",
+                root.display()
+            );
+            return None;
+        }
+    };
+    if let Err(err) = watcher.watch(root, RecursiveMode::Recursive) {
+        tracing::warn!(
+            "cannot watch {} for code index updates: {err}",
+            root.display()
+        );
+        return None;
+    }
+    Some(watcher)
+}
+
+/// A new or changed file found by a walk.
+struct PendingFile {
+    rel: String,
+    path: PathBuf,
+    mtime_ns: u64,
+    len: u64,
+}
+
+/// Parses the files and writes their shards. Returns `None` entries for files
+/// that could not be read.
+fn index_files(files: Vec<PendingFile>, store: &ShardStore) -> Vec<(String, Option<FileEntry>)> {
+    let index_one = |file: PendingFile| {
+        let entry = index_file(&file);
+        if let Some(entry) = &entry
+            && let Err(err) = store.write(entry)
+        {
+            tracing::warn!("failed to save code index entry for {}: {err}", file.rel);
+        }
+        (file.rel, entry)
+    };
+    let threads = std::thread::available_parallelism()
+        .map(std::num::NonZeroUsize::get)
+        .unwrap_or(1);
+    if files.len() < 64 || threads == 1 {
+        return files.into_iter().map(index_one).collect();
+    }
+    // The first build of a large tree is dominated by parsing; spread it out.
+    let per_thread = files.len().div_ceil(threads);
+    let mut batches = Vec::new();
+    let mut files = files.into_iter().peekable();
+    while files.peek().is_some() {
+        batches.push(files.by_ref().take(per_thread).collect::<Vec<_>>());
+    }
+    std::thread::scope(|scope| {
+        // Collected so every batch is spawned before the first join.
+        #[allow(clippy::needless_collect)]
+        let handles: Vec<_> = batches
+            .into_iter()
+            .map(|batch| scope.spawn(move || batch.into_iter().map(index_one).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+/// Returns `None` when the file cannot be read.
+fn index_file(file: &PendingFile) -> Option<FileEntry> {
+    let bytes = std::fs::read(&file.path).ok()?;
+    let text = if bytes.iter().take(8 * 1024).any(|byte| *byte == 0) {
+        None
+    } else {
+        String::from_utf8(bytes).ok()
+    };
+    let Some(text) = text else {
+        return Some(FileEntry {
+            rel: file.rel.clone(),
+            mtime_ns: file.mtime_ns,
+            len: file.len,
+            symbols: Vec::new(),
+            chunks: Vec::new(),
+        });
+    };
+    let symbols = SourceLanguage::from_path(&file.path)
+        .map(|language| extract_symbols(language, &text))
+        .unwrap_or_default();
+    let chunks = build_chunks(&text, &symbols);
+    Some(FileEntry {
+        rel: file.rel.clone(),
+        mtime_ns: file.mtime_ns,
+        len: file.len,
+        symbols,
+        chunks,
+    })
+}
+
+/// Splits a file into chunks that start at symbol definitions where
+/// possible, so a hit usually covers one function or type.
+fn build_chunks(text: &str, symbols: &[Symbol]) -> Vec<Chunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let boundaries: HashSet<usize> = symbols.iter().map(|symbol| symbol.start_line).collect();
+    let mut chunks = Vec::new();
+    let mut start = 1;
+    for line in 1..=lines.len() {
+        let size = line - start;
+        let at_boundary = boundaries.contains(&line) && size >= MIN_CHUNK_LINES;
+        if line > start && (at_boundary || size >= MAX_CHUNK_LINES) {
+            chunks.push(make_chunk(&lines, start, line - 1));
+            start = line;
+        }
+    }
+    if start <= lines.len() {
+        chunks.push(make_chunk(&lines, start, lines.len()));
+    }
+    chunks.retain(|chunk| chunk.len > 0);
+    chunks
+}
+
+fn make_chunk(lines: &[&str], start_line: usize, end_line: usize) -> Chunk {
+    let (terms, len) = bm25::term_counts(&lines[start_line - 1..end_line].join("\n"));
+    Chunk {
+        start_line,
+        end_line,
+        len,
+        terms,
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let parts: Vec<&str> = rel
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+fn is_under(rel: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    prefix.is_empty()
+        || rel == prefix
+        || rel
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const CONFIG_RS: &str = r#"use std::path::Path;
+
+/// Reads the configuration file.
+pub fn parse_config(path: &Path) -> Config {
+    let text = std::fs::read_to_string(path).unwrap();
+    Config { text }
+}
+
+pub struct Config {
+    text: String,
+}
+"#;
+
+    #[test]
+    fn finds_symbols_and_refreshes_incrementally() {
+        let home = tempdir().unwrap();
+        let repo = tempdir().unwrap();
+        std::fs::create_dir_all(repo.path().join("src")).unwrap();
+        std::fs::write(repo.path().join("src/config.rs"), CONFIG_RS).unwrap();
+        std::fs::write(
+            repo.path().join("src/retry.py"),
+            "def backoff(attempt):\n    return 2 ** attempt\n",
+        )
+        .unwrap();
+
+        let mut index = CodeIndex::open(home.path(), repo.path());
+        let stats = index.refresh();
+        assert_eq!(
+            stats,
+            RefreshStats {
+                indexed_files: 2,
+                updated_files: 2,
+                removed_files: 0,
+            }
+        );
+
+        let hits = index.search("parseConfig", 5, None);
+        assert_eq!(hits[0].path, "src/config.rs");
+        assert_eq!(hits[0].symbols, vec!["fn parse_config"]);
+        assert!(
+            hits[0].snippet.contains("L4: pub fn parse_config"),
+            "{}",
+            hits[0].snippet
+        );
+
+        let hits = index.search("backoff", 5, Some("src/retry.py"));
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].start_line, hits[0].end_line), (1, 2));
+        assert!(index.search("backoff", 5, Some("lib")).is_empty());
+
+        // A fresh process picks the stored index up and only re-reads changes.
+        let mut reopened = CodeIndex::open(home.path(), repo.path());
+        std::fs::remove_file(repo.path().join("src/retry.py")).unwrap();
+        assert_eq!(
+            reopened.refresh(),
+            RefreshStats {
+                indexed_files: 1,
+                updated_files: 0,
+                removed_files: 1,
+            }
+        );
+        assert!(reopened.search("backoff", 5, None).is_empty());
+    }
+
+    #[test]
+    fn refresh_paths_rescans_only_changed_directories() {
+        let home = tempdir().unwrap();
+        let repo = tempdir().unwrap();
+        std::fs::write(repo.path().join(".ignore"), "generated/\n").unwrap();
+        std::fs::create_dir_all(repo.path().join("src")).unwrap();
+        std::fs::write(repo.path().join("src/config.rs"), CONFIG_RS).unwrap();
+        std::fs::write(
+            repo.path().join("src/retry.py"),
+            "def backoff():\n    pass\n",
+        )
+        .unwrap();
+        std::fs::write(repo.path().join("README.md"), "retry docs\n").unwrap();
+        let mut index = CodeIndex::open(home.path(), repo.path());
+        index.refresh();
+
+        std::fs::write(
+            repo.path().join("src/retry.py"),
+            "def jittered_backoff(attempt):\n    return 2 ** attempt\n",
+        )
+        .unwrap();
+        std::fs::remove_file(repo.path().join("src/config.rs")).unwrap();
+        std::fs::create_dir_all(repo.path().join("src/net")).unwrap();
+        std::fs::write(repo.path().join("src/net/http.rs"), "fn fetch_page() {}\n").unwrap();
+        std::fs::create_dir_all(repo.path().join("generated")).unwrap();
+        std::fs::write(repo.path().join("generated/fetch.rs"), "fn fetch() {}\n").unwrap();
+        let changed: HashSet<PathBuf> = [
+            "src/retry.py",
+            "src/config.rs",
+            "src/net",
+            "src/net/http.rs",
+            "generated",
+            "generated/fetch.rs",
+        ]
+        .into_iter()
+        .map(|rel| repo.path().join(rel))
+        .collect();
+        assert_eq!(
+            index.refresh_paths(&changed),
+            RefreshStats {
+                indexed_files: 3,
+                updated_files: 2,
+                removed_files: 1,
+            }
+        );
+        let paths = |query: &str| -> Vec<String> {
+            index
+                .search(query, 5, None)
+                .into_iter()
+                .map(|hit| hit.path)
+                .collect()
+        };
+        assert_eq!(paths("jittered_backoff"), vec!["src/retry.py"]);
+        assert_eq!(paths("fetch"), vec!["src/net/http.rs"]);
+        assert!(paths("parseConfig").is_empty());
+
+        // The shards on disk match, so a new session starts from this state.
+        let mut reopened = CodeIndex::open(home.path(), repo.path());
+        assert_eq!(
+            reopened.refresh(),
+            RefreshStats {
+                indexed_files: 3,
+                updated_files: 0,
+                removed_files: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn unchanged_binary_files_are_not_reindexed() {
+        let home = tempdir().unwrap();
+        let repo = tempdir().unwrap();
+        std::fs::write(repo.path().join("config.rs"), CONFIG_RS).unwrap();
+        std::fs::write(repo.path().join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0").unwrap();
+        std::fs::write(repo.path().join("latin1.txt"), b"caf\xe9\n").unwrap();
+
+        let mut index = CodeIndex::open(home.path(), repo.path());
+        assert_eq!(
+            index.refresh(),
+            RefreshStats {
+                indexed_files: 1,
+                updated_files: 3,
+                removed_files: 0,
+            }
+        );
+        assert_eq!(
+            index.refresh(),
+            RefreshStats {
+                indexed_files: 1,
+                updated_files: 0,
+                removed_files: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn chunks_start_at_symbols() {
+        let mut text = String::new();
+        for i in 0..20 {
+            text.push_str(&format!(
+                "fn f{i}() {{\n    body();\n    more();\n    done();\n}}\n"
+            ));
+        }
+        let symbols = extract_symbols(SourceLanguage::Rust, &text);
+        let chunks = build_chunks(&text, &symbols);
+        let ranges: Vec<(usize, usize)> = chunks
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect();
+        assert_eq!(ranges[0], (1, 10));
+        assert_eq!(ranges[1], (11, 20));
+        assert_eq!(ranges.last(), Some(&(91, 100)));
+    }
+}
@@ -0,0 +1,290 @@
+//! In-memory inverted index: for every term, the chunks that contain it.
+//!
+//! Replacing or deleting a file only retires its id and lowers the live
+//! counts of its terms; searches skip postings of retired files, and the
+//! lists are compacted once dead postings make up half of them.
+
+use std::collections::HashMap;
+
+use super::FileEntry;
+use super::SYMBOL_BOOST;
+use super::Symbol;
+use super::bm25;
+
+pub(super) type FileId = u32;
+
+/// Below this many dead postings compaction is not worth the pass.
+const MIN_COMPACTION_POSTINGS: usize = 1 << 16;
+
+pub(super) struct IndexedFile {
+    pub(super) rel: String,
+    pub(super) mtime_ns: u64,
+    pub(super) len: u64,
+    pub(super) symbols: Vec<Symbol>,
+    pub(super) chunks: Vec<ChunkSpan>,
+    /// Each term in the file and how many of its chunks contain it, so the
+    /// file can be retired without walking posting lists.
+    terms: Vec<(u32, u32)>,
+}
+
+/// Lines are 1-indexed and inclusive.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ChunkSpan {
+    pub(super) start_line: usize,
+    pub(super) end_line: usize,
+    pub(super) len: u32,
+}
+
+struct Posting {
+    file: FileId,
+    chunk: u32,
+    term_freq: u32,
+}
+
+#[derive(Default)]
+struct TermPostings {
+    postings: Vec<Posting>,
+    /// Postings that belong to files still in the index, i.e. the term's
+    /// document frequency.
+    live: usize,
+}
+
+pub(super) struct ScoredChunk {
+    pub(super) file: FileId,
+    pub(super) chunk: usize,
+    pub(super) score: f64,
+}
+
+#[derive(Default)]
+pub(super) struct InvertedIndex {
+    files: Vec<Option<IndexedFile>>,
+    /// Retired ids whose postings are gone, so they can be handed out again.
+    free_ids: Vec<FileId>,
+    term_ids: HashMap<String, u32>,
+    terms: Vec<TermPostings>,
+    live_chunks: usize,
+    live_len: u64,
+    indexed_files: usize,
+    postings: usize,
+    dead_postings: usize,
+}
+
+impl InvertedIndex {
+    pub(super) fn file(&self, id: FileId) -> Option<&IndexedFile> {
+        self.files.get(id as usize).and_then(Option::as_ref)
+    }
+
+    /// Files with at least one chunk, i.e. not binary or empty.
+    pub(super) fn indexed_files(&self) -> usize {
+        self.indexed_files
+    }
+
+    pub(super) fn insert(&mut self, entry: FileEntry) -> FileId {
+        let id = match self.free_ids.pop() {
+            Some(id) => id,
+            None => {
+                self.files.push(None);
+                (self.files.len() - 1) as FileId
+            }
+        };
+        let mut file_terms: HashMap<u32, u32> = HashMap::new();
+        let mut chunks = Vec::with_capacity(entry.chunks.len());
+        for (chunk_index, chunk) in entry.chunks.into_iter().enumerate() {
+            for (term, term_freq) in chunk.terms {
+                let term_id = self.term_id(term);
+                let term_postings = &mut self.terms[term_id as usize];
+                term_postings.postings.push(Posting {
+                    file: id,
+                    chunk: chunk_index as u32,
+                    term_freq,
+                });
+                term_postings.live += 1;
+                *file_terms.entry(term_id).or_default() += 1;
+                self.postings += 1;
+            }
+            self.live_chunks += 1;
+            self.live_len += u64::from(chunk.len);
+            chunks.push(ChunkSpan {
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                len: chunk.len,
+            });
+        }
+        if !chunks.is_empty() {
+            self.indexed_files += 1;
+        }
+        self.files[id as usize] = Some(IndexedFile {
+            rel: entry.rel,
+            mtime_ns: entry.mtime_ns,
+            len: entry.len,
+            symbols: entry.symbols,
+            chunks,
+            terms: file_terms.into_iter().collect(),
+        });
+        id
+    }
+
+    pub(super) fn remove(&mut self, id: FileId) {
+        let Some(file) = self.files.get_mut(id as usize).and_then(Option::take) else {
+            return;
+        };
+        for (term_id, chunks) in file.terms {
+            self.terms[term_id as usize].live -= chunks as usize;
+            self.dead_postings += chunks as usize;
+        }
+        self.live_chunks -= file.chunks.len();
+        self.live_len -= file
+            .chunks
+            .iter()
+            .map(|chunk| u64::from(chunk.len))
+            .sum::<u64>();
+        if !file.chunks.is_empty() {
+            self.indexed_files -= 1;
+        }
+        if self.dead_postings >= MIN_COMPACTION_POSTINGS && self.dead_postings * 2 >= self.postings
+        {
+            self.compact();
+        }
+    }
+
+    /// Scores every chunk that contains a query term with BM25, plus a boost
+    /// for chunks defining a symbol named like a term. `terms` must be sorted
+    /// and deduplicated; `include` filters by path relative to the root.
+    pub(super) fn search(
+        &self,
+        terms: &[String],
+        include: impl Fn(&str) -> bool,
+    ) -> Vec<ScoredChunk> {
+        if self.live_chunks == 0 {
+            return Vec::new();
+        }
+        let avg_doc_len = self.live_len as f64 / self.live_chunks as f64;
+        let mut included: HashMap<FileId, bool> = HashMap::new();
+        let mut scores: HashMap<(FileId, u32), f64> = HashMap::new();
+        let mut idfs = Vec::with_capacity(terms.len());
+        for term in terms {
+            let term_postings = self
+                .term_ids
+                .get(term)
+                .map(|term_id| &self.terms[*term_id as usize]);
+            let idf = bm25::idf(
+                self.live_chunks,
+                term_postings.map_or(0, |term_postings| term_postings.live),
+            );
+            idfs.push(idf);
+            for posting in term_postings
+                .iter()
+                .flat_map(|term_postings| &term_postings.postings)
+            {
+                let Some(file) = self.file(posting.file) else {
+                    continue;
+                };
+                if !*included
+                    .entry(posting.file)
+                    .or_insert_with(|| include(&file.rel))
+                {
+                    continue;
+                }
+                let chunk_len = file.chunks[posting.chunk as usize].len;
+                *scores.entry((posting.file, posting.chunk)).or_default() +=
+                    bm25::score(posting.term_freq, chunk_len, avg_doc_len, idf);
+            }
+        }
+
+        // `parseConfig` should find `parse_config`, so symbol names are
+        // compared without underscores.
+        let compact_terms: Vec<String> = terms.iter().map(|term| term.replace('_', "")).collect();
+        scores
+            .into_iter()
+            .filter_map(|((file_id, chunk), mut score)| {
+                let file = self.file(file_id)?;
+                let span = file.chunks[chunk as usize];
+                for symbol in file
+                    .symbols
+                    .iter()
+                    .filter(|symbol| (span.start_line..=span.end_line).contains(&symbol.start_line))
+                {
+                    let name = symbol.name.to_lowercase().replace('_', "");
+                    if let Some(index) = compact_terms.iter().position(|term| *term == name) {
+                        score += SYMBOL_BOOST * idfs[index];
+                    }
+                }
+                Some(ScoredChunk {
+                    file: file_id,
+                    chunk: chunk as usize,
+                    score,
+                })
+            })
+            .collect()
+    }
+
+    fn term_id(&mut self, term: String) -> u32 {
+        if let Some(term_id) = self.term_ids.get(&term) {
+            return *term_id;
+        }
+        let term_id = self.terms.len() as u32;
+        self.terms.push(TermPostings::default());
+        self.term_ids.insert(term, term_id);
+        term_id
+    }
+
+    fn compact(&mut self) {
+        let files = &self.files;
+        for term_postings in &mut self.terms {
+            term_postings
+                .postings
+                .retain(|posting| files[posting.file as usize].is_some());
+        }
+        self.free_ids = (0..self.files.len() as FileId)
+            .filter(|id| self.files[*id as usize].is_none())
+            .collect();
+        self.postings -= self.dead_postings;
+        self.dead_postings = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_index::Chunk;
+    use pretty_assertions::assert_eq;
+
+    fn entry(rel: &str, text: &str) -> FileEntry {
+        let (terms, len) = bm25::term_counts(text);
+        FileEntry {
+            rel: rel.to_string(),
+            mtime_ns: 0,
+            len: text.len() as u64,
+            symbols: Vec::new(),
+            chunks: vec![Chunk {
+                start_line: 1,
+                end_line: 1,
+                len,
+                terms,
+            }],
+        }
+    }
+
+    #[test]
+    fn replaced_files_stop_matching_and_ids_are_reused_after_compaction() {
+        let mut index = InvertedIndex::default();
+        let old = index.insert(entry("a.rs", "retry backoff"));
+        index.insert(entry("b.rs", "retry"));
+        index.remove(old);
+        let new = index.insert(entry("a.rs", "jitter"));
+        assert_ne!(old, new);
+
+        let hits = index.search(&["backoff".to_string(), "retry".to_string()], |_| true);
+        let paths: Vec<&str> = hits
+            .iter()
+            .filter_map(|hit| index.file(hit.file).map(|file| file.rel.as_str()))
+            .collect();
+        assert_eq!(paths, vec!["b.rs"]);
+        assert_eq!(index.indexed_files(), 2);
+
+        index.compact();
+        assert_eq!(index.free_ids, vec![old]);
+        assert_eq!(index.insert(entry("c.rs", "retry")), old);
+        assert_eq!(index.search(&["retry".to_string()], |_| true).len(), 2);
+    }
+}
@@ -0,0 +1,126 @@
+//! On-disk layout of the code index: one small shard per source file, so a
+//! refresh rewrites only the files that changed.
+//!
+//! `$CODEX_HOME/code_index/<hash of the root>/meta.json` records the format
+//! version and the root, and `files/<hash of the path>.json` holds one
+//! [`FileEntry`].
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+
+use super::FileEntry;
+
+const INDEX_VERSION: u32 = 2;
+const INDEX_DIR: &str = "code_index";
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Meta {
+    version: u32,
+    root: PathBuf,
+}
+
+pub(super) struct ShardStore {
+    dir: PathBuf,
+}
+
+impl ShardStore {
+    /// Opens the store for `root` and returns the entries saved by earlier
+    /// runs. A store written by another version, or for another root with the
+    /// same hash, is discarded.
+    pub(super) fn open(codex_home: &Path, root: &Path) -> (Self, Vec<FileEntry>) {
+        let base = codex_home.join(INDEX_DIR);
+        let hash = sha1_hex(root.to_string_lossy().as_bytes());
+        // Version 1 kept the whole index in a single file next to the shards.
+        let _ = std::fs::remove_file(base.join(format!("{hash}.json")));
+        let store = Self {
+            dir: base.join(hash),
+        };
+        let meta = Meta {
+            version: INDEX_VERSION,
+            root: root.to_path_buf(),
+        };
+        let stored = std::fs::read(store.meta_path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Meta>(&bytes).ok());
+        if stored.as_ref() != Some(&meta) {
+            if let Err(err) = store.reset(&meta) {
+                tracing::warn!(
+                    "failed to reset code index at {}: {err}",
+                    store.dir.display()
+                );
+            }
+            return (store, Vec::new());
+        }
+        let entries = store.load_shards();
+        (store, entries)
+    }
+
+    pub(super) fn write(&self, entry: &FileEntry) -> io::Result<()> {
+        let json = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        write_atomic(&self.shard_path(&entry.rel), &json)
+    }
+
+    pub(super) fn remove(&self, rel: &str) -> io::Result<()> {
+        match std::fs::remove_file(self.shard_path(rel)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn reset(&self, meta: &Meta) -> io::Result<()> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        std::fs::create_dir_all(self.files_dir())?;
+        let json = serde_json::to_vec(meta).map_err(io::Error::other)?;
+        write_atomic(&self.meta_path(), &json)
+    }
+
+    fn load_shards(&self) -> Vec<FileEntry> {
+        let Ok(dir) = std::fs::read_dir(self.files_dir()) else {
+            return Vec::new();
+        };
+        dir.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let bytes = std::fs::read(&path).ok()?;
+                serde_json::from_slice::<FileEntry>(&bytes).ok()
+            })
+            .collect()
+    }
+
+    fn meta_path(&self) -> PathBuf {
+        self.dir.join("meta.json")
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.dir.join("files")
+    }
+
+    fn shard_path(&self, rel: &str) -> PathBuf {
+        self.files_dir()
+            .join(format!("{}.json", sha1_hex(rel.as_bytes())))
+    }
+}
+
+/// Writes through a temporary file so readers, including other sessions on
+/// the same root, never see a partial shard.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,254 @@
+//! Symbol extraction with tree-sitter.
+
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+
+/// Languages whose definitions are extracted as symbols. Other text files are
+/// still indexed for full-text search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SourceLanguage {
+    Rust,
+    Python,
+    Go,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Bash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Trait,
+    Interface,
+    Impl,
+    Module,
+    Type,
+    Const,
+}
+
+impl SymbolKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "fn",
+            SymbolKind::Method => "method",
+            SymbolKind::Class => "class",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Impl => "impl",
+            SymbolKind::Module => "mod",
+            SymbolKind::Type => "type",
+            SymbolKind::Const => "const",
+        }
+    }
+}
+
+/// A named definition. Lines are 1-indexed and inclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Symbol {
+    pub(crate) name: String,
+    pub(crate) kind: SymbolKind,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+}
+
+impl SourceLanguage {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let language = match path.extension()?.to_str()? {
+            "rs" => SourceLanguage::Rust,
+            "py" | "pyi" => SourceLanguage::Python,
+            "go" => SourceLanguage::Go,
+            "js" | "jsx" | "mjs" | "cjs" => SourceLanguage::JavaScript,
+            "ts" | "mts" | "cts" => SourceLanguage::TypeScript,
+            "tsx" => SourceLanguage::Tsx,
+            "sh" | "bash" => SourceLanguage::Bash,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            SourceLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+            SourceLanguage::Python => tree_sitter_python::LANGUAGE.into(),
+            SourceLanguage::Go => tree_sitter_go::LANGUAGE.into(),
+            SourceLanguage::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            SourceLanguage::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            SourceLanguage::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            SourceLanguage::Bash => tree_sitter_bash::LANGUAGE.into(),
+        }
+    }
+
+    /// Maps a node kind to the symbol it defines. `in_container` is true
+    /// inside classes, impls and traits, where functions are methods.
+    fn symbol_kind(self, node_kind: &str, in_container: bool) -> Option<SymbolKind> {
+        let function = if in_container {
+            SymbolKind::Method
+        } else {
+            SymbolKind::Function
+        };
+        let kind = match (self, node_kind) {
+            (SourceLanguage::Rust, "function_item" | "function_signature_item") => function,
+            (SourceLanguage::Rust, "struct_item" | "union_item") => SymbolKind::Struct,
+            (SourceLanguage::Rust, "enum_item") => SymbolKind::Enum,
+            (SourceLanguage::Rust, "trait_item") => SymbolKind::Trait,
+            (SourceLanguage::Rust, "impl_item") => SymbolKind::Impl,
+            (SourceLanguage::Rust, "mod_item") => SymbolKind::Module,
+            (SourceLanguage::Rust, "type_item") => SymbolKind::Type,
+            (SourceLanguage::Rust, "const_item" | "static_item") => SymbolKind::Const,
+            (SourceLanguage::Python, "function_definition") => function,
+            (SourceLanguage::Python, "class_definition") => SymbolKind::Class,
+            (SourceLanguage::Go, "function_declaration") => SymbolKind::Function,
+            (SourceLanguage::Go, "method_declaration") => SymbolKind::Method,
+            (SourceLanguage::Go, "type_spec") => SymbolKind::Type,
+            (
+                SourceLanguage::JavaScript | SourceLanguage::TypeScript | SourceLanguage::Tsx,
+                "function_declaration" | "generator_function_declaration",
+            ) => function,
+            (
+                SourceLanguage::JavaScript | SourceLanguage::TypeScript | SourceLanguage::Tsx,
+                "class_declaration" | "abstract_class_declaration",
+            ) => SymbolKind::Class,
+            (
+                SourceLanguage::JavaScript | SourceLanguage::TypeScript | SourceLanguage::Tsx,
+                "method_definition",
+            ) => SymbolKind::Method,
+            (SourceLanguage::TypeScript | SourceLanguage::Tsx, "interface_declaration") => {
+                SymbolKind::Interface
+            }
+            (SourceLanguage::TypeScript | SourceLanguage::Tsx, "type_alias_declaration") => {
+                SymbolKind::Type
+            }
+            (SourceLanguage::TypeScript | SourceLanguage::Tsx, "enum_declaration") => {
+                SymbolKind::Enum
+            }
+            (SourceLanguage::Bash, "function_definition") => SymbolKind::Function,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// Extracts named definitions from `source`, in source order. Returns an empty
+/// list when the grammar cannot be loaded or parsing fails.
+pub(crate) fn extract_symbols(language: SourceLanguage, source: &str) -> Vec<Symbol> {
+    let mut parser = Parser::new();
+    if parser.set_language(&language.grammar()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let mut symbols = Vec::new();
+    // Explicit stack instead of recursion: generated code can nest deeply.
+    let mut stack: Vec<(Node, bool)> = vec![(tree.root_node(), false)];
+    while let Some((node, in_container)) = stack.pop() {
+        let kind = language.symbol_kind(node.kind(), in_container);
+        if let Some(kind) = kind
+            && let Some(name) = symbol_name(node, kind, source)
+        {
+            symbols.push(Symbol {
+                name,
+                kind,
+                start_line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+            });
+        }
+        let child_in_container = match kind {
+            Some(
+                SymbolKind::Class | SymbolKind::Impl | SymbolKind::Trait | SymbolKind::Interface,
+            ) => true,
+            Some(_) => false,
+            None => in_container,
+        };
+        let mut cursor = node.walk();
+        // Reversed so children are popped in source order.
+        let start = stack.len();
+        stack.extend(
+            node.named_children(&mut cursor)
+                .map(|child| (child, child_in_container)),
+        );
+        stack[start..].reverse();
+    }
+    symbols
+}
+
+fn symbol_name(node: Node, kind: SymbolKind, source: &str) -> Option<String> {
+    // `impl Display for Foo<T>` is named after the type it implements.
+    let field = if kind == SymbolKind::Impl {
+        "type"
+    } else {
+        "name"
+    };
+    let text = node
+        .child_by_field_name(field)?
+        .utf8_text(source.as_bytes())
+        .ok()?;
+    let name = text.split('<').next().unwrap_or(text).trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn names(symbols: &[Symbol]) -> Vec<(SymbolKind, &str, usize)> {
+        symbols
+            .iter()
+            .map(|symbol| (symbol.kind, symbol.name.as_str(), symbol.start_line))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_rust_items_and_methods() {
+        let source = r#"pub struct Config {
+    path: String,
+}
+
+impl Config<T> {
+    pub fn load() -> Self {
+        todo!()
+    }
+}
+
+fn main() {}
+"#;
+        let symbols = extract_symbols(SourceLanguage::Rust, source);
+        assert_eq!(
+            names(&symbols),
+            vec![
+                (SymbolKind::Struct, "Config", 1),
+                (SymbolKind::Impl, "Config", 5),
+                (SymbolKind::Method, "load", 6),
+                (SymbolKind::Function, "main", 11),
+            ]
+        );
+        assert_eq!(symbols[1].end_line, 9);
+    }
+
+    #[test]
+    fn extracts_python_classes_and_functions() {
+        let source = "class Store:\n    def get(self, key):\n        return key\n\ndef helper():\n    pass\n";
+        assert_eq!(
+            names(&extract_symbols(SourceLanguage::Python, source)),
+            vec![
+                (SymbolKind::Class, "Store", 1),
+                (SymbolKind::Method, "get", 2),
+                (SymbolKind::Function, "helper", 5),
+            ]
+        );
+    }
+}
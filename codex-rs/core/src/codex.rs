@@ -5,22 +5,37 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::time::SystemTime;
 
 use crate::AuthManager;
 use crate::SandboxState;
 use crate::auth::azure::AzureAuth;
+use crate::budget::BudgetCheck;
+use crate::budget::SessionBudget;
+use crate::checkpoints;
 use crate::client_common::REVIEW_PROMPT;
+use crate::code_index::CodeIndexManager;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
 use crate::compact_remote::run_inline_remote_auto_compact_task;
+use crate::custom_tools::load_custom_tools;
 use crate::exec_policy::ExecPolicyManager;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::hooks::HookContext;
+use crate::hooks::HookDecision;
+use crate::hooks::HookEvent;
+use crate::hooks::Hooks;
+use crate::hooks::SESSION_START_HOOKS_TIMEOUT;
+use crate::lsp::LspManager;
 use crate::models_manager::manager::ModelsManager;
 use crate::models_manager::model_family::ModelFamily;
+use crate::output_store::OutputStore;
 use crate::parse_command::parse_command;
 use crate::parse_turn_item;
+use crate::plan_mode::plan_mode_from_rollout;
+use crate::pricing::pricing_for_model;
 use crate::stream_events_utils::HandleOutputCtx;
 use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
@@ -28,8 +43,15 @@ use crate::terminal;
 use crate::truncate::TruncationPolicy;
 use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
+use crate::verify::Verifier;
+use crate::verify::VerifyState;
+use crate::verify::verify_turn_edits;
+use crate::web_fetch::WebFetcher;
 use async_channel::Receiver;
 use async_channel::Sender;
+use codex_git::GhostCommit;
+use codex_git::RestoreGhostCommitOptions;
+use codex_git::restore_ghost_commit_with_options;
 use codex_protocol::ConversationId;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::items::TurnItem;
@@ -56,6 +78,7 @@ use mcp_types::ListResourcesResult;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
+use mcp_types::ToolAnnotations;
 use serde_json;
 use serde_json::Value;
 use tokio::sync::Mutex;
@@ -81,6 +104,8 @@ use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::types::CompactStrategy;
+use crate::config::types::ModelPricing;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
@@ -89,21 +114,32 @@ use crate::error::Result as CodexResult;
 #[cfg(test)]
 use crate::exec::StreamOutput;
 use crate::exec_policy::ExecPolicyUpdateError;
+use crate::git_info::collect_git_status;
+use crate::git_info::get_git_repo_root;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+use crate::project_doc::discover_nested_project_doc_paths;
 use crate::project_doc::get_user_instructions;
+use crate::project_doc::loaded_project_docs_from_rollout;
+use crate::project_doc::read_nested_project_doc;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::BudgetExceededEvent;
+use crate::protocol::BudgetLimit;
+use crate::protocol::BudgetUpdatedEvent;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::Op;
+use crate::protocol::PlanApprovalRequestEvent;
+use crate::protocol::PlanModeUpdatedEvent;
+use crate::protocol::ProjectDocLoadedEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
@@ -114,6 +150,7 @@ use crate::protocol::SkillErrorInfo;
 use crate::protocol::SkillMetadata as ProtocolSkillMetadata;
 use crate::protocol::StreamErrorEvent;
 use crate::protocol::Submission;
+use crate::protocol::ThreadRolledBackEvent;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
@@ -145,6 +182,7 @@ use crate::tools::spec::ToolsConfigParams;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::DeveloperInstructions;
+use crate::user_instructions::NestedInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
@@ -155,6 +193,8 @@ use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::Checkpoint;
 use codex_protocol::protocol::CodexErrorInfo;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::user_input::UserInput;
@@ -224,6 +264,9 @@ impl Codex {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
 
+        crate::default_client::set_network_settings(&config.network)
+            .map_err(|err| CodexErr::Fatal(format!("invalid [network] configuration: {err}")))?;
+
         let loaded_skills = config
             .features
             .enabled(Feature::Skills)
@@ -274,6 +317,7 @@ impl Codex {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source,
+            plan_mode: false,
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -379,6 +423,9 @@ pub(crate) struct TurnContext {
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    /// Plan mode: writes are rejected and commands run under a read-only
+    /// sandbox until the user approves the plan.
+    pub(crate) plan_mode: bool,
 }
 
 impl TurnContext {
@@ -389,9 +436,23 @@ impl TurnContext {
     }
 
     pub(crate) fn compact_prompt(&self) -> &str {
-        self.compact_prompt
-            .as_deref()
-            .unwrap_or(compact::SUMMARIZATION_PROMPT)
+        if let Some(prompt) = self.compact_prompt.as_deref() {
+            return prompt;
+        }
+        match self.client.config().compact_strategy {
+            CompactStrategy::Structured => compact::STRUCTURED_SUMMARIZATION_PROMPT,
+            CompactStrategy::Single | CompactStrategy::Rolling => compact::SUMMARIZATION_PROMPT,
+        }
+    }
+
+    /// Token prices for this turn's model, if it is in the pricing catalog.
+    pub(crate) fn model_pricing(&self) -> Option<ModelPricing> {
+        let config = self.client.config();
+        pricing_for_model(
+            &config.pricing,
+            &config.model_provider_id,
+            &self.client.get_model(),
+        )
     }
 }
 
@@ -436,6 +497,8 @@ pub(crate) struct SessionConfiguration {
     original_config_do_not_use: Arc<Config>,
     /// Source of the session (cli, vscode, exec, mcp, ...)
     session_source: SessionSource,
+    /// Whether writes are held back until the user approves a plan.
+    plan_mode: bool,
 }
 
 impl SessionConfiguration {
@@ -531,7 +594,11 @@ impl Session {
             compact_prompt: session_configuration.compact_prompt.clone(),
             user_instructions: session_configuration.user_instructions.clone(),
             approval_policy: session_configuration.approval_policy.value(),
-            sandbox_policy: session_configuration.sandbox_policy.get().clone(),
+            sandbox_policy: if session_configuration.plan_mode {
+                SandboxPolicy::new_read_only_policy()
+            } else {
+                session_configuration.sandbox_policy.get().clone()
+            },
             shell_environment_policy: per_turn_config.shell_environment_policy.clone(),
             tools_config,
             ghost_snapshot: per_turn_config.ghost_snapshot.clone(),
@@ -542,6 +609,7 @@ impl Session {
                 per_turn_config.as_ref(),
                 model_family.truncation_policy,
             ),
+            plan_mode: session_configuration.plan_mode,
         }
     }
 
@@ -597,6 +665,7 @@ impl Session {
         let auth_statuses_fut = compute_auth_statuses(
             config.mcp_servers.iter(),
             config.mcp_oauth_credentials_store_mode,
+            &config.network,
         );
         // Create Azure auth if configured
         let azure_auth_fut = async {
@@ -679,6 +748,8 @@ impl Session {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(config.notify.clone()),
+            hooks: Hooks::new(&config.hooks),
+            budget: SessionBudget::new(config.budget.clone()),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: Arc::new(default_shell),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -689,7 +760,21 @@ impl Session {
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
+            output_store: OutputStore::new(&config.codex_home, conversation_id),
+            code_index: CodeIndexManager::new(&config.codex_home),
+            lsp: LspManager::new(config.lsp.clone()),
+            custom_tools: load_custom_tools(&config),
+            web_fetch: WebFetcher::new(&config.fetch_url),
+            verifier: Verifier::new(&config.verify),
         };
+        if config.features.enabled(Feature::CodeIndex) {
+            // Build in the background so the first search_code call does not
+            // wait for a whole-tree parse.
+            let cwd = &session_configuration.cwd;
+            services
+                .code_index
+                .start(get_git_repo_root(cwd).unwrap_or_else(|| cwd.clone()));
+        }
 
         let sess = Arc::new(Session {
             conversation_id,
@@ -724,6 +809,30 @@ impl Session {
         for event in events {
             sess.send_event_raw(event).await;
         }
+        if sess.services.budget.is_enabled() {
+            let mut budget_events = vec![EventMsg::BudgetUpdated(BudgetUpdatedEvent {
+                status: sess.services.budget.status(),
+            })];
+            if sess.services.budget.limits().max_cost_usd.is_some()
+                && pricing_for_model(
+                    &config.pricing,
+                    &config.model_provider_id,
+                    &session_configuration.model,
+                )
+                .is_none()
+            {
+                budget_events.push(EventMsg::Warning(WarningEvent {
+                    message: "budget.max_cost_usd is set, but no pricing is available for this model, so the cost limit is not enforced.".to_string(),
+                }));
+            }
+            for msg in budget_events {
+                sess.send_event_raw(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg,
+                })
+                .await;
+            }
+        }
 
         // Construct sandbox_state before initialize() so it can be sent to each
         // MCP server immediately after it becomes ready (avoiding blocking).
@@ -749,6 +858,27 @@ impl Session {
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
 
+        // Finish the session_start hooks before returning so the submission
+        // loop cannot start the first turn until whatever they set up exists.
+        if !sess.hooks().is_empty() {
+            let context = HookContext {
+                session_id: sess.conversation_id.to_string(),
+                turn_id: None,
+                cwd: &session_configuration.cwd,
+            };
+            let mut event = HookEvent::SessionStart {};
+            let run = sess.hooks().run(&context, &mut event);
+            if tokio::time::timeout(SESSION_START_HOOKS_TIMEOUT, run)
+                .await
+                .is_err()
+            {
+                warn!(
+                    "session_start hooks did not finish within {}s; starting the session anyway",
+                    SESSION_START_HOOKS_TIMEOUT.as_secs()
+                );
+            }
+        }
+
         Ok(sess)
     }
 
@@ -781,6 +911,100 @@ impl Session {
         state.get_total_token_usage()
     }
 
+    /// Prunes old tool outputs from history once token usage passes the
+    /// configured share of the auto-compaction limit.
+    async fn maybe_prune_context(&self, turn_context: &TurnContext, auto_compact_limit: i64) {
+        let config = turn_context.client.config();
+        let pruning = &config.context_pruning;
+        if !pruning.enabled || auto_compact_limit == i64::MAX {
+            return;
+        }
+        let start = auto_compact_limit.saturating_mul(i64::from(pruning.start_percent)) / 100;
+        let mut state = self.state.lock().await;
+        if state.get_total_token_usage() < start {
+            return;
+        }
+        let freed = state
+            .history
+            .prune_tool_outputs(pruning.keep_recent_turns, pruning.min_output_tokens);
+        if freed > 0 {
+            info!(freed_tokens = freed, "pruned old tool outputs from history");
+        }
+    }
+
+    /// Turn context used to summarize the conversation. Uses `compact_model`
+    /// and `compact_provider` when configured and `turn_context` otherwise.
+    pub(crate) async fn compaction_turn_context(
+        &self,
+        turn_context: &Arc<TurnContext>,
+    ) -> Arc<TurnContext> {
+        let config = turn_context.client.config();
+        if config.compact_model.is_none() && config.compact_provider.is_none() {
+            return Arc::clone(turn_context);
+        }
+        let model = config
+            .compact_model
+            .clone()
+            .unwrap_or_else(|| turn_context.client.get_model());
+        let provider = config
+            .compact_provider
+            .clone()
+            .unwrap_or_else(|| turn_context.client.get_provider());
+        let model_family = self
+            .services
+            .models_manager
+            .construct_model_family(&model, &config)
+            .await;
+
+        let mut per_turn_config = (*config).clone();
+        per_turn_config.model = Some(model.clone());
+        per_turn_config.model_provider = provider.clone();
+        // The session's effort may not be supported by a different model.
+        if config.compact_model.is_some() {
+            per_turn_config.model_reasoning_effort = model_family.default_reasoning_effort;
+        }
+        let per_turn_config = Arc::new(per_turn_config);
+        let otel_manager = turn_context
+            .client
+            .get_otel_manager()
+            .with_model(model.as_str(), model_family.slug.as_str());
+        let client = ModelClient::new(
+            per_turn_config.clone(),
+            turn_context.client.get_auth_manager(),
+            self.services.azure_auth.clone(),
+            model_family.clone(),
+            otel_manager,
+            provider,
+            per_turn_config.model_reasoning_effort,
+            per_turn_config.model_reasoning_summary,
+            self.conversation_id,
+            turn_context.client.get_session_source(),
+        );
+
+        Arc::new(TurnContext {
+            sub_id: turn_context.sub_id.clone(),
+            client,
+            tools_config: turn_context.tools_config.clone(),
+            ghost_snapshot: turn_context.ghost_snapshot.clone(),
+            developer_instructions: turn_context.developer_instructions.clone(),
+            user_instructions: turn_context.user_instructions.clone(),
+            base_instructions: turn_context.base_instructions.clone(),
+            compact_prompt: turn_context.compact_prompt.clone(),
+            approval_policy: turn_context.approval_policy,
+            sandbox_policy: turn_context.sandbox_policy.clone(),
+            shell_environment_policy: turn_context.shell_environment_policy.clone(),
+            cwd: turn_context.cwd.clone(),
+            final_output_json_schema: None,
+            codex_linux_sandbox_exe: turn_context.codex_linux_sandbox_exe.clone(),
+            tool_call_gate: Arc::clone(&turn_context.tool_call_gate),
+            truncation_policy: TruncationPolicy::new(
+                &per_turn_config,
+                model_family.truncation_policy,
+            ),
+            plan_mode: turn_context.plan_mode,
+        })
+    }
+
     async fn record_initial_history(&self, conversation_history: InitialHistory) {
         let turn_context = self.new_default_turn().await;
         match conversation_history {
@@ -823,6 +1047,19 @@ impl Session {
                     }
                 }
 
+                // Plan mode and a pending plan carry over. Nested project docs
+                // still in the reconstructed history should not be injected
+                // again.
+                {
+                    let mut state = self.state.lock().await;
+                    let (plan_mode, proposed_plan) = plan_mode_from_rollout(&rollout_items);
+                    state.session_configuration.plan_mode = plan_mode;
+                    state.proposed_plan = proposed_plan;
+                    for path in loaded_project_docs_from_rollout(&rollout_items) {
+                        state.mark_project_doc_loaded(path);
+                    }
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history =
                     self.reconstruct_history_from_rollout(&turn_context, &rollout_items);
@@ -859,6 +1096,54 @@ impl Session {
         }
     }
 
+    /// Turns plan mode on or off for subsequent turns. Entering plan mode
+    /// discards any plan proposed earlier.
+    pub(crate) async fn set_plan_mode(&self, sub_id: String, enabled: bool) {
+        {
+            let mut state = self.state.lock().await;
+            state.session_configuration.plan_mode = enabled;
+            if enabled {
+                state.proposed_plan = None;
+            }
+        }
+        self.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::PlanModeUpdated(PlanModeUpdatedEvent { enabled }),
+        })
+        .await;
+    }
+
+    /// Records the plan the model proposed while in plan mode.
+    pub(crate) async fn set_proposed_plan(&self, plan: UpdatePlanArgs) {
+        self.state.lock().await.proposed_plan = Some(plan);
+    }
+
+    /// Takes the plan awaiting approval, if plan mode is on and the model has
+    /// proposed one.
+    async fn take_proposed_plan(&self) -> Option<UpdatePlanArgs> {
+        let mut state = self.state.lock().await;
+        if !state.session_configuration.plan_mode {
+            return None;
+        }
+        state.proposed_plan.take()
+    }
+
+    /// Asks the user to approve the plan once a plan mode turn has finished
+    /// with one.
+    async fn request_plan_approval(&self, turn_context: &TurnContext) {
+        if !turn_context.plan_mode {
+            return;
+        }
+        let plan = self.state.lock().await.proposed_plan.clone();
+        if let Some(plan) = plan {
+            self.send_event(
+                turn_context,
+                EventMsg::PlanApprovalRequest(PlanApprovalRequestEvent { plan }),
+            )
+            .await;
+        }
+    }
+
     pub(crate) async fn new_turn_with_sub_id(
         &self,
         sub_id: String,
@@ -941,6 +1226,7 @@ impl Session {
             self.conversation_id,
             sub_id,
         );
+        turn_context.tools_config.custom_tools = self.services.custom_tools.clone();
         if let Some(final_schema) = final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
@@ -981,6 +1267,30 @@ impl Session {
         )))
     }
 
+    /// Returns the repository state as an environment context update when the
+    /// `git_context` feature is on and the state changed since it was last
+    /// sent to the model.
+    async fn build_git_context_update_item(
+        &self,
+        turn_context: &TurnContext,
+    ) -> Option<ResponseItem> {
+        if !self.enabled(Feature::GitContext) {
+            return None;
+        }
+        let status = collect_git_status(&turn_context.cwd).await?;
+        let changed = {
+            let mut state = self.state.lock().await;
+            state.update_git_status(&status)
+        };
+        if !changed {
+            return None;
+        }
+        let shell = self.user_shell();
+        Some(ResponseItem::from(
+            EnvironmentContext::new(None, None, None, shell.as_ref().clone()).with_git(status),
+        ))
+    }
+
     /// Persist the event to rollout and send it to clients.
     pub(crate) async fn send_event(&self, turn_context: &TurnContext, msg: EventMsg) {
         let legacy_source = msg.clone();
@@ -1226,6 +1536,9 @@ impl Session {
                         history.replace(rebuilt);
                     }
                 }
+                RolloutItem::EventMsg(EventMsg::ThreadRolledBack(rollback)) => {
+                    history.drop_last_turns(rollback.num_turns as usize);
+                }
                 _ => {}
             }
         }
@@ -1263,6 +1576,124 @@ impl Session {
         state.replace_history(items);
     }
 
+    /// Drops the last `num_turns` user turns from history. With
+    /// `restore_worktree`, first restores the ghost snapshot taken at the start
+    /// of the earliest dropped turn; history is left untouched if that fails.
+    async fn rollback_turns(
+        &self,
+        turn_context: &TurnContext,
+        num_turns: u32,
+        restore_worktree: bool,
+    ) -> Result<ThreadRolledBackEvent, String> {
+        if num_turns == 0 {
+            return Err("rollback requires at least one turn".to_string());
+        }
+        if self.active_turn.lock().await.is_some() {
+            return Err("cannot roll back while a turn is running".to_string());
+        }
+
+        let mut history = self.clone_history().await;
+        let Some(removed) = history.drop_last_turns(num_turns as usize) else {
+            return Err(format!(
+                "cannot roll back {num_turns} turn(s): the conversation has fewer turns"
+            ));
+        };
+
+        if restore_worktree {
+            let Some(ghost_commit) = removed.into_iter().find_map(|item| match item {
+                ResponseItem::GhostSnapshot { ghost_commit } => Some(ghost_commit),
+                _ => None,
+            }) else {
+                return Err("no ghost snapshot was recorded for the rolled back turns".to_string());
+            };
+            self.restore_ghost_snapshot(turn_context, ghost_commit)
+                .await?;
+        }
+
+        self.replace_history(history.get_history()).await;
+        Ok(ThreadRolledBackEvent {
+            num_turns,
+            worktree_restored: restore_worktree,
+        })
+    }
+
+    /// Restores the working tree from the snapshot taken at the start of the
+    /// `num_turns`-th most recent turn, keeping the conversation as is.
+    async fn restore_checkpoint_worktree(
+        &self,
+        turn_context: &TurnContext,
+        num_turns: u32,
+    ) -> Result<String, String> {
+        if self.active_turn.lock().await.is_some() {
+            return Err("cannot restore a checkpoint while a turn is running".to_string());
+        }
+        let Some(snapshot) = self
+            .clone_history()
+            .await
+            .turn_snapshots()
+            .into_iter()
+            .find(|snapshot| snapshot.num_turns == num_turns as usize)
+        else {
+            return Err(format!(
+                "no checkpoint was recorded {num_turns} turn(s) back"
+            ));
+        };
+        let short_id: String = snapshot.ghost_commit.id().chars().take(7).collect();
+        self.restore_ghost_snapshot(turn_context, snapshot.ghost_commit)
+            .await?;
+        Ok(format!(
+            "Restored the working tree to checkpoint {short_id}."
+        ))
+    }
+
+    async fn restore_ghost_snapshot(
+        &self,
+        turn_context: &TurnContext,
+        ghost_commit: GhostCommit,
+    ) -> Result<(), String> {
+        let commit_id = ghost_commit.id().to_string();
+        let repo_path = turn_context.cwd.clone();
+        let ghost_snapshot = turn_context.ghost_snapshot.clone();
+        let restore_result = tokio::task::spawn_blocking(move || {
+            let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+            restore_ghost_commit_with_options(&options, &ghost_commit)
+        })
+        .await;
+        match restore_result {
+            Ok(Ok(())) => {
+                info!(commit_id, "restored ghost snapshot");
+                Ok(())
+            }
+            Ok(Err(err)) => Err(format!("failed to restore snapshot {commit_id}: {err}")),
+            Err(err) => Err(format!("failed to restore snapshot {commit_id}: {err}")),
+        }
+    }
+
+    /// Undo checkpoints for the turns that recorded a ghost snapshot, newest
+    /// first.
+    async fn list_checkpoints(
+        &self,
+        turn_context: &TurnContext,
+    ) -> Result<Vec<Checkpoint>, String> {
+        let snapshots = self.clone_history().await.turn_snapshots();
+        let activity = self.state.lock().await.task_activity();
+        let cwd = turn_context.cwd.clone();
+        let ghost_snapshot = turn_context.ghost_snapshot.clone();
+        tokio::task::spawn_blocking(move || {
+            checkpoints::list_checkpoints(&cwd, ghost_snapshot, snapshots, &activity)
+        })
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| format!("failed to list checkpoints: {err}"))
+    }
+
+    pub(crate) async fn record_task_activity(&self, started_at: SystemTime) {
+        self.state
+            .lock()
+            .await
+            .record_task_activity(started_at, SystemTime::now());
+    }
+
     async fn persist_rollout_response_items(&self, items: &[ResponseItem]) {
         let rollout_items: Vec<RolloutItem> = items
             .iter()
@@ -1336,7 +1767,8 @@ impl Session {
         turn_context: &TurnContext,
         token_usage: Option<&TokenUsage>,
     ) {
-        {
+        let pricing = turn_context.model_pricing();
+        let cost = {
             let mut state = self.state.lock().await;
             if let Some(token_usage) = token_usage {
                 state.update_token_info_from_usage(
@@ -1344,10 +1776,58 @@ impl Session {
                     turn_context.client.get_model_context_window(),
                 );
             }
+            token_usage.zip(pricing).map(|(token_usage, pricing)| {
+                state.add_cost(&turn_context.sub_id, pricing.cost_usd(token_usage))
+            })
+        };
+        if let Some(token_usage) = token_usage {
+            self.services.budget.record_usage(token_usage);
+        }
+        if let Some(cost) = cost {
+            self.services.budget.set_cost_usd(cost.session_usd);
         }
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Emits budget warnings and the current budget status. Returns the
+    /// exceeded limit, if any, after notifying clients about it.
+    pub(crate) async fn enforce_budget(&self, turn_context: &TurnContext) -> Option<BudgetLimit> {
+        let budget = &self.services.budget;
+        if !budget.is_enabled() {
+            return None;
+        }
+        let BudgetCheck { warnings, exceeded } = budget.check();
+        for message in warnings {
+            self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
+        self.send_event(
+            turn_context,
+            EventMsg::BudgetUpdated(BudgetUpdatedEvent {
+                status: budget.status(),
+            }),
+        )
+        .await;
+        if let Some(limit) = exceeded {
+            self.send_budget_exceeded(turn_context, limit).await;
+        }
+        exceeded
+    }
+
+    pub(crate) async fn send_budget_exceeded(
+        &self,
+        turn_context: &TurnContext,
+        limit: BudgetLimit,
+    ) {
+        let budget = &self.services.budget;
+        let event = EventMsg::BudgetExceeded(BudgetExceededEvent {
+            limit,
+            message: budget.exceeded_message(limit),
+            status: budget.status(),
+        });
+        self.send_event(turn_context, event).await;
+    }
+
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
         let Some(estimated_total_tokens) = self
             .clone_history()
@@ -1394,11 +1874,16 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, cost) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            (info, rate_limits, state.cost_for_turn(&turn_context.sub_id))
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info,
+            rate_limits,
+            cost,
+        });
         self.send_event(turn_context, event).await;
     }
 
@@ -1499,6 +1984,80 @@ impl Session {
         }
     }
 
+    /// Adds nested `AGENTS.md` files that apply to `paths` (files or
+    /// directories the agent just read or wrote) to the next model request as
+    /// developer messages. Each file is loaded at most once per session.
+    pub(crate) async fn load_nested_project_docs(
+        &self,
+        turn_context: &TurnContext,
+        paths: &[PathBuf],
+    ) {
+        let config = turn_context.client.config();
+        let mut docs: Vec<PathBuf> = Vec::new();
+        for path in paths {
+            let dir = if path.is_dir() {
+                path.as_path()
+            } else {
+                match path.parent() {
+                    Some(parent) => parent,
+                    None => continue,
+                }
+            };
+            match discover_nested_project_doc_paths(&config, dir) {
+                Ok(found) => docs.extend(found),
+                Err(err) => warn!(
+                    "failed to discover project docs under {}: {err}",
+                    dir.display()
+                ),
+            }
+        }
+        if docs.is_empty() {
+            return;
+        }
+
+        let docs: Vec<PathBuf> = {
+            let state = self.state.lock().await;
+            docs.into_iter()
+                .filter(|doc| !state.is_project_doc_loaded(doc))
+                .collect()
+        };
+        for doc in docs {
+            let text = match read_nested_project_doc(&config, &doc).await {
+                Ok(Some(text)) => text,
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!("failed to read project doc {}: {err}", doc.display());
+                    continue;
+                }
+            };
+            let directory = doc.parent().unwrap_or(&doc).display().to_string();
+            let item: ResponseInputItem = NestedInstructions { directory, text }.into();
+            // Marked only once the doc is queued for the model, so a doc
+            // that could not be injected is tried again later.
+            let injected = {
+                let mut active = self.active_turn.lock().await;
+                match active.as_mut() {
+                    Some(at) => {
+                        let mut state = self.state.lock().await;
+                        let newly_loaded = state.mark_project_doc_loaded(doc.clone());
+                        if newly_loaded {
+                            at.turn_state.lock().await.push_pending_input(item);
+                        }
+                        newly_loaded
+                    }
+                    None => false,
+                }
+            };
+            if injected {
+                self.send_event(
+                    turn_context,
+                    EventMsg::ProjectDocLoaded(ProjectDocLoadedEvent { path: doc }),
+                )
+                .await;
+            }
+        }
+    }
+
     pub async fn get_pending_input(&self) -> Vec<ResponseInputItem> {
         let mut active = self.active_turn.lock().await;
         match active.as_mut() {
@@ -1563,6 +2122,19 @@ impl Session {
             .await
     }
 
+    pub(crate) async fn mcp_tool_annotations(
+        &self,
+        server: &str,
+        tool: &str,
+    ) -> Option<ToolAnnotations> {
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .tool_annotations(server, tool)
+            .await
+    }
+
     pub(crate) async fn parse_mcp_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
         self.services
             .mcp_connection_manager
@@ -1586,6 +2158,25 @@ impl Session {
         &self.services.notifier
     }
 
+    pub(crate) fn hooks(&self) -> &Hooks {
+        &self.services.hooks
+    }
+
+    /// Runs the configured hooks for `event` in the context of `turn_context`,
+    /// applying any modifications a hook returns to `event`.
+    pub(crate) async fn run_hooks(
+        &self,
+        turn_context: &TurnContext,
+        event: &mut HookEvent,
+    ) -> HookDecision {
+        let context = HookContext {
+            session_id: self.conversation_id.to_string(),
+            turn_id: Some(&turn_context.sub_id),
+            cwd: &turn_context.cwd,
+        };
+        self.services.hooks.run(&context, event).await
+    }
+
     pub(crate) fn user_shell(&self) -> Arc<shell::Shell> {
         Arc::clone(&self.services.user_shell)
     }
@@ -1662,9 +2253,45 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
+            Op::Rollback {
+                num_turns,
+                restore_worktree,
+            } => {
+                handlers::rollback(&sess, sub.id.clone(), num_turns, restore_worktree).await;
+            }
+            Op::ListCheckpoints => {
+                handlers::list_checkpoints(&sess, sub.id.clone()).await;
+            }
+            Op::RestoreCheckpoint { num_turns, scope } => {
+                handlers::restore_checkpoint(&sess, sub.id.clone(), num_turns, scope).await;
+            }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
             }
+            Op::ListMemories => {
+                handlers::list_memories(&sess, sub.id.clone()).await;
+            }
+            Op::AddMemory { scope, text } => {
+                handlers::edit_memory(&sess, sub.id.clone(), |store| {
+                    store.add(scope, &text).map(Some)
+                })
+                .await;
+            }
+            Op::UpdateMemory { scope, id, text } => {
+                handlers::edit_memory(&sess, sub.id.clone(), |store| {
+                    store.update(scope, id, &text)
+                })
+                .await;
+            }
+            Op::ForgetMemory { scope, id } => {
+                handlers::edit_memory(&sess, sub.id.clone(), |store| store.forget(scope, id)).await;
+            }
+            Op::SetPlanMode { enabled } => {
+                sess.set_plan_mode(sub.id.clone(), enabled).await;
+            }
+            Op::ApprovePlan => {
+                handlers::approve_plan(&sess, sub.id.clone(), &mut previous_context).await;
+            }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
                     &sess,
@@ -1704,25 +2331,36 @@ mod handlers {
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
     use crate::features::Feature;
+    use crate::hooks::HookDecision;
+    use crate::hooks::HookEvent;
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
+    use crate::memory::MemoryEdit;
+    use crate::memory::MemoryStore;
+    use crate::plan_mode::PLAN_APPROVED_PROMPT;
+    use crate::plan_mode::plan_mode_update_item;
     use crate::review_prompts::resolve_review_request;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::CheckpointRestoreScope;
     use codex_protocol::protocol::CodexErrorInfo;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::ListCheckpointsResponseEvent;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::ListMemoriesResponseEvent;
     use codex_protocol::protocol::ListSkillsResponseEvent;
+    use codex_protocol::protocol::MemoryScope;
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::SkillsListEntry;
     use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::UndoCompletedEvent;
     use codex_protocol::protocol::WarningEvent;
 
     use codex_protocol::user_input::UserInput;
@@ -1796,6 +2434,10 @@ mod handlers {
             .get_otel_manager()
             .user_prompt(&items);
 
+        let Some(items) = run_user_prompt_submit_hooks(sess, &current_context, items).await else {
+            return;
+        };
+
         // Attempt to inject input into current task
         if let Err(items) = sess.inject_input(items).await {
             if let Some(env_item) =
@@ -1804,6 +2446,19 @@ mod handlers {
                 sess.record_conversation_items(&current_context, std::slice::from_ref(&env_item))
                     .await;
             }
+            if let Some(plan_mode_item) =
+                plan_mode_update_item(previous_context.as_deref(), &current_context)
+            {
+                sess.record_conversation_items(
+                    &current_context,
+                    std::slice::from_ref(&plan_mode_item),
+                )
+                .await;
+            }
+            if let Some(git_item) = sess.build_git_context_update_item(&current_context).await {
+                sess.record_conversation_items(&current_context, std::slice::from_ref(&git_item))
+                    .await;
+            }
 
             sess.spawn_task(Arc::clone(&current_context), items, RegularTask)
                 .await;
@@ -1811,6 +2466,82 @@ mod handlers {
         }
     }
 
+    /// Leaves plan mode and starts a turn that implements the approved plan.
+    pub async fn approve_plan(
+        sess: &Arc<Session>,
+        sub_id: String,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        if sess.take_proposed_plan().await.is_none() {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "There is no plan to approve; plan mode is off or the agent has not proposed a plan yet.".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+            return;
+        }
+        sess.set_plan_mode(sub_id.clone(), false).await;
+        let items = vec![UserInput::Text {
+            text: PLAN_APPROVED_PROMPT.trim_end().to_string(),
+        }];
+        user_input_or_turn(sess, sub_id, Op::UserInput { items }, previous_context).await;
+    }
+
+    /// Lets `user_prompt_submit` hooks veto or rewrite the text of a prompt.
+    /// Returns `None` (after reporting the denial) when the prompt is blocked.
+    async fn run_user_prompt_submit_hooks(
+        sess: &Arc<Session>,
+        turn_context: &TurnContext,
+        items: Vec<UserInput>,
+    ) -> Option<Vec<UserInput>> {
+        if sess.hooks().is_empty() {
+            return Some(items);
+        }
+        let original = items
+            .iter()
+            .filter_map(|item| match item {
+                UserInput::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut event = HookEvent::UserPromptSubmit {
+            prompt: original.clone(),
+        };
+        if let HookDecision::Deny { reason } = sess.run_hooks(turn_context, &mut event).await {
+            sess.send_event(
+                turn_context,
+                EventMsg::Error(ErrorEvent {
+                    message: format!("prompt blocked by hook: {reason}"),
+                    codex_error_info: Some(CodexErrorInfo::Other),
+                }),
+            )
+            .await;
+            return None;
+        }
+        let HookEvent::UserPromptSubmit { prompt } = event else {
+            return Some(items);
+        };
+        if prompt == original {
+            return Some(items);
+        }
+        // Replace all text items with the rewritten prompt, keeping images and
+        // skills where they were.
+        let mut rewritten = Some(prompt);
+        Some(
+            items
+                .into_iter()
+                .filter_map(|item| match item {
+                    UserInput::Text { .. } => rewritten.take().map(|text| UserInput::Text { text }),
+                    other => Some(other),
+                })
+                .collect(),
+        )
+    }
+
     pub async fn run_user_shell_command(
         sess: &Arc<Session>,
         sub_id: String,
@@ -1943,6 +2674,7 @@ mod handlers {
             compute_auth_statuses(
                 config.mcp_servers.iter(),
                 config.mcp_oauth_credentials_store_mode,
+                &config.network,
             )
             .await,
         )
@@ -2019,6 +2751,142 @@ mod handlers {
             .await;
     }
 
+    pub async fn rollback(
+        sess: &Arc<Session>,
+        sub_id: String,
+        num_turns: u32,
+        restore_worktree: bool,
+    ) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let msg = match sess
+            .rollback_turns(&turn_context, num_turns, restore_worktree)
+            .await
+        {
+            Ok(event) => EventMsg::ThreadRolledBack(event),
+            Err(message) => {
+                warn!("rollback failed: {message}");
+                EventMsg::Error(ErrorEvent {
+                    message,
+                    codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                })
+            }
+        };
+        sess.send_event(&turn_context, msg).await;
+    }
+
+    pub async fn list_checkpoints(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let msg = match sess.list_checkpoints(&turn_context).await {
+            Ok(checkpoints) => {
+                EventMsg::ListCheckpointsResponse(ListCheckpointsResponseEvent { checkpoints })
+            }
+            Err(message) => {
+                warn!("{message}");
+                EventMsg::Error(ErrorEvent {
+                    message,
+                    codex_error_info: Some(CodexErrorInfo::Other),
+                })
+            }
+        };
+        sess.send_event(&turn_context, msg).await;
+    }
+
+    pub async fn list_memories(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let store = MemoryStore::new(&turn_context.client.config().codex_home, &turn_context.cwd);
+        send_memories(sess, &turn_context, &store).await;
+    }
+
+    /// Applies a memory change requested by the user, who needs no approval,
+    /// and replies with the updated entries.
+    pub async fn edit_memory(
+        sess: &Arc<Session>,
+        sub_id: String,
+        edit: impl FnOnce(&MemoryStore) -> std::io::Result<Option<MemoryEdit>>,
+    ) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let store = MemoryStore::new(&turn_context.client.config().codex_home, &turn_context.cwd);
+        let result = match edit(&store) {
+            Ok(Some(edit)) => edit.apply(),
+            Ok(None) => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "memory entry not found",
+            )),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            send_memory_error(sess, &turn_context, err).await;
+            return;
+        }
+        send_memories(sess, &turn_context, &store).await;
+    }
+
+    async fn send_memories(sess: &Arc<Session>, turn_context: &TurnContext, store: &MemoryStore) {
+        let entries = match store.list() {
+            Ok(entries) => entries,
+            Err(err) => {
+                send_memory_error(sess, turn_context, err).await;
+                return;
+            }
+        };
+        let event = ListMemoriesResponseEvent {
+            entries,
+            user_path: store.path(MemoryScope::User).to_path_buf(),
+            project_path: store.path(MemoryScope::Project).to_path_buf(),
+        };
+        sess.send_event(turn_context, EventMsg::ListMemoriesResponse(event))
+            .await;
+    }
+
+    async fn send_memory_error(
+        sess: &Arc<Session>,
+        turn_context: &TurnContext,
+        err: std::io::Error,
+    ) {
+        let message = format!("failed to update memory: {err}");
+        warn!("{message}");
+        let event = EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: Some(CodexErrorInfo::Other),
+        });
+        sess.send_event(turn_context, event).await;
+    }
+
+    pub async fn restore_checkpoint(
+        sess: &Arc<Session>,
+        sub_id: String,
+        num_turns: u32,
+        scope: CheckpointRestoreScope,
+    ) {
+        let restore_worktree = match scope {
+            CheckpointRestoreScope::Conversation => false,
+            CheckpointRestoreScope::Both => true,
+            CheckpointRestoreScope::Worktree => {
+                let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+                let completed = match sess
+                    .restore_checkpoint_worktree(&turn_context, num_turns)
+                    .await
+                {
+                    Ok(message) => UndoCompletedEvent {
+                        success: true,
+                        message: Some(message),
+                    },
+                    Err(message) => {
+                        warn!("checkpoint restore failed: {message}");
+                        UndoCompletedEvent {
+                            success: false,
+                            message: Some(message),
+                        }
+                    }
+                };
+                sess.send_event(&turn_context, EventMsg::UndoCompleted(completed))
+                    .await;
+                return;
+            }
+        };
+        rollback(sess, sub_id, num_turns, restore_worktree).await;
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
 
@@ -2038,6 +2906,7 @@ mod handlers {
             .unified_exec_manager
             .terminate_all_sessions()
             .await;
+        sess.services.lsp.shutdown_all().await;
         info!("Shutting down Codex instance");
 
         // Gracefully flush and shutdown rollout recorder on session end so tests
@@ -2176,6 +3045,7 @@ async fn spawn_review_thread(
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         truncation_policy: TruncationPolicy::new(&per_turn_config, model_family.truncation_policy),
+        plan_mode: parent_turn_context.plan_mode,
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -2248,6 +3118,8 @@ pub(crate) async fn run_task(
         .get_model_family()
         .auto_compact_token_limit()
         .unwrap_or(i64::MAX);
+    sess.maybe_prune_context(&turn_context, auto_compact_limit)
+        .await;
     let total_usage_tokens = sess.get_total_token_usage().await;
     if total_usage_tokens >= auto_compact_limit {
         run_auto_compact(&sess, &turn_context).await;
@@ -2257,6 +3129,11 @@ pub(crate) async fn run_task(
     });
     sess.send_event(&turn_context, event).await;
 
+    if let Err(limit) = sess.services.budget.try_start_turn() {
+        sess.send_budget_exceeded(&turn_context, limit).await;
+        return None;
+    }
+
     let skills_outcome = sess.enabled(Feature::Skills).then(|| {
         sess.services
             .skills_manager
@@ -2289,6 +3166,7 @@ pub(crate) async fn run_task(
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+    let mut verify_state = VerifyState::default();
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -2330,6 +3208,12 @@ pub(crate) async fn run_task(
                     needs_follow_up,
                     last_agent_message: turn_last_agent_message,
                 } = turn_output;
+                if sess.enforce_budget(&turn_context).await.is_some() {
+                    last_agent_message = turn_last_agent_message;
+                    break;
+                }
+                sess.maybe_prune_context(&turn_context, auto_compact_limit)
+                    .await;
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
@@ -2340,7 +3224,23 @@ pub(crate) async fn run_task(
                 }
 
                 if !needs_follow_up {
+                    // Verification failures go back to the model as tool
+                    // output for another turn.
+                    match verify_turn_edits(
+                        &sess,
+                        &turn_context,
+                        &turn_diff_tracker,
+                        &mut verify_state,
+                    )
+                    .or_cancel(&cancellation_token)
+                    .await
+                    {
+                        Ok(true) => continue,
+                        Ok(false) => {}
+                        Err(codex_async_utils::CancelErr::Cancelled) => break,
+                    }
                     last_agent_message = turn_last_agent_message;
+                    sess.request_plan_approval(&turn_context).await;
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             thread_id: sess.conversation_id.to_string(),
@@ -2378,10 +3278,17 @@ pub(crate) async fn run_task(
 }
 
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
-    if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
-        run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
+    let compact_context = sess.compaction_turn_context(turn_context).await;
+    if should_use_remote_compact_task(sess.as_ref(), &compact_context) {
+        run_inline_remote_auto_compact_task(
+            Arc::clone(sess),
+            Arc::clone(turn_context),
+            compact_context,
+        )
+        .await;
     } else {
-        run_inline_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
+        run_inline_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context), compact_context)
+            .await;
     }
 }
 
@@ -2775,6 +3682,7 @@ mod tests {
     use super::*;
     use crate::CodexAuth;
     use crate::config::ConfigBuilder;
+    use crate::config::types::BudgetConfig;
     use crate::exec::ExecToolCallOutput;
     use crate::function_tool::FunctionCallError;
     use crate::shell::default_user_shell;
@@ -2875,6 +3783,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -2941,6 +3850,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -3148,6 +4058,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_family = ModelsManager::construct_model_family_offline(
@@ -3169,6 +4080,8 @@ mod tests {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: Hooks::default(),
+            budget: SessionBudget::new(BudgetConfig::default()),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -3179,6 +4092,12 @@ mod tests {
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
+            output_store: OutputStore::new(&config.codex_home, conversation_id),
+            code_index: CodeIndexManager::new(&config.codex_home),
+            lsp: LspManager::new(config.lsp.clone()),
+            custom_tools: Vec::new(),
+            web_fetch: WebFetcher::new(&config.fetch_url),
+            verifier: Verifier::new(&config.verify),
         };
 
         let turn_context = Session::make_turn_context(
@@ -3237,6 +4156,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_family = ModelsManager::construct_model_family_offline(
@@ -3258,6 +4178,8 @@ mod tests {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: Hooks::default(),
+            budget: SessionBudget::new(BudgetConfig::default()),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -3268,6 +4190,12 @@ mod tests {
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
+            output_store: OutputStore::new(&config.codex_home, conversation_id),
+            code_index: CodeIndexManager::new(&config.codex_home),
+            lsp: LspManager::new(config.lsp.clone()),
+            custom_tools: Vec::new(),
+            web_fetch: WebFetcher::new(&config.fetch_url),
+            verifier: Verifier::new(&config.verify),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
-use crate::ModelProviderInfo;
 use crate::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::codex::get_last_assistant_message_from_turn;
+use crate::config::types::CompactStrategy;
+use crate::context_manager::ContextManager;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::features::Feature;
@@ -29,46 +30,60 @@ use futures::prelude::*;
 use tracing::error;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../templates/compact/prompt.md");
+pub const STRUCTURED_SUMMARIZATION_PROMPT: &str =
+    include_str!("../templates/compact/structured.md");
 pub const SUMMARY_PREFIX: &str = include_str!("../templates/compact/summary_prefix.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
 
+/// Whether `compact_context` (see [`Session::compaction_turn_context`])
+/// compacts through the provider's compaction endpoint. The structured
+/// strategy needs a readable summary, so it always compacts locally.
 pub(crate) fn should_use_remote_compact_task(
     session: &Session,
-    provider: &ModelProviderInfo,
+    compact_context: &TurnContext,
 ) -> bool {
-    provider.is_openai() && session.enabled(Feature::RemoteCompaction)
+    compact_context.client.get_provider().is_openai()
+        && session.enabled(Feature::RemoteCompaction)
+        && compact_context.client.config().compact_strategy != CompactStrategy::Structured
 }
 
 pub(crate) async fn run_inline_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
+    compact_context: Arc<TurnContext>,
 ) {
     let prompt = turn_context.compact_prompt().to_string();
     let input = vec![UserInput::Text { text: prompt }];
 
-    run_compact_task_inner(sess, turn_context, input).await;
+    run_compact_task_inner(sess, turn_context, compact_context, input).await;
 }
 
 pub(crate) async fn run_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
+    compact_context: Arc<TurnContext>,
     input: Vec<UserInput>,
 ) {
     let start_event = EventMsg::TaskStarted(TaskStartedEvent {
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, start_event).await;
-    run_compact_task_inner(sess.clone(), turn_context, input).await;
+    run_compact_task_inner(sess.clone(), turn_context, compact_context, input).await;
 }
 
+/// Summarizes with `compact_context`, which may use a different model than
+/// the session, and rebuilds history for `turn_context`.
 async fn run_compact_task_inner(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
+    compact_context: Arc<TurnContext>,
     input: Vec<UserInput>,
 ) {
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
 
     let mut history = sess.clone_history().await;
+    let recent_items = split_kept_turns(&mut history, &turn_context);
+    let summarized_items = history.get_history();
     history.record_items(
         &[initial_input_for_turn.into()],
         turn_context.truncation_policy,
@@ -76,7 +91,7 @@ async fn run_compact_task_inner(
 
     let mut truncated_count = 0usize;
 
-    let max_retries = turn_context.client.get_provider().stream_max_retries();
+    let max_retries = compact_context.client.get_provider().stream_max_retries();
     let mut retries = 0;
 
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
@@ -100,7 +115,7 @@ async fn run_compact_task_inner(
             input: turn_input.clone(),
             ..Default::default()
         };
-        let attempt_result = drain_to_completed(&sess, turn_context.as_ref(), &prompt).await;
+        let attempt_result = drain_to_completed(&sess, compact_context.as_ref(), &prompt).await;
 
         match attempt_result {
             Ok(()) => {
@@ -159,22 +174,30 @@ async fn run_compact_task_inner(
     let summary_suffix =
         get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
     let summary_text = format!("{SUMMARY_PREFIX}\n{summary_suffix}");
-    let user_messages = collect_user_messages(&history_snapshot);
+    let user_messages = collect_user_messages(&summarized_items);
 
     let initial_context = sess.build_initial_context(turn_context.as_ref());
     let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
-    let ghost_snapshots: Vec<ResponseItem> = history_snapshot
+    let ghost_snapshots: Vec<ResponseItem> = summarized_items
         .iter()
         .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
         .cloned()
         .collect();
     new_history.extend(ghost_snapshots);
+    // The summary alone cannot rebuild the turns kept verbatim, so persist
+    // the full history for resume.
+    let replacement_history = if recent_items.is_empty() {
+        None
+    } else {
+        new_history.extend(recent_items);
+        Some(new_history.clone())
+    };
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
         message: summary_text.clone(),
-        replacement_history: None,
+        replacement_history,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
@@ -226,6 +249,64 @@ pub(crate) fn is_summary_message(message: &str) -> bool {
     message.starts_with(format!("{SUMMARY_PREFIX}\n").as_str())
 }
 
+/// Splits off the turns the configured strategy keeps verbatim after the
+/// summary. Kept turns that fill most of the auto-compact limit would leave
+/// the compacted history over it, and every turn would compact again, so they
+/// may use at most half of it.
+pub(crate) fn split_kept_turns(
+    history: &mut ContextManager,
+    turn_context: &TurnContext,
+) -> Vec<ResponseItem> {
+    let config = turn_context.client.config();
+    match config.compact_strategy {
+        CompactStrategy::Rolling => {
+            let max_recent_tokens = turn_context
+                .client
+                .get_model_family()
+                .auto_compact_token_limit()
+                .map_or(i64::MAX, |limit| limit / 2);
+            split_recent_turns(history, config.compact_keep_recent_turns, max_recent_tokens)
+        }
+        CompactStrategy::Single | CompactStrategy::Structured => Vec::new(),
+    }
+}
+
+/// Splits the last `keep_recent_turns` user turns off `history` for the
+/// rolling strategy and returns them. Keeps fewer turns when they exceed
+/// `max_recent_tokens`, and none (a single summary) when not even the last
+/// turn fits. Returns nothing, leaving `history` untouched, when no older
+/// user turn would be left to summarize.
+pub(crate) fn split_recent_turns(
+    history: &mut ContextManager,
+    keep_recent_turns: usize,
+    max_recent_tokens: i64,
+) -> Vec<ResponseItem> {
+    for keep in (1..=keep_recent_turns).rev() {
+        let mut older = history.clone();
+        let Some(recent) = older.drop_last_turns(keep) else {
+            return Vec::new();
+        };
+        if collect_user_messages(&older.get_history()).is_empty() {
+            return Vec::new();
+        }
+        if approx_items_tokens(&recent) <= max_recent_tokens {
+            *history = older;
+            return recent;
+        }
+    }
+    Vec::new()
+}
+
+fn approx_items_tokens(items: &[ResponseItem]) -> i64 {
+    items
+        .iter()
+        .map(|item| {
+            let serialized = serde_json::to_string(item).unwrap_or_default();
+            i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+        })
+        .fold(0, i64::saturating_add)
+}
+
 pub(crate) fn build_compacted_history(
     initial_context: Vec<ResponseItem>,
     user_messages: &[String],
@@ -479,4 +560,69 @@ mod tests {
         };
         assert_eq!(summary, summary_text);
     }
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        let content = if role == "user" {
+            ContentItem::InputText {
+                text: text.to_string(),
+            }
+        } else {
+            ContentItem::OutputText {
+                text: text.to_string(),
+            }
+        };
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        }
+    }
+
+    #[test]
+    fn split_recent_turns_keeps_last_turns_and_needs_older_turn() {
+        let items = vec![
+            message("user", "first"),
+            message("assistant", "first reply"),
+            message("user", "second"),
+            message("assistant", "second reply"),
+        ];
+        let mut history = ContextManager::new();
+        history.record_items(items.iter(), TruncationPolicy::Tokens(10_000));
+
+        let recent = split_recent_turns(&mut history, 1, i64::MAX);
+        assert_eq!(recent, items[2..].to_vec());
+        assert_eq!(history.get_history(), items[..2].to_vec());
+
+        // Keeping every turn would leave nothing to summarize.
+        let recent = split_recent_turns(&mut history, 1, i64::MAX);
+        assert_eq!(recent, Vec::new());
+        assert_eq!(history.get_history(), items[..2].to_vec());
+        assert_eq!(split_recent_turns(&mut history, 0, i64::MAX), Vec::new());
+    }
+
+    #[test]
+    fn split_recent_turns_keeps_fewer_turns_when_they_do_not_fit() {
+        let items = vec![
+            message("user", "first"),
+            message("assistant", "first reply"),
+            message("user", &"large paste ".repeat(1_000)),
+            message("assistant", "second reply"),
+            message("user", "third"),
+            message("assistant", "third reply"),
+        ];
+        let mut history = ContextManager::new();
+        history.record_items(items.iter(), TruncationPolicy::Tokens(100_000));
+
+        // Keeping the last two turns would carry the large paste past the
+        // compaction, so only the last one is kept.
+        let mut rolling = history.clone();
+        let recent = split_recent_turns(&mut rolling, 2, 500);
+        assert_eq!(recent, items[4..].to_vec());
+        assert_eq!(rolling.get_history(), items[..4].to_vec());
+
+        // When not even the last turn fits, everything is summarized.
+        let mut single = history.clone();
+        assert_eq!(split_recent_turns(&mut single, 2, 10), Vec::new());
+        assert_eq!(single.get_history(), items);
+    }
 }
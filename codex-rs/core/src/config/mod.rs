@@ -1,18 +1,28 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::AgentsConfig;
+use crate::config::types::BudgetConfig;
+use crate::config::types::CompactStrategy;
+use crate::config::types::ContextPruningConfig;
+use crate::config::types::CustomToolConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::FetchUrlConfig;
 use crate::config::types::History;
+use crate::config::types::HooksConfig;
+use crate::config::types::LspConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::PricingConfig;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ScrollInputMode;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::VerifyConfig;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::LoaderOverrides;
@@ -31,8 +41,11 @@ use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use codex_app_server_protocol::ConfigLayerSource;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
+use codex_client::CassetteMode;
+use codex_client::NetworkSettings;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -72,6 +85,9 @@ pub use service::ConfigServiceError;
 
 const OPENAI_DEFAULT_REVIEW_MODEL: &str = "gpt-5.1-codex-max";
 
+/// User turns kept verbatim by the rolling compaction strategy.
+const DEFAULT_COMPACT_KEEP_RECENT_TURNS: usize = 2;
+
 pub use codex_git::GhostSnapshotConfig;
 
 /// Maximum number of bytes of the documentation that will be embedded. Larger
@@ -141,6 +157,10 @@ pub struct Config {
     /// Defaults to `false`.
     pub show_raw_agent_reasoning: bool,
 
+    /// Record model HTTP traffic to, or replay it from, a cassette file.
+    /// Set by `codex exec --record/--replay`; not read from `config.toml`.
+    pub http_cassette: Option<CassetteMode>,
+
     /// User-provided instructions from AGENTS.md.
     pub user_instructions: Option<String>,
 
@@ -153,6 +173,19 @@ pub struct Config {
     /// Compact prompt override.
     pub compact_prompt: Option<String>,
 
+    /// Model used to summarize the conversation when compacting. Defaults to
+    /// the session model.
+    pub compact_model: Option<String>,
+
+    /// Provider for `compact_model`. Defaults to the session provider.
+    pub compact_provider: Option<ModelProviderInfo>,
+
+    /// How compaction summarizes the conversation.
+    pub compact_strategy: CompactStrategy,
+
+    /// User turns kept verbatim by [`CompactStrategy::Rolling`].
+    pub compact_keep_recent_turns: usize,
+
     /// Optional external notifier command. When set, Codex will spawn this
     /// program after each completed *turn* (i.e. when the agent finishes
     /// processing a user submission). The value must be the full command
@@ -175,6 +208,42 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Commands run at lifecycle events (session start, prompt submit, before
+    /// and after tool calls, approval requests and turn stop). See
+    /// `core/src/hooks.rs`.
+    pub hooks: HooksConfig,
+
+    /// Defaults for sub-agents started through the `spawn_agent` tool.
+    pub agents: AgentsConfig,
+
+    /// Session-wide token, cost, tool call, turn and time limits.
+    pub budget: BudgetConfig,
+
+    /// When and how old tool outputs are pruned from the context.
+    pub context_pruning: ContextPruningConfig,
+
+    /// Overrides for the built-in token price list.
+    pub pricing: PricingConfig,
+
+    /// Language servers used by the LSP tools.
+    pub lsp: LspConfig,
+
+    /// Command-backed tools declared in config.toml, keyed by tool name.
+    /// Excludes the repository's `.codex/config.toml`; see
+    /// `project_custom_tools`.
+    pub custom_tools: BTreeMap<String, CustomToolConfig>,
+
+    /// `[custom_tools]` from the repository's `.codex/config.toml`. Only used
+    /// when the project is trusted, and never replaces a tool from
+    /// `custom_tools`.
+    pub project_custom_tools: BTreeMap<String, CustomToolConfig>,
+
+    /// Domain policy and paging for the `fetch_url` tool.
+    pub fetch_url: FetchUrlConfig,
+
+    /// Commands run after a turn edits matching files.
+    pub verify: VerifyConfig,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -373,6 +442,10 @@ pub struct Config {
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
 
+    /// Proxy, extra CA certificates and client certificate applied to every
+    /// outbound HTTP client.
+    pub network: NetworkSettings,
+
     /// Azure Entra ID authentication configuration.
     /// When set, enables Azure authentication for Azure endpoints.
     pub azure_auth: Option<crate::auth::azure_config::AzureAuthConfig>,
@@ -442,12 +515,21 @@ impl ConfigBuilder {
         let config_toml: ConfigToml = merged_toml
             .try_into()
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        Config::load_config_with_layer_stack(
+        let config = Config::load_config_with_layer_stack(
             config_toml,
             harness_overrides,
             codex_home,
             config_layer_stack,
-        )
+        )?;
+        // Login, model listing and other clients built before a session starts
+        // must already go through the configured proxy and trust store.
+        crate::default_client::set_network_settings(&config.network).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid [network] configuration: {e}"),
+            )
+        })?;
+        Ok(config)
     }
 }
 
@@ -548,6 +630,28 @@ pub async fn load_global_mcp_servers(
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
 }
 
+/// Reads the `key` table merged from either the project layers
+/// (`.codex/config.toml` in the repository) or every other layer. Settings that
+/// run commands use this to ignore what an untrusted repository configures.
+fn layered_setting<T>(
+    config_layer_stack: &ConfigLayerStack,
+    key: &str,
+    from_project: bool,
+) -> std::io::Result<Option<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let merged = config_layer_stack.effective_config_of(|source| {
+        matches!(source, ConfigLayerSource::Project { .. }) == from_project
+    });
+    merged
+        .get(key)
+        .cloned()
+        .map(TomlValue::try_into)
+        .transpose()
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, format!("invalid [{key}]: {e}")))
+}
+
 /// We briefly allowed plain text bearer_token fields in MCP server configs.
 /// We want to warn people who recently added these fields but can remove this after a few months.
 fn ensure_no_inline_bearer_tokens(value: &TomlValue) -> std::io::Result<()> {
@@ -727,6 +831,42 @@ pub struct ConfigToml {
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hook commands.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+
+    /// Sub-agent settings for the `spawn_agent` tool.
+    #[serde(default)]
+    pub agents: Option<AgentsConfig>,
+
+    /// Session-wide limits; see [`BudgetConfig`].
+    #[serde(default)]
+    pub budget: Option<BudgetConfig>,
+
+    /// Tool output pruning; see [`ContextPruningConfig`].
+    #[serde(default)]
+    pub context_pruning: Option<ContextPruningConfig>,
+
+    /// Language servers for the LSP tools; see [`LspConfig`].
+    #[serde(default)]
+    pub lsp: Option<LspConfig>,
+
+    /// Tools backed by local commands; see [`CustomToolConfig`].
+    #[serde(default)]
+    pub custom_tools: Option<BTreeMap<String, CustomToolConfig>>,
+
+    /// Settings for the `fetch_url` tool; see [`FetchUrlConfig`].
+    #[serde(default)]
+    pub fetch_url: Option<FetchUrlConfig>,
+
+    /// Post-edit verification commands; see [`VerifyConfig`].
+    #[serde(default)]
+    pub verify: Option<VerifyConfig>,
+
+    /// Token prices used for cost estimates; see [`PricingConfig`].
+    #[serde(default)]
+    pub pricing: Option<PricingConfig>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -737,6 +877,18 @@ pub struct ConfigToml {
     /// Compact prompt used for history compaction.
     pub compact_prompt: Option<String>,
 
+    /// Model used for history compaction, e.g. a cheaper deployment.
+    pub compact_model: Option<String>,
+
+    /// Provider for `compact_model`, from the model_providers map.
+    pub compact_provider: Option<String>,
+
+    /// Summarization strategy used for history compaction.
+    pub compact_strategy: Option<CompactStrategy>,
+
+    /// User turns kept verbatim by the `rolling` compaction strategy.
+    pub compact_keep_recent_turns: Option<usize>,
+
     /// When set, restricts ChatGPT login to a specific workspace identifier.
     #[serde(default)]
     pub forced_chatgpt_workspace_id: Option<String>,
@@ -845,6 +997,9 @@ pub struct ConfigToml {
     /// OTEL configuration.
     pub otel: Option<crate::config::types::OtelConfigToml>,
 
+    /// Corporate proxy, trust store and mutual TLS settings.
+    pub network: Option<NetworkSettings>,
+
     /// Azure Entra ID authentication configuration.
     /// When set, enables Azure authentication for Azure endpoints.
     pub azure_auth: Option<crate::auth::azure_config::AzureAuthConfig>,
@@ -1235,6 +1390,12 @@ impl Config {
         let mut model_providers = built_in_model_providers();
         // Merge user-defined providers into the built-in list.
         for (key, provider) in cfg.model_providers.clone().into_iter() {
+            provider.validate_auth_headers().map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid model provider `{key}`: {e}"),
+                )
+            })?;
             model_providers.entry(key).or_insert(provider);
         }
 
@@ -1289,6 +1450,8 @@ impl Config {
                 auth_header_type: codex_api::AuthHeaderType::Bearer,
                 is_azure: true,
                 skip_azure_detection: false,
+                auth_headers: Vec::new(),
+                apim: None,
             };
 
             // Azure provider using Chat Completions API (for Claude, Grok, etc.)
@@ -1316,6 +1479,8 @@ impl Config {
                 auth_header_type: codex_api::AuthHeaderType::Bearer,
                 is_azure: true,
                 skip_azure_detection: false,
+                auth_headers: Vec::new(),
+                apim: None,
             };
 
             model_providers.insert("azure".to_string(), azure_responses_provider);
@@ -1343,6 +1508,58 @@ impl Config {
             })?
             .clone();
 
+        let compact_provider = cfg
+            .compact_provider
+            .as_ref()
+            .map(|id| {
+                model_providers.get(id).cloned().ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("Compaction model provider `{id}` not found"),
+                    )
+                })
+            })
+            .transpose()?;
+
+        let network = cfg.network.unwrap_or_default();
+        // Fail at startup rather than on the first request when a certificate
+        // file is missing or the proxy URL is malformed.
+        network.load().map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid [network] configuration: {e}"),
+            )
+        })?;
+
+        // Hooks run commands automatically, so a repository only gets to
+        // configure them once the user has trusted the project.
+        let project_hooks: Option<HooksConfig> =
+            layered_setting(&config_layer_stack, "hooks", true)?;
+        let hooks = if project_hooks.is_none() || active_project.is_trusted() {
+            cfg.hooks
+        } else {
+            tracing::warn!("ignoring [hooks] from .codex/config.toml: the project is not trusted");
+            layered_setting(&config_layer_stack, "hooks", false)?
+        }
+        .unwrap_or_default();
+        crate::hooks::validate_hooks_config(&hooks).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid [hooks] configuration: {e}"),
+            )
+        })?;
+
+        // Track which layer each custom tool came from so the repository's
+        // definitions can be gated on trust and never shadow the user's.
+        let project_custom_tools: Option<BTreeMap<String, CustomToolConfig>> =
+            layered_setting(&config_layer_stack, "custom_tools", true)?;
+        let custom_tools = if project_custom_tools.is_some() {
+            layered_setting(&config_layer_stack, "custom_tools", false)?
+        } else {
+            cfg.custom_tools
+        }
+        .unwrap_or_default();
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let history = cfg.history.unwrap_or_default();
@@ -1469,10 +1686,26 @@ impl Config {
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
             notify: cfg.notify,
+            hooks,
+            agents: cfg.agents.unwrap_or_default(),
+            budget: cfg.budget.unwrap_or_default(),
+            context_pruning: cfg.context_pruning.unwrap_or_default(),
+            pricing: cfg.pricing.unwrap_or_default(),
+            lsp: cfg.lsp.unwrap_or_default(),
+            custom_tools,
+            project_custom_tools: project_custom_tools.unwrap_or_default(),
+            fetch_url: cfg.fetch_url.unwrap_or_default(),
+            verify: cfg.verify.unwrap_or_default(),
             user_instructions,
             base_instructions,
             developer_instructions,
             compact_prompt,
+            compact_model: cfg.compact_model,
+            compact_provider,
+            compact_strategy: cfg.compact_strategy.unwrap_or_default(),
+            compact_keep_recent_turns: cfg
+                .compact_keep_recent_turns
+                .unwrap_or(DEFAULT_COMPACT_KEEP_RECENT_TURNS),
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
@@ -1507,6 +1740,7 @@ impl Config {
                 .show_raw_agent_reasoning
                 .or(show_raw_agent_reasoning)
                 .unwrap_or(false),
+            http_cassette: None,
             model_reasoning_effort: config_profile
                 .model_reasoning_effort
                 .or(cfg.model_reasoning_effort),
@@ -1579,6 +1813,7 @@ impl Config {
                     trace_exporter,
                 }
             },
+            network,
             // Default azure_auth to "default" mode (tries all auth methods) when
             // azure_endpoint is set but azure_auth is not explicitly configured
             azure_auth: cfg.azure_auth.or_else(|| {
@@ -1714,6 +1949,7 @@ mod tests {
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
     use crate::config::types::HistoryPersistence;
+    use crate::config::types::HookCommand;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
     use crate::features::Feature;
@@ -1789,6 +2025,247 @@ persistence = "none"
         );
     }
 
+    #[test]
+    fn network_config_parses_and_loads() -> std::io::Result<()> {
+        let cfg = r#"
+[network]
+proxy = "http://proxy.corp.example.com:8080"
+no_proxy = ["localhost", ".internal.example.com"]
+"#;
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("network config should parse");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.network,
+            NetworkSettings {
+                proxy: Some("http://proxy.corp.example.com:8080".to_string()),
+                no_proxy: vec!["localhost".to_string(), ".internal.example.com".to_string()],
+                ..Default::default()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn network_config_with_missing_ca_file_is_rejected() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = ConfigToml {
+            network: Some(NetworkSettings {
+                ca_certificates: vec![temp_dir.path().join("missing-ca.pem")],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )
+        .expect_err("missing CA file should fail config load");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        Ok(())
+    }
+
+    #[test]
+    fn budget_config_parses() -> std::io::Result<()> {
+        let cfg = r#"
+[budget]
+max_output_tokens = 200000
+max_cost_usd = 5.0
+max_turns = 20
+max_duration_secs = 3600
+"#;
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("budget config should parse");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.budget,
+            BudgetConfig {
+                max_output_tokens: Some(200_000),
+                max_cost_usd: Some(5.0),
+                max_turns: Some(20),
+                max_duration_secs: Some(3600),
+                ..Default::default()
+            }
+        );
+
+        assert!(toml::from_str::<ConfigToml>("[budget]\nmax_tokens = 10\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn context_pruning_config_parses() -> std::io::Result<()> {
+        let cfg = r#"
+[context_pruning]
+enabled = true
+keep_recent_turns = 4
+min_output_tokens = 2000
+"#;
+        let parsed =
+            toml::from_str::<ConfigToml>(cfg).expect("context pruning config should parse");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.context_pruning,
+            ContextPruningConfig {
+                enabled: true,
+                keep_recent_turns: 4,
+                min_output_tokens: 2000,
+                ..Default::default()
+            }
+        );
+        assert!(!ContextPruningConfig::default().enabled);
+        Ok(())
+    }
+
+    #[test]
+    fn compaction_settings_resolve_provider() -> std::io::Result<()> {
+        let cfg = r#"
+compact_model = "gpt-5-mini"
+compact_provider = "cheap"
+compact_strategy = "rolling"
+compact_keep_recent_turns = 3
+
+[model_providers.cheap]
+name = "Cheap"
+base_url = "https://cheap.example.com/v1"
+"#;
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("compaction config should parse");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+        assert_eq!(config.compact_model.as_deref(), Some("gpt-5-mini"));
+        assert_eq!(
+            config
+                .compact_provider
+                .as_ref()
+                .and_then(|provider| provider.base_url.as_deref()),
+            Some("https://cheap.example.com/v1")
+        );
+        assert_eq!(config.compact_strategy, CompactStrategy::Rolling);
+        assert_eq!(config.compact_keep_recent_turns, 3);
+
+        let missing = toml::from_str::<ConfigToml>("compact_provider = \"nope\"\n")
+            .expect("config should parse");
+        let err = Config::load_from_base_config_with_overrides(
+            missing,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )
+        .expect_err("unknown compaction provider should fail");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_config_parses_and_rejects_invalid_matchers() -> std::io::Result<()> {
+        let cfg = r#"
+[[hooks.pre_tool_use]]
+command = ["./scripts/guard-infra.sh"]
+matcher = "apply_patch|shell"
+timeout_ms = 5000
+
+[[hooks.post_tool_use]]
+command = ["cargo", "fmt"]
+matcher = "apply_patch"
+"#;
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("hooks config should parse");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.hooks,
+            HooksConfig {
+                pre_tool_use: vec![HookCommand {
+                    command: vec!["./scripts/guard-infra.sh".to_string()],
+                    matcher: Some("apply_patch|shell".to_string()),
+                    timeout_ms: Some(5000),
+                }],
+                post_tool_use: vec![HookCommand {
+                    command: vec!["cargo".to_string(), "fmt".to_string()],
+                    matcher: Some("apply_patch".to_string()),
+                    timeout_ms: None,
+                }],
+                ..Default::default()
+            }
+        );
+
+        let invalid = toml::from_str::<ConfigToml>(
+            r#"
+[[hooks.stop]]
+command = ["true"]
+matcher = "("
+"#,
+        )
+        .expect("hooks config should parse");
+        let err = Config::load_from_base_config_with_overrides(
+            invalid,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )
+        .expect_err("invalid matcher should fail config load");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn project_hooks_load_only_in_trusted_projects() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let project = TempDir::new()?;
+        std::fs::create_dir(project.path().join(".codex"))?;
+        std::fs::write(
+            project.path().join(".codex").join(CONFIG_TOML_FILE),
+            "[[hooks.session_start]]\ncommand = [\"./setup.sh\"]\n",
+        )?;
+        let user_config = "[[hooks.stop]]\ncommand = [\"notify-send\", \"done\"]\n";
+        std::fs::write(codex_home.path().join(CONFIG_TOML_FILE), user_config)?;
+        let load = || {
+            ConfigBuilder::default()
+                .codex_home(codex_home.path().to_path_buf())
+                .harness_overrides(ConfigOverrides {
+                    cwd: Some(project.path().to_path_buf()),
+                    ..Default::default()
+                })
+                .build()
+        };
+
+        let config = load().await?;
+        assert_eq!(config.hooks.stop.len(), 1);
+        assert!(config.hooks.session_start.is_empty());
+
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            format!(
+                "{user_config}\n[projects.'{}']\ntrust_level = \"trusted\"\n",
+                project.path().display()
+            ),
+        )?;
+        let config = load().await?;
+        assert_eq!(config.hooks.stop.len(), 1);
+        assert_eq!(config.hooks.session_start.len(), 1);
+        Ok(())
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -3280,6 +3757,8 @@ model_verbosity = "high"
             auth_header_type: Default::default(),
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -3346,6 +3825,16 @@ model_verbosity = "high"
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: HooksConfig::default(),
+                agents: AgentsConfig::default(),
+                budget: BudgetConfig::default(),
+                lsp: LspConfig::default(),
+                custom_tools: BTreeMap::new(),
+                project_custom_tools: BTreeMap::new(),
+                fetch_url: FetchUrlConfig::default(),
+                verify: VerifyConfig::default(),
+                context_pruning: ContextPruningConfig::default(),
+                pricing: PricingConfig::default(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: HashMap::new(),
@@ -3361,6 +3850,7 @@ model_verbosity = "high"
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
                 show_raw_agent_reasoning: false,
+                http_cassette: None,
                 model_reasoning_effort: Some(ReasoningEffort::High),
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 model_supports_reasoning_summaries: None,
@@ -3369,6 +3859,10 @@ model_verbosity = "high"
                 base_instructions: None,
                 developer_instructions: None,
                 compact_prompt: None,
+                compact_model: None,
+                compact_provider: None,
+                compact_strategy: CompactStrategy::Single,
+                compact_keep_recent_turns: DEFAULT_COMPACT_KEEP_RECENT_TURNS,
                 forced_chatgpt_workspace_id: None,
                 forced_login_method: None,
                 include_apply_patch_tool: false,
@@ -3398,6 +3892,7 @@ model_verbosity = "high"
                 tui_scroll_invert: false,
                 tui_theme: None,
                 otel: OtelConfig::default(),
+                network: NetworkSettings::default(),
                 azure_endpoint: None,
                 azure_api_version: "2025-04-01-preview".to_string(),
                 azure_auth: None,
@@ -3435,6 +3930,16 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
+            agents: AgentsConfig::default(),
+            budget: BudgetConfig::default(),
+            lsp: LspConfig::default(),
+            custom_tools: BTreeMap::new(),
+            project_custom_tools: BTreeMap::new(),
+            fetch_url: FetchUrlConfig::default(),
+            verify: VerifyConfig::default(),
+            context_pruning: ContextPruningConfig::default(),
+            pricing: PricingConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3450,6 +3955,7 @@ model_verbosity = "high"
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            http_cassette: None,
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
@@ -3458,6 +3964,10 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            compact_model: None,
+            compact_provider: None,
+            compact_strategy: CompactStrategy::Single,
+            compact_keep_recent_turns: DEFAULT_COMPACT_KEEP_RECENT_TURNS,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -3487,6 +3997,7 @@ model_verbosity = "high"
             tui_scroll_invert: false,
             tui_theme: None,
             otel: OtelConfig::default(),
+            network: NetworkSettings::default(),
             azure_endpoint: None,
             azure_api_version: "2025-04-01-preview".to_string(),
             azure_auth: None,
@@ -3539,6 +4050,16 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
+            agents: AgentsConfig::default(),
+            budget: BudgetConfig::default(),
+            lsp: LspConfig::default(),
+            custom_tools: BTreeMap::new(),
+            project_custom_tools: BTreeMap::new(),
+            fetch_url: FetchUrlConfig::default(),
+            verify: VerifyConfig::default(),
+            context_pruning: ContextPruningConfig::default(),
+            pricing: PricingConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3554,6 +4075,7 @@ model_verbosity = "high"
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            http_cassette: None,
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
@@ -3562,6 +4084,10 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            compact_model: None,
+            compact_provider: None,
+            compact_strategy: CompactStrategy::Single,
+            compact_keep_recent_turns: DEFAULT_COMPACT_KEEP_RECENT_TURNS,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -3591,6 +4117,7 @@ model_verbosity = "high"
             tui_scroll_invert: false,
             tui_theme: None,
             otel: OtelConfig::default(),
+            network: NetworkSettings::default(),
             azure_endpoint: None,
             azure_api_version: "2025-04-01-preview".to_string(),
             azure_auth: None,
@@ -3629,6 +4156,16 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
+            agents: AgentsConfig::default(),
+            budget: BudgetConfig::default(),
+            lsp: LspConfig::default(),
+            custom_tools: BTreeMap::new(),
+            project_custom_tools: BTreeMap::new(),
+            fetch_url: FetchUrlConfig::default(),
+            verify: VerifyConfig::default(),
+            context_pruning: ContextPruningConfig::default(),
+            pricing: PricingConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3644,6 +4181,7 @@ model_verbosity = "high"
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            http_cassette: None,
             model_reasoning_effort: Some(ReasoningEffort::High),
             model_reasoning_summary: ReasoningSummary::Detailed,
             model_supports_reasoning_summaries: None,
@@ -3652,6 +4190,10 @@ model_verbosity = "high"
             base_instructions: None,
             developer_instructions: None,
             compact_prompt: None,
+            compact_model: None,
+            compact_provider: None,
+            compact_strategy: CompactStrategy::Single,
+            compact_keep_recent_turns: DEFAULT_COMPACT_KEEP_RECENT_TURNS,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
             include_apply_patch_tool: false,
@@ -3681,6 +4223,7 @@ model_verbosity = "high"
             tui_scroll_invert: false,
             tui_theme: None,
             otel: OtelConfig::default(),
+            network: NetworkSettings::default(),
             azure_endpoint: None,
             azure_api_version: "2025-04-01-preview".to_string(),
             azure_auth: None,
@@ -4,6 +4,7 @@
 // definitions that do not contain business logic.
 
 use codex_utils_absolute_path::AbsolutePathBuf;
+use indexmap::IndexMap;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -363,6 +364,336 @@ impl Default for Notifications {
     }
 }
 
+/// Lifecycle hooks configured under `[hooks]`. Each event maps to a list of
+/// commands that run in order; see `core/src/hooks.rs` for the stdin/stdout
+/// protocol.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Runs once after the session has been configured.
+    #[serde(default)]
+    pub session_start: Vec<HookCommand>,
+
+    /// Runs before a user prompt is sent to the model. May deny or rewrite it.
+    #[serde(default)]
+    pub user_prompt_submit: Vec<HookCommand>,
+
+    /// Runs before a tool call executes. May deny it or rewrite its input.
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookCommand>,
+
+    /// Runs after a tool call produced output. May replace the output.
+    #[serde(default)]
+    pub post_tool_use: Vec<HookCommand>,
+
+    /// Runs before the user is asked to approve a tool call. May approve or
+    /// reject it without prompting.
+    #[serde(default)]
+    pub approval_request: Vec<HookCommand>,
+
+    /// Runs when a turn completes or is aborted.
+    #[serde(default)]
+    pub stop: Vec<HookCommand>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.session_start.is_empty()
+            && self.user_prompt_submit.is_empty()
+            && self.pre_tool_use.is_empty()
+            && self.post_tool_use.is_empty()
+            && self.approval_request.is_empty()
+            && self.stop.is_empty()
+    }
+
+    pub(crate) fn all(&self) -> impl Iterator<Item = &HookCommand> {
+        self.session_start
+            .iter()
+            .chain(&self.user_prompt_submit)
+            .chain(&self.pre_tool_use)
+            .chain(&self.post_tool_use)
+            .chain(&self.approval_request)
+            .chain(&self.stop)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HookCommand {
+    /// Program and arguments. The event payload is written to stdin as JSON.
+    pub command: Vec<String>,
+
+    /// Regular expression matched against the whole tool name. Only used by
+    /// tool and approval hooks; when unset the hook runs for every tool.
+    #[serde(default)]
+    pub matcher: Option<String>,
+
+    /// How long to wait for the command before ignoring it. Defaults to 60s.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Settings for sub-agents started through the `spawn_agent` tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AgentsConfig {
+    /// Model (or deployment) used when the tool call does not name one.
+    /// Defaults to the parent session's model.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// How many sub-agents may run at the same time. `1` runs them one after
+    /// another. Defaults to 3.
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+}
+
+/// Session-wide limits configured under `[budget]`. Unset limits are not
+/// enforced.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetConfig {
+    /// Input tokens (including cached input) billed across the session.
+    #[serde(default)]
+    pub max_input_tokens: Option<u64>,
+
+    /// Output tokens (including reasoning) billed across the session.
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+
+    /// Estimated spend in US dollars. Only enforced when pricing is known for
+    /// the session's model.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+
+    /// Tool calls the model may make.
+    #[serde(default)]
+    pub max_tool_calls: Option<u64>,
+
+    /// User turns the session may run.
+    #[serde(default)]
+    pub max_turns: Option<u64>,
+
+    /// Wall-clock time since the session started, in seconds.
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+}
+
+impl BudgetConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Pruning of old tool outputs as the context fills up, configured under
+/// `[context_pruning]`. Pruned outputs are replaced with a stub that tells the
+/// model how to fetch them again. Off by default: rewriting old outputs
+/// changes the prompt prefix, so the next request misses the prompt cache.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct ContextPruningConfig {
+    /// Set to `true` to prune before falling back to compaction.
+    pub enabled: bool,
+
+    /// Percentage of the auto-compaction token limit at which pruning starts.
+    pub start_percent: u8,
+
+    /// Most recent user turns whose tool outputs are never pruned.
+    pub keep_recent_turns: usize,
+
+    /// Outputs estimated below this many tokens are kept.
+    pub min_output_tokens: usize,
+}
+
+impl Default for ContextPruningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_percent: 70,
+            keep_recent_turns: 2,
+            min_output_tokens: 500,
+        }
+    }
+}
+
+/// How compaction summarizes the conversation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactStrategy {
+    /// Replace the whole conversation with one summary.
+    #[default]
+    Single,
+
+    /// Summarize everything but the most recent user turns, which are kept
+    /// verbatim (see `compact_keep_recent_turns`).
+    Rolling,
+
+    /// Summarize into fixed sections: the plan, files touched and open TODOs.
+    /// Always runs locally since remote compaction takes no prompt.
+    Structured,
+}
+
+/// Token prices in US dollars per million tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPricing {
+    pub input: f64,
+
+    /// Price of cached input tokens. Defaults to `input`.
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+
+    pub output: f64,
+
+    /// Price of reasoning tokens. Defaults to `output`.
+    #[serde(default)]
+    pub reasoning: Option<f64>,
+}
+
+/// Overrides for the built-in price list, configured under `[pricing]`.
+/// Keys are model slugs (or deployment names) and also match dated
+/// snapshots, so `gpt-5` covers `gpt-5-2025-08-07` but not `gpt-5-mini`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PricingConfig {
+    /// Prices that apply to every provider.
+    #[serde(default)]
+    pub models: HashMap<String, ModelPricing>,
+
+    /// Prices for a single provider, keyed by provider id and then by model
+    /// or deployment name. These win over `models` and the built-in list.
+    #[serde(default)]
+    pub providers: HashMap<String, HashMap<String, ModelPricing>>,
+}
+
+/// Language servers for the LSP tools, configured under `[lsp]`. Servers for
+/// Rust (rust-analyzer), Python (pyright) and Go (gopls) are built in;
+/// entries in `servers` replace a built-in server of the same name or add a
+/// new one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LspConfig {
+    #[serde(default)]
+    pub servers: BTreeMap<String, LspServerConfig>,
+
+    /// Names of built-in or configured servers that should never be started.
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// How long to wait for diagnostics after `apply_patch`, in milliseconds.
+    #[serde(default)]
+    pub diagnostics_timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LspServerConfig {
+    /// Executable that speaks LSP over stdio.
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// File extensions (without the dot) handled by this server.
+    pub extensions: Vec<String>,
+
+    /// `languageId` sent when opening documents. Defaults to the server name.
+    #[serde(default)]
+    pub language_id: Option<String>,
+}
+
+/// A tool backed by a local command, declared under `[custom_tools.<name>]`
+/// in config.toml or as `.codex/tools/<name>.toml`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomToolConfig {
+    /// Tells the model what the tool does and when to call it.
+    pub description: String,
+
+    /// Program and arguments. With `input = "argv"`, an element `{{name}}`
+    /// (or one containing it) is filled from the argument of that name.
+    pub command: Vec<String>,
+
+    /// JSON Schema for the arguments. Must describe an object; defaults to
+    /// an object without properties.
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+
+    #[serde(default)]
+    pub input: CustomToolInput,
+
+    /// Working directory, relative to the session cwd.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+
+    /// Extra environment variables for the command.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// How a custom tool receives its arguments.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomToolInput {
+    /// The arguments object is written to stdin as JSON.
+    #[default]
+    Stdin,
+    /// Arguments are validated and substituted into `command`.
+    Argv,
+}
+
+/// Settings for the `fetch_url` tool, under `[fetch_url]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FetchUrlConfig {
+    /// Hosts the tool may fetch from; an entry also matches its subdomains.
+    /// When empty, any public host is allowed. Loopback and private
+    /// addresses are only reachable when listed here.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+
+    /// Hosts that are never fetched, even when they match the allowlist.
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+
+    /// Characters of converted markdown returned per page.
+    #[serde(default)]
+    pub page_chars: Option<usize>,
+
+    /// Largest response body read, in bytes.
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
+
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Commands run after a turn edits files, under `[verify]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyConfig {
+    /// Glob -> shell commands run, in order, when an edited file matches.
+    /// Globs are checked in the order they are written. A glob without `/`
+    /// matches the file name; otherwise it matches the path relative to the
+    /// session cwd. Commands may use `{files}` (the matching files,
+    /// shell-quoted) and `{crate}` (the package name of the nearest
+    /// `Cargo.toml`, running the command once per crate).
+    #[serde(default)]
+    pub commands: IndexMap<String, Vec<String>>,
+
+    /// How many extra model turns a failing verification may trigger before
+    /// the task ends with the failure reported. Defaults to 2.
+    #[serde(default)]
+    pub max_fix_attempts: Option<u32>,
+
+    /// Per-command timeout in milliseconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
 /// How TUI2 should interpret mouse scroll events.
 ///
 /// Terminals generally encode both mouse wheels and trackpads as the same "scroll up/down" mouse
@@ -161,6 +161,16 @@ impl ConfigLayerStack {
         merged
     }
 
+    /// Like [Self::effective_config], but merges only the layers whose
+    /// source satisfies `include`.
+    pub fn effective_config_of(&self, include: impl Fn(&ConfigLayerSource) -> bool) -> TomlValue {
+        let mut merged = TomlValue::Table(toml::map::Map::new());
+        for layer in self.layers.iter().filter(|layer| include(&layer.name)) {
+            merge_toml_values(&mut merged, &layer.config);
+        }
+        merged
+    }
+
     pub fn origins(&self) -> HashMap<String, ConfigLayerMetadata> {
         let mut origins = HashMap::new();
         let mut path = Vec::new();
@@ -1,10 +1,15 @@
 use crate::codex::TurnContext;
+use crate::compact::is_summary_message;
 use crate::context_manager::normalize;
+use crate::context_manager::prune;
+use crate::event_mapping::parse_turn_item;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
+use codex_git::GhostCommit;
+use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
@@ -19,6 +24,8 @@ pub(crate) struct ContextManager {
     /// The oldest items are at the beginning of the vector.
     items: Vec<ResponseItem>,
     token_info: Option<TokenUsageInfo>,
+    /// Tokens freed by pruning since the model last reported usage.
+    pruned_tokens: usize,
 }
 
 impl ContextManager {
@@ -26,6 +33,7 @@ impl ContextManager {
         Self {
             items: Vec::new(),
             token_info: TokenUsageInfo::new_or_append(&None, &None, None),
+            pruned_tokens: 0,
         }
     }
 
@@ -117,6 +125,87 @@ impl ContextManager {
         }
     }
 
+    /// Drops the last `num_turns` user turns, i.e. everything from the
+    /// `num_turns`-th most recent user message onwards, and returns the removed
+    /// items. Leaves history untouched and returns `None` when it holds fewer
+    /// turns than requested.
+    pub(crate) fn drop_last_turns(&mut self, num_turns: usize) -> Option<Vec<ResponseItem>> {
+        if num_turns == 0 {
+            return Some(Vec::new());
+        }
+        let start = self
+            .items
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, item)| is_user_turn_start(item))
+            .nth(num_turns - 1)
+            .map(|(idx, _)| idx)?;
+        Some(self.items.split_off(start))
+    }
+
+    /// Replaces large tool outputs older than the last `keep_recent_turns` user
+    /// turns with stubs, unless the recent turns refer to them. Returns the
+    /// approximate number of tokens freed, which is also deducted from the
+    /// total usage until the model reports usage again.
+    pub(crate) fn prune_tool_outputs(
+        &mut self,
+        keep_recent_turns: usize,
+        min_output_tokens: usize,
+    ) -> usize {
+        let recent_start = if keep_recent_turns == 0 {
+            self.items.len()
+        } else {
+            let Some(start) = self
+                .items
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, item)| is_user_turn_start(item))
+                .nth(keep_recent_turns - 1)
+                .map(|(idx, _)| idx)
+            else {
+                return 0;
+            };
+            start
+        };
+        let (older, recent) = self.items.split_at_mut(recent_start);
+        let freed = prune::prune_tool_outputs(older, recent, min_output_tokens);
+        self.pruned_tokens = self.pruned_tokens.saturating_add(freed);
+        freed
+    }
+
+    /// Ghost snapshots recorded in each user turn, newest first. Turns without a
+    /// snapshot (e.g. already undone) are skipped.
+    pub(crate) fn turn_snapshots(&self) -> Vec<TurnSnapshot> {
+        let mut snapshots = Vec::new();
+        let mut num_turns = 0;
+        let mut pending: Option<GhostCommit> = None;
+        for item in self.items.iter().rev() {
+            if let ResponseItem::GhostSnapshot { ghost_commit } = item {
+                pending = Some(ghost_commit.clone());
+                continue;
+            }
+            if !is_user_turn_start(item) {
+                continue;
+            }
+            num_turns += 1;
+            let Some(ghost_commit) = pending.take() else {
+                continue;
+            };
+            let prompt = match parse_turn_item(item) {
+                Some(TurnItem::UserMessage(user)) => user.message(),
+                _ => String::new(),
+            };
+            snapshots.push(TurnSnapshot {
+                num_turns,
+                prompt,
+                ghost_commit,
+            });
+        }
+        snapshots
+    }
+
     pub(crate) fn replace(&mut self, items: Vec<ResponseItem>) {
         self.items = items;
     }
@@ -162,6 +251,7 @@ impl ContextManager {
             &Some(usage.clone()),
             model_context_window,
         );
+        self.pruned_tokens = 0;
     }
 
     fn get_non_last_reasoning_items_tokens(&self) -> usize {
@@ -202,6 +292,7 @@ impl ContextManager {
             .map(|info| info.last_token_usage.total_tokens)
             .unwrap_or(0)
             .saturating_add(self.get_non_last_reasoning_items_tokens() as i64)
+            .saturating_sub(self.pruned_tokens as i64)
     }
 
     /// This function enforces a couple of invariants on the in-memory history:
@@ -265,6 +356,25 @@ impl ContextManager {
     }
 }
 
+/// Ghost snapshot recorded during a user turn.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TurnSnapshot {
+    /// Trailing turns `drop_last_turns` removes to return to this turn.
+    pub(crate) num_turns: usize,
+    /// Message that started the turn.
+    pub(crate) prompt: String,
+    pub(crate) ghost_commit: GhostCommit,
+}
+
+/// Whether `item` is a message the user typed, as opposed to injected context
+/// or a compaction summary.
+fn is_user_turn_start(item: &ResponseItem) -> bool {
+    match parse_turn_item(item) {
+        Some(TurnItem::UserMessage(user)) => !is_summary_message(&user.message()),
+        _ => false,
+    }
+}
+
 /// API messages include every non-system item (user/assistant messages, reasoning,
 /// tool calls, tool outputs, shell calls, and web-search calls).
 fn is_api_message(message: &ResponseItem) -> bool {
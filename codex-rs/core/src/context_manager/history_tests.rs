@@ -161,6 +161,111 @@ fn get_history_for_prompt_drops_ghost_commits() {
     assert_eq!(filtered, vec![]);
 }
 
+#[test]
+fn drop_last_turns_removes_trailing_user_turns() {
+    let ghost = ResponseItem::GhostSnapshot {
+        ghost_commit: GhostCommit::new("ghost-2".to_string(), None, Vec::new(), Vec::new()),
+    };
+    let items = vec![
+        user_msg("first"),
+        assistant_msg("one"),
+        user_msg("second"),
+        ghost,
+        assistant_msg("two"),
+        user_msg("third"),
+        assistant_msg("three"),
+    ];
+    let mut history = create_history_with_items(items.clone());
+
+    assert_eq!(history.drop_last_turns(4), None);
+    assert_eq!(history.get_history(), items);
+
+    let removed = history.drop_last_turns(2).expect("two turns to drop");
+    assert_eq!(removed, items[2..].to_vec());
+    assert_eq!(history.get_history(), items[..2].to_vec());
+    assert_eq!(history.drop_last_turns(0), Some(Vec::new()));
+}
+
+#[test]
+fn turn_snapshots_pairs_snapshots_with_their_turns() {
+    let ghost = |id: &str| ResponseItem::GhostSnapshot {
+        ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+    };
+    let history = create_history_with_items(vec![
+        user_msg("first"),
+        ghost("ghost-1"),
+        assistant_msg("one"),
+        user_msg("second"),
+        assistant_msg("two"),
+        user_msg("third"),
+        ghost("ghost-3"),
+        assistant_msg("three"),
+    ]);
+
+    let snapshots = history.turn_snapshots();
+    let summary: Vec<(usize, &str, &str)> = snapshots
+        .iter()
+        .map(|s| (s.num_turns, s.prompt.as_str(), s.ghost_commit.id()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![(1, "third", "ghost-3"), (3, "first", "ghost-1")]
+    );
+}
+
+#[test]
+fn prune_tool_outputs_stubs_old_large_outputs() {
+    let call = |call_id: &str, path: &str| ResponseItem::FunctionCall {
+        id: None,
+        name: "read_file".to_string(),
+        arguments: format!(r#"{{"file_path":"{path}","workdir":"/repo"}}"#),
+        call_id: call_id.to_string(),
+    };
+    let output = |call_id: &str, content: String| ResponseItem::FunctionCallOutput {
+        call_id: call_id.to_string(),
+        output: FunctionCallOutputPayload {
+            content,
+            success: Some(true),
+            ..Default::default()
+        },
+    };
+    let large = "fn body() {}\n".repeat(200);
+    let items = vec![
+        user_msg("read the parser"),
+        call("call-old", "/repo/src/parser.rs"),
+        output("call-old", large.clone()),
+        call("call-small", "/repo/Cargo.toml"),
+        output("call-small", "[package]".to_string()),
+        call("call-kept", "/repo/src/lexer.rs"),
+        output("call-kept", large.clone()),
+        assistant_msg("done"),
+        user_msg("now fix lexer.rs in /repo"),
+        call("call-recent", "/repo/src/main.rs"),
+        output("call-recent", large),
+    ];
+    let mut history = create_history_with_items(items.clone());
+
+    assert_eq!(history.prune_tool_outputs(5, 100), 0);
+    let freed = history.prune_tool_outputs(1, 100);
+    assert!(freed > 0);
+
+    let contents = history.get_history();
+    let ResponseItem::FunctionCallOutput { output: pruned, .. } = &contents[2] else {
+        panic!("expected function call output");
+    };
+    assert!(pruned.content.starts_with(prune::PRUNED_OUTPUT_PREFIX));
+    assert!(pruned.content.contains("read_file"));
+    assert!(pruned.content.contains("/repo/src/parser.rs"));
+    assert_eq!(pruned.success, Some(true));
+    // Small outputs, outputs the recent turn refers to and the recent turn
+    // itself are untouched.
+    assert_eq!(contents[4], items[4]);
+    assert_eq!(contents[6], items[6]);
+    assert_eq!(contents[10], items[10]);
+
+    assert_eq!(history.prune_tool_outputs(1, 100), 0);
+}
+
 #[test]
 fn remove_first_item_removes_matching_output_for_function_call() {
     let items = vec![
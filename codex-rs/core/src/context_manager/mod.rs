@@ -1,4 +1,6 @@
 mod history;
 mod normalize;
+mod prune;
 
 pub(crate) use history::ContextManager;
+pub(crate) use history::TurnSnapshot;
@@ -0,0 +1,200 @@
+//! Replaces old, large tool outputs with short stubs so a long session can
+//! keep going before it needs a full compaction.
+
+use std::collections::HashMap;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseItem;
+use serde_json::Value;
+
+use crate::truncate::approx_token_count;
+
+/// Prefix of the text that replaces a pruned output.
+pub(crate) const PRUNED_OUTPUT_PREFIX: &str = "[Output pruned to save context";
+
+/// Longest slice of the call arguments repeated in a stub.
+const STUB_ARGUMENTS_MAX_CHARS: usize = 200;
+
+/// Rough cost of an image in a tool output; its data URL length says little
+/// about what the model is billed.
+const IMAGE_TOKENS: usize = 1_000;
+
+/// Shortest argument token treated as a reference (e.g. a path).
+const MIN_REFERENCE_LEN: usize = 4;
+
+/// Call arguments that name where a command runs rather than what it reads.
+const WORKDIR_ARGUMENTS: &[&str] = &["workdir", "cwd"];
+
+struct CallInfo {
+    name: String,
+    arguments: String,
+}
+
+/// Replaces the outputs in `older` that are at least `min_output_tokens` long
+/// and that nothing in `recent` refers to. Returns the approximate number of
+/// tokens freed.
+pub(crate) fn prune_tool_outputs(
+    older: &mut [ResponseItem],
+    recent: &[ResponseItem],
+    min_output_tokens: usize,
+) -> usize {
+    let calls: HashMap<String, CallInfo> = older
+        .iter()
+        .filter_map(|item| match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => Some((
+                call_id.clone(),
+                CallInfo {
+                    name: name.clone(),
+                    arguments: arguments.clone(),
+                },
+            )),
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => Some((
+                call_id.clone(),
+                CallInfo {
+                    name: name.clone(),
+                    arguments: input.clone(),
+                },
+            )),
+            _ => None,
+        })
+        .collect();
+    let recent_text = referenced_text(recent);
+
+    let mut freed = 0;
+    for item in older.iter_mut() {
+        let (call_id, tokens) = match item {
+            ResponseItem::FunctionCallOutput { call_id, output }
+                if !output.content.starts_with(PRUNED_OUTPUT_PREFIX) =>
+            {
+                (call_id.as_str(), payload_tokens(output))
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output }
+                if !output.starts_with(PRUNED_OUTPUT_PREFIX) =>
+            {
+                (call_id.as_str(), approx_token_count(output))
+            }
+            _ => continue,
+        };
+        if tokens < min_output_tokens {
+            continue;
+        }
+        let call = calls.get(call_id);
+        if call.is_some_and(|call| is_referenced(&call.arguments, &recent_text)) {
+            continue;
+        }
+        let stub = stub_for(call, tokens);
+        freed += tokens.saturating_sub(approx_token_count(&stub));
+        match item {
+            ResponseItem::FunctionCallOutput { output, .. } => {
+                *output = FunctionCallOutputPayload {
+                    content: stub,
+                    content_items: None,
+                    success: output.success,
+                };
+            }
+            ResponseItem::CustomToolCallOutput { output, .. } => *output = stub,
+            _ => {}
+        }
+    }
+    freed
+}
+
+fn payload_tokens(payload: &FunctionCallOutputPayload) -> usize {
+    let items_tokens: usize = payload
+        .content_items
+        .iter()
+        .flatten()
+        .map(|item| match item {
+            FunctionCallOutputContentItem::InputText { text } => approx_token_count(text),
+            FunctionCallOutputContentItem::InputImage { .. } => IMAGE_TOKENS,
+        })
+        .sum();
+    approx_token_count(&payload.content).max(items_tokens)
+}
+
+/// Text the model or user wrote in the recent turns: messages and the
+/// arguments of tool calls. Tool outputs are left out so that one large
+/// listing does not keep every path it mentions alive.
+fn referenced_text(recent: &[ResponseItem]) -> String {
+    let mut text = String::new();
+    for item in recent {
+        match item {
+            ResponseItem::Message { content, .. } => {
+                for content_item in content {
+                    if let ContentItem::InputText { text: part }
+                    | ContentItem::OutputText { text: part } = content_item
+                    {
+                        text.push_str(part);
+                        text.push('\n');
+                    }
+                }
+            }
+            ResponseItem::FunctionCall { arguments, .. } => {
+                text.push_str(arguments);
+                text.push('\n');
+            }
+            ResponseItem::CustomToolCall { input, .. } => {
+                text.push_str(input);
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Whether any path-like token of `arguments` (or its file name) appears in
+/// `recent_text`. The working directory of a call is ignored since most calls
+/// share it.
+fn is_referenced(arguments: &str, recent_text: &str) -> bool {
+    let arguments = match serde_json::from_str::<Value>(arguments) {
+        Ok(Value::Object(mut fields)) => {
+            for key in WORKDIR_ARGUMENTS {
+                fields.remove(*key);
+            }
+            Value::Object(fields).to_string()
+        }
+        _ => arguments.to_string(),
+    };
+    arguments
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '/' | '.' | '_' | '-')))
+        .filter(|token| token.contains('/') || token.contains('.'))
+        .flat_map(|token| {
+            let file_name = token.rsplit('/').next().filter(|name| name.contains('.'));
+            std::iter::once(token).chain(file_name)
+        })
+        .filter(|token| token.len() >= MIN_REFERENCE_LEN && token.chars().any(char::is_alphabetic))
+        .any(|token| recent_text.contains(token))
+}
+
+fn stub_for(call: Option<&CallInfo>, tokens: usize) -> String {
+    let Some(call) = call else {
+        return format!(
+            "{PRUNED_OUTPUT_PREFIX}: ~{tokens} tokens. Run the tool again if you need it.]"
+        );
+    };
+    let mut arguments: String = call
+        .arguments
+        .chars()
+        .take(STUB_ARGUMENTS_MAX_CHARS)
+        .collect();
+    if arguments.len() < call.arguments.len() {
+        arguments.push('…');
+    }
+    format!(
+        "{PRUNED_OUTPUT_PREFIX}: ~{tokens} tokens from `{}`. Call it again with the same arguments if you need it: {arguments}]",
+        call.name
+    )
+}
@@ -0,0 +1,538 @@
+//! Tools backed by local commands.
+//!
+//! Tools come from `[custom_tools.<name>]` in the user's config.toml, then
+//! `$CODEX_HOME/tools/*.toml`, then, when the project is trusted,
+//! `[custom_tools.<name>]` in the repository's `.codex/config.toml` and the
+//! nearest `.codex/tools/*.toml` under the project root. The first definition
+//! of a name wins, so a repository cannot replace a tool the user defined.
+//! Each file defines one tool named after the file stem. Definitions are
+//! loaded once per session.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::Map;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::config::types::CustomToolConfig;
+use crate::config::types::CustomToolInput;
+use crate::git_info::resolve_root_git_project_for_trust;
+
+const REPO_CONFIG_DIR_NAME: &str = ".codex";
+const TOOLS_DIR_NAME: &str = "tools";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CustomTool {
+    pub(crate) name: String,
+    pub(crate) config: CustomToolConfig,
+}
+
+/// The argv and optional stdin for one call of a custom tool.
+#[derive(Debug, PartialEq)]
+pub(crate) struct CustomToolInvocation {
+    pub(crate) command: Vec<String>,
+    pub(crate) stdin: Option<String>,
+}
+
+pub(crate) fn load_custom_tools(config: &Config) -> Vec<CustomTool> {
+    let repo_dir = repo_tools_dir(&config.cwd);
+    let project = if config.active_project.is_trusted() {
+        Some((&config.project_custom_tools, repo_dir.as_deref()))
+    } else {
+        if repo_dir.is_some() || !config.project_custom_tools.is_empty() {
+            warn!(
+                "ignoring custom tools defined in {}: the project is not trusted",
+                config.cwd.display()
+            );
+        }
+        None
+    };
+    collect_tools(
+        &config.custom_tools,
+        &config.codex_home.join(TOOLS_DIR_NAME),
+        project,
+    )
+}
+
+/// `project` holds the repository's `[custom_tools]` table and its tools
+/// directory; both rank below the user's own definitions.
+fn collect_tools(
+    configured: &BTreeMap<String, CustomToolConfig>,
+    user_dir: &Path,
+    project: Option<(&BTreeMap<String, CustomToolConfig>, Option<&Path>)>,
+) -> Vec<CustomTool> {
+    let clone_all = |tools: &BTreeMap<String, CustomToolConfig>| {
+        tools
+            .iter()
+            .map(|(name, tool)| (name.clone(), tool.clone()))
+            .collect::<Vec<_>>()
+    };
+    let mut candidates = clone_all(configured);
+    candidates.extend(load_tools_dir(user_dir));
+    if let Some((project_configured, repo_dir)) = project {
+        candidates.extend(clone_all(project_configured));
+        if let Some(dir) = repo_dir {
+            candidates.extend(load_tools_dir(dir));
+        }
+    }
+
+    let mut tools: Vec<CustomTool> = Vec::new();
+    for (name, config) in candidates {
+        if tools.iter().any(|tool| tool.name == name) {
+            continue;
+        }
+        match validate_tool(&name, &config) {
+            Ok(()) => tools.push(CustomTool { name, config }),
+            Err(err) => warn!("ignoring custom tool `{name}`: {err}"),
+        }
+    }
+    tools
+}
+
+/// The nearest `.codex/tools` directory between `cwd` and the project root.
+fn repo_tools_dir(cwd: &Path) -> Option<PathBuf> {
+    let repo_root = resolve_root_git_project_for_trust(cwd);
+    for dir in cwd.ancestors() {
+        let tools_dir = dir.join(REPO_CONFIG_DIR_NAME).join(TOOLS_DIR_NAME);
+        if tools_dir.is_dir() {
+            return Some(tools_dir);
+        }
+        // Outside a repository only the cwd itself is considered.
+        if repo_root.as_deref().is_none_or(|root| dir == root) {
+            break;
+        }
+    }
+    None
+}
+
+fn load_tools_dir(dir: &Path) -> Vec<(String, CustomToolConfig)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|text| {
+                    toml::from_str::<CustomToolConfig>(&text).map_err(|err| err.to_string())
+                });
+            match parsed {
+                Ok(config) => Some((name, config)),
+                Err(err) => {
+                    warn!("failed to load custom tool {}: {err}", path.display());
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+fn validate_tool(name: &str, config: &CustomToolConfig) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err("names may only contain letters, digits, `_` and `-`".to_string());
+    }
+    if config.command.is_empty() {
+        return Err("`command` must not be empty".to_string());
+    }
+    let schema = parameters_schema(config);
+    if schema.get("type").and_then(Value::as_str) != Some("object") {
+        return Err("`parameters` must be a JSON schema with type \"object\"".to_string());
+    }
+    if config.input == CustomToolInput::Argv {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for arg in &config.command {
+            for placeholder in placeholders(arg) {
+                if !properties.is_some_and(|properties| properties.contains_key(placeholder)) {
+                    return Err(format!(
+                        "`{{{{{placeholder}}}}}` in `command` is not a declared parameter"
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The declared parameter schema, defaulting to an object with no properties.
+pub(crate) fn parameters_schema(config: &CustomToolConfig) -> Value {
+    let mut schema = config
+        .parameters
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({ "type": "object" }));
+    if let Some(object) = schema.as_object_mut() {
+        object
+            .entry("properties")
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    schema
+}
+
+/// Validates the model's arguments against the tool's schema and builds the
+/// command to run.
+pub(crate) fn build_invocation(
+    config: &CustomToolConfig,
+    arguments: &str,
+) -> Result<CustomToolInvocation, String> {
+    let arguments = if arguments.trim().is_empty() {
+        Value::Object(Map::new())
+    } else {
+        serde_json::from_str(arguments)
+            .map_err(|err| format!("failed to parse function arguments: {err}"))?
+    };
+    let Value::Object(args) = arguments else {
+        return Err("arguments must be a JSON object".to_string());
+    };
+    validate_arguments(&parameters_schema(config), &args)?;
+
+    match config.input {
+        CustomToolInput::Stdin => Ok(CustomToolInvocation {
+            command: config.command.clone(),
+            stdin: Some(Value::Object(args).to_string()),
+        }),
+        CustomToolInput::Argv => Ok(CustomToolInvocation {
+            command: substitute_argv(&config.command, &args)?,
+            stdin: None,
+        }),
+    }
+}
+
+fn validate_arguments(schema: &Value, args: &Map<String, Value>) -> Result<(), String> {
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if args.get(name).is_none_or(Value::is_null) {
+                return Err(format!("missing required argument `{name}`"));
+            }
+        }
+    }
+    let additional_allowed = schema.get("additionalProperties") != Some(&Value::Bool(false));
+    for (name, value) in args {
+        match properties.get(name) {
+            Some(property) => validate_value(name, property, value)?,
+            None if additional_allowed => {}
+            None => return Err(format!("unknown argument `{name}`")),
+        }
+    }
+    Ok(())
+}
+
+fn validate_value(name: &str, schema: &Value, value: &Value) -> Result<(), String> {
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let matches = match expected {
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!("argument `{name}` must be of type {expected}"));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        return Err(format!(
+            "argument `{name}` must be one of {}",
+            allowed.join(", ")
+        ));
+    }
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for item in values {
+            validate_value(name, items, item)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fills `{{name}}` placeholders. An element that is exactly a placeholder
+/// becomes one argument per value (arrays expand); an element with a
+/// placeholder inside it, such as `--limit={{limit}}`, is dropped when the
+/// argument is absent.
+fn substitute_argv(command: &[String], args: &Map<String, Value>) -> Result<Vec<String>, String> {
+    let mut argv = Vec::with_capacity(command.len());
+    for element in command {
+        let names = placeholders(element);
+        if names.is_empty() {
+            argv.push(element.clone());
+            continue;
+        }
+        if let [name] = names.as_slice()
+            && element.trim() == format!("{{{{{name}}}}}")
+        {
+            let values = match args.get(*name) {
+                None | Some(Value::Null) => Vec::new(),
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .map(|item| scalar_arg(name, item))
+                    .collect::<Result<_, _>>()?,
+                Some(value) => vec![scalar_arg(name, value)?],
+            };
+            for value in values {
+                // A whole-argument value must not be read as an option.
+                if value.starts_with('-') {
+                    return Err(format!("argument `{name}` must not start with `-`"));
+                }
+                argv.push(value);
+            }
+            continue;
+        }
+
+        let mut filled = element.clone();
+        let mut complete = true;
+        for name in names {
+            match args.get(name) {
+                None | Some(Value::Null) => complete = false,
+                Some(value) => {
+                    let value = scalar_arg(name, value)?;
+                    filled = filled.replace(&format!("{{{{{name}}}}}"), &value);
+                }
+            }
+        }
+        if complete {
+            argv.push(filled);
+        }
+    }
+    Ok(argv)
+}
+
+fn scalar_arg(name: &str, value: &Value) -> Result<String, String> {
+    let arg = match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => {
+            return Err(format!(
+                "argument `{name}` cannot be passed on the command line"
+            ));
+        }
+    };
+    if arg.contains('\0') {
+        return Err(format!("argument `{name}` must not contain NUL bytes"));
+    }
+    Ok(arg)
+}
+
+fn placeholders(element: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = element;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn argv_tool() -> CustomToolConfig {
+        CustomToolConfig {
+            description: "Lint migrations".to_string(),
+            command: vec![
+                "migration-lint".to_string(),
+                "--format=json".to_string(),
+                "--level={{level}}".to_string(),
+                "{{files}}".to_string(),
+            ],
+            parameters: Some(json!({
+                "type": "object",
+                "properties": {
+                    "files": { "type": "array", "items": { "type": "string" } },
+                    "level": { "type": "string", "enum": ["warn", "error"] },
+                },
+                "required": ["files"],
+                "additionalProperties": false,
+            })),
+            input: CustomToolInput::Argv,
+            cwd: None,
+            env: HashMap::new(),
+            timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn argv_tools_fill_placeholders_and_validate_arguments() {
+        let tool = argv_tool();
+        assert_eq!(
+            build_invocation(&tool, r#"{"files": ["a.sql", "b.sql"]}"#),
+            Ok(CustomToolInvocation {
+                command: vec![
+                    "migration-lint".to_string(),
+                    "--format=json".to_string(),
+                    "a.sql".to_string(),
+                    "b.sql".to_string(),
+                ],
+                stdin: None,
+            })
+        );
+        assert_eq!(
+            build_invocation(&tool, r#"{"files": ["a.sql"], "level": "error"}"#)
+                .map(|invocation| invocation.command[2].clone()),
+            Ok("--level=error".to_string())
+        );
+        assert_eq!(
+            build_invocation(&tool, r#"{"files": ["--fix"]}"#),
+            Err("argument `files` must not start with `-`".to_string())
+        );
+        assert_eq!(
+            build_invocation(&tool, r#"{"files": [], "level": "info"}"#),
+            Err(r#"argument `level` must be one of "warn", "error""#.to_string())
+        );
+        assert_eq!(
+            build_invocation(&tool, r#"{"level": "warn"}"#),
+            Err("missing required argument `files`".to_string())
+        );
+        assert_eq!(
+            build_invocation(&tool, r#"{"files": [], "force": true}"#),
+            Err("unknown argument `force`".to_string())
+        );
+    }
+
+    #[test]
+    fn stdin_tools_receive_arguments_as_json() {
+        let tool = CustomToolConfig {
+            input: CustomToolInput::Stdin,
+            command: vec!["schema-registry".to_string(), "lookup".to_string()],
+            ..argv_tool()
+        };
+        assert_eq!(
+            build_invocation(&tool, r#"{"files": ["users"]}"#),
+            Ok(CustomToolInvocation {
+                command: vec!["schema-registry".to_string(), "lookup".to_string()],
+                stdin: Some(r#"{"files":["users"]}"#.to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        let mut tool = argv_tool();
+        tool.command.push("{{missing}}".to_string());
+        assert_eq!(
+            validate_tool("lint", &tool),
+            Err("`{{missing}}` in `command` is not a declared parameter".to_string())
+        );
+        assert!(validate_tool("lint migrations", &argv_tool()).is_err());
+        assert_eq!(validate_tool("lint_migrations", &argv_tool()), Ok(()));
+    }
+
+    #[test]
+    fn loads_tool_files_and_skips_invalid_ones() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("schema.toml"),
+            "description = \"Look up a schema\"\ncommand = [\"schema-registry\"]\n",
+        )
+        .expect("write tool");
+        std::fs::write(dir.path().join("broken.toml"), "command = 1\n").expect("write tool");
+        std::fs::write(dir.path().join("README.md"), "not a tool").expect("write readme");
+
+        let loaded = load_tools_dir(dir.path());
+        let names: Vec<&str> = loaded.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["schema"]);
+        assert_eq!(loaded[0].1.input, CustomToolInput::Stdin);
+    }
+
+    #[test]
+    fn user_tools_win_over_repository_tools() {
+        let user_dir = tempfile::tempdir().expect("tempdir");
+        let repo_dir = tempfile::tempdir().expect("tempdir");
+        let write = |dir: &Path, name: &str, command: &str| {
+            std::fs::write(
+                dir.join(format!("{name}.toml")),
+                format!("description = \"{name}\"\ncommand = [\"{command}\"]\n"),
+            )
+            .expect("write tool");
+        };
+        write(user_dir.path(), "lint", "user-lint");
+        write(repo_dir.path(), "lint", "repo-lint");
+        write(repo_dir.path(), "schema", "schema-registry");
+
+        let commands = |tools: Vec<CustomTool>| -> Vec<(String, String)> {
+            tools
+                .into_iter()
+                .map(|tool| (tool.name, tool.config.command[0].clone()))
+                .collect()
+        };
+        let no_tools = BTreeMap::new();
+        assert_eq!(
+            commands(collect_tools(
+                &no_tools,
+                user_dir.path(),
+                Some((&no_tools, Some(repo_dir.path())))
+            )),
+            vec![
+                ("lint".to_string(), "user-lint".to_string()),
+                ("schema".to_string(), "schema-registry".to_string()),
+            ]
+        );
+
+        // The same applies to `[custom_tools]` in the repository's config.
+        let mut user_configured = BTreeMap::new();
+        let mut project_configured = BTreeMap::new();
+        for (tools, name, command) in [
+            (&mut user_configured, "deploy", "user-deploy"),
+            (&mut project_configured, "deploy", "repo-deploy"),
+            (&mut project_configured, "seed", "seed-db"),
+        ] {
+            tools.insert(
+                name.to_string(),
+                toml::from_str::<CustomToolConfig>(&format!(
+                    "description = \"{name}\"\ncommand = [\"{command}\"]\n"
+                ))
+                .expect("parse tool"),
+            );
+        }
+        assert_eq!(
+            commands(collect_tools(
+                &user_configured,
+                user_dir.path(),
+                Some((&project_configured, None))
+            )),
+            vec![
+                ("deploy".to_string(), "user-deploy".to_string()),
+                ("lint".to_string(), "user-lint".to_string()),
+                ("seed".to_string(), "seed-db".to_string()),
+            ]
+        );
+        assert_eq!(
+            commands(collect_tools(&no_tools, user_dir.path(), None)),
+            vec![("lint".to_string(), "user-lint".to_string())]
+        );
+    }
+}
@@ -1,10 +1,21 @@
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
+use codex_client::CassetteMode;
+use codex_client::CassetteTransport;
 use codex_client::CodexHttpClient;
 pub use codex_client::CodexRequestBuilder;
+use codex_client::HttpCassette;
+use codex_client::HttpNetwork;
+use codex_client::NetworkConfigError;
+use codex_client::NetworkSettings;
+use codex_client::ReqwestTransport;
 use reqwest::header::HeaderValue;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io;
 use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::sync::OnceLock;
+use std::sync::RwLock;
 
 /// Set this to add a suffix to the User-Agent string.
 ///
@@ -147,10 +158,82 @@ pub fn build_reqwest_client() -> reqwest::Client {
     if is_sandboxed() {
         builder = builder.no_proxy();
     }
+    builder = apply_network_settings(builder);
 
     builder.build().unwrap_or_else(|_| reqwest::Client::new())
 }
 
+/// `[network]` settings for this process. Like [`USER_AGENT_SUFFIX`] this is a
+/// global so that every client picks it up without threading config through
+/// each call site.
+static NETWORK: LazyLock<RwLock<(NetworkSettings, HttpNetwork)>> =
+    LazyLock::new(|| RwLock::new(Default::default()));
+
+/// Install the proxy, CA and client certificate settings used by every client
+/// built afterwards.
+pub fn set_network_settings(settings: &NetworkSettings) -> Result<(), NetworkConfigError> {
+    let network = settings.load()?;
+    let mut guard = match NETWORK.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *guard = (settings.clone(), network);
+    Ok(())
+}
+
+/// The settings last passed to [`set_network_settings`].
+pub fn network_settings() -> NetworkSettings {
+    match NETWORK.read() {
+        Ok(guard) => guard.0.clone(),
+        Err(poisoned) => poisoned.into_inner().0.clone(),
+    }
+}
+
+/// Apply the configured proxy, extra root certificates and client identity.
+/// Explicit settings win over the sandbox's `no_proxy()` default.
+pub fn apply_network_settings(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    match NETWORK.read() {
+        Ok(guard) => guard.1.apply(builder),
+        Err(poisoned) => poisoned.into_inner().1.apply(builder),
+    }
+}
+
+/// Cassettes opened by this process, keyed by mode and path, so every model
+/// request of a session records into (or replays from) the same cassette even
+/// though clients are rebuilt per request.
+static HTTP_CASSETTES: LazyLock<Mutex<HashMap<CassetteMode, HttpCassette>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Build the transport used for model requests, honoring `Config::http_cassette`.
+/// Fails when the cassette cannot be created or loaded, so a session never
+/// runs without the recording it asked for.
+pub fn build_http_transport(
+    cassette: Option<&CassetteMode>,
+) -> io::Result<CassetteTransport<ReqwestTransport>> {
+    let live = ReqwestTransport::new(build_reqwest_client());
+    let Some(mode) = cassette else {
+        return Ok(CassetteTransport::Live(live));
+    };
+
+    let mut cassettes = match HTTP_CASSETTES.lock() {
+        Ok(cassettes) => cassettes,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let cassette = match cassettes.entry(mode.clone()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let cassette = HttpCassette::open(mode).map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("failed to open HTTP cassette {mode:?}: {err}"),
+                )
+            })?;
+            entry.insert(cassette)
+        }
+    };
+    Ok(cassette.wrap(live))
+}
+
 fn is_sandboxed() -> bool {
     std::env::var(CODEX_SANDBOX_ENV_VAR).as_deref() == Ok("seatbelt")
 }
@@ -1,4 +1,5 @@
 use crate::codex::TurnContext;
+use crate::git_info::GitStatusSummary;
 use crate::protocol::AskForApproval;
 use crate::protocol::NetworkAccess;
 use crate::protocol::SandboxPolicy;
@@ -21,6 +22,8 @@ pub(crate) struct EnvironmentContext {
     pub sandbox_mode: Option<SandboxMode>,
     pub network_access: Option<NetworkAccess>,
     pub writable_roots: Option<Vec<AbsolutePathBuf>>,
+    /// Repository state, when the `git_context` feature is enabled.
+    pub git: Option<GitStatusSummary>,
     pub shell: Shell,
 }
 
@@ -64,10 +67,16 @@ impl EnvironmentContext {
                 }
                 _ => None,
             },
+            git: None,
             shell,
         }
     }
 
+    pub fn with_git(mut self, git: GitStatusSummary) -> Self {
+        self.git = Some(git);
+        self
+    }
+
     /// Compares two environment contexts, ignoring the shell. Useful when
     /// comparing turn to turn, since the initial environment_context will
     /// include the shell, and then it is not configurable from turn to turn.
@@ -78,6 +87,7 @@ impl EnvironmentContext {
             sandbox_mode,
             network_access,
             writable_roots,
+            git,
             // should compare all fields except shell
             shell: _,
         } = other;
@@ -87,6 +97,7 @@ impl EnvironmentContext {
             && self.sandbox_mode == *sandbox_mode
             && self.network_access == *network_access
             && self.writable_roots == *writable_roots
+            && self.git == *git
     }
 
     pub fn diff(before: &TurnContext, after: &TurnContext, shell: &Shell) -> Self {
@@ -130,6 +141,7 @@ impl EnvironmentContext {
     ///   <sandbox_mode>...</sandbox_mode>
     ///   <writable_roots>...</writable_roots>
     ///   <network_access>...</network_access>
+    ///   <git>...</git>
     ///   <shell>...</shell>
     /// </environment_context>
     /// ```
@@ -161,6 +173,9 @@ impl EnvironmentContext {
             }
             lines.push("  </writable_roots>".to_string());
         }
+        if let Some(git) = self.git {
+            push_git_lines(&mut lines, git);
+        }
 
         let shell_name = self.shell.name();
         lines.push(format!("  <shell>{shell_name}</shell>"));
@@ -169,6 +184,59 @@ impl EnvironmentContext {
     }
 }
 
+fn push_git_lines(lines: &mut Vec<String>, git: GitStatusSummary) {
+    lines.push("  <git>".to_string());
+    let branch = git.branch.as_deref().unwrap_or("(detached HEAD)");
+    lines.push(format!("    <branch>{}</branch>", escape_xml(branch)));
+    if let Some(upstream) = git.upstream {
+        lines.push(format!(
+            "    <upstream>{}</upstream>",
+            escape_xml(&upstream)
+        ));
+    }
+    if let Some((ahead, behind)) = git.ahead_behind {
+        lines.push(format!("    <ahead>{ahead}</ahead>"));
+        lines.push(format!("    <behind>{behind}</behind>"));
+    }
+    if let Some(operation) = git.operation {
+        lines.push(format!(
+            "    <in_progress>{}</in_progress>",
+            operation.as_str()
+        ));
+    }
+    if git.changed_files.is_empty() {
+        lines.push("    <changes>clean</changes>".to_string());
+    } else {
+        lines.push(format!(
+            "    <changes staged=\"{}\" unstaged=\"{}\" untracked=\"{}\" conflicted=\"{}\">",
+            git.staged, git.unstaged, git.untracked, git.conflicted
+        ));
+        for file in git.changed_files {
+            lines.push(format!("      <file>{}</file>", escape_xml(&file)));
+        }
+        if git.omitted_files > 0 {
+            lines.push(format!("      <more>{}</more>", git.omitted_files));
+        }
+        lines.push("    </changes>".to_string());
+    }
+    if !git.recent_commits.is_empty() {
+        lines.push("    <recent_commits>".to_string());
+        for commit in git.recent_commits {
+            lines.push(format!("      <commit>{}</commit>", escape_xml(&commit)));
+        }
+        lines.push("    </recent_commits>".to_string());
+    }
+    lines.push("  </git>".to_string());
+}
+
+/// Branch names, paths and commit subjects come from the repository, so they
+/// must not be able to close or inject tags.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 impl From<EnvironmentContext> for ResponseItem {
     fn from(ec: EnvironmentContext) -> Self {
         ResponseItem::Message {
@@ -183,6 +251,7 @@ impl From<EnvironmentContext> for ResponseItem {
 
 #[cfg(test)]
 mod tests {
+    use crate::git_info::GitOperation;
     use crate::shell::ShellType;
 
     use super::*;
@@ -327,6 +396,87 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_git_environment_context() {
+        let context =
+            EnvironmentContext::new(None, None, None, fake_shell()).with_git(GitStatusSummary {
+                branch: Some("feature".to_string()),
+                upstream: Some("origin/feature".to_string()),
+                ahead_behind: Some((2, 0)),
+                staged: 1,
+                unstaged: 1,
+                untracked: 0,
+                conflicted: 0,
+                changed_files: vec!["MM src/lib.rs".to_string()],
+                omitted_files: 4,
+                recent_commits: vec!["abc1234 Add parser".to_string()],
+                operation: Some(GitOperation::Rebase),
+            });
+
+        let expected = r#"<environment_context>
+  <git>
+    <branch>feature</branch>
+    <upstream>origin/feature</upstream>
+    <ahead>2</ahead>
+    <behind>0</behind>
+    <in_progress>rebase</in_progress>
+    <changes staged="1" unstaged="1" untracked="0" conflicted="0">
+      <file>MM src/lib.rs</file>
+      <more>4</more>
+    </changes>
+    <recent_commits>
+      <commit>abc1234 Add parser</commit>
+    </recent_commits>
+  </git>
+  <shell>bash</shell>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
+    #[test]
+    fn serialize_git_environment_context_escapes_repository_text() {
+        let context =
+            EnvironmentContext::new(None, None, None, fake_shell()).with_git(GitStatusSummary {
+                branch: Some("fix/<tags>".to_string()),
+                changed_files: vec!["?? a&b.txt".to_string()],
+                untracked: 1,
+                recent_commits: vec!["abc1234 Close </environment_context>".to_string()],
+                ..Default::default()
+            });
+
+        let expected = r#"<environment_context>
+  <git>
+    <branch>fix/&lt;tags&gt;</branch>
+    <changes staged="0" unstaged="0" untracked="1" conflicted="0">
+      <file>?? a&amp;b.txt</file>
+    </changes>
+    <recent_commits>
+      <commit>abc1234 Close &lt;/environment_context&gt;</commit>
+    </recent_commits>
+  </git>
+  <shell>bash</shell>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
+    #[test]
+    fn serialize_clean_detached_git_environment_context() {
+        let context = EnvironmentContext::new(None, None, None, fake_shell())
+            .with_git(GitStatusSummary::default());
+
+        let expected = r#"<environment_context>
+  <git>
+    <branch>(detached HEAD)</branch>
+    <changes>clean</changes>
+  </git>
+  <shell>bash</shell>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn equals_except_shell_compares_approval_policy() {
         // Approval policy
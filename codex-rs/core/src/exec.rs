@@ -12,6 +12,7 @@ use std::time::Instant;
 use async_channel::Sender;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
@@ -167,6 +168,7 @@ pub async fn process_exec_tool_call(
         expiration,
         sandbox_permissions,
         justification,
+        stdin: None,
     };
 
     let manager = SandboxManager::new();
@@ -198,6 +200,7 @@ pub(crate) async fn execute_exec_env(
         sandbox_permissions,
         justification,
         arg0,
+        stdin,
     } = env;
 
     let params = ExecParams {
@@ -211,7 +214,7 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream, stdin).await;
     let duration = start.elapsed();
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
@@ -522,6 +525,7 @@ async fn exec(
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
     stdout_stream: Option<StdoutStream>,
+    stdin: Option<String>,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
     if sandbox == SandboxType::WindowsRestrictedToken
@@ -530,6 +534,12 @@ async fn exec(
             SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
         )
     {
+        if stdin.is_some() {
+            return Err(CodexErr::Io(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "stdin input is not supported under the Windows sandbox",
+            )));
+        }
         return exec_windows_sandbox(params, sandbox_policy).await;
     }
     let ExecParams {
@@ -548,16 +558,31 @@ async fn exec(
         ))
     })?;
     let arg0_ref = arg0.as_deref();
-    let child = spawn_child_async(
+    let stdio_policy = if stdin.is_some() {
+        StdioPolicy::Piped
+    } else {
+        StdioPolicy::RedirectForShellTool
+    };
+    let mut child = spawn_child_async(
         PathBuf::from(program),
         args.into(),
         arg0_ref,
         cwd,
         sandbox_policy,
-        StdioPolicy::RedirectForShellTool,
+        stdio_policy,
         env,
     )
     .await?;
+    if let (Some(input), Some(mut child_stdin)) = (stdin, child.stdin.take()) {
+        // Write from a task so a child that produces output before reading all
+        // of its input cannot deadlock against us. Dropping the handle closes
+        // stdin so the child sees EOF.
+        tokio::spawn(async move {
+            if let Err(err) = child_stdin.write_all(input.as_bytes()).await {
+                tracing::debug!("failed to write command stdin: {err}");
+            }
+        });
+    }
     consume_truncated_output(child, expiration, stdout_stream).await
 }
 
@@ -883,7 +908,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            None,
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
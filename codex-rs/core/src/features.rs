@@ -94,6 +94,22 @@ pub enum Feature {
     Skills,
     /// Enforce UTF8 output in Powershell.
     PowershellUtf8,
+    /// Let the model delegate subtasks to child agents via `spawn_agent`.
+    SpawnAgent,
+    /// Remember facts across sessions with the `memory` tool.
+    Memory,
+    /// Save truncated tool output to scratch files and expose `read_output`.
+    OutputSpill,
+    /// Index the project locally and expose `search_code`.
+    CodeIndex,
+    /// Start configured language servers and expose definition, reference,
+    /// hover and diagnostics tools.
+    Lsp,
+    /// Expose the `fetch_url` tool for reading web pages as markdown.
+    FetchUrl,
+    /// Include the repository branch, upstream, changes and recent commits in
+    /// the environment context.
+    GitContext,
 }
 
 impl Feature {
@@ -397,4 +413,46 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::SpawnAgent,
+        key: "spawn_agent",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Memory,
+        key: "memory",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::OutputSpill,
+        key: "output_spill",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::CodeIndex,
+        key: "code_index",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Lsp,
+        key: "lsp",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::FetchUrl,
+        key: "fetch_url",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::GitContext,
+        key: "git_context",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
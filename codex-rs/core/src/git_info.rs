@@ -168,6 +168,215 @@ pub async fn recent_commits(cwd: &Path, limit: usize) -> Vec<CommitLogEntry> {
     entries
 }
 
+/// Maximum number of changed paths listed in a [`GitStatusSummary`].
+const MAX_CHANGED_FILES: usize = 20;
+
+/// Number of recent commit subjects included in a [`GitStatusSummary`].
+const MAX_RECENT_COMMITS: usize = 5;
+
+/// Commit subjects longer than this are cut off with an ellipsis.
+const MAX_SUBJECT_CHARS: usize = 100;
+
+/// An operation that stops partway and waits for the user to continue it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+impl GitOperation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GitOperation::Merge => "merge",
+            GitOperation::Rebase => "rebase",
+            GitOperation::CherryPick => "cherry-pick",
+            GitOperation::Revert => "revert",
+            GitOperation::Bisect => "bisect",
+        }
+    }
+}
+
+/// Size-bounded snapshot of the working tree, suitable for the model's
+/// environment context.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitStatusSummary {
+    /// Checked out branch; `None` when HEAD is detached.
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    /// Commits ahead of and behind the upstream.
+    pub ahead_behind: Option<(usize, usize)>,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    /// Up to [`MAX_CHANGED_FILES`] entries in short-status form, e.g. `M  src/lib.rs`.
+    pub changed_files: Vec<String>,
+    /// Changed paths left out of `changed_files`.
+    pub omitted_files: usize,
+    /// `<short sha> <subject>` for the most recent commits, newest first.
+    pub recent_commits: Vec<String>,
+    pub operation: Option<GitOperation>,
+}
+
+/// Summarizes the branch, upstream, working tree changes, recent commits and
+/// any in-progress merge, rebase, cherry-pick, revert or bisect at `cwd`.
+/// Returns `None` outside a git repository or when `git status` fails.
+pub async fn collect_git_status(cwd: &Path) -> Option<GitStatusSummary> {
+    let (status_result, git_dir_result, commits) = tokio::join!(
+        // Skip the index refresh so this never holds index.lock while the
+        // user runs git.
+        run_git_command_with_timeout(
+            &[
+                "--no-optional-locks",
+                "status",
+                "--porcelain=v2",
+                "--branch"
+            ],
+            cwd
+        ),
+        run_git_command_with_timeout(&["rev-parse", "--git-dir"], cwd),
+        recent_commits(cwd, MAX_RECENT_COMMITS)
+    );
+
+    let status = status_result?;
+    if !status.status.success() {
+        return None;
+    }
+    let mut summary = parse_porcelain_status(&String::from_utf8_lossy(&status.stdout));
+
+    if let Some(output) = git_dir_result
+        && output.status.success()
+    {
+        let git_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        summary.operation = detect_git_operation(&cwd.join(git_dir));
+    }
+
+    summary.recent_commits = commits
+        .into_iter()
+        .map(|commit| {
+            let short_sha: String = commit.sha.chars().take(7).collect();
+            format!("{short_sha} {}", truncate_subject(&commit.subject))
+        })
+        .collect();
+
+    Some(summary)
+}
+
+/// Parses the output of `git status --porcelain=v2 --branch`.
+fn parse_porcelain_status(output: &str) -> GitStatusSummary {
+    let mut summary = GitStatusSummary::default();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            if let Some(head) = header.strip_prefix("branch.head ") {
+                summary.branch = (head != "(detached)").then(|| head.to_string());
+            } else if let Some(upstream) = header.strip_prefix("branch.upstream ") {
+                summary.upstream = Some(upstream.to_string());
+            } else if let Some(counts) = header.strip_prefix("branch.ab ") {
+                let mut parts = counts.split_whitespace();
+                let ahead = parts.next().and_then(|n| n.strip_prefix('+')?.parse().ok());
+                let behind = parts.next().and_then(|n| n.strip_prefix('-')?.parse().ok());
+                if let (Some(ahead), Some(behind)) = (ahead, behind) {
+                    summary.ahead_behind = Some((ahead, behind));
+                }
+            }
+            continue;
+        }
+
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match kind {
+            // Ordinary changes: `1 XY sub mH mI mW hH hI path`.
+            "1" => {
+                let fields: Vec<&str> = rest.splitn(8, ' ').collect();
+                if let [xy, .., path] = fields.as_slice() {
+                    let xy = count_changes(&mut summary, xy);
+                    push_changed_file(&mut summary, format!("{xy} {path}"));
+                }
+            }
+            // Renames and copies: `2 XY sub mH mI mW hH hI Xscore path<TAB>orig`.
+            "2" => {
+                let fields: Vec<&str> = rest.splitn(9, ' ').collect();
+                if let [xy, .., paths] = fields.as_slice() {
+                    let xy = count_changes(&mut summary, xy);
+                    let entry = match paths.split_once('\t') {
+                        Some((path, orig)) => format!("{xy} {orig} -> {path}"),
+                        None => format!("{xy} {paths}"),
+                    };
+                    push_changed_file(&mut summary, entry);
+                }
+            }
+            // Unmerged paths: `u XY sub m1 m2 m3 mW h1 h2 h3 path`.
+            "u" => {
+                let fields: Vec<&str> = rest.splitn(10, ' ').collect();
+                if let [xy, .., path] = fields.as_slice() {
+                    summary.conflicted += 1;
+                    push_changed_file(&mut summary, format!("{xy} {path}"));
+                }
+            }
+            "?" => {
+                summary.untracked += 1;
+                push_changed_file(&mut summary, format!("?? {rest}"));
+            }
+            _ => {}
+        }
+    }
+
+    summary
+}
+
+fn push_changed_file(summary: &mut GitStatusSummary, entry: String) {
+    if summary.changed_files.len() < MAX_CHANGED_FILES {
+        summary.changed_files.push(entry);
+    } else {
+        summary.omitted_files += 1;
+    }
+}
+
+/// Counts a porcelain v2 `XY` field as staged and/or unstaged and returns it
+/// in short-status form, with `.` shown as a space.
+fn count_changes(summary: &mut GitStatusSummary, xy: &str) -> String {
+    let mut chars = xy.chars();
+    let index = chars.next().unwrap_or('.');
+    let worktree = chars.next().unwrap_or('.');
+    if index != '.' {
+        summary.staged += 1;
+    }
+    if worktree != '.' {
+        summary.unstaged += 1;
+    }
+    xy.replace('.', " ")
+}
+
+/// Detects an in-progress operation from the marker files git leaves in
+/// `git_dir`.
+fn detect_git_operation(git_dir: &Path) -> Option<GitOperation> {
+    if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        Some(GitOperation::Rebase)
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        Some(GitOperation::Merge)
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        Some(GitOperation::CherryPick)
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        Some(GitOperation::Revert)
+    } else if git_dir.join("BISECT_LOG").exists() {
+        Some(GitOperation::Bisect)
+    } else {
+        None
+    }
+}
+
+fn truncate_subject(subject: &str) -> String {
+    if subject.chars().count() <= MAX_SUBJECT_CHARS {
+        return subject.to_string();
+    }
+    let mut truncated: String = subject.chars().take(MAX_SUBJECT_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
 /// Returns the closest git sha to HEAD that is on a remote as well as the diff to that sha.
 pub async fn git_diff_to_remote(cwd: &Path) -> Option<GitDiffToRemote> {
     get_git_repo_root(cwd)?;
@@ -738,6 +947,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_porcelain_status_summarizes_branch_and_changes() {
+        let output = "\
+# branch.oid 1234567890abcdef1234567890abcdef12345678
+# branch.head feature
+# branch.upstream origin/feature
+# branch.ab +2 -1
+1 M. N... 100644 100644 100644 aaaa bbbb src/lib.rs
+1 .M N... 100644 100644 100644 aaaa bbbb README.md
+2 R. N... 100644 100644 100644 aaaa bbbb R100 src/new name.rs\tsrc/old.rs
+u UU N... 100644 100644 100644 100644 aaaa bbbb cccc conflict.txt
+? notes.txt
+";
+
+        let summary = parse_porcelain_status(output);
+
+        assert_eq!(
+            summary,
+            GitStatusSummary {
+                branch: Some("feature".to_string()),
+                upstream: Some("origin/feature".to_string()),
+                ahead_behind: Some((2, 1)),
+                staged: 2,
+                unstaged: 1,
+                untracked: 1,
+                conflicted: 1,
+                changed_files: vec![
+                    "M  src/lib.rs".to_string(),
+                    " M README.md".to_string(),
+                    "R  src/old.rs -> src/new name.rs".to_string(),
+                    "UU conflict.txt".to_string(),
+                    "?? notes.txt".to_string(),
+                ],
+                omitted_files: 0,
+                recent_commits: Vec::new(),
+                operation: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_porcelain_status_bounds_changed_files() {
+        let mut output = "# branch.head (detached)\n".to_string();
+        for i in 0..MAX_CHANGED_FILES + 3 {
+            output.push_str(&format!("? file{i}.txt\n"));
+        }
+
+        let summary = parse_porcelain_status(&output);
+
+        assert_eq!(summary.branch, None);
+        assert_eq!(summary.untracked, MAX_CHANGED_FILES + 3);
+        assert_eq!(summary.changed_files.len(), MAX_CHANGED_FILES);
+        assert_eq!(summary.omitted_files, 3);
+    }
+
+    #[tokio::test]
+    async fn test_collect_git_status_non_git_directory() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        assert_eq!(collect_git_status(temp_dir.path()).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_git_status_reports_changes_and_merge_state() {
+        skip_if_sandbox!();
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        fs::write(repo_path.join("test.txt"), "updated").expect("write test.txt");
+        fs::write(repo_path.join("new.txt"), "new").expect("write new.txt");
+        let head = String::from_utf8(
+            Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .current_dir(&repo_path)
+                .output()
+                .await
+                .expect("git rev-parse")
+                .stdout,
+        )
+        .expect("utf-8 sha");
+        fs::write(repo_path.join(".git").join("MERGE_HEAD"), head).expect("write MERGE_HEAD");
+
+        let summary = collect_git_status(&repo_path)
+            .await
+            .expect("Should collect git status");
+
+        assert!(summary.branch.is_some());
+        assert_eq!(summary.upstream, None);
+        assert_eq!(
+            (summary.staged, summary.unstaged, summary.untracked),
+            (0, 1, 1)
+        );
+        assert_eq!(
+            summary.changed_files,
+            vec![" M test.txt".to_string(), "?? new.txt".to_string()]
+        );
+        assert_eq!(summary.recent_commits.len(), 1);
+        assert!(summary.recent_commits[0].ends_with(" Initial commit"));
+        assert_eq!(summary.operation, Some(GitOperation::Merge));
+    }
+
     async fn create_test_git_repo_with_remote(temp_dir: &TempDir) -> (PathBuf, String) {
         let repo_path = create_test_git_repo(temp_dir).await;
         let remote_path = temp_dir.path().join("remote.git");
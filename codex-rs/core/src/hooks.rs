@@ -0,0 +1,552 @@
+//! Lifecycle hooks configured under `[hooks]` in `config.toml`.
+//!
+//! Every matching hook command is spawned with the event serialized as JSON on
+//! stdin. A hook answers through its exit status:
+//!
+//! - `0`, optionally printing `{"decision": "allow" | "deny" | "modify", ...}`
+//!   on stdout. `modify` replaces `tool_input` (pre_tool_use), `prompt`
+//!   (user_prompt_submit) or `tool_output` (post_tool_use).
+//! - `2` denies the action, using stderr as the reason.
+//!
+//! Any other exit status, a timeout or unparseable output is logged and
+//! ignored so that a broken hook never wedges the session.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config::types::HookCommand;
+use crate::config::types::HooksConfig;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long the first turn waits for the `session_start` hooks as a whole.
+pub(crate) const SESSION_START_HOOKS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Exit status a hook uses to deny an action without printing JSON.
+const DENY_EXIT_CODE: i32 = 2;
+
+/// Event passed to hooks. Fields a hook may rewrite are updated in place by
+/// [`Hooks::run`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "hook_event", rename_all = "snake_case")]
+pub(crate) enum HookEvent {
+    SessionStart {},
+    UserPromptSubmit {
+        prompt: String,
+    },
+    PreToolUse {
+        tool_name: String,
+        call_id: String,
+        tool_input: Value,
+    },
+    PostToolUse {
+        tool_name: String,
+        call_id: String,
+        tool_input: Value,
+        tool_output: String,
+        success: bool,
+    },
+    ApprovalRequest {
+        tool_name: String,
+        call_id: String,
+        /// The command, patch or arguments awaiting approval.
+        tool_input: Value,
+        reason: Option<String>,
+    },
+    Stop {
+        last_assistant_message: Option<String>,
+        aborted: bool,
+    },
+}
+
+impl HookEvent {
+    fn tool_name(&self) -> Option<&str> {
+        match self {
+            HookEvent::PreToolUse { tool_name, .. }
+            | HookEvent::PostToolUse { tool_name, .. }
+            | HookEvent::ApprovalRequest { tool_name, .. } => Some(tool_name),
+            HookEvent::SessionStart {}
+            | HookEvent::UserPromptSubmit { .. }
+            | HookEvent::Stop { .. } => None,
+        }
+    }
+
+    fn apply(&mut self, response: HookResponse) {
+        match self {
+            HookEvent::UserPromptSubmit { prompt } => {
+                if let Some(new_prompt) = response.prompt {
+                    *prompt = new_prompt;
+                }
+            }
+            HookEvent::PreToolUse { tool_input, .. } => {
+                if let Some(new_input) = response.tool_input {
+                    *tool_input = new_input;
+                }
+            }
+            HookEvent::PostToolUse { tool_output, .. } => {
+                if let Some(new_output) = response.tool_output {
+                    *tool_output = new_output;
+                }
+            }
+            HookEvent::SessionStart {}
+            | HookEvent::ApprovalRequest { .. }
+            | HookEvent::Stop { .. } => {}
+        }
+    }
+}
+
+/// Where the event happened; serialized next to the event fields.
+pub(crate) struct HookContext<'a> {
+    pub session_id: String,
+    pub turn_id: Option<&'a str>,
+    pub cwd: &'a Path,
+}
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    session_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turn_id: Option<&'a str>,
+    cwd: &'a Path,
+    #[serde(flatten)]
+    event: &'a HookEvent,
+}
+
+/// Combined answer of all hooks that ran for an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HookDecision {
+    /// No hook expressed an opinion; proceed as usual.
+    Continue,
+    /// At least one hook explicitly allowed the action and none denied it.
+    Allow,
+    Deny {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: Option<ResponseDecision>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    tool_input: Option<Value>,
+    #[serde(default)]
+    prompt: Option<String>,
+    #[serde(default)]
+    tool_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ResponseDecision {
+    Allow,
+    Deny,
+    Modify,
+}
+
+enum HookOutcome {
+    Respond(HookResponse),
+    Deny(String),
+    Ignored,
+}
+
+#[derive(Debug)]
+struct Hook {
+    command: Vec<String>,
+    matcher: Option<Regex>,
+    timeout: Duration,
+}
+
+impl Hook {
+    fn matches(&self, event: &HookEvent) -> bool {
+        match (&self.matcher, event.tool_name()) {
+            (Some(matcher), Some(tool_name)) => matcher.is_match(tool_name),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Hooks {
+    session_start: Vec<Hook>,
+    user_prompt_submit: Vec<Hook>,
+    pre_tool_use: Vec<Hook>,
+    post_tool_use: Vec<Hook>,
+    approval_request: Vec<Hook>,
+    stop: Vec<Hook>,
+}
+
+impl Hooks {
+    pub(crate) fn new(config: &HooksConfig) -> Self {
+        let compile = |commands: &[HookCommand]| {
+            commands
+                .iter()
+                .filter_map(|command| match compile_hook(command) {
+                    Ok(hook) => Some(hook),
+                    Err(err) => {
+                        warn!("ignoring hook {:?}: {err}", command.command);
+                        None
+                    }
+                })
+                .collect()
+        };
+        Self {
+            session_start: compile(&config.session_start),
+            user_prompt_submit: compile(&config.user_prompt_submit),
+            pre_tool_use: compile(&config.pre_tool_use),
+            post_tool_use: compile(&config.post_tool_use),
+            approval_request: compile(&config.approval_request),
+            stop: compile(&config.stop),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.session_start.is_empty()
+            && self.user_prompt_submit.is_empty()
+            && self.pre_tool_use.is_empty()
+            && self.post_tool_use.is_empty()
+            && self.approval_request.is_empty()
+            && self.stop.is_empty()
+    }
+
+    fn hooks_for(&self, event: &HookEvent) -> &[Hook] {
+        match event {
+            HookEvent::SessionStart {} => &self.session_start,
+            HookEvent::UserPromptSubmit { .. } => &self.user_prompt_submit,
+            HookEvent::PreToolUse { .. } => &self.pre_tool_use,
+            HookEvent::PostToolUse { .. } => &self.post_tool_use,
+            HookEvent::ApprovalRequest { .. } => &self.approval_request,
+            HookEvent::Stop { .. } => &self.stop,
+        }
+    }
+
+    /// Runs the matching hooks in configuration order. Each hook sees the
+    /// event as modified by the hooks before it; the first denial wins.
+    pub(crate) async fn run(
+        &self,
+        context: &HookContext<'_>,
+        event: &mut HookEvent,
+    ) -> HookDecision {
+        let mut decision = HookDecision::Continue;
+        for hook in self.hooks_for(event) {
+            if !hook.matches(event) {
+                continue;
+            }
+            let payload = HookPayload {
+                session_id: &context.session_id,
+                turn_id: context.turn_id,
+                cwd: context.cwd,
+                event,
+            };
+            let payload = match serde_json::to_vec(&payload) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!("failed to serialize hook payload: {err}");
+                    return decision;
+                }
+            };
+            match run_hook(hook, &payload, context.cwd).await {
+                HookOutcome::Deny(reason) => return HookDecision::Deny { reason },
+                HookOutcome::Respond(response) => match response.decision {
+                    Some(ResponseDecision::Deny) => {
+                        let reason = response
+                            .reason
+                            .unwrap_or_else(|| "no reason given".to_string());
+                        return HookDecision::Deny { reason };
+                    }
+                    Some(ResponseDecision::Allow) => decision = HookDecision::Allow,
+                    Some(ResponseDecision::Modify) => event.apply(response),
+                    None => {}
+                },
+                HookOutcome::Ignored => {}
+            }
+        }
+        decision
+    }
+}
+
+/// Checks that every configured hook has a command and a valid matcher.
+pub(crate) fn validate_hooks_config(config: &HooksConfig) -> Result<(), String> {
+    for command in config.all() {
+        compile_hook(command)?;
+    }
+    Ok(())
+}
+
+fn compile_hook(command: &HookCommand) -> Result<Hook, String> {
+    if command.command.is_empty() {
+        return Err("hook command must not be empty".to_string());
+    }
+    let matcher = command
+        .matcher
+        .as_deref()
+        .map(|matcher| {
+            Regex::new(&format!("^(?:{matcher})$"))
+                .map_err(|err| format!("invalid hook matcher `{matcher}`: {err}"))
+        })
+        .transpose()?;
+    Ok(Hook {
+        command: command.command.clone(),
+        matcher,
+        timeout: command
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_HOOK_TIMEOUT),
+    })
+}
+
+async fn run_hook(hook: &Hook, payload: &[u8], cwd: &Path) -> HookOutcome {
+    let program = &hook.command[0];
+    let mut child = match Command::new(program)
+        .args(&hook.command[1..])
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            warn!("failed to spawn hook '{program}': {err}");
+            return HookOutcome::Ignored;
+        }
+    };
+
+    let stdin = child.stdin.take();
+    let run = async move {
+        if let Some(mut stdin) = stdin {
+            // A hook that ignores stdin may exit before reading it.
+            if let Err(err) = stdin.write_all(payload).await {
+                tracing::debug!("hook '{program}' did not read its input: {err}");
+            }
+        }
+        child.wait_with_output().await
+    };
+    let output = match tokio::time::timeout(hook.timeout, run).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) => {
+            warn!("hook '{program}' failed: {err}");
+            return HookOutcome::Ignored;
+        }
+        Err(_) => {
+            warn!(
+                "hook '{program}' timed out after {}ms",
+                hook.timeout.as_millis()
+            );
+            return HookOutcome::Ignored;
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    match output.status.code() {
+        Some(0) => {}
+        Some(DENY_EXIT_CODE) => {
+            let reason = if stderr.is_empty() {
+                format!("denied by hook '{program}'")
+            } else {
+                stderr
+            };
+            return HookOutcome::Deny(reason);
+        }
+        _ => {
+            warn!("hook '{program}' exited with {}: {stderr}", output.status);
+            return HookOutcome::Ignored;
+        }
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return HookOutcome::Respond(HookResponse::default());
+    }
+    match serde_json::from_str(stdout) {
+        Ok(response) => HookOutcome::Respond(response),
+        Err(err) => {
+            warn!("ignoring unparseable output from hook '{program}': {err}");
+            HookOutcome::Ignored
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn hooks(pre_tool_use: Vec<HookCommand>) -> Hooks {
+        Hooks::new(&HooksConfig {
+            pre_tool_use,
+            ..Default::default()
+        })
+    }
+
+    fn sh(script: &str, matcher: Option<&str>, timeout_ms: Option<u64>) -> HookCommand {
+        HookCommand {
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            matcher: matcher.map(str::to_string),
+            timeout_ms,
+        }
+    }
+
+    fn pre_tool_use(tool_name: &str) -> HookEvent {
+        HookEvent::PreToolUse {
+            tool_name: tool_name.to_string(),
+            call_id: "call-1".to_string(),
+            tool_input: json!({"command": ["ls"]}),
+        }
+    }
+
+    fn context(cwd: &Path) -> HookContext<'_> {
+        HookContext {
+            session_id: "session-1".to_string(),
+            turn_id: Some("turn-1"),
+            cwd,
+        }
+    }
+
+    #[test]
+    fn payload_flattens_event_fields() {
+        let event = pre_tool_use("shell");
+        let payload = HookPayload {
+            session_id: "session-1",
+            turn_id: Some("turn-1"),
+            cwd: Path::new("/repo"),
+            event: &event,
+        };
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({
+                "session_id": "session-1",
+                "turn_id": "turn-1",
+                "cwd": "/repo",
+                "hook_event": "pre_tool_use",
+                "tool_name": "shell",
+                "call_id": "call-1",
+                "tool_input": {"command": ["ls"]},
+            })
+        );
+
+        let event = HookEvent::SessionStart {};
+        let payload = HookPayload {
+            session_id: "session-1",
+            turn_id: None,
+            cwd: Path::new("/repo"),
+            event: &event,
+        };
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({"session_id": "session-1", "cwd": "/repo", "hook_event": "session_start"})
+        );
+    }
+
+    #[test]
+    fn validate_rejects_empty_commands_and_bad_matchers() {
+        let mut config = HooksConfig {
+            stop: vec![HookCommand {
+                command: Vec::new(),
+                matcher: None,
+                timeout_ms: None,
+            }],
+            ..Default::default()
+        };
+        assert!(validate_hooks_config(&config).is_err());
+
+        config.stop = vec![sh("true", Some("("), None)];
+        assert!(validate_hooks_config(&config).is_err());
+
+        config.stop = vec![sh("true", Some("apply_patch|shell"), None)];
+        assert_eq!(validate_hooks_config(&config), Ok(()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn exit_code_two_denies_with_stderr_reason() {
+        let cwd = tempfile::tempdir().unwrap();
+        let hooks = hooks(vec![sh(
+            "echo 'infra is read-only' >&2; exit 2",
+            None,
+            None,
+        )]);
+        let mut event = pre_tool_use("apply_patch");
+        let decision = hooks.run(&context(cwd.path()), &mut event).await;
+        assert_eq!(
+            decision,
+            HookDecision::Deny {
+                reason: "infra is read-only".to_string()
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn modify_rewrites_input_for_later_hooks() {
+        let cwd = tempfile::tempdir().unwrap();
+        let hooks = hooks(vec![
+            sh(
+                r#"cat >/dev/null; echo '{"decision":"modify","tool_input":{"command":["ls","-la"]}}'"#,
+                None,
+                None,
+            ),
+            // Allows only if it observes the rewritten input.
+            sh(
+                r#"grep -q '"-la"' && echo '{"decision":"allow"}'"#,
+                None,
+                None,
+            ),
+        ]);
+        let mut event = pre_tool_use("shell");
+        let decision = hooks.run(&context(cwd.path()), &mut event).await;
+        assert_eq!(decision, HookDecision::Allow);
+        assert_eq!(
+            event,
+            HookEvent::PreToolUse {
+                tool_name: "shell".to_string(),
+                call_id: "call-1".to_string(),
+                tool_input: json!({"command": ["ls", "-la"]}),
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn matcher_limits_hooks_to_tool_names() {
+        let cwd = tempfile::tempdir().unwrap();
+        let hooks = hooks(vec![sh("exit 2", Some("apply_patch"), None)]);
+        let decision = hooks
+            .run(&context(cwd.path()), &mut pre_tool_use("shell"))
+            .await;
+        assert_eq!(decision, HookDecision::Continue);
+        let decision = hooks
+            .run(&context(cwd.path()), &mut pre_tool_use("apply_patch"))
+            .await;
+        assert!(matches!(decision, HookDecision::Deny { .. }));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn failing_or_slow_hooks_are_ignored() {
+        let cwd = tempfile::tempdir().unwrap();
+        let hooks = hooks(vec![
+            sh("exit 1", None, None),
+            sh("echo not-json", None, None),
+            sh("sleep 5; exit 2", None, Some(50)),
+        ]);
+        let mut event = pre_tool_use("shell");
+        let decision = hooks.run(&context(cwd.path()), &mut event).await;
+        assert_eq!(decision, HookDecision::Continue);
+        assert_eq!(event, pre_tool_use("shell"));
+    }
+}
@@ -10,11 +10,15 @@ mod apply_patch;
 pub mod auth;
 pub mod azure;
 pub mod bash;
+mod budget;
+mod checkpoints;
 mod client;
 mod client_common;
+mod code_index;
 pub mod codex;
 mod codex_conversation;
 mod compact_remote;
+mod custom_tools;
 pub use codex_conversation::CodexConversation;
 mod codex_delegate;
 mod command_safety;
@@ -31,7 +35,9 @@ mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
+mod hooks;
 pub mod landlock;
+mod lsp;
 pub mod mcp;
 mod mcp_connection_manager;
 pub mod models_manager;
@@ -39,11 +45,15 @@ pub use mcp_connection_manager::MCP_SANDBOX_STATE_CAPABILITY;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_METHOD;
 pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
+mod memory;
 mod message_history;
 mod model_provider_info;
+mod output_store;
 pub mod parse_command;
 pub mod path_utils;
+mod plan_mode;
 pub mod powershell;
+mod pricing;
 pub mod sandboxing;
 mod stream_events_utils;
 mod text_encoding;
@@ -51,6 +61,12 @@ pub mod token_data;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
+mod verify;
+mod web_fetch;
+pub use model_provider_info::APIM_DEFAULT_API_VERSION;
+pub use model_provider_info::APIM_SUBSCRIPTION_KEY_HEADER;
+pub use model_provider_info::ApimConfig;
+pub use model_provider_info::AuthHeaderSource;
 pub use model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
 pub use model_provider_info::DEFAULT_OLLAMA_PORT;
@@ -65,6 +81,9 @@ mod event_mapping;
 pub mod review_format;
 pub mod review_prompts;
 pub use codex_protocol::protocol::InitialHistory;
+// Re-export cassette types so front ends and tests can record/replay sessions.
+pub use codex_client::Cassette;
+pub use codex_client::CassetteMode;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
 // Re-export common auth types for workspace consumers
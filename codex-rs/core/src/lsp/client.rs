@@ -0,0 +1,633 @@
+//! Minimal JSON-RPC client for one language server over stdio.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use url::Url;
+
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+/// Servers often publish twice after a change (fast syntax checks, then a
+/// full check); wait this long for a follow-up before returning.
+const DIAGNOSTICS_SETTLE: Duration = Duration::from_millis(300);
+
+type Writer = Arc<Mutex<Box<dyn AsyncWrite + Unpin + Send>>>;
+type Pending = Arc<StdMutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) struct Position {
+    pub(crate) line: u32,
+    pub(crate) character: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) struct Range {
+    pub(crate) start: Position,
+    pub(crate) end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Location {
+    pub(crate) path: PathBuf,
+    pub(crate) range: Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Diagnostic {
+    pub(crate) range: Range,
+    #[serde(default)]
+    pub(crate) severity: Option<u8>,
+    #[serde(default)]
+    pub(crate) source: Option<String>,
+    pub(crate) message: String,
+}
+
+#[derive(Default)]
+struct PublishedDiagnostics {
+    /// Bumped on every `publishDiagnostics` for the document.
+    generation: u64,
+    diagnostics: Vec<Diagnostic>,
+}
+
+struct OpenDocument {
+    version: i64,
+    text: String,
+}
+
+pub(crate) struct LspClient {
+    writer: Writer,
+    next_id: AtomicI64,
+    pending: Pending,
+    published: Arc<StdMutex<HashMap<String, PublishedDiagnostics>>>,
+    published_rx: watch::Receiver<u64>,
+    documents: Mutex<HashMap<PathBuf, OpenDocument>>,
+    language_id: String,
+    reader_task: JoinHandle<()>,
+}
+
+impl LspClient {
+    /// Starts the reader loop and performs the `initialize` handshake.
+    pub(crate) async fn connect(
+        reader: impl AsyncRead + Unpin + Send + 'static,
+        writer: impl AsyncWrite + Unpin + Send + 'static,
+        root: &Path,
+        language_id: &str,
+    ) -> Result<Self, String> {
+        let writer: Writer = Arc::new(Mutex::new(Box::new(writer)));
+        let pending: Pending = Arc::new(StdMutex::new(HashMap::new()));
+        let published = Arc::new(StdMutex::new(HashMap::new()));
+        let (published_tx, published_rx) = watch::channel(0);
+        let reader_task = tokio::spawn(read_loop(
+            BufReader::new(reader),
+            Arc::clone(&writer),
+            Arc::clone(&pending),
+            Arc::clone(&published),
+            published_tx,
+        ));
+        let client = Self {
+            writer,
+            next_id: AtomicI64::new(1),
+            pending,
+            published,
+            published_rx,
+            documents: Mutex::new(HashMap::new()),
+            language_id: language_id.to_string(),
+            reader_task,
+        };
+
+        let root_uri = file_uri(root)?;
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let params = json!({
+            "processId": std::process::id(),
+            "clientInfo": { "name": "codex" },
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "workspace": { "configuration": true, "workspaceFolders": true },
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "publishDiagnostics": { "versionSupport": true },
+                    "definition": { "linkSupport": true },
+                    "references": {},
+                    "hover": { "contentFormat": ["markdown", "plaintext"] },
+                },
+            },
+        });
+        client
+            .request_with_timeout("initialize", params, INITIALIZE_TIMEOUT)
+            .await?;
+        client
+            .notify("initialized", json!({}))
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(client)
+    }
+
+    pub(crate) async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.request_with_timeout(method, params, REQUEST_TIMEOUT)
+            .await
+    }
+
+    async fn request_with_timeout(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, tx);
+        }
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(err) = write_message(&self.writer, &message).await {
+            self.forget(id);
+            return Err(format!("failed to send {method}: {err}"));
+        }
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("language server exited".to_string()),
+            Err(_) => {
+                self.forget(id);
+                Err(format!("{method} timed out after {}s", timeout.as_secs()))
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> io::Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&self.writer, &message).await
+    }
+
+    fn forget(&self, id: i64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+    }
+
+    /// Opens `path` or sends its current contents if they changed since the
+    /// last sync. Returns the text and whether anything was sent.
+    pub(crate) async fn sync_document(&self, path: &Path) -> Result<(String, bool), String> {
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let uri = file_uri(path)?;
+        let mut documents = self.documents.lock().await;
+        let sent = match documents.get_mut(path) {
+            Some(document) if document.text == text => false,
+            Some(document) => {
+                document.version += 1;
+                document.text = text.clone();
+                let version = document.version;
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": text }],
+                    }),
+                )
+                .await
+                .map_err(|err| err.to_string())?;
+                // Servers such as rust-analyzer run their full check on save.
+                self.notify(
+                    "textDocument/didSave",
+                    json!({ "textDocument": { "uri": uri } }),
+                )
+                .await
+                .map_err(|err| err.to_string())?;
+                true
+            }
+            None => {
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": self.language_id,
+                            "version": 1,
+                            "text": text,
+                        },
+                    }),
+                )
+                .await
+                .map_err(|err| err.to_string())?;
+                documents.insert(
+                    path.to_path_buf(),
+                    OpenDocument {
+                        version: 1,
+                        text: text.clone(),
+                    },
+                );
+                true
+            }
+        };
+        Ok((text, sent))
+    }
+
+    /// Syncs `path` and returns the diagnostics the server publishes for it.
+    /// Returns `None` when the server did not publish within `timeout`.
+    pub(crate) async fn diagnostics(
+        &self,
+        path: &Path,
+        timeout: Duration,
+    ) -> Result<Option<Vec<Diagnostic>>, String> {
+        let uri = file_uri(path)?;
+        let before = self.generation(&uri);
+        let (_, sent) = self.sync_document(path).await?;
+        if !sent && before > 0 {
+            return Ok(Some(self.current_diagnostics(&uri)));
+        }
+
+        let mut rx = self.published_rx.clone();
+        let deadline = tokio::time::Instant::now() + timeout;
+        while self.generation(&uri) == before {
+            match tokio::time::timeout_at(deadline, rx.changed()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(_)) => return Err("language server exited".to_string()),
+                Err(_) => return Ok(None),
+            }
+        }
+        loop {
+            let seen = self.generation(&uri);
+            let settle = (tokio::time::Instant::now() + DIAGNOSTICS_SETTLE).min(deadline);
+            match tokio::time::timeout_at(settle, rx.changed()).await {
+                Ok(Ok(())) if self.generation(&uri) == seen => continue,
+                Ok(Ok(())) => {}
+                _ => break,
+            }
+        }
+        Ok(Some(self.current_diagnostics(&uri)))
+    }
+
+    fn generation(&self, uri: &str) -> u64 {
+        self.published
+            .lock()
+            .ok()
+            .and_then(|published| published.get(uri).map(|entry| entry.generation))
+            .unwrap_or(0)
+    }
+
+    fn current_diagnostics(&self, uri: &str) -> Vec<Diagnostic> {
+        self.published
+            .lock()
+            .ok()
+            .and_then(|published| published.get(uri).map(|entry| entry.diagnostics.clone()))
+            .unwrap_or_default()
+    }
+
+    /// False once the server's output stream has ended, after which no
+    /// request can succeed.
+    pub(crate) fn is_connected(&self) -> bool {
+        !self.reader_task.is_finished()
+    }
+
+    pub(crate) async fn shutdown(&self) {
+        let _ = self
+            .request_with_timeout("shutdown", Value::Null, SHUTDOWN_TIMEOUT)
+            .await;
+        let _ = self.notify("exit", Value::Null).await;
+        self.reader_task.abort();
+    }
+}
+
+async fn read_loop(
+    mut reader: impl AsyncBufRead + Unpin,
+    writer: Writer,
+    pending: Pending,
+    published: Arc<StdMutex<HashMap<String, PublishedDiagnostics>>>,
+    published_tx: watch::Sender<u64>,
+) {
+    loop {
+        let message = match read_message(&mut reader).await {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(err) => {
+                tracing::debug!("language server stream failed: {err}");
+                break;
+            }
+        };
+        let id = message.get("id").cloned();
+        match (message.get("method").and_then(Value::as_str), id) {
+            // Response to one of our requests.
+            (None, Some(id)) => {
+                let Some(id) = id.as_i64() else { continue };
+                let Some(tx) = pending
+                    .lock()
+                    .ok()
+                    .and_then(|mut pending| pending.remove(&id))
+                else {
+                    continue;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("request failed")
+                        .to_string()),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let _ = tx.send(result);
+            }
+            // Request from the server. We only answer what servers block on.
+            (Some(method), Some(id)) => {
+                let result = match method {
+                    "workspace/configuration" => {
+                        let items = message
+                            .pointer("/params/items")
+                            .and_then(Value::as_array)
+                            .map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    _ => Value::Null,
+                };
+                let response = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                if let Err(err) = write_message(&writer, &response).await {
+                    tracing::debug!("failed to answer {method}: {err}");
+                }
+            }
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let Some(params) = message.get("params") else {
+                    continue;
+                };
+                let Some(uri) = params.get("uri").and_then(Value::as_str) else {
+                    continue;
+                };
+                let diagnostics = params
+                    .get("diagnostics")
+                    .cloned()
+                    .and_then(|value| serde_json::from_value(value).ok())
+                    .unwrap_or_default();
+                if let Ok(mut published) = published.lock() {
+                    let entry = published.entry(uri.to_string()).or_default();
+                    entry.generation += 1;
+                    entry.diagnostics = diagnostics;
+                }
+                published_tx.send_modify(|count| *count += 1);
+            }
+            _ => {}
+        }
+    }
+    // Fail outstanding requests instead of letting them time out.
+    if let Ok(mut pending) = pending.lock() {
+        pending.clear();
+    }
+}
+
+async fn read_message(reader: &mut (impl AsyncBufRead + Unpin)) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(io::Error::other)
+}
+
+async fn write_message(writer: &Writer, message: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(message).map_err(io::Error::other)?;
+    let mut writer = writer.lock().await;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await
+}
+
+pub(crate) fn file_uri(path: &Path) -> Result<String, String> {
+    Url::from_file_path(path)
+        .map(String::from)
+        .map_err(|()| format!("{} is not an absolute path", path.display()))
+}
+
+/// Parses the result of `textDocument/definition` or `references`: a
+/// location, a list of locations or location links, or null.
+pub(crate) fn parse_locations(value: &Value) -> Vec<Location> {
+    let items = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![value],
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let (uri, range) = match item.get("targetUri") {
+                Some(uri) => (
+                    uri,
+                    item.get("targetSelectionRange")
+                        .or_else(|| item.get("targetRange"))?,
+                ),
+                None => (item.get("uri")?, item.get("range")?),
+            };
+            let path = Url::parse(uri.as_str()?).ok()?.to_file_path().ok()?;
+            let range = serde_json::from_value(range.clone()).ok()?;
+            Some(Location { path, range })
+        })
+        .collect()
+}
+
+/// Extracts the text of a `textDocument/hover` result.
+pub(crate) fn hover_text(value: &Value) -> Option<String> {
+    fn marked(value: &Value) -> Option<String> {
+        match value {
+            Value::String(text) => Some(text.clone()),
+            Value::Object(object) => object
+                .get("value")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            _ => None,
+        }
+    }
+    let text = match value.get("contents")? {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(marked)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        contents => marked(contents)?,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::io::duplex;
+
+    /// Plays a tiny language server on the other end of an in-memory pipe:
+    /// answers `initialize` and `definition`, asks for configuration, and
+    /// publishes one diagnostic when a document is opened.
+    async fn fake_server(stream: tokio::io::DuplexStream) {
+        let (read, write) = tokio::io::split(stream);
+        let writer: Writer = Arc::new(Mutex::new(Box::new(write)));
+        let mut reader = BufReader::new(read);
+        while let Ok(Some(message)) = read_message(&mut reader).await {
+            let method = message.get("method").and_then(Value::as_str);
+            let id = message.get("id").cloned();
+            let reply = match method {
+                Some("initialize") => json!({ "capabilities": {} }),
+                Some("textDocument/definition") => json!([{
+                    "uri": message["params"]["textDocument"]["uri"],
+                    "range": {
+                        "start": { "line": 0, "character": 3 },
+                        "end": { "line": 0, "character": 7 },
+                    },
+                }]),
+                Some("textDocument/didOpen") => {
+                    let request = json!({
+                        "jsonrpc": "2.0",
+                        "id": 99,
+                        "method": "workspace/configuration",
+                        "params": { "items": [{}] },
+                    });
+                    write_message(&writer, &request).await.unwrap();
+                    let uri = &message["params"]["textDocument"]["uri"];
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": "textDocument/publishDiagnostics",
+                        "params": {
+                            "uri": uri,
+                            "diagnostics": [{
+                                "range": {
+                                    "start": { "line": 1, "character": 4 },
+                                    "end": { "line": 1, "character": 5 },
+                                },
+                                "severity": 1,
+                                "source": "fake",
+                                "message": "mismatched types",
+                            }],
+                        },
+                    });
+                    write_message(&writer, &notification).await.unwrap();
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some(id) = id {
+                let response = json!({ "jsonrpc": "2.0", "id": id, "result": reply });
+                write_message(&writer, &response).await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn talks_to_a_language_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        std::fs::write(&path, "fn main() {\n    let x: u32 = \"\";\n}\n").unwrap();
+
+        let (client_stream, server_stream) = duplex(64 * 1024);
+        tokio::spawn(fake_server(server_stream));
+        let (read, write) = tokio::io::split(client_stream);
+        let client = LspClient::connect(read, write, dir.path(), "rust")
+            .await
+            .unwrap();
+
+        let diagnostics = client
+            .diagnostics(&path, Duration::from_secs(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "mismatched types");
+        assert_eq!(diagnostics[0].range.start.line, 1);
+
+        let result = client
+            .request(
+                "textDocument/definition",
+                json!({
+                    "textDocument": { "uri": file_uri(&path).unwrap() },
+                    "position": { "line": 0, "character": 4 },
+                }),
+            )
+            .await
+            .unwrap();
+        let locations = parse_locations(&result);
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].path, path);
+        assert_eq!(locations[0].range.start.character, 3);
+    }
+
+    #[tokio::test]
+    async fn reports_disconnect_once_the_server_stream_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let (client_stream, server_stream) = duplex(64 * 1024);
+        // Completes the handshake, then goes away.
+        tokio::spawn(async move {
+            let (read, write) = tokio::io::split(server_stream);
+            let writer: Writer = Arc::new(Mutex::new(Box::new(write)));
+            let mut reader = BufReader::new(read);
+            let request = read_message(&mut reader).await.unwrap().unwrap();
+            let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": {} });
+            write_message(&writer, &response).await.unwrap();
+            // `initialized`
+            read_message(&mut reader).await.unwrap();
+        });
+        let (read, write) = tokio::io::split(client_stream);
+        let client = LspClient::connect(read, write, dir.path(), "rust")
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while client.is_connected() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn hover_text_accepts_markup_and_marked_strings() {
+        assert_eq!(
+            hover_text(&json!({ "contents": { "kind": "markdown", "value": "fn main()" } })),
+            Some("fn main()".to_string())
+        );
+        assert_eq!(
+            hover_text(&json!({ "contents": ["a", { "language": "rust", "value": "b" }] })),
+            Some("a\n\nb".to_string())
+        );
+        assert_eq!(hover_text(&json!({ "contents": "" })), None);
+    }
+}
@@ -0,0 +1,456 @@
+//! Language servers started on demand for the files the agent works on.
+//!
+//! Servers are keyed by name and project root (the git root of the file, or
+//! the turn cwd) and started through the unified exec session manager, so
+//! they go through the same approval and sandbox orchestration as
+//! `exec_command`, with piped stdio instead of a PTY for JSON-RPC.
+
+mod client;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::DuplexStream;
+use tokio::sync::Mutex;
+use tokio::sync::OnceCell;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::LspConfig;
+use crate::config::types::LspServerConfig;
+use crate::features::Feature;
+use crate::git_info::get_git_repo_root;
+use crate::tools::sandboxing::ToolCtx;
+use crate::unified_exec::UnifiedExecSession;
+
+pub(crate) use client::Diagnostic;
+pub(crate) use client::LspClient;
+pub(crate) use client::Range;
+pub(crate) use client::file_uri;
+pub(crate) use client::hover_text;
+pub(crate) use client::parse_locations;
+
+const DEFAULT_DIAGNOSTICS_TIMEOUT_MS: u64 = 3_000;
+/// How long a server that failed to start is left alone before the next
+/// request tries again.
+const FAILED_START_RETRY_DELAY: Duration = Duration::from_secs(60);
+/// Diagnostics appended to `apply_patch` output, across all changed files.
+const MAX_REPORTED_DIAGNOSTICS: usize = 20;
+
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+/// Buffer between the exec session channels and the JSON-RPC streams.
+const STDIO_BUFFER_BYTES: usize = 64 * 1024;
+
+struct RunningServer {
+    client: Arc<LspClient>,
+    // Held so the server is terminated when the manager drops it.
+    _process: UnifiedExecSession,
+}
+
+enum ServerSlot {
+    Running(RunningServer),
+    /// Spawning or initializing failed; retried once
+    /// `FAILED_START_RETRY_DELAY` has passed.
+    Failed {
+        error: String,
+        at: Instant,
+    },
+}
+
+impl ServerSlot {
+    /// Whether the next request should replace this slot with a fresh start:
+    /// the server exited or its stdio bridge broke, or a failed start is due
+    /// for a retry.
+    fn is_stale(&self) -> bool {
+        match self {
+            ServerSlot::Running(server) => !server.client.is_connected(),
+            ServerSlot::Failed { at, .. } => at.elapsed() >= FAILED_START_RETRY_DELAY,
+        }
+    }
+}
+
+/// Servers are keyed by name and project root.
+type ServerKey = (String, PathBuf);
+
+pub(crate) struct LspManager {
+    servers: BTreeMap<String, LspServerConfig>,
+    diagnostics_timeout: Duration,
+    /// One cell per server so a server that is initializing only blocks
+    /// callers waiting for that same server.
+    running: Mutex<HashMap<ServerKey, Arc<OnceCell<ServerSlot>>>>,
+}
+
+impl LspManager {
+    pub(crate) fn new(config: LspConfig) -> Self {
+        let mut servers = default_servers();
+        servers.extend(config.servers);
+        for name in &config.disabled {
+            servers.remove(name);
+        }
+        Self {
+            servers,
+            diagnostics_timeout: Duration::from_millis(
+                config
+                    .diagnostics_timeout_ms
+                    .unwrap_or(DEFAULT_DIAGNOSTICS_TIMEOUT_MS),
+            ),
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn server_for(&self, path: &Path) -> Option<(&str, &LspServerConfig)> {
+        let extension = path.extension()?.to_str()?;
+        self.servers
+            .iter()
+            .find(|(_, server)| server.extensions.iter().any(|ext| ext == extension))
+            .map(|(name, server)| (name.as_str(), server))
+    }
+
+    /// Returns the running client for `path`, starting its server if needed.
+    pub(crate) async fn client_for(
+        &self,
+        session: &Session,
+        turn: &TurnContext,
+        call_id: &str,
+        path: &Path,
+    ) -> Result<Arc<LspClient>, String> {
+        let Some((name, server)) = self.server_for(path) else {
+            return Err(format!(
+                "no language server is configured for {}",
+                path.display()
+            ));
+        };
+        let root = path
+            .parent()
+            .and_then(get_git_repo_root)
+            .unwrap_or_else(|| turn.cwd.clone());
+
+        let cell = {
+            let mut running = self.running.lock().await;
+            let cell = running.entry((name.to_string(), root.clone())).or_default();
+            if cell.get().is_some_and(ServerSlot::is_stale) {
+                // Dropping the old slot terminates a server that is still
+                // running but no longer reachable.
+                *cell = Arc::default();
+            }
+            Arc::clone(cell)
+        };
+        let slot = cell
+            .get_or_init(|| async {
+                match start_server(session, turn, call_id, name, server, &root).await {
+                    Ok(server) => ServerSlot::Running(server),
+                    Err(err) => {
+                        let err = format!("failed to start language server `{name}`: {err}");
+                        tracing::warn!("{err}");
+                        ServerSlot::Failed {
+                            error: err,
+                            at: Instant::now(),
+                        }
+                    }
+                }
+            })
+            .await;
+        match slot {
+            ServerSlot::Running(server) => Ok(Arc::clone(&server.client)),
+            ServerSlot::Failed { error, .. } => Err(error.clone()),
+        }
+    }
+
+    /// Collects errors and warnings for files changed by a patch. Returns
+    /// `None` when the feature is off or no changed file has a server.
+    pub(crate) async fn diagnostics_after_edit(
+        &self,
+        session: &Session,
+        turn: &TurnContext,
+        call_id: &str,
+        paths: &[PathBuf],
+    ) -> Option<String> {
+        if !session.enabled(Feature::Lsp) {
+            return None;
+        }
+        let mut checked = 0;
+        let mut reported = Vec::new();
+        let mut omitted = 0;
+        for path in paths {
+            if self.server_for(path).is_none() {
+                continue;
+            }
+            let Ok(client) = self.client_for(session, turn, call_id, path).await else {
+                continue;
+            };
+            let diagnostics = match client.diagnostics(path, self.diagnostics_timeout).await {
+                Ok(Some(diagnostics)) => diagnostics,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::debug!("diagnostics for {} failed: {err}", path.display());
+                    continue;
+                }
+            };
+            checked += 1;
+            for diagnostic in diagnostics.iter().filter(|d| is_error_or_warning(d)) {
+                if reported.len() < MAX_REPORTED_DIAGNOSTICS {
+                    reported.push(format_diagnostic(&turn.cwd, path, diagnostic));
+                } else {
+                    omitted += 1;
+                }
+            }
+        }
+        if checked == 0 {
+            return None;
+        }
+        if reported.is_empty() {
+            return Some("Language server diagnostics: no errors or warnings.".to_string());
+        }
+        let mut text = format!("Language server diagnostics:\n{}", reported.join("\n"));
+        if omitted > 0 {
+            text.push_str(&format!("\n... {omitted} more not shown"));
+        }
+        Some(text)
+    }
+
+    pub(crate) fn diagnostics_timeout(&self) -> Duration {
+        self.diagnostics_timeout
+    }
+
+    pub(crate) async fn shutdown_all(&self) {
+        let servers: Vec<Arc<OnceCell<ServerSlot>>> = self
+            .running
+            .lock()
+            .await
+            .drain()
+            .map(|(_, cell)| cell)
+            .collect();
+        for cell in servers {
+            if let Some(ServerSlot::Running(server)) = cell.get() {
+                server.client.shutdown().await;
+            }
+        }
+    }
+}
+
+fn default_servers() -> BTreeMap<String, LspServerConfig> {
+    let server = |command: &str, args: &[&str], extensions: &[&str]| LspServerConfig {
+        command: command.to_string(),
+        args: args.iter().map(ToString::to_string).collect(),
+        extensions: extensions.iter().map(ToString::to_string).collect(),
+        language_id: None,
+    };
+    BTreeMap::from([
+        ("rust".to_string(), server("rust-analyzer", &[], &["rs"])),
+        (
+            "python".to_string(),
+            server("pyright-langserver", &["--stdio"], &["py", "pyi"]),
+        ),
+        ("go".to_string(), server("gopls", &[], &["go"])),
+    ])
+}
+
+async fn start_server(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    name: &str,
+    server: &LspServerConfig,
+    root: &Path,
+) -> Result<RunningServer, String> {
+    let mut command = vec![server.command.clone()];
+    command.extend(server.args.iter().cloned());
+    let tool_ctx = ToolCtx {
+        session,
+        turn,
+        call_id: call_id.to_string(),
+        tool_name: "lsp".to_string(),
+    };
+    let process = session
+        .services
+        .unified_exec_manager
+        .open_stdio_session(&command, root.to_path_buf(), &tool_ctx)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let (reader, writer) = stdio_streams(&process);
+    let language_id = server.language_id.as_deref().unwrap_or(name);
+    let client = LspClient::connect(reader, writer, root, language_id).await?;
+    Ok(RunningServer {
+        client: Arc::new(client),
+        _process: process,
+    })
+}
+
+/// Bridges the session's output broadcast and input channel to the byte
+/// streams `LspClient` reads from and writes to.
+fn stdio_streams(process: &UnifiedExecSession) -> (DuplexStream, DuplexStream) {
+    let (reader, mut output) = tokio::io::duplex(STDIO_BUFFER_BYTES);
+    let mut output_rx = process.output_receiver();
+    tokio::spawn(async move {
+        loop {
+            match output_rx.recv().await {
+                Ok(chunk) => {
+                    if output.write_all(&chunk).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    // Skipped bytes would break the message framing, so end
+                    // the stream: pending requests fail and the next request
+                    // restarts the server.
+                    tracing::warn!("language server output lagged by {skipped} chunks");
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let (writer, mut input) = tokio::io::duplex(STDIO_BUFFER_BYTES);
+    let input_tx = process.writer_sender();
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 8_192];
+        loop {
+            match input.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if input_tx.send(buf[..n].to_vec()).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    (reader, writer)
+}
+
+fn is_error_or_warning(diagnostic: &Diagnostic) -> bool {
+    // Servers may omit the severity; the spec says the client decides, so
+    // treat it as an error rather than hide it.
+    matches!(
+        diagnostic.severity,
+        None | Some(SEVERITY_ERROR) | Some(SEVERITY_WARNING)
+    )
+}
+
+/// Renders a diagnostic as `path:line:col: severity: message (source)` with
+/// 1-based positions and the path relative to `cwd` when possible.
+pub(crate) fn format_diagnostic(cwd: &Path, path: &Path, diagnostic: &Diagnostic) -> String {
+    let severity = match diagnostic.severity {
+        Some(SEVERITY_WARNING) => "warning",
+        Some(3) => "info",
+        Some(4) => "hint",
+        _ => "error",
+    };
+    let message = diagnostic.message.lines().next().unwrap_or_default();
+    let mut line = format!(
+        "{}:{}:{}: {severity}: {message}",
+        display_path(cwd, path),
+        diagnostic.range.start.line + 1,
+        diagnostic.range.start.character + 1,
+    );
+    if let Some(source) = &diagnostic.source {
+        line.push_str(&format!(" ({source})"));
+    }
+    line
+}
+
+pub(crate) fn display_path(cwd: &Path, path: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Converts a 0-based character column to the UTF-16 offset LSP expects.
+pub(crate) fn utf16_column(line: &str, column: usize) -> u32 {
+    line.chars()
+        .take(column)
+        .map(|c| c.len_utf16() as u32)
+        .sum()
+}
+
+/// Converts a UTF-16 offset from the server back to a 0-based character column.
+pub(crate) fn char_column(line: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    for (index, c) in line.chars().enumerate() {
+        if units >= utf16 {
+            return index;
+        }
+        units += c.len_utf16() as u32;
+    }
+    line.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::client::Position;
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn configured_servers_override_and_disable_defaults() {
+        let config = LspConfig {
+            servers: BTreeMap::from([(
+                "python".to_string(),
+                LspServerConfig {
+                    command: "pylsp".to_string(),
+                    args: Vec::new(),
+                    extensions: vec!["py".to_string()],
+                    language_id: None,
+                },
+            )]),
+            disabled: vec!["go".to_string()],
+            diagnostics_timeout_ms: None,
+        };
+        let manager = LspManager::new(config);
+        let command = |path: &str| {
+            manager
+                .server_for(Path::new(path))
+                .map(|(_, server)| server.command.clone())
+        };
+        assert_eq!(command("src/main.rs"), Some("rust-analyzer".to_string()));
+        assert_eq!(command("app.py"), Some("pylsp".to_string()));
+        assert_eq!(command("main.go"), None);
+        assert_eq!(command("README.md"), None);
+        assert_eq!(
+            manager.diagnostics_timeout(),
+            Duration::from_millis(DEFAULT_DIAGNOSTICS_TIMEOUT_MS)
+        );
+    }
+
+    #[test]
+    fn formats_diagnostics_and_converts_columns() {
+        let diagnostic = Diagnostic {
+            range: Range {
+                start: Position {
+                    line: 4,
+                    character: 8,
+                },
+                end: Position {
+                    line: 4,
+                    character: 9,
+                },
+            },
+            severity: Some(SEVERITY_ERROR),
+            source: Some("rustc".to_string()),
+            message: "mismatched types\nexpected `u32`".to_string(),
+        };
+        assert_eq!(
+            format_diagnostic(
+                Path::new("/repo"),
+                Path::new("/repo/src/lib.rs"),
+                &diagnostic
+            ),
+            "src/lib.rs:5:9: error: mismatched types (rustc)"
+        );
+
+        let line = "let é = \"😀\"; x";
+        let column = line.chars().position(|c| c == 'x').unwrap();
+        let utf16 = utf16_column(line, column);
+        assert_eq!(utf16, column as u32 + 1);
+        assert_eq!(char_column(line, utf16), column);
+    }
+}
@@ -30,6 +30,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
     let auth_status_entries = compute_auth_statuses(
         config.mcp_servers.iter(),
         config.mcp_oauth_credentials_store_mode,
+        &config.network,
     )
     .await;
 
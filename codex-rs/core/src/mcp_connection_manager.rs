@@ -48,6 +48,7 @@ use mcp_types::RequestId;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
 use mcp_types::Tool;
+use mcp_types::ToolAnnotations;
 
 use serde::Deserialize;
 use serde::Serialize;
@@ -449,6 +450,16 @@ impl McpConnectionManager {
         tools
     }
 
+    /// Returns the annotations `server` published for `tool`, if any.
+    pub async fn tool_annotations(&self, server: &str, tool: &str) -> Option<ToolAnnotations> {
+        let client = self.client_by_name(server).await.ok()?;
+        client
+            .tools
+            .into_iter()
+            .find(|info| info.tool_name == tool)
+            .and_then(|info| info.tool.annotations)
+    }
+
     /// Returns a single map that contains all resources. Each key is the
     /// server name and the value is a vector of resources.
     pub async fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
@@ -886,6 +897,7 @@ async fn make_rmcp_client(
                 http_headers,
                 env_http_headers,
                 store_mode,
+                &crate::default_client::network_settings(),
             )
             .await
             .map_err(StartupOutcomeError::from)
@@ -8,8 +8,10 @@ use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::tools::sandboxing::PLAN_MODE_REJECTION;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
+use mcp_types::ToolAnnotations;
 
 /// Handles the specified tool call dispatches the appropriate
 /// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`.
@@ -42,6 +44,23 @@ pub(crate) async fn handle_mcp_tool_call(
         }
     };
 
+    if turn_context.plan_mode
+        && is_mutating_tool(
+            sess.mcp_tool_annotations(&server, &tool_name)
+                .await
+                .as_ref(),
+        )
+    {
+        return ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: PLAN_MODE_REJECTION.to_string(),
+                success: Some(false),
+                ..Default::default()
+            },
+        };
+    }
+
     let invocation = McpInvocation {
         server: server.clone(),
         tool: tool_name.clone(),
@@ -75,6 +94,14 @@ pub(crate) async fn handle_mcp_tool_call(
     ResponseInputItem::McpToolCallOutput { call_id, result }
 }
 
+/// Whether the server flagged the tool as mutating: annotated as not
+/// read-only, or as destructive. Tools without annotations are not flagged.
+fn is_mutating_tool(annotations: Option<&ToolAnnotations>) -> bool {
+    annotations.is_some_and(|annotations| {
+        annotations.read_only_hint == Some(false) || annotations.destructive_hint == Some(true)
+    })
+}
+
 async fn notify_mcp_tool_call_event(sess: &Session, turn_context: &TurnContext, event: EventMsg) {
     sess.send_event(turn_context, event).await;
 }
@@ -0,0 +1,435 @@
+//! Facts the agent remembers across sessions.
+//!
+//! User memories live in `$CODEX_HOME/memory.json` and apply to every project.
+//! Project memories live in `.codex/memory.json` at the project root (the Git
+//! root, or the working directory outside a repository) so they can be
+//! committed with the project. Both are injected into the user instructions
+//! next to `AGENTS.md` (see `project_doc.rs`) and edited through the `memory`
+//! tool or the `/memory` view.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::FileChange;
+use codex_protocol::protocol::MemoryEntry;
+use codex_protocol::protocol::MemoryScope;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::git_info::get_git_repo_root;
+
+pub(crate) const MEMORY_FILENAME: &str = "memory.json";
+
+/// Budget for the memories injected into the initial context. Newer entries
+/// win; the rest stay reachable through `recall`.
+const MEMORY_SECTION_MAX_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MemoryFile {
+    #[serde(default)]
+    next_id: u64,
+    #[serde(default)]
+    entries: Vec<StoredEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredEntry {
+    id: u64,
+    text: String,
+}
+
+/// A change to one memory file, computed before it is written so it can be
+/// shown for approval.
+pub(crate) struct MemoryEdit {
+    pub(crate) path: PathBuf,
+    pub(crate) change: FileChange,
+    pub(crate) entry: MemoryEntry,
+    /// File contents before the edit; `None` when the file does not exist.
+    pub(crate) before: Option<String>,
+    pub(crate) contents: String,
+}
+
+impl MemoryEdit {
+    pub(crate) fn apply(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, &self.contents)
+    }
+}
+
+/// The memory files visible from one working directory.
+#[derive(Debug, Clone)]
+pub(crate) struct MemoryStore {
+    user_path: PathBuf,
+    project_path: PathBuf,
+}
+
+impl MemoryStore {
+    pub(crate) fn new(codex_home: &Path, cwd: &Path) -> Self {
+        let project_root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        Self {
+            user_path: codex_home.join(MEMORY_FILENAME),
+            project_path: project_root.join(".codex").join(MEMORY_FILENAME),
+        }
+    }
+
+    pub(crate) fn path(&self, scope: MemoryScope) -> &Path {
+        match scope {
+            MemoryScope::User => &self.user_path,
+            MemoryScope::Project => &self.project_path,
+        }
+    }
+
+    /// All entries, user memories first, each scope oldest first.
+    pub(crate) fn list(&self) -> io::Result<Vec<MemoryEntry>> {
+        let mut entries = Vec::new();
+        for scope in [MemoryScope::User, MemoryScope::Project] {
+            let file = read_file(self.path(scope))?;
+            entries.extend(file.entries.into_iter().map(|entry| MemoryEntry {
+                scope,
+                id: entry.id,
+                text: entry.text,
+            }));
+        }
+        Ok(entries)
+    }
+
+    /// Entries containing any word of `query`, best match first. An empty
+    /// query returns every entry, newest first.
+    pub(crate) fn recall(&self, query: &str) -> io::Result<Vec<MemoryEntry>> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut scored: Vec<(usize, MemoryEntry)> = self
+            .list()?
+            .into_iter()
+            .rev()
+            .filter_map(|entry| {
+                let text = entry.text.to_lowercase();
+                let score = terms.iter().filter(|term| text.contains(*term)).count();
+                (terms.is_empty() || score > 0).then_some((score, entry))
+            })
+            .collect();
+        scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+        Ok(scored.into_iter().map(|(_, entry)| entry).collect())
+    }
+
+    pub(crate) fn add(&self, scope: MemoryScope, text: &str) -> io::Result<MemoryEdit> {
+        let path = self.path(scope);
+        let (before, mut file) = read_file_with_contents(path)?;
+        let id = file.next_id.max(next_free_id(&file));
+        file.next_id = id + 1;
+        file.entries.push(StoredEntry {
+            id,
+            text: text.to_string(),
+        });
+        edit(
+            path,
+            before,
+            &file,
+            MemoryEntry {
+                scope,
+                id,
+                text: text.to_string(),
+            },
+        )
+    }
+
+    /// Returns `None` when no entry has `id`.
+    pub(crate) fn update(
+        &self,
+        scope: MemoryScope,
+        id: u64,
+        text: &str,
+    ) -> io::Result<Option<MemoryEdit>> {
+        let path = self.path(scope);
+        let (before, mut file) = read_file_with_contents(path)?;
+        let Some(entry) = file.entries.iter_mut().find(|entry| entry.id == id) else {
+            return Ok(None);
+        };
+        entry.text = text.to_string();
+        edit(
+            path,
+            before,
+            &file,
+            MemoryEntry {
+                scope,
+                id,
+                text: text.to_string(),
+            },
+        )
+        .map(Some)
+    }
+
+    /// Returns `None` when no entry has `id`.
+    pub(crate) fn forget(&self, scope: MemoryScope, id: u64) -> io::Result<Option<MemoryEdit>> {
+        let path = self.path(scope);
+        let (before, mut file) = read_file_with_contents(path)?;
+        let Some(index) = file.entries.iter().position(|entry| entry.id == id) else {
+            return Ok(None);
+        };
+        let removed = file.entries.remove(index);
+        file.next_id = file.next_id.max(next_free_id(&file)).max(id + 1);
+        edit(
+            path,
+            before,
+            &file,
+            MemoryEntry {
+                scope,
+                id,
+                text: removed.text,
+            },
+        )
+        .map(Some)
+    }
+}
+
+/// `user#3`-style label the model uses to refer to an entry.
+pub(crate) fn entry_label(entry: &MemoryEntry) -> String {
+    format!("{}#{}", scope_name(entry.scope), entry.id)
+}
+
+pub(crate) fn scope_name(scope: MemoryScope) -> &'static str {
+    match scope {
+        MemoryScope::User => "user",
+        MemoryScope::Project => "project",
+    }
+}
+
+/// Renders the memories injected next to `AGENTS.md`, newest first within
+/// [`MEMORY_SECTION_MAX_BYTES`]. Returns `None` when nothing is stored.
+pub(crate) fn render_memory_section(store: &MemoryStore) -> Option<String> {
+    let entries = match store.list() {
+        Ok(entries) => entries,
+        Err(err) => {
+            tracing::warn!("failed to read memories: {err}");
+            return None;
+        }
+    };
+    if entries.is_empty() {
+        return None;
+    }
+
+    let mut lines = Vec::new();
+    let mut used = 0;
+    let mut omitted = 0;
+    for entry in entries.iter().rev() {
+        let line = format!("- [{}] {}", entry_label(entry), entry.text);
+        if used + line.len() > MEMORY_SECTION_MAX_BYTES {
+            omitted += 1;
+            continue;
+        }
+        used += line.len() + 1;
+        lines.push(line);
+    }
+
+    let mut section = String::from(
+        "## Memory\nFacts remembered from earlier sessions. Use the `memory` tool to recall more, remember new facts or forget stale ones.\n",
+    );
+    section.push_str(&lines.join("\n"));
+    if omitted > 0 {
+        section.push_str(&format!(
+            "\n- ({omitted} older entries omitted; use `memory` with action `recall` to search them)"
+        ));
+    }
+    Some(section)
+}
+
+fn read_file(path: &Path) -> io::Result<MemoryFile> {
+    read_file_with_contents(path).map(|(_, file)| file)
+}
+
+fn read_file_with_contents(path: &Path) -> io::Result<(Option<String>, MemoryFile)> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok((None, MemoryFile::default()));
+        }
+        Err(err) => return Err(err),
+    };
+    let file = serde_json::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid memory file {}: {err}", path.display()),
+        )
+    })?;
+    Ok((Some(contents), file))
+}
+
+fn next_free_id(file: &MemoryFile) -> u64 {
+    file.entries
+        .iter()
+        .map(|entry| entry.id + 1)
+        .max()
+        .unwrap_or(1)
+}
+
+fn edit(
+    path: &Path,
+    before: Option<String>,
+    file: &MemoryFile,
+    entry: MemoryEntry,
+) -> io::Result<MemoryEdit> {
+    let mut contents = serde_json::to_string_pretty(file).map_err(io::Error::other)?;
+    contents.push('\n');
+    let change = match &before {
+        Some(before) => FileChange::Update {
+            unified_diff: similar::TextDiff::from_lines(before, &contents)
+                .unified_diff()
+                .context_radius(1)
+                .to_string(),
+            move_path: None,
+        },
+        None => FileChange::Add {
+            content: contents.clone(),
+        },
+    };
+    Ok(MemoryEdit {
+        path: path.to_path_buf(),
+        change,
+        entry,
+        before,
+        contents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn store(home: &TempDir, project: &TempDir) -> MemoryStore {
+        std::fs::create_dir(project.path().join(".git")).expect("create .git");
+        let nested = project.path().join("src");
+        std::fs::create_dir(&nested).expect("create src");
+        MemoryStore::new(home.path(), &nested)
+    }
+
+    #[test]
+    fn add_update_forget_round_trip() {
+        let home = TempDir::new().expect("tempdir");
+        let project = TempDir::new().expect("tempdir");
+        let store = store(&home, &project);
+        assert_eq!(
+            store.path(MemoryScope::Project),
+            project.path().join(".codex").join(MEMORY_FILENAME)
+        );
+
+        let first = store
+            .add(MemoryScope::Project, "run tests with `just test`")
+            .expect("add");
+        assert!(matches!(first.change, FileChange::Add { .. }));
+        first.apply().expect("apply");
+        let second = store
+            .add(MemoryScope::Project, "prefer anyhow in binaries")
+            .expect("add");
+        assert!(matches!(second.change, FileChange::Update { .. }));
+        second.apply().expect("apply");
+        store
+            .add(MemoryScope::User, "user likes terse replies")
+            .expect("add")
+            .apply()
+            .expect("apply");
+
+        store
+            .update(MemoryScope::Project, 1, "run tests with `cargo nextest`")
+            .expect("update")
+            .expect("entry exists")
+            .apply()
+            .expect("apply");
+        store
+            .forget(MemoryScope::Project, 2)
+            .expect("forget")
+            .expect("entry exists")
+            .apply()
+            .expect("apply");
+        assert!(
+            store
+                .forget(MemoryScope::Project, 2)
+                .expect("forget")
+                .is_none()
+        );
+
+        let labels: Vec<(String, String)> = store
+            .list()
+            .expect("list")
+            .iter()
+            .map(|entry| (entry_label(entry), entry.text.clone()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("user#1".to_string(), "user likes terse replies".to_string()),
+                (
+                    "project#1".to_string(),
+                    "run tests with `cargo nextest`".to_string()
+                ),
+            ]
+        );
+
+        // Forgotten ids are not reused.
+        let third = store
+            .add(MemoryScope::Project, "docs live in docs/")
+            .expect("add");
+        assert_eq!(third.entry.id, 3);
+    }
+
+    #[test]
+    fn recall_ranks_by_matching_terms() {
+        let home = TempDir::new().expect("tempdir");
+        let project = TempDir::new().expect("tempdir");
+        let store = store(&home, &project);
+        for text in [
+            "integration tests need docker",
+            "format with rustfmt",
+            "unit tests run with cargo test",
+        ] {
+            store
+                .add(MemoryScope::Project, text)
+                .expect("add")
+                .apply()
+                .expect("apply");
+        }
+
+        let texts = |query: &str| -> Vec<String> {
+            store
+                .recall(query)
+                .expect("recall")
+                .into_iter()
+                .map(|entry| entry.text)
+                .collect()
+        };
+        assert_eq!(
+            texts("cargo TESTS"),
+            vec![
+                "unit tests run with cargo test".to_string(),
+                "integration tests need docker".to_string(),
+            ]
+        );
+        assert_eq!(texts("python"), Vec::<String>::new());
+        assert_eq!(texts("").len(), 3);
+    }
+
+    #[test]
+    fn memory_section_lists_newest_first() {
+        let home = TempDir::new().expect("tempdir");
+        let project = TempDir::new().expect("tempdir");
+        let store = store(&home, &project);
+        assert_eq!(render_memory_section(&store), None);
+
+        for text in ["older fact", "newer fact"] {
+            store
+                .add(MemoryScope::Project, text)
+                .expect("add")
+                .apply()
+                .expect("apply");
+        }
+        let section = render_memory_section(&store).expect("section");
+        assert!(section.starts_with("## Memory\n"));
+        assert!(
+            section.ends_with("- [project#2] newer fact\n- [project#1] older fact"),
+            "{section}"
+        );
+    }
+}
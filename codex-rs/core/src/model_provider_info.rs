@@ -5,6 +5,7 @@
 //!   2. User-defined entries inside `~/.codex/config.toml` under the `model_providers`
 //!      key. These override or extend the defaults at runtime.
 
+use codex_api::AZURE_INFERENCE_API_VERSION;
 use codex_api::AuthHeaderType;
 use codex_api::Provider as ApiProvider;
 use codex_api::WireApi as ApiWireApi;
@@ -31,6 +32,12 @@ pub const CHAT_WIRE_API_DEPRECATION_SUMMARY: &str = r#"Support for the "chat" wi
 
 const OPENAI_PROVIDER_NAME: &str = "OpenAI";
 
+/// `api-version` sent to Azure OpenAI behind API Management unless the
+/// provider sets one.
+pub const APIM_DEFAULT_API_VERSION: &str = "2025-04-01-preview";
+/// Header Azure API Management reads the subscription key from.
+pub const APIM_SUBSCRIPTION_KEY_HEADER: &str = "Ocp-Apim-Subscription-Key";
+
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
 /// itself (and a handful of others) additionally expose the more modern
@@ -49,6 +56,48 @@ pub enum WireApi {
 
     /// Anthropic Messages API for Claude models on Azure AI Services.
     Anthropic,
+
+    /// Azure AI Model Inference API (`/models/chat/completions`) for Mistral,
+    /// Llama, DeepSeek, Phi and other non-OpenAI models deployed on Azure AI
+    /// Foundry or as serverless endpoints.
+    #[serde(rename = "azure_inference")]
+    AzureInference,
+}
+
+/// A credential header sent in addition to the provider's primary credential.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AuthHeaderSource {
+    /// Header name, e.g. `Ocp-Apim-Subscription-Key`.
+    pub header: String,
+
+    /// Environment variable holding the header value.
+    pub env_var: Option<String>,
+
+    /// Azure Key Vault secret URL holding the header value, e.g.
+    /// `https://myvault.vault.azure.net/secrets/apim-key`. Read with the
+    /// configured Azure Entra ID credentials.
+    pub key_vault_secret: Option<String>,
+}
+
+/// Defaults for Azure OpenAI deployments fronted by Azure API Management.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ApimConfig {
+    /// API URL suffix configured in APIM, appended to `base_url`, e.g.
+    /// `openai` or `ai/prod/openai`.
+    pub path_prefix: Option<String>,
+
+    /// `api-version` query parameter. Defaults to [`APIM_DEFAULT_API_VERSION`];
+    /// set to an empty string to omit it (e.g. for the `/openai/v1` API).
+    /// An `api-version` in `query_params` takes precedence.
+    pub api_version: Option<String>,
+
+    /// Environment variable holding the APIM subscription key.
+    pub subscription_key_env_var: Option<String>,
+
+    /// Azure Key Vault secret URL holding the APIM subscription key.
+    pub subscription_key_secret: Option<String>,
 }
 
 /// Serializable representation of a provider definition.
@@ -122,6 +171,16 @@ pub struct ModelProviderInfo {
     /// be treated as Azure (e.g., a local mock server).
     #[serde(default)]
     pub skip_azure_detection: bool,
+
+    /// Credential headers sent alongside the primary credential, each read
+    /// from an environment variable or an Azure Key Vault secret.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auth_headers: Vec<AuthHeaderSource>,
+
+    /// Azure API Management settings. Marks the provider as Azure OpenAI and
+    /// applies the APIM path prefix, `api-version` and subscription key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apim: Option<ApimConfig>,
 }
 
 impl ModelProviderInfo {
@@ -171,8 +230,8 @@ impl ModelProviderInfo {
         };
 
         let mut base_url = self
+            .with_apim_path_prefix()
             .base_url
-            .clone()
             .unwrap_or_else(|| default_base_url.to_string());
 
         // For Azure OpenAI with auto-configured endpoint, the base_url ends with
@@ -187,6 +246,27 @@ impl ModelProviderInfo {
             base_url = format!("{base_url}/{model_name}");
         }
 
+        // The Azure AI Model Inference API requires an api-version; default it so
+        // discovered Foundry and serverless deployments work without extra config.
+        let mut query_params = self.query_params.clone();
+        if self.wire_api == WireApi::AzureInference {
+            query_params
+                .get_or_insert_with(HashMap::new)
+                .entry("api-version".to_string())
+                .or_insert_with(|| AZURE_INFERENCE_API_VERSION.to_string());
+        } else if let Some(apim) = &self.apim {
+            let api_version = apim
+                .api_version
+                .as_deref()
+                .unwrap_or(APIM_DEFAULT_API_VERSION);
+            if !api_version.is_empty() {
+                query_params
+                    .get_or_insert_with(HashMap::new)
+                    .entry("api-version".to_string())
+                    .or_insert_with(|| api_version.to_string());
+            }
+        }
+
         let headers = self.build_header_map()?;
         let retry = ApiRetryConfig {
             max_attempts: self.request_max_retries(),
@@ -199,18 +279,73 @@ impl ModelProviderInfo {
         Ok(ApiProvider {
             name: self.name.clone(),
             base_url,
-            query_params: self.query_params.clone(),
+            query_params,
             wire: match self.wire_api {
                 WireApi::Responses => ApiWireApi::Responses,
                 WireApi::Chat => ApiWireApi::Chat,
                 WireApi::Anthropic => ApiWireApi::Anthropic,
+                WireApi::AzureInference => ApiWireApi::AzureInference,
             },
             headers,
             retry,
             stream_idle_timeout: self.stream_idle_timeout(),
+            is_azure: self.is_azure_endpoint(),
         })
     }
 
+    /// Returns a copy with the APIM `path_prefix` folded into `base_url`, so
+    /// callers that rewrite the URL per wire API see the full path.
+    pub(crate) fn with_apim_path_prefix(&self) -> ModelProviderInfo {
+        let mut provider = self.clone();
+        if let Some(apim) = provider.apim.as_mut()
+            && let Some(prefix) = apim.path_prefix.take()
+        {
+            provider.base_url = provider
+                .base_url
+                .as_deref()
+                .map(|base_url| join_path_prefix(base_url, &prefix));
+        }
+        provider
+    }
+
+    /// Every extra credential header for this provider: `auth_headers` plus
+    /// the APIM subscription key when configured.
+    pub fn extra_auth_headers(&self) -> Vec<AuthHeaderSource> {
+        let mut sources = self.auth_headers.clone();
+        if let Some(apim) = &self.apim
+            && (apim.subscription_key_env_var.is_some() || apim.subscription_key_secret.is_some())
+            && !sources.iter().any(|source| {
+                source
+                    .header
+                    .eq_ignore_ascii_case(APIM_SUBSCRIPTION_KEY_HEADER)
+            })
+        {
+            sources.push(AuthHeaderSource {
+                header: APIM_SUBSCRIPTION_KEY_HEADER.to_string(),
+                env_var: apim.subscription_key_env_var.clone(),
+                key_vault_secret: apim.subscription_key_secret.clone(),
+            });
+        }
+        sources
+    }
+
+    /// Checks that every `auth_headers` entry names a valid header and says
+    /// where its value comes from.
+    pub fn validate_auth_headers(&self) -> Result<(), String> {
+        for source in &self.auth_headers {
+            if HeaderName::try_from(source.header.as_str()).is_err() {
+                return Err(format!("invalid auth header name `{}`", source.header));
+            }
+            if source.env_var.is_none() && source.key_vault_secret.is_none() {
+                return Err(format!(
+                    "auth header `{}` needs `env_var` or `key_vault_secret`",
+                    source.header
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// If `env_key` is Some, returns the API key for this provider if present
     /// (and non-empty) in the environment. If `env_key` is required but
     /// cannot be found, returns an error.
@@ -298,6 +433,8 @@ impl ModelProviderInfo {
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         }
     }
 
@@ -344,6 +481,8 @@ impl ModelProviderInfo {
             auth_header_type: AuthHeaderType::ApiKey,
             is_azure: true,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         }
     }
 
@@ -356,8 +495,8 @@ impl ModelProviderInfo {
     /// Uses explicit `is_azure` flag if set, otherwise falls back to hostname-based
     /// detection unless `skip_azure_detection` is enabled.
     pub fn is_azure_endpoint(&self) -> bool {
-        // Explicit flag takes precedence
-        if self.is_azure {
+        // Explicit configuration takes precedence
+        if self.is_azure || self.apim.is_some() {
             return true;
         }
 
@@ -395,6 +534,17 @@ impl ModelProviderInfo {
     }
 }
 
+/// Appends an APIM path prefix to `base_url` unless it is already there.
+fn join_path_prefix(base_url: &str, prefix: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() || base.ends_with(&format!("/{prefix}")) {
+        base.to_string()
+    } else {
+        format!("{base}/{prefix}")
+    }
+}
+
 /// Checks if a URL hostname matches known Azure patterns.
 fn is_azure_hostname(url: &str) -> bool {
     let lower = url.to_ascii_lowercase();
@@ -481,6 +631,8 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         auth_header_type: AuthHeaderType::Bearer,
         is_azure: false,
         skip_azure_detection: true, // Local providers shouldn't trigger Azure detection
+        auth_headers: Vec::new(),
+        apim: None,
     }
 }
 
@@ -512,6 +664,8 @@ base_url = "http://localhost:11434/v1"
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -545,6 +699,8 @@ query_params = { api-version = "2025-04-01-preview" }
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -577,6 +733,8 @@ is_azure = true
             auth_header_type: AuthHeaderType::ApiKey,
             is_azure: true,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -613,6 +771,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -647,6 +807,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 auth_header_type: AuthHeaderType::Bearer,
                 is_azure: false,
                 skip_azure_detection: false,
+                auth_headers: Vec::new(),
+                apim: None,
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -672,6 +834,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
         let named_api = named_provider.to_api_provider(None).expect("api provider");
         assert!(named_api.is_azure_responses_endpoint());
@@ -699,6 +863,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 auth_header_type: AuthHeaderType::Bearer,
                 is_azure: false,
                 skip_azure_detection: false,
+                auth_headers: Vec::new(),
+                apim: None,
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -708,6 +874,116 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
         }
     }
 
+    #[test]
+    fn apim_provider_applies_prefix_api_version_and_subscription_key() {
+        let provider_toml = r#"
+name = "Contoso APIM"
+base_url = "https://contoso.example.com/"
+wire_api = "responses"
+apim = { path_prefix = "/ai/openai/", subscription_key_env_var = "CONTOSO_APIM_KEY" }
+
+[[auth_headers]]
+header = "X-Tenant-Key"
+key_vault_secret = "https://contoso.vault.azure.net/secrets/tenant-key"
+        "#;
+
+        let provider: ModelProviderInfo = toml::from_str(provider_toml).unwrap();
+        assert!(provider.is_azure_endpoint());
+        assert_eq!(
+            provider.extra_auth_headers(),
+            vec![
+                AuthHeaderSource {
+                    header: "X-Tenant-Key".into(),
+                    env_var: None,
+                    key_vault_secret: Some(
+                        "https://contoso.vault.azure.net/secrets/tenant-key".into()
+                    ),
+                },
+                AuthHeaderSource {
+                    header: APIM_SUBSCRIPTION_KEY_HEADER.into(),
+                    env_var: Some("CONTOSO_APIM_KEY".into()),
+                    key_vault_secret: None,
+                },
+            ]
+        );
+
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert!(api.is_azure_responses_endpoint());
+        assert_eq!(
+            api.url_for_path("responses"),
+            format!(
+                "https://contoso.example.com/ai/openai/responses?api-version={APIM_DEFAULT_API_VERSION}"
+            )
+        );
+    }
+
+    #[test]
+    fn auth_header_without_a_source_is_rejected() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Contoso APIM"
+base_url = "https://contoso.example.com/"
+
+[[auth_headers]]
+header = "X-Tenant-Key"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.validate_auth_headers(),
+            Err("auth header `X-Tenant-Key` needs `env_var` or `key_vault_secret`".to_string())
+        );
+    }
+
+    #[test]
+    fn apim_empty_api_version_is_omitted_and_query_params_win() {
+        let mut provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Contoso APIM"
+base_url = "https://contoso.example.com/openai/v1"
+wire_api = "responses"
+apim = { api_version = "" }
+            "#,
+        )
+        .unwrap();
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert_eq!(
+            api.url_for_path("responses"),
+            "https://contoso.example.com/openai/v1/responses"
+        );
+
+        provider.apim = Some(ApimConfig::default());
+        provider.query_params = Some(maplit::hashmap! {
+            "api-version".to_string() => "2024-10-21".to_string(),
+        });
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert_eq!(
+            api.url_for_path("responses"),
+            "https://contoso.example.com/openai/v1/responses?api-version=2024-10-21"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_azure_inference_wire_api() {
+        let provider_toml = r#"
+name = "Azure AI Foundry"
+base_url = "https://myresource.services.ai.azure.com/models"
+env_key = "AZURE_AI_API_KEY"
+wire_api = "azure_inference"
+        "#;
+
+        let provider: ModelProviderInfo = toml::from_str(provider_toml).unwrap();
+        assert_eq!(provider.wire_api, WireApi::AzureInference);
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert_eq!(api.wire, ApiWireApi::AzureInference);
+        assert_eq!(
+            api.url_for_path("chat/completions"),
+            format!(
+                "https://myresource.services.ai.azure.com/models/chat/completions?api-version={AZURE_INFERENCE_API_VERSION}"
+            )
+        );
+    }
+
     #[test]
     fn test_is_azure_endpoint_explicit_flag() {
         let provider = ModelProviderInfo {
@@ -727,6 +1003,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             auth_header_type: AuthHeaderType::ApiKey,
             is_azure: true, // Explicitly marked as Azure
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
         assert!(provider.is_azure_endpoint());
     }
@@ -750,6 +1028,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: false,
             skip_azure_detection: true, // Skip detection even though URL looks like Azure
+            auth_headers: Vec::new(),
+            apim: None,
         };
         assert!(!provider.is_azure_endpoint());
     }
@@ -774,6 +1054,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             auth_header_type: AuthHeaderType::Bearer, // Default
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
         // Should auto-detect as Azure and use ApiKey
         assert_eq!(
@@ -802,6 +1084,8 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             auth_header_type: AuthHeaderType::Bearer,
             is_azure: true,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         };
         // No env_key, so should keep Bearer
         assert_eq!(
@@ -457,6 +457,8 @@ mod tests {
             auth_header_type: Default::default(),
             is_azure: false,
             skip_azure_detection: false,
+            auth_headers: Vec::new(),
+            apim: None,
         }
     }
 
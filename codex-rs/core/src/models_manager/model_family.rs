@@ -52,7 +52,8 @@ pub struct ModelFamily {
     pub supports_parallel_tool_calls: bool,
 
     /// Present if the model performs better when `apply_patch` is provided as
-    /// a tool call instead of just a bash command
+    /// a tool call instead of just a bash command, or when it should edit
+    /// files through `edit_file`/`create_file` instead.
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
 
     // Instructions to use for querying the model
@@ -390,7 +391,7 @@ pub(super) fn find_family_for_model(slug: &str) -> ModelFamily {
     } else if is_claude_model(&slug.to_lowercase()) {
         model_family!(
             slug, "claude",
-            apply_patch_tool_type: Some(ApplyPatchToolType::Freeform),
+            apply_patch_tool_type: Some(ApplyPatchToolType::EditFile),
             context_window: Some(200_000),
         )
     } else {
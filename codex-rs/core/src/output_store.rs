@@ -0,0 +1,232 @@
+//! Keeps the full text of tool outputs that were truncated for the model.
+//!
+//! Each truncated output is written to a per-session scratch directory under
+//! `$CODEX_HOME/outputs/<conversation id>/` and gets a short handle such as
+//! `out-7f3a-3`. The model pages through or greps it with `read_output`, and
+//! clients open the file directly in a pager.
+//!
+//! Files are kept for as long as the conversation can be resumed, because the
+//! notices in its history keep pointing at them. Ids embed a nonce picked when
+//! the store is created, so a resumed session never hands out an id that an
+//! earlier run already used for different output.
+
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use rand::Rng;
+
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ToolOutputSpilledEvent;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::features::Feature;
+use crate::truncate::TruncationPolicy;
+
+const OUTPUT_ID_PREFIX: &str = "out-";
+
+pub(crate) struct OutputStore {
+    dir: PathBuf,
+    /// Distinguishes this process's ids from those of earlier runs of the
+    /// same conversation.
+    nonce: String,
+    next_id: AtomicU64,
+}
+
+/// A saved output and the numbers the model needs to page through it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SpilledOutput {
+    pub(crate) id: String,
+    pub(crate) path: PathBuf,
+    pub(crate) total_lines: usize,
+    pub(crate) total_bytes: usize,
+}
+
+impl SpilledOutput {
+    /// Line appended to the truncated excerpt so the model knows where the
+    /// rest went.
+    pub(crate) fn notice(&self) -> String {
+        format!(
+            "[Output truncated. The full output ({} lines) is saved as `{}` at {}. Use `read_output` with this id to page through it or search it with a pattern.]",
+            self.total_lines,
+            self.id,
+            self.path.display()
+        )
+    }
+}
+
+impl OutputStore {
+    pub(crate) fn new(codex_home: &Path, conversation_id: ConversationId) -> Self {
+        Self {
+            dir: codex_home.join("outputs").join(conversation_id.to_string()),
+            nonce: format!("{:04x}", rand::rng().random::<u16>()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub(crate) fn save(&self, content: &str) -> io::Result<SpilledOutput> {
+        std::fs::create_dir_all(&self.dir)?;
+        // Never overwrite an earlier run's output, even if the nonces match.
+        let (id, path, mut file) = loop {
+            let id = format!(
+                "{OUTPUT_ID_PREFIX}{}-{}",
+                self.nonce,
+                self.next_id.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = self.dir.join(format!("{id}.txt"));
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(file) => break (id, path, file),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        };
+        file.write_all(content.as_bytes())?;
+        Ok(SpilledOutput {
+            id,
+            path,
+            total_lines: content.lines().count(),
+            total_bytes: content.len(),
+        })
+    }
+
+    /// Reads a saved output back, including outputs saved by earlier runs of
+    /// this conversation. Only well-formed ids resolve, so the model cannot
+    /// use `read_output` to read arbitrary files.
+    pub(crate) fn read(&self, id: &str) -> io::Result<String> {
+        let valid = id
+            .strip_prefix(OUTPUT_ID_PREFIX)
+            .and_then(|rest| rest.split_once('-'))
+            .is_some_and(|(nonce, n)| {
+                !nonce.is_empty()
+                    && nonce.bytes().all(|b| b.is_ascii_hexdigit())
+                    && !n.is_empty()
+                    && n.bytes().all(|b| b.is_ascii_digit())
+            });
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{id}` is not an output id; ids look like `out-7f3a-3`"),
+            ));
+        }
+        std::fs::read_to_string(self.dir.join(format!("{id}.txt"))).map_err(|err| {
+            if err.kind() == io::ErrorKind::NotFound {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no saved output `{id}` in this session"),
+                )
+            } else {
+                err
+            }
+        })
+    }
+}
+
+/// Saves `content` when it does not fit `policy` and returns the notice to
+/// show the model next to the truncated excerpt. Returns `None` when nothing
+/// is cut, when the feature is off, or when the file cannot be written.
+pub(crate) async fn spill_truncated_output(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    content: &str,
+    policy: TruncationPolicy,
+) -> Option<String> {
+    if content.len() <= policy.byte_budget() {
+        return None;
+    }
+    spill_output(session, turn, call_id, content).await
+}
+
+/// Saves `content`, which the caller knows was truncated for the model, and
+/// returns the notice pointing at it. Returns `None` when the feature is off
+/// or the file cannot be written.
+pub(crate) async fn spill_output(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    content: &str,
+) -> Option<String> {
+    if !session.enabled(Feature::OutputSpill) {
+        return None;
+    }
+    let spilled = match session.services.output_store.save(content) {
+        Ok(spilled) => spilled,
+        Err(err) => {
+            tracing::warn!("failed to save truncated tool output: {err}");
+            return None;
+        }
+    };
+    session
+        .send_event(
+            turn,
+            EventMsg::ToolOutputSpilled(ToolOutputSpilledEvent {
+                call_id: call_id.to_string(),
+                output_id: spilled.id.clone(),
+                path: spilled.path.clone(),
+                total_lines: spilled.total_lines as u64,
+                total_bytes: spilled.total_bytes as u64,
+            }),
+        )
+        .await;
+    Some(spilled.notice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn saved_outputs_round_trip_by_id_only() {
+        let home = tempdir().unwrap();
+        let store = OutputStore::new(home.path(), ConversationId::new());
+
+        let first = store.save("a\nb\nc\n").unwrap();
+        let second = store.save("d\n").unwrap();
+        let nonce = &store.nonce;
+        assert_eq!(first.id, format!("out-{nonce}-1"));
+        assert_eq!(second.id, format!("out-{nonce}-2"));
+        assert_eq!(first.total_lines, 3);
+        assert_eq!(store.read(&first.id).unwrap(), "a\nb\nc\n");
+
+        assert_eq!(
+            store.read("../../config.toml").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            store.read("out-3").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            store.read(&format!("out-{nonce}-9")).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn resumed_sessions_keep_earlier_outputs_and_never_reuse_their_ids() {
+        let home = tempdir().unwrap();
+        let conversation_id = ConversationId::new();
+        let first_run = OutputStore::new(home.path(), conversation_id);
+        let earlier = first_run.save("earlier\n").unwrap();
+
+        let mut resumed = OutputStore::new(home.path(), conversation_id);
+        // Worst case: the resumed run drew the same nonce.
+        resumed.nonce = first_run.nonce.clone();
+        let later = resumed.save("later\n").unwrap();
+
+        assert_ne!(earlier.id, later.id);
+        assert_eq!(resumed.read(&earlier.id).unwrap(), "earlier\n");
+        assert_eq!(resumed.read(&later.id).unwrap(), "later\n");
+    }
+}
@@ -0,0 +1,61 @@
+//! Plan mode: read-only exploration that ends in a plan the user approves.
+//!
+//! While plan mode is on, turns run under a read-only sandbox and the tool
+//! orchestrator rejects writes (see `Approvable::allowed_in_plan_mode`).
+//! `Op::ApprovePlan` turns plan mode off and starts a turn that implements the
+//! plan recorded with `update_plan`.
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+
+use crate::codex::TurnContext;
+use crate::user_instructions::DeveloperInstructions;
+
+const PLAN_MODE_ENTER_INSTRUCTIONS: &str = include_str!("../templates/plan_mode/enter.md");
+const PLAN_MODE_EXIT_INSTRUCTIONS: &str = include_str!("../templates/plan_mode/exit.md");
+
+/// User message that starts the implementation turn after approval.
+pub(crate) const PLAN_APPROVED_PROMPT: &str = include_str!("../templates/plan_mode/approved.md");
+
+/// Developer message telling the model that plan mode changed between
+/// `previous` and `next`, or `None` when it did not. The first turn of a
+/// session only gets a message when it starts in plan mode.
+pub(crate) fn plan_mode_update_item(
+    previous: Option<&TurnContext>,
+    next: &TurnContext,
+) -> Option<ResponseItem> {
+    let was_planning = previous.is_some_and(|previous| previous.plan_mode);
+    if was_planning == next.plan_mode {
+        return None;
+    }
+    let text = if next.plan_mode {
+        PLAN_MODE_ENTER_INSTRUCTIONS
+    } else {
+        PLAN_MODE_EXIT_INSTRUCTIONS
+    };
+    Some(DeveloperInstructions::new(text.trim_end()).into())
+}
+
+/// Plan mode and the plan awaiting approval as recorded in a rollout, so a
+/// resumed session continues where it left off.
+pub(crate) fn plan_mode_from_rollout(items: &[RolloutItem]) -> (bool, Option<UpdatePlanArgs>) {
+    let mut plan_mode = false;
+    let mut proposed_plan = None;
+    for item in items {
+        match item {
+            RolloutItem::EventMsg(EventMsg::PlanModeUpdated(event)) => {
+                plan_mode = event.enabled;
+                if event.enabled {
+                    proposed_plan = None;
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::PlanApprovalRequest(event)) => {
+                proposed_plan = Some(event.plan.clone());
+            }
+            _ => {}
+        }
+    }
+    (plan_mode, proposed_plan)
+}
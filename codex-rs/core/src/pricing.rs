@@ -0,0 +1,248 @@
+//! Token prices used to estimate the cost of a session.
+//!
+//! Prices come from, in order of precedence: `[pricing.providers.<id>]`,
+//! `[pricing.models]` and the built-in list below. Keys match the model slug
+//! (or Azure deployment name) exactly or followed by a dated snapshot suffix
+//! such as `-2025-08-07`, so `gpt-5` never prices `gpt-5-mini` or `gpt-5.1`.
+//! A discovered Azure deployment without an entry of its own is priced as the
+//! model it serves.
+
+use codex_protocol::protocol::TokenUsage;
+
+use crate::azure::deployments::discovered_model;
+use crate::config::types::ModelPricing;
+use crate::config::types::PricingConfig;
+
+const fn price(input: f64, cached_input: f64, output: f64) -> ModelPricing {
+    ModelPricing {
+        input,
+        cached_input: Some(cached_input),
+        output,
+        reasoning: None,
+    }
+}
+
+/// Prices for models without a cached-input discount.
+const fn uncached_price(input: f64, output: f64) -> ModelPricing {
+    ModelPricing {
+        input,
+        cached_input: None,
+        output,
+        reasoning: None,
+    }
+}
+
+/// Public list prices in USD per million tokens.
+/// Cached prices are for cache reads.
+const BUILT_IN_PRICING: &[(&str, ModelPricing)] = &[
+    ("gpt-5.2", price(1.75, 0.175, 14.0)),
+    ("gpt-5.2-chat-latest", price(1.75, 0.175, 14.0)),
+    ("gpt-5.2-pro", uncached_price(21.0, 168.0)),
+    ("gpt-5.1", price(1.25, 0.125, 10.0)),
+    ("gpt-5.1-chat-latest", price(1.25, 0.125, 10.0)),
+    ("gpt-5.1-codex", price(1.25, 0.125, 10.0)),
+    ("gpt-5.1-codex-max", price(1.25, 0.125, 10.0)),
+    ("gpt-5.1-codex-mini", price(0.25, 0.025, 2.0)),
+    ("gpt-5", price(1.25, 0.125, 10.0)),
+    ("gpt-5-chat-latest", price(1.25, 0.125, 10.0)),
+    ("gpt-5-codex", price(1.25, 0.125, 10.0)),
+    ("gpt-5-mini", price(0.25, 0.025, 2.0)),
+    ("gpt-5-nano", price(0.05, 0.005, 0.4)),
+    ("gpt-5-pro", uncached_price(15.0, 120.0)),
+    ("gpt-4.1", price(2.0, 0.5, 8.0)),
+    ("gpt-4.1-mini", price(0.4, 0.1, 1.6)),
+    ("gpt-4.1-nano", price(0.1, 0.025, 0.4)),
+    ("gpt-4o", price(2.5, 1.25, 10.0)),
+    ("gpt-4o-mini", price(0.15, 0.075, 0.6)),
+    ("o1", price(15.0, 7.5, 60.0)),
+    ("o1-mini", price(1.1, 0.55, 4.4)),
+    ("o1-pro", uncached_price(150.0, 600.0)),
+    ("o3", price(2.0, 0.5, 8.0)),
+    ("o3-mini", price(1.1, 0.55, 4.4)),
+    ("o3-pro", uncached_price(20.0, 80.0)),
+    ("o4-mini", price(1.1, 0.275, 4.4)),
+    ("codex-mini-latest", price(1.5, 0.375, 6.0)),
+    ("claude-opus-4-5", price(5.0, 0.5, 25.0)),
+    ("claude-opus-4-1", price(15.0, 1.5, 75.0)),
+    ("claude-opus-4", price(15.0, 1.5, 75.0)),
+    ("claude-sonnet-4-5", price(3.0, 0.3, 15.0)),
+    ("claude-sonnet-4", price(3.0, 0.3, 15.0)),
+    ("claude-haiku-4-5", price(1.0, 0.1, 5.0)),
+    ("claude-3-7-sonnet", price(3.0, 0.3, 15.0)),
+    ("claude-3-5-haiku", price(0.8, 0.08, 4.0)),
+];
+
+/// Returns the prices for `model` served by the provider `provider_id`, or
+/// `None` when the model is not in the catalog.
+pub(crate) fn pricing_for_model(
+    config: &PricingConfig,
+    provider_id: &str,
+    model: &str,
+) -> Option<ModelPricing> {
+    lookup(config, provider_id, model).or_else(|| {
+        discovered_model(model)
+            .filter(|underlying| underlying != model)
+            .and_then(|underlying| lookup(config, provider_id, &underlying))
+    })
+}
+
+fn lookup(config: &PricingConfig, provider_id: &str, model: &str) -> Option<ModelPricing> {
+    config
+        .providers
+        .get(provider_id)
+        .and_then(|prices| longest_match(prices.iter().map(|(k, v)| (k.as_str(), v)), model))
+        .or_else(|| longest_match(config.models.iter().map(|(k, v)| (k.as_str(), v)), model))
+        .or_else(|| longest_match(BUILT_IN_PRICING.iter().map(|(k, v)| (*k, v)), model))
+}
+
+fn longest_match<'a>(
+    entries: impl Iterator<Item = (&'a str, &'a ModelPricing)>,
+    model: &str,
+) -> Option<ModelPricing> {
+    entries
+        .filter(|(key, _)| model.strip_prefix(key).is_some_and(is_snapshot_suffix))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Whether `suffix` is empty or a dated snapshot such as `-2025-08-07` or
+/// `-20250929`. Version and variant suffixes (`.1`, `-mini`, `-4-5`) are
+/// different models with their own prices.
+fn is_snapshot_suffix(suffix: &str) -> bool {
+    let Some(date) = suffix.strip_prefix('-') else {
+        return suffix.is_empty();
+    };
+    date.len() >= 8
+        && date.bytes().take(4).all(|b| b.is_ascii_digit())
+        && date.bytes().all(|b| b.is_ascii_digit() || b == b'-')
+}
+
+impl ModelPricing {
+    /// Estimated cost of `usage` in USD. Cached input is part of
+    /// `input_tokens` and reasoning is part of `output_tokens`, so each is
+    /// billed at its own rate and the remainder at the base rate.
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        let input = usage.input_tokens.max(0);
+        let cached_input = usage.cached_input_tokens.clamp(0, input);
+        let output = usage.output_tokens.max(0);
+        let reasoning = usage.reasoning_output_tokens.clamp(0, output);
+
+        let cost = (input - cached_input) as f64 * self.input
+            + cached_input as f64 * self.cached_input.unwrap_or(self.input)
+            + (output - reasoning) as f64 * self.output
+            + reasoning as f64 * self.reasoning.unwrap_or(self.output);
+        cost / 1_000_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn built_in_prices_match_exact_models_and_snapshots() {
+        let config = PricingConfig::default();
+        assert_eq!(
+            pricing_for_model(&config, "openai", "gpt-5-mini-2025-08-07"),
+            Some(price(0.25, 0.025, 2.0))
+        );
+        assert_eq!(
+            pricing_for_model(&config, "openai", "gpt-5.1-codex-max"),
+            Some(price(1.25, 0.125, 10.0))
+        );
+        assert_eq!(
+            pricing_for_model(&config, "openai", "o3-mini"),
+            Some(price(1.1, 0.55, 4.4))
+        );
+        assert_eq!(
+            pricing_for_model(&config, "azure", "claude-sonnet-4-5-20250929"),
+            Some(price(3.0, 0.3, 15.0))
+        );
+        assert_eq!(pricing_for_model(&config, "oss", "gpt-oss:20b"), None);
+    }
+
+    #[test]
+    fn unknown_versions_and_variants_are_not_priced_as_their_base_model() {
+        let config = PricingConfig::default();
+        assert_eq!(pricing_for_model(&config, "openai", "gpt-5.3"), None);
+        assert_eq!(pricing_for_model(&config, "openai", "gpt-5-turbo"), None);
+        assert_eq!(
+            pricing_for_model(&config, "openai", "o3-deep-research"),
+            None
+        );
+        assert_eq!(
+            pricing_for_model(&config, "azure", "claude-sonnet-4-6"),
+            None
+        );
+    }
+
+    #[test]
+    fn discovered_azure_deployments_use_their_underlying_model() {
+        use crate::azure::deployments::AzureDeployment;
+
+        let deployment: AzureDeployment = serde_json::from_value(serde_json::json!({
+            "name": "pricing-test-team-gpt",
+            "properties": { "model": { "name": "gpt-5.1", "format": "OpenAI" } },
+        }))
+        .expect("deployment");
+        crate::azure::deployments::record_discovered_deployments(&[deployment]);
+
+        let config = PricingConfig::default();
+        assert_eq!(
+            pricing_for_model(&config, "azure", "pricing-test-team-gpt"),
+            Some(price(1.25, 0.125, 10.0))
+        );
+        let negotiated = price(1.0, 0.1, 8.0);
+        let config = PricingConfig {
+            providers: HashMap::from([(
+                "azure".to_string(),
+                HashMap::from([("pricing-test-team-gpt".to_string(), negotiated)]),
+            )]),
+            ..Default::default()
+        };
+        assert_eq!(
+            pricing_for_model(&config, "azure", "pricing-test-team-gpt"),
+            Some(negotiated)
+        );
+    }
+
+    #[test]
+    fn provider_overrides_win_over_models_and_defaults() {
+        let negotiated = price(1.0, 0.1, 8.0);
+        let custom = price(3.0, 3.0, 12.0);
+        let config = PricingConfig {
+            models: HashMap::from([("gpt-5".to_string(), custom)]),
+            providers: HashMap::from([(
+                "azure".to_string(),
+                HashMap::from([("prod-gpt5".to_string(), negotiated)]),
+            )]),
+        };
+        assert_eq!(
+            pricing_for_model(&config, "azure", "prod-gpt5"),
+            Some(negotiated)
+        );
+        assert_eq!(pricing_for_model(&config, "azure", "gpt-5"), Some(custom));
+        assert_eq!(pricing_for_model(&config, "openai", "prod-gpt5"), None);
+    }
+
+    #[test]
+    fn cost_bills_cached_and_reasoning_tokens_separately() {
+        let pricing = ModelPricing {
+            input: 2.0,
+            cached_input: Some(0.5),
+            output: 8.0,
+            reasoning: Some(10.0),
+        };
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: 400_000,
+            output_tokens: 100_000,
+            reasoning_output_tokens: 50_000,
+            total_tokens: 1_100_000,
+        };
+        // 0.6M * $2 + 0.4M * $0.5 + 0.05M * $8 + 0.05M * $10
+        assert_eq!(pricing.cost_usd(&usage), 1.2 + 0.2 + 0.4 + 0.5);
+    }
+}
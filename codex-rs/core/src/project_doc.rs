@@ -12,11 +12,22 @@
 //!     current working directory (inclusive) and concatenate their contents in
 //!     that order.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! Instruction files in directories *below* the working directory are not
+//! part of the initial instructions. They are loaded on demand, the first time
+//! the agent reads or writes a path under their directory (see
+//! [`discover_nested_project_doc_paths`]).
 
 use crate::config::Config;
+use crate::features::Feature;
+use crate::memory::MemoryStore;
+use crate::memory::render_memory_section;
 use crate::skills::SkillMetadata;
 use crate::skills::render_skills_section;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
 use dunce::canonicalize as normalize_path;
+use std::path::Path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
 use tracing::error;
@@ -47,6 +58,10 @@ pub(crate) async fn get_user_instructions(
     };
 
     let combined_project_docs = merge_project_docs_with_skills(project_docs, skills_section);
+    let combined_project_docs = match (combined_project_docs, memory_section(config)) {
+        (Some(docs), Some(memory)) => Some(format!("{docs}\n\n{memory}")),
+        (docs, memory) => docs.or(memory),
+    };
 
     let mut parts: Vec<String> = Vec::new();
 
@@ -68,6 +83,14 @@ pub(crate) async fn get_user_instructions(
     }
 }
 
+/// Stored memories (see `memory.rs`) when the `memory` feature is enabled.
+fn memory_section(config: &Config) -> Option<String> {
+    if !config.features.enabled(Feature::Memory) {
+        return None;
+    }
+    render_memory_section(&MemoryStore::new(&config.codex_home, &config.cwd))
+}
+
 /// Attempt to locate and load the project documentation.
 ///
 /// On success returns `Ok(Some(contents))` where `contents` is the
@@ -94,25 +117,10 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
             break;
         }
 
-        let file = match tokio::fs::File::open(&p).await {
-            Ok(f) => f,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-            Err(e) => return Err(e),
+        let Some(data) = read_doc_limited(&p, remaining).await? else {
+            continue;
         };
 
-        let size = file.metadata().await?.len();
-        let mut reader = tokio::io::BufReader::new(file).take(remaining);
-        let mut data: Vec<u8> = Vec::new();
-        reader.read_to_end(&mut data).await?;
-
-        if size > remaining {
-            tracing::warn!(
-                "Project doc `{}` exceeds remaining budget ({} bytes) - truncating.",
-                p.display(),
-                remaining,
-            );
-        }
-
         let text = String::from_utf8_lossy(&data).to_string();
         if !text.trim().is_empty() {
             parts.push(text);
@@ -127,12 +135,127 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
     }
 }
 
+/// Reads the instruction file at `path` for injection as a nested doc,
+/// truncated to `project_doc_max_bytes`. Returns `Ok(None)` when the file is
+/// missing, empty, or docs are disabled.
+pub(crate) async fn read_nested_project_doc(
+    config: &Config,
+    path: &Path,
+) -> std::io::Result<Option<String>> {
+    let limit = config.project_doc_max_bytes as u64;
+    if limit == 0 {
+        return Ok(None);
+    }
+    let Some(data) = read_doc_limited(path, limit).await? else {
+        return Ok(None);
+    };
+    let text = String::from_utf8_lossy(&data).to_string();
+    Ok((!text.trim().is_empty()).then_some(text))
+}
+
+/// Reads at most `limit` bytes from `path`, or `Ok(None)` if it does not exist.
+async fn read_doc_limited(path: &Path, limit: u64) -> std::io::Result<Option<Vec<u8>>> {
+    let file = match tokio::fs::File::open(path).await {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let size = file.metadata().await?.len();
+    let mut reader = tokio::io::BufReader::new(file).take(limit);
+    let mut data: Vec<u8> = Vec::new();
+    reader.read_to_end(&mut data).await?;
+
+    if size > limit {
+        tracing::warn!(
+            "Project doc `{}` exceeds remaining budget ({} bytes) - truncating.",
+            path.display(),
+            limit,
+        );
+    }
+
+    Ok(Some(data))
+}
+
 /// Discover the list of AGENTS.md files using the same search rules as
 /// `read_project_docs`, but return the file paths instead of concatenated
 /// contents. The list is ordered from repository root to the current working
 /// directory (inclusive). Symlinks are allowed. When `project_doc_max_bytes`
 /// is zero, returns an empty list.
 pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    let candidate_filenames = candidate_filenames(config);
+    for d in search_dirs(config)? {
+        if let Some(doc) = find_doc_in_dir(&d, &candidate_filenames)? {
+            found.push(doc);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Discover the AGENTS.md files that apply to `dir` but are not covered by
+/// [`discover_project_doc_paths`]: those in directories between the project
+/// root (the Git root, or the working directory when there is none) and `dir`
+/// that are not ancestors of the working directory. The list is ordered from
+/// the outermost directory inward and is empty when `dir` lies outside the
+/// project or `project_doc_max_bytes` is zero.
+pub fn discover_nested_project_doc_paths(
+    config: &Config,
+    dir: &Path,
+) -> std::io::Result<Vec<PathBuf>> {
+    if config.project_doc_max_bytes == 0 {
+        return Ok(Vec::new());
+    }
+    let Some(root) = search_dirs(config)?.into_iter().next() else {
+        return Ok(Vec::new());
+    };
+    let root = normalize_path(&root).unwrap_or(root);
+    let cwd = normalize_path(&config.cwd).unwrap_or_else(|_| config.cwd.clone());
+    let Ok(dir) = normalize_path(dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut nested_dirs: Vec<&Path> = dir
+        .ancestors()
+        .take_while(|d| d.starts_with(&root))
+        .filter(|d| !cwd.starts_with(d))
+        .collect();
+    nested_dirs.reverse();
+
+    let mut found: Vec<PathBuf> = Vec::new();
+    let candidate_filenames = candidate_filenames(config);
+    for d in nested_dirs {
+        if let Some(doc) = find_doc_in_dir(d, &candidate_filenames)? {
+            found.push(doc);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Nested docs loaded in a rollout that are still part of its history.
+/// Compaction and rollback rewrite the history and drop the docs injected
+/// before them, so only docs loaded after the last rewrite count.
+pub(crate) fn loaded_project_docs_from_rollout(items: &[RolloutItem]) -> Vec<PathBuf> {
+    let mut loaded = Vec::new();
+    for item in items {
+        match item {
+            RolloutItem::Compacted(_) | RolloutItem::EventMsg(EventMsg::ThreadRolledBack(_)) => {
+                loaded.clear()
+            }
+            RolloutItem::EventMsg(EventMsg::ProjectDocLoaded(event)) => {
+                loaded.push(event.path.clone());
+            }
+            _ => {}
+        }
+    }
+    loaded
+}
+
+/// Directories searched at session start, from the Git root (inclusive) down
+/// to the working directory, or just the working directory outside Git.
+fn search_dirs(config: &Config) -> std::io::Result<Vec<PathBuf>> {
     let mut dir = config.cwd.clone();
     if let Ok(canon) = normalize_path(&dir) {
         dir = canon;
@@ -177,27 +300,26 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
         vec![config.cwd.clone()]
     };
 
-    let mut found: Vec<PathBuf> = Vec::new();
-    let candidate_filenames = candidate_filenames(config);
-    for d in search_dirs {
-        for name in &candidate_filenames {
-            let candidate = d.join(name);
-            match std::fs::symlink_metadata(&candidate) {
-                Ok(md) => {
-                    let ft = md.file_type();
-                    // Allow regular files and symlinks; opening will later fail for dangling links.
-                    if ft.is_file() || ft.is_symlink() {
-                        found.push(candidate);
-                        break;
-                    }
+    Ok(search_dirs)
+}
+
+/// Returns the first candidate file present in `dir`.
+fn find_doc_in_dir(dir: &Path, candidate_filenames: &[&str]) -> std::io::Result<Option<PathBuf>> {
+    for name in candidate_filenames {
+        let candidate = dir.join(name);
+        match std::fs::symlink_metadata(&candidate) {
+            Ok(md) => {
+                let ft = md.file_type();
+                // Allow regular files and symlinks; opening will later fail for dangling links.
+                if ft.is_file() || ft.is_symlink() {
+                    return Ok(Some(candidate));
                 }
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(e) => return Err(e),
             }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
         }
     }
-
-    Ok(found)
+    Ok(None)
 }
 
 fn candidate_filenames<'a>(config: &'a Config) -> Vec<&'a str> {
@@ -234,6 +356,7 @@ mod tests {
     use super::*;
     use crate::config::ConfigBuilder;
     use crate::skills::load_skills;
+    use codex_protocol::protocol::MemoryScope;
     use std::fs;
     use std::path::PathBuf;
     use tempfile::TempDir;
@@ -423,6 +546,49 @@ mod tests {
         assert_eq!(res, "root doc\n\ncrate doc");
     }
 
+    /// Docs below the working directory are only discovered for the
+    /// directory being worked in, including siblings of the working directory.
+    #[tokio::test]
+    async fn discovers_nested_docs_below_the_working_directory() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            repo.path().join(".git"),
+            "gitdir: /path/to/actual/git/dir\n",
+        )
+        .unwrap();
+        fs::write(repo.path().join("AGENTS.md"), "root doc").unwrap();
+        let services = repo.path().join("services");
+        let foo = services.join("foo/src");
+        let bar = services.join("bar");
+        std::fs::create_dir_all(&foo).unwrap();
+        std::fs::create_dir_all(&bar).unwrap();
+        fs::write(services.join("AGENTS.md"), "services doc").unwrap();
+        fs::write(services.join("foo/AGENTS.md"), "foo doc").unwrap();
+        fs::write(bar.join("AGENTS.md"), "bar doc").unwrap();
+
+        let cfg = make_config(&repo, 4096, None).await;
+        let repo_root = normalize_path(repo.path()).unwrap();
+        assert_eq!(
+            discover_nested_project_doc_paths(&cfg, &foo).unwrap(),
+            vec![
+                repo_root.join("services/AGENTS.md"),
+                repo_root.join("services/foo/AGENTS.md"),
+            ]
+        );
+        assert_eq!(
+            discover_nested_project_doc_paths(&cfg, repo.path()).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+
+        // Docs already loaded from the working directory chain are skipped.
+        let mut cfg = make_config(&repo, 4096, None).await;
+        cfg.cwd = bar;
+        assert_eq!(
+            discover_nested_project_doc_paths(&cfg, &foo).unwrap(),
+            vec![repo_root.join("services/foo/AGENTS.md")]
+        );
+    }
+
     /// AGENTS.override.md is preferred over AGENTS.md when both are present.
     #[tokio::test]
     async fn agents_local_md_preferred() {
@@ -544,10 +710,69 @@ mod tests {
         assert_eq!(res, expected);
     }
 
+    #[tokio::test]
+    async fn memories_are_appended_when_enabled() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(tmp.path().join("AGENTS.md"), "base doc").unwrap();
+        let mut cfg = make_config(&tmp, 4096, None).await;
+        MemoryStore::new(&cfg.codex_home, &cfg.cwd)
+            .add(MemoryScope::Project, "tests need docker")
+            .expect("add memory")
+            .apply()
+            .expect("write memory");
+
+        let res = get_user_instructions(&cfg, None).await;
+        assert_eq!(res.as_deref(), Some("base doc"));
+
+        cfg.features.enable(Feature::Memory);
+        let res = get_user_instructions(&cfg, None)
+            .await
+            .expect("instructions expected");
+        assert_eq!(
+            res,
+            "base doc\n\n## Memory\nFacts remembered from earlier sessions. Use the `memory` tool to recall more, remember new facts or forget stale ones.\n- [project#1] tests need docker"
+        );
+    }
+
     fn create_skill(codex_home: PathBuf, name: &str, description: &str) {
         let skill_dir = codex_home.join(format!("skills/{name}"));
         fs::create_dir_all(&skill_dir).unwrap();
         let content = format!("---\nname: {name}\ndescription: {description}\n---\n\n# Body\n");
         fs::write(skill_dir.join("SKILL.md"), content).unwrap();
     }
+
+    #[test]
+    fn rollout_docs_loaded_before_compaction_are_forgotten() {
+        let loaded = |path: &str| {
+            RolloutItem::EventMsg(EventMsg::ProjectDocLoaded(
+                codex_protocol::protocol::ProjectDocLoadedEvent {
+                    path: PathBuf::from(path),
+                },
+            ))
+        };
+        let items = vec![
+            loaded("/repo/a/AGENTS.md"),
+            RolloutItem::Compacted(codex_protocol::protocol::CompactedItem {
+                message: "summary".to_string(),
+                replacement_history: None,
+            }),
+            loaded("/repo/b/AGENTS.md"),
+        ];
+        assert_eq!(
+            loaded_project_docs_from_rollout(&items),
+            vec![PathBuf::from("/repo/b/AGENTS.md")]
+        );
+
+        let mut rolled_back = items;
+        rolled_back.push(RolloutItem::EventMsg(EventMsg::ThreadRolledBack(
+            codex_protocol::protocol::ThreadRolledBackEvent {
+                num_turns: 1,
+                worktree_restored: false,
+            },
+        )));
+        assert_eq!(
+            loaded_project_docs_from_rollout(&rolled_back),
+            Vec::<PathBuf>::new()
+        );
+    }
 }
@@ -46,6 +46,8 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::EnteredReviewMode(_)
         | EventMsg::ExitedReviewMode(_)
         | EventMsg::UndoCompleted(_)
+        | EventMsg::ThreadRolledBack(_)
+        | EventMsg::ProjectDocLoaded(_)
         | EventMsg::TurnAborted(_) => true,
         EventMsg::Error(_)
         | EventMsg::Warning(_)
@@ -61,6 +63,8 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpToolCallEnd(_)
         | EventMsg::WebSearchBegin(_)
         | EventMsg::WebSearchEnd(_)
+        | EventMsg::FetchUrlBegin(_)
+        | EventMsg::FetchUrlEnd(_)
         | EventMsg::ExecCommandBegin(_)
         | EventMsg::TerminalInteraction(_)
         | EventMsg::ExecCommandOutputDelta(_)
@@ -80,9 +84,19 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpStartupComplete(_)
         | EventMsg::ListCustomPromptsResponse(_)
         | EventMsg::ListSkillsResponse(_)
+        | EventMsg::ListCheckpointsResponse(_)
+        | EventMsg::ListMemoriesResponse(_)
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
+        | EventMsg::ToolOutputSpilled(_)
+        | EventMsg::SubAgentBegin(_)
+        | EventMsg::SubAgentActivity(_)
+        | EventMsg::BudgetUpdated(_)
+        | EventMsg::BudgetExceeded(_)
+        | EventMsg::PlanModeUpdated(_)
+        | EventMsg::PlanApprovalRequest(_)
+        | EventMsg::SubAgentEnd(_)
         | EventMsg::DeprecationNotice(_)
         | EventMsg::ItemStarted(_)
         | EventMsg::ItemCompleted(_)
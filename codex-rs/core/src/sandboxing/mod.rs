@@ -35,6 +35,8 @@ pub struct CommandSpec {
     pub expiration: ExecExpiration,
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
+    /// Written to the child's stdin, which is otherwise closed.
+    pub stdin: Option<String>,
 }
 
 #[derive(Debug)]
@@ -47,6 +49,7 @@ pub struct ExecEnv {
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub arg0: Option<String>,
+    pub stdin: Option<String>,
 }
 
 pub enum SandboxPreference {
@@ -163,6 +166,7 @@ impl SandboxManager {
             sandbox_permissions: spec.sandbox_permissions,
             justification: spec.justification,
             arg0: arg0_override,
+            stdin: spec.stdin,
         })
     }
 
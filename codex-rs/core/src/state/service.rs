@@ -3,13 +3,21 @@ use std::sync::Arc;
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::auth::azure::AzureAuth;
+use crate::budget::SessionBudget;
+use crate::code_index::CodeIndexManager;
+use crate::custom_tools::CustomTool;
 use crate::exec_policy::ExecPolicyManager;
+use crate::hooks::Hooks;
+use crate::lsp::LspManager;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::output_store::OutputStore;
 use crate::skills::SkillsManager;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use crate::verify::Verifier;
+use crate::web_fetch::WebFetcher;
 use codex_otel::otel_manager::OtelManager;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -20,6 +28,8 @@ pub(crate) struct SessionServices {
     pub(crate) mcp_startup_cancellation_token: CancellationToken,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: Hooks,
+    pub(crate) budget: SessionBudget,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: Arc<crate::shell::Shell>,
     pub(crate) show_raw_agent_reasoning: bool,
@@ -31,4 +41,10 @@ pub(crate) struct SessionServices {
     pub(crate) otel_manager: OtelManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
     pub(crate) skills_manager: Arc<SkillsManager>,
+    pub(crate) output_store: OutputStore,
+    pub(crate) code_index: CodeIndexManager,
+    pub(crate) lsp: LspManager,
+    pub(crate) custom_tools: Vec<CustomTool>,
+    pub(crate) web_fetch: WebFetcher,
+    pub(crate) verifier: Verifier,
 }
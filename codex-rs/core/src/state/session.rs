@@ -1,10 +1,18 @@
 //! Session-wide mutable state.
 
 use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
+use crate::checkpoints::TaskActivity;
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::git_info::GitStatusSummary;
 use crate::protocol::RateLimitSnapshot;
+use crate::protocol::TokenCost;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::truncate::TruncationPolicy;
@@ -14,6 +22,22 @@ pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Estimated spend, once any request has been priced.
+    cost: Option<SessionCost>,
+    /// Latest plan recorded with `update_plan` while in plan mode.
+    pub(crate) proposed_plan: Option<UpdatePlanArgs>,
+    /// When tasks ran, so checkpoints can flag edits made outside Codex.
+    task_activity: Vec<TaskActivity>,
+    /// Nested `AGENTS.md` files already added to the conversation.
+    loaded_project_docs: HashSet<PathBuf>,
+    /// Repository state last sent to the model with the `git_context` feature.
+    last_git_status: Option<GitStatusSummary>,
+}
+
+struct SessionCost {
+    session_usd: f64,
+    turn_usd: f64,
+    turn_id: String,
 }
 
 impl SessionState {
@@ -24,6 +48,11 @@ impl SessionState {
             session_configuration,
             history,
             latest_rate_limits: None,
+            cost: None,
+            proposed_plan: None,
+            task_activity: Vec::new(),
+            loaded_project_docs: HashSet::new(),
+            last_git_status: None,
         }
     }
 
@@ -42,6 +71,37 @@ impl SessionState {
 
     pub(crate) fn replace_history(&mut self, items: Vec<ResponseItem>) {
         self.history.replace(items);
+        // The rewritten history may no longer carry the repository state or
+        // the nested project docs injected so far.
+        self.last_git_status = None;
+        self.loaded_project_docs.clear();
+    }
+
+    pub(crate) fn record_task_activity(&mut self, started_at: SystemTime, ended_at: SystemTime) {
+        self.task_activity.push((started_at, ended_at));
+    }
+
+    pub(crate) fn task_activity(&self) -> Vec<TaskActivity> {
+        self.task_activity.clone()
+    }
+
+    pub(crate) fn is_project_doc_loaded(&self, path: &Path) -> bool {
+        self.loaded_project_docs.contains(path)
+    }
+
+    /// Marks a nested project doc as loaded; returns `false` if it already was.
+    pub(crate) fn mark_project_doc_loaded(&mut self, path: PathBuf) -> bool {
+        self.loaded_project_docs.insert(path)
+    }
+
+    /// Records `status` as sent to the model; returns `false` if it matches
+    /// what was sent last.
+    pub(crate) fn update_git_status(&mut self, status: &GitStatusSummary) -> bool {
+        if self.last_git_status.as_ref() == Some(status) {
+            return false;
+        }
+        self.last_git_status = Some(status.clone());
+        true
     }
 
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
@@ -74,6 +134,37 @@ impl SessionState {
         (self.token_info(), self.latest_rate_limits.clone())
     }
 
+    /// Adds the cost of one model request made during `turn_id`.
+    pub(crate) fn add_cost(&mut self, turn_id: &str, cost_usd: f64) -> TokenCost {
+        let cost = self.cost.get_or_insert_with(|| SessionCost {
+            session_usd: 0.0,
+            turn_usd: 0.0,
+            turn_id: turn_id.to_string(),
+        });
+        if cost.turn_id != turn_id {
+            cost.turn_usd = 0.0;
+            cost.turn_id = turn_id.to_string();
+        }
+        cost.session_usd += cost_usd;
+        cost.turn_usd += cost_usd;
+        TokenCost {
+            session_usd: cost.session_usd,
+            turn_usd: cost.turn_usd,
+        }
+    }
+
+    /// Spend so far, with the turn cost reported for `turn_id`.
+    pub(crate) fn cost_for_turn(&self, turn_id: &str) -> Option<TokenCost> {
+        self.cost.as_ref().map(|cost| TokenCost {
+            session_usd: cost.session_usd,
+            turn_usd: if cost.turn_id == turn_id {
+                cost.turn_usd
+            } else {
+                0.0
+            },
+        })
+    }
+
     pub(crate) fn set_token_usage_full(&mut self, context_window: i64) {
         self.history.set_token_usage_full(context_window);
     }
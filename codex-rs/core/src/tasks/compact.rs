@@ -25,13 +25,11 @@ impl SessionTask for CompactTask {
         _cancellation_token: CancellationToken,
     ) -> Option<String> {
         let session = session.clone_session();
-        if crate::compact::should_use_remote_compact_task(
-            session.as_ref(),
-            &ctx.client.get_provider(),
-        ) {
-            crate::compact_remote::run_remote_compact_task(session, ctx).await
+        let compact_ctx = session.compaction_turn_context(&ctx).await;
+        if crate::compact::should_use_remote_compact_task(session.as_ref(), &compact_ctx) {
+            crate::compact_remote::run_remote_compact_task(session, ctx, compact_ctx).await
         } else {
-            crate::compact::run_compact_task(session, ctx, input).await
+            crate::compact::run_compact_task(session, ctx, compact_ctx, input).await
         }
 
         None
@@ -7,6 +7,7 @@ mod user_shell;
 
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::SystemTime;
 
 use async_trait::async_trait;
 use tokio::select;
@@ -19,6 +20,7 @@ use tracing::warn;
 use crate::AuthManager;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::hooks::HookEvent;
 use crate::models_manager::manager::ModelsManager;
 use crate::protocol::EventMsg;
 use crate::protocol::TaskCompleteEvent;
@@ -116,6 +118,7 @@ impl Session {
 
         let cancellation_token = CancellationToken::new();
         let done = Arc::new(Notify::new());
+        let started_at = SystemTime::now();
 
         let done_clone = Arc::clone(&done);
         let handle = {
@@ -133,6 +136,10 @@ impl Session {
                         task_cancellation_token.child_token(),
                     )
                     .await;
+                session_ctx
+                    .clone_session()
+                    .record_task_activity(started_at)
+                    .await;
                 session_ctx.clone_session().flush_rollout().await;
                 if !task_cancellation_token.is_cancelled() {
                     // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
@@ -151,6 +158,7 @@ impl Session {
             task,
             cancellation_token,
             turn_context: Arc::clone(&turn_context),
+            started_at,
         };
         self.register_new_active_task(running_task).await;
     }
@@ -180,6 +188,14 @@ impl Session {
         if should_close_sessions {
             self.close_unified_exec_sessions().await;
         }
+        self.run_hooks(
+            turn_context.as_ref(),
+            &mut HookEvent::Stop {
+                last_assistant_message: last_agent_message.clone(),
+                aborted: false,
+            },
+        )
+        .await;
         let event = EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message });
         self.send_event(turn_context.as_ref(), event).await;
     }
@@ -229,12 +245,22 @@ impl Session {
         }
 
         task.handle.abort();
+        self.record_task_activity(task.started_at).await;
 
         let session_ctx = Arc::new(SessionTaskContext::new(Arc::clone(self)));
         session_task
             .abort(session_ctx, Arc::clone(&task.turn_context))
             .await;
 
+        self.run_hooks(
+            task.turn_context.as_ref(),
+            &mut HookEvent::Stop {
+                last_assistant_message: None,
+                aborted: true,
+            },
+        )
+        .await;
+
         let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
         self.send_event(task.turn_context.as_ref(), event).await;
     }
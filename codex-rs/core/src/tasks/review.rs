@@ -12,6 +12,7 @@ use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExitedReviewModeEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::SubAgentSource;
 use codex_protocol::protocol::TaskStartedEvent;
 use codex_protocol::protocol::WarningEvent;
 use tokio_util::sync::CancellationToken;
@@ -234,6 +235,7 @@ async fn run_fix_once(
         ctx.clone(),
         cancellation_token,
         None,
+        SubAgentSource::Review,
     )
     .await;
 
@@ -295,6 +297,7 @@ async fn start_review_conversation(
         ctx.clone(),
         cancellation_token,
         None,
+        SubAgentSource::Review,
     )
     .await)
         .ok()
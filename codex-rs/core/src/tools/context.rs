@@ -4,6 +4,7 @@ use crate::tools::TELEMETRY_PREVIEW_MAX_BYTES;
 use crate::tools::TELEMETRY_PREVIEW_MAX_LINES;
 use crate::tools::TELEMETRY_PREVIEW_TRUNCATION_NOTICE;
 use crate::turn_diff_tracker::TurnDiffTracker;
+use codex_apply_patch::Hunk;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
@@ -44,6 +45,9 @@ pub enum ToolPayload {
     },
 }
 
+/// Argument names that built-in tools use for the file or directory they act on.
+const PATH_ARGUMENTS: [&str; 4] = ["path", "file_path", "dir_path", "workdir"];
+
 impl ToolPayload {
     pub fn log_payload(&self) -> Cow<'_, str> {
         match self {
@@ -53,6 +57,50 @@ impl ToolPayload {
             ToolPayload::Mcp { raw_arguments, .. } => Cow::Borrowed(raw_arguments),
         }
     }
+
+    /// Paths the call reads or writes, as written by the model: path-like
+    /// arguments, the working directory of shell calls, and the files named
+    /// in an `apply_patch` body.
+    pub fn referenced_paths(&self) -> Vec<String> {
+        match self {
+            ToolPayload::Function { arguments } => {
+                let Ok(serde_json::Value::Object(arguments)) =
+                    serde_json::from_str::<serde_json::Value>(arguments)
+                else {
+                    return Vec::new();
+                };
+                let mut paths: Vec<String> = PATH_ARGUMENTS
+                    .iter()
+                    .filter_map(|key| arguments.get(*key)?.as_str())
+                    .map(str::to_string)
+                    .collect();
+                if let Some(input) = arguments.get("input").and_then(serde_json::Value::as_str) {
+                    paths.extend(patch_paths(input));
+                }
+                paths
+            }
+            ToolPayload::Custom { input } => patch_paths(input),
+            ToolPayload::LocalShell { params } => params.workdir.iter().cloned().collect(),
+            ToolPayload::Mcp { .. } => Vec::new(),
+        }
+    }
+}
+
+fn patch_paths(input: &str) -> Vec<String> {
+    let Ok(patch) = codex_apply_patch::parse_patch(input) else {
+        return Vec::new();
+    };
+    patch
+        .hunks
+        .iter()
+        .flat_map(|hunk| match hunk {
+            Hunk::AddFile { path, .. } | Hunk::DeleteFile { path } => vec![path],
+            Hunk::UpdateFile {
+                path, move_path, ..
+            } => std::iter::once(path).chain(move_path).collect(),
+        })
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect()
 }
 
 #[derive(Clone)]
@@ -160,6 +208,27 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn referenced_paths_cover_arguments_and_patches() {
+        let read = ToolPayload::Function {
+            arguments: r#"{"file_path":"services/foo/src/lib.rs","offset":1}"#.to_string(),
+        };
+        let shell = ToolPayload::Function {
+            arguments: r#"{"command":"make test","workdir":"services/foo"}"#.to_string(),
+        };
+        let patch = ToolPayload::Custom {
+            input: "*** Begin Patch\n*** Add File: services/bar/new.txt\n+hi\n*** Update File: a.txt\n*** Move to: b/a.txt\n@@\n-x\n+y\n*** End Patch"
+                .to_string(),
+        };
+
+        assert_eq!(read.referenced_paths(), vec!["services/foo/src/lib.rs"]);
+        assert_eq!(shell.referenced_paths(), vec!["services/foo"]);
+        assert_eq!(
+            patch.referenced_paths(),
+            vec!["services/bar/new.txt", "a.txt", "b/a.txt"]
+        );
+    }
+
     #[test]
     fn custom_tool_calls_should_roundtrip_as_custom_outputs() {
         let payload = ToolPayload::Custom {
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::path::PathBuf;
 
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
@@ -26,6 +27,8 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::spec::ApplyPatchToolArgs;
 use crate::tools::spec::JsonSchema;
 use async_trait::async_trait;
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
 
 pub struct ApplyPatchHandler;
 
@@ -81,63 +84,15 @@ impl ToolHandler for ApplyPatchHandler {
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
         match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
-                match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), &call_id, changes)
-                    .await
-                {
-                    InternalApplyPatchInvocation::Output(item) => {
-                        let content = item?;
-                        Ok(ToolOutput::Function {
-                            content,
-                            content_items: None,
-                            success: Some(true),
-                        })
-                    }
-                    InternalApplyPatchInvocation::DelegateToExec(apply) => {
-                        let emitter = ToolEmitter::apply_patch(
-                            convert_apply_patch_to_protocol(&apply.action),
-                            !apply.user_explicitly_approved_this_action,
-                        );
-                        let event_ctx = ToolEventCtx::new(
-                            session.as_ref(),
-                            turn.as_ref(),
-                            &call_id,
-                            Some(&tracker),
-                        );
-                        emitter.begin(event_ctx).await;
-
-                        let req = ApplyPatchRequest {
-                            patch: apply.action.patch.clone(),
-                            cwd: apply.action.cwd.clone(),
-                            timeout_ms: None,
-                            user_explicitly_approved: apply.user_explicitly_approved_this_action,
-                            codex_exe: turn.codex_linux_sandbox_exe.clone(),
-                        };
-
-                        let mut orchestrator = ToolOrchestrator::new();
-                        let mut runtime = ApplyPatchRuntime::new();
-                        let tool_ctx = ToolCtx {
-                            session: session.as_ref(),
-                            turn: turn.as_ref(),
-                            call_id: call_id.clone(),
-                            tool_name: tool_name.to_string(),
-                        };
-                        let out = orchestrator
-                            .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
-                            .await;
-                        let event_ctx = ToolEventCtx::new(
-                            session.as_ref(),
-                            turn.as_ref(),
-                            &call_id,
-                            Some(&tracker),
-                        );
-                        let content = emitter.finish(event_ctx, out).await?;
-                        Ok(ToolOutput::Function {
-                            content,
-                            content_items: None,
-                            success: Some(true),
-                        })
-                    }
-                }
+                run_apply_patch_action(
+                    session.as_ref(),
+                    turn.as_ref(),
+                    &tracker,
+                    &call_id,
+                    &tool_name,
+                    changes,
+                )
+                .await
             }
             codex_apply_patch::MaybeApplyPatchVerified::CorrectnessError(parse_error) => {
                 Err(FunctionCallError::RespondToModel(format!(
@@ -159,6 +114,91 @@ impl ToolHandler for ApplyPatchHandler {
     }
 }
 
+/// Runs a verified patch through approval, the sandboxed apply_patch runtime
+/// and the `PatchApplyBegin`/`PatchApplyEnd` events.
+pub(crate) async fn run_apply_patch_action(
+    session: &Session,
+    turn: &TurnContext,
+    tracker: &SharedTurnDiffTracker,
+    call_id: &str,
+    tool_name: &str,
+    action: ApplyPatchAction,
+) -> Result<ToolOutput, FunctionCallError> {
+    match apply_patch::apply_patch(session, turn, call_id, action).await {
+        InternalApplyPatchInvocation::Output(item) => {
+            let content = item?;
+            Ok(ToolOutput::Function {
+                content,
+                content_items: None,
+                success: Some(true),
+            })
+        }
+        InternalApplyPatchInvocation::DelegateToExec(apply) => {
+            let emitter = ToolEmitter::apply_patch(
+                convert_apply_patch_to_protocol(&apply.action),
+                !apply.user_explicitly_approved_this_action,
+            );
+            let event_ctx = ToolEventCtx::new(session, turn, call_id, Some(tracker));
+            emitter.begin(event_ctx).await;
+
+            let req = ApplyPatchRequest {
+                patch: apply.action.patch.clone(),
+                cwd: apply.action.cwd.clone(),
+                timeout_ms: None,
+                user_explicitly_approved: apply.user_explicitly_approved_this_action,
+                codex_exe: turn.codex_linux_sandbox_exe.clone(),
+            };
+
+            let mut orchestrator = ToolOrchestrator::new();
+            let mut runtime = ApplyPatchRuntime::new();
+            let tool_ctx = ToolCtx {
+                session,
+                turn,
+                call_id: call_id.to_string(),
+                tool_name: tool_name.to_string(),
+            };
+            let out = orchestrator
+                .run(&mut runtime, &req, &tool_ctx, turn, turn.approval_policy)
+                .await;
+            let event_ctx = ToolEventCtx::new(session, turn, call_id, Some(tracker));
+            let mut content = emitter.finish(event_ctx, out).await?;
+            let changed_paths = changed_paths(&apply.action);
+            if let Some(diagnostics) = session
+                .services
+                .lsp
+                .diagnostics_after_edit(session, turn, call_id, &changed_paths)
+                .await
+            {
+                content.push_str("\n\n");
+                content.push_str(&diagnostics);
+            }
+            Ok(ToolOutput::Function {
+                content,
+                content_items: None,
+                success: Some(true),
+            })
+        }
+    }
+}
+
+/// Files that exist after the patch, for language server diagnostics.
+fn changed_paths(action: &ApplyPatchAction) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = action
+        .changes()
+        .iter()
+        .filter_map(|(path, change)| match change {
+            ApplyPatchFileChange::Add { .. } => Some(path.clone()),
+            ApplyPatchFileChange::Update { move_path, .. } => {
+                Some(move_path.clone().unwrap_or_else(|| path.clone()))
+            }
+            ApplyPatchFileChange::Delete { .. } => None,
+        })
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn intercept_apply_patch(
     command: &[String],
@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+
+use crate::custom_tools::CustomTool;
+use crate::custom_tools::build_invocation;
+use crate::exec_env::create_env;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::ExecCommandSource;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+/// Approval prompts show at most this much of a tool's JSON input.
+const APPROVAL_INPUT_PREVIEW_BYTES: usize = 500;
+
+/// Runs a config-defined tool through the shell runtime, so it gets the same
+/// sandbox, approval policy and exec events as a model-issued command.
+pub struct CustomToolHandler {
+    tool: CustomTool,
+}
+
+impl CustomToolHandler {
+    pub(crate) fn new(tool: CustomTool) -> Self {
+        Self { tool }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CustomToolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        // The command is opaque to us, so assume it may write.
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unsupported payload for custom tool {tool_name}"
+            )));
+        };
+        let config = &self.tool.config;
+        let invocation =
+            build_invocation(config, &arguments).map_err(FunctionCallError::RespondToModel)?;
+
+        let cwd = turn.resolve_path(
+            config
+                .cwd
+                .as_ref()
+                .map(|cwd| cwd.to_string_lossy().to_string()),
+        );
+        let mut env = create_env(&turn.shell_environment_policy);
+        env.extend(config.env.clone());
+        let justification = match &invocation.stdin {
+            Some(input) => format!(
+                "`{}` tool with input {}",
+                self.tool.name,
+                truncate_text(input, TruncationPolicy::Bytes(APPROVAL_INPUT_PREVIEW_BYTES))
+            ),
+            None => format!("`{}` tool", self.tool.name),
+        };
+
+        let emitter = ToolEmitter::shell(
+            invocation.command.clone(),
+            cwd.clone(),
+            ExecCommandSource::Agent,
+            false,
+        );
+        let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+        emitter.begin(event_ctx).await;
+
+        let features = session.features();
+        let exec_approval_requirement = session
+            .services
+            .exec_policy
+            .create_exec_approval_requirement_for_command(
+                &features,
+                &invocation.command,
+                turn.approval_policy,
+                &turn.sandbox_policy,
+                SandboxPermissions::UseDefault,
+            )
+            .await;
+        let req = ShellRequest {
+            command: invocation.command,
+            cwd,
+            timeout_ms: config.timeout_ms,
+            env,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: Some(justification),
+            exec_approval_requirement,
+            stdin: invocation.stdin,
+        };
+        let mut orchestrator = ToolOrchestrator::new();
+        let mut runtime = ShellRuntime::new();
+        let tool_ctx = ToolCtx {
+            session: session.as_ref(),
+            turn: turn.as_ref(),
+            call_id: call_id.clone(),
+            tool_name: tool_name.clone(),
+        };
+        let out = orchestrator
+            .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
+            .await;
+        let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+        let content = emitter.finish(event_ctx, out).await?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -0,0 +1,506 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use async_trait::async_trait;
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use serde::Deserialize;
+use similar::ChangeTag;
+use similar::TextDiff;
+
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::apply_patch::run_apply_patch_action;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+
+/// Lines of unchanged context around each hunk of the synthesized patch.
+const PATCH_CONTEXT_LINES: usize = 3;
+
+/// Handles `edit_file` and `create_file`. Both are turned into an
+/// `apply_patch` action so they share its approval, sandbox and diff events.
+pub struct EditFileHandler;
+
+#[derive(Deserialize)]
+struct EditFileArgs {
+    path: String,
+    old_string: String,
+    new_string: String,
+    #[serde(default)]
+    replace_all: bool,
+    #[serde(default)]
+    occurrence: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct CreateFileArgs {
+    path: String,
+    content: String,
+}
+
+fn parse_arguments<T: for<'de> Deserialize<'de>>(
+    payload: &ToolPayload,
+    tool_name: &str,
+) -> Result<T, FunctionCallError> {
+    let ToolPayload::Function { arguments } = payload else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "{tool_name} handler received unsupported payload"
+        )));
+    };
+    serde_json::from_str(arguments).map_err(|e| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+    })
+}
+
+#[async_trait]
+impl ToolHandler for EditFileHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            call_id,
+            tool_name,
+            payload,
+        } = invocation;
+
+        let (path, patch) = match tool_name.as_str() {
+            "create_file" => {
+                let args: CreateFileArgs = parse_arguments(&payload, &tool_name)?;
+                let path = turn.resolve_path(Some(args.path));
+                (path.clone(), create_file_patch(&path, &args.content)?)
+            }
+            _ => {
+                let args: EditFileArgs = parse_arguments(&payload, &tool_name)?;
+                let path = turn.resolve_path(Some(args.path.clone()));
+                let original = std::fs::read_to_string(&path).map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to read {}: {err}",
+                        path.display()
+                    ))
+                })?;
+                let updated = replace_in_contents(&original, &args)?;
+                (path.clone(), edit_file_patch(&path, &original, &updated)?)
+            }
+        };
+
+        let action = verify_patch(&path, &patch, &turn.cwd)?;
+        run_apply_patch_action(
+            session.as_ref(),
+            turn.as_ref(),
+            &tracker,
+            &call_id,
+            &tool_name,
+            action,
+        )
+        .await
+    }
+}
+
+/// Applies the requested replacement to `original`, insisting on an
+/// unambiguous match unless `replace_all` or `occurrence` says otherwise.
+fn replace_in_contents(original: &str, args: &EditFileArgs) -> Result<String, FunctionCallError> {
+    let EditFileArgs {
+        path,
+        old_string,
+        new_string,
+        replace_all,
+        occurrence,
+    } = args;
+    if old_string.is_empty() {
+        return Err(FunctionCallError::RespondToModel(
+            "`old_string` must not be empty; use `create_file` to write a new file".to_string(),
+        ));
+    }
+    if old_string == new_string {
+        return Err(FunctionCallError::RespondToModel(
+            "`old_string` and `new_string` are identical; nothing to change".to_string(),
+        ));
+    }
+
+    let matches: Vec<usize> = original
+        .match_indices(old_string.as_str())
+        .map(|(idx, _)| idx)
+        .collect();
+    if matches.is_empty() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "`old_string` was not found in {path}; re-read the file and copy the text exactly, including whitespace"
+        )));
+    }
+    if *replace_all {
+        return Ok(original.replace(old_string.as_str(), new_string));
+    }
+    let start = match occurrence {
+        Some(n) => *matches.get(n.wrapping_sub(1)).ok_or_else(|| {
+            FunctionCallError::RespondToModel(format!(
+                "`occurrence` {n} is out of range; `old_string` occurs {} time(s) in {path}",
+                matches.len()
+            ))
+        })?,
+        None if matches.len() == 1 => matches[0],
+        None => {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`old_string` occurs {} times in {path}; include more surrounding context, set `occurrence`, or set `replace_all`",
+                matches.len()
+            )));
+        }
+    };
+    let mut updated = String::with_capacity(original.len() + new_string.len());
+    updated.push_str(&original[..start]);
+    updated.push_str(new_string);
+    updated.push_str(&original[start + old_string.len()..]);
+    Ok(updated)
+}
+
+pub(crate) fn create_file_patch(path: &Path, content: &str) -> Result<String, FunctionCallError> {
+    if path.exists() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "{} already exists; use `edit_file` to change it",
+            path.display()
+        )));
+    }
+    let mut patch = format!("*** Begin Patch\n*** Add File: {}\n", path.display());
+    for line in content.strip_suffix('\n').unwrap_or(content).split('\n') {
+        patch.push('+');
+        patch.push_str(line);
+        patch.push('\n');
+    }
+    patch.push_str("*** End Patch\n");
+    Ok(patch)
+}
+
+/// Expresses `original` -> `updated` as an `apply_patch` update with a few
+/// lines of context per hunk. A file without a final newline keeps lacking
+/// one, so the edit adds no unrequested change.
+pub(crate) fn edit_file_patch(
+    path: &Path,
+    original: &str,
+    updated: &str,
+) -> Result<String, FunctionCallError> {
+    let keep_missing_newline = !original.is_empty()
+        && !original.ends_with('\n')
+        && !updated.is_empty()
+        && !updated.ends_with('\n');
+    let expected = if keep_missing_newline {
+        updated.to_string()
+    } else {
+        with_trailing_newline(updated)
+    };
+    let original = with_trailing_newline(original);
+    let updated = with_trailing_newline(updated);
+    let diff = TextDiff::from_lines(original.as_str(), updated.as_str());
+
+    // The patch format locates hunks by their context, which can be ambiguous
+    // in repetitive files. Fall back to a single hunk spanning the whole file
+    // when the compact patch would not produce the edited contents.
+    let whole_file = original.lines().count() + 1;
+    for context_lines in [PATCH_CONTEXT_LINES, whole_file] {
+        let patch = render_update_patch(path, &diff, context_lines, keep_missing_newline);
+        if resulting_contents(path, &patch).as_deref() == Some(expected.as_str()) {
+            return Ok(patch);
+        }
+    }
+    Err(FunctionCallError::RespondToModel(format!(
+        "the edit to {} could not be expressed as a patch; apply it with `apply_patch` or the shell instead",
+        path.display()
+    )))
+}
+
+fn render_update_patch(
+    path: &Path,
+    diff: &TextDiff<'_, '_, '_, str>,
+    context_lines: usize,
+    no_final_newline: bool,
+) -> String {
+    let mut patch = format!("*** Begin Patch\n*** Update File: {}\n", path.display());
+    let mut reaches_end = false;
+    for group in diff.grouped_ops(context_lines) {
+        reaches_end = group
+            .last()
+            .is_some_and(|op| op.old_range().end == diff.old_slices().len());
+        patch.push_str("@@\n");
+        for op in group {
+            for change in diff.iter_changes(&op) {
+                patch.push(match change.tag() {
+                    ChangeTag::Equal => ' ',
+                    ChangeTag::Delete => '-',
+                    ChangeTag::Insert => '+',
+                });
+                patch.push_str(change.value().strip_suffix('\n').unwrap_or(change.value()));
+                patch.push('\n');
+            }
+        }
+    }
+    if no_final_newline {
+        // The marker must close a hunk that ends at the last line; pin that
+        // line in a hunk of its own when the edit does not reach it.
+        if !reaches_end && let Some(last_line) = diff.old_slices().last() {
+            patch.push_str("@@\n ");
+            patch.push_str(last_line.strip_suffix('\n').unwrap_or(last_line));
+            patch.push('\n');
+        }
+        patch.push_str("*** No newline at end of file\n");
+    }
+    patch.push_str("*** End Patch\n");
+    patch
+}
+
+fn with_trailing_newline(contents: &str) -> String {
+    if contents.is_empty() || contents.ends_with('\n') {
+        contents.to_string()
+    } else {
+        format!("{contents}\n")
+    }
+}
+
+fn resulting_contents(path: &Path, patch: &str) -> Option<String> {
+    let cwd = path.parent()?;
+    let action = parse_patch(patch, cwd).ok()?;
+    match action.changes().get(path)? {
+        ApplyPatchFileChange::Update { new_content, .. } => Some(new_content.clone()),
+        _ => None,
+    }
+}
+
+fn parse_patch(patch: &str, cwd: &Path) -> Result<ApplyPatchAction, FunctionCallError> {
+    let command = vec!["apply_patch".to_string(), patch.to_string()];
+    match codex_apply_patch::maybe_parse_apply_patch_verified(&command, cwd) {
+        MaybeApplyPatchVerified::Body(action) => Ok(action),
+        MaybeApplyPatchVerified::CorrectnessError(err) => Err(FunctionCallError::RespondToModel(
+            format!("edit verification failed: {err}"),
+        )),
+        MaybeApplyPatchVerified::ShellParseError(_) | MaybeApplyPatchVerified::NotApplyPatch => {
+            Err(FunctionCallError::RespondToModel(
+                "edit could not be expressed as a patch".to_string(),
+            ))
+        }
+    }
+}
+
+pub(crate) fn verify_patch(
+    path: &Path,
+    patch: &str,
+    cwd: &Path,
+) -> Result<ApplyPatchAction, FunctionCallError> {
+    let action = parse_patch(patch, cwd)?;
+    if action.changes().contains_key(path) {
+        Ok(action)
+    } else {
+        Err(FunctionCallError::RespondToModel(format!(
+            "edit verification failed for {}",
+            path.display()
+        )))
+    }
+}
+
+pub(crate) fn create_edit_file_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Path of the file to edit, relative to the working directory.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "old_string".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Exact text to replace, including whitespace and indentation. Must match the file byte for byte."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "new_string".to_string(),
+        JsonSchema::String {
+            description: Some("Text to put in place of `old_string`.".to_string()),
+        },
+    );
+    properties.insert(
+        "replace_all".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Replace every occurrence of `old_string` (default false).".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "occurrence".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "1-based occurrence to replace when `old_string` appears more than once."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "edit_file".to_string(),
+        description: "Edits a file by replacing an exact string. `old_string` must match exactly once unless `occurrence` or `replace_all` is set; include enough surrounding lines to make it unique. Read the file before editing it."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec![
+                "path".to_string(),
+                "old_string".to_string(),
+                "new_string".to_string(),
+            ]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+pub(crate) fn create_create_file_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Path of the new file, relative to the working directory. Parent directories are created as needed."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "content".to_string(),
+        JsonSchema::String {
+            description: Some("Full contents of the new file.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "create_file".to_string(),
+        description: "Creates a new file with the given contents. Fails if the file already exists; use `edit_file` to change existing files."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string(), "content".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn args(old_string: &str, new_string: &str) -> EditFileArgs {
+        EditFileArgs {
+            path: "lib.rs".to_string(),
+            old_string: old_string.to_string(),
+            new_string: new_string.to_string(),
+            replace_all: false,
+            occurrence: None,
+        }
+    }
+
+    #[test]
+    fn replacement_requires_a_unique_match() {
+        let original = "let a = 1;\nlet b = 1;\n";
+        let err = replace_in_contents(original, &args("= 1", "= 2")).unwrap_err();
+        assert!(err.to_string().contains("occurs 2 times"), "{err}");
+
+        let mut second = args("= 1", "= 2");
+        second.occurrence = Some(2);
+        assert_eq!(
+            replace_in_contents(original, &second).unwrap(),
+            "let a = 1;\nlet b = 2;\n"
+        );
+
+        let mut all = args("= 1", "= 2");
+        all.replace_all = true;
+        assert_eq!(
+            replace_in_contents(original, &all).unwrap(),
+            "let a = 2;\nlet b = 2;\n"
+        );
+
+        let err = replace_in_contents(original, &args("= 3", "= 2")).unwrap_err();
+        assert!(err.to_string().contains("was not found"), "{err}");
+    }
+
+    #[test]
+    fn synthesized_patch_targets_the_right_repeated_block() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        let original = "fn a() {\n    x();\n}\n\nfn b() {\n    x();\n}\n";
+        std::fs::write(&path, original).unwrap();
+
+        let mut second = args("    x();", "    y();");
+        second.occurrence = Some(2);
+        let updated = replace_in_contents(original, &second).unwrap();
+        let patch = edit_file_patch(&path, original, &updated).unwrap();
+        let action = verify_patch(&path, &patch, dir.path()).unwrap();
+
+        let Some(ApplyPatchFileChange::Update { new_content, .. }) = action.changes().get(&path)
+        else {
+            panic!("expected an update for {}", path.display());
+        };
+        assert_eq!(
+            new_content,
+            "fn a() {\n    x();\n}\n\nfn b() {\n    y();\n}\n"
+        );
+    }
+
+    #[test]
+    fn create_file_refuses_to_overwrite() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("new.txt");
+        let patch = create_file_patch(&path, "hello\nworld").unwrap();
+        let action = verify_patch(&path, &patch, dir.path()).unwrap();
+        assert_eq!(
+            action.changes().get(&path),
+            Some(&ApplyPatchFileChange::Add {
+                content: "hello\nworld\n".to_string()
+            })
+        );
+
+        std::fs::write(&path, "old").unwrap();
+        let err = create_file_patch(&path, "hello").unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+    }
+
+    #[test]
+    fn edit_keeps_a_missing_trailing_newline() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        let original = "let a = 1;\n1;\n2;\n3;\n4;\n5;\n6;\nlet b = 2;";
+        std::fs::write(&path, original).unwrap();
+        let new_content = |updated: &str| {
+            let patch = edit_file_patch(&path, original, updated).unwrap();
+            let action = verify_patch(&path, &patch, dir.path()).unwrap();
+            let Some(ApplyPatchFileChange::Update { new_content, .. }) =
+                action.changes().get(&path)
+            else {
+                panic!("expected an update");
+            };
+            new_content.clone()
+        };
+
+        // The edit touches the last line.
+        let updated = replace_in_contents(original, &args("b = 2", "b = 3")).unwrap();
+        assert_eq!(new_content(&updated), updated);
+        // The edit stops short of it.
+        let updated = replace_in_contents(original, &args("a = 1", "a = 0")).unwrap();
+        assert_eq!(new_content(&updated), updated);
+    }
+}
@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
+use serde::Deserialize;
+use url::Url;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::FetchUrlBeginEvent;
+use crate::protocol::FetchUrlEndEvent;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::sandboxing::with_cached_approval;
+
+pub struct FetchUrlHandler;
+
+#[derive(Deserialize)]
+struct FetchUrlArgs {
+    url: String,
+    #[serde(default)]
+    page: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for FetchUrlHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(
+                "fetch_url handler received unsupported payload".to_string(),
+            ));
+        };
+        let args: FetchUrlArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+        let page = args.page.unwrap_or(1);
+        if page == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "page numbers start at 1".to_string(),
+            ));
+        }
+        approve_network_access(&session, &turn, &call_id, &args.url).await?;
+
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::FetchUrlBegin(FetchUrlBeginEvent {
+                    call_id: call_id.clone(),
+                    url: args.url.clone(),
+                }),
+            )
+            .await;
+
+        // Redirects may lead to hosts the user has not approved yet.
+        let hop_call_id = call_id.clone();
+        let approve_hop = {
+            let (session, turn, call_id) = (&session, &turn, &hop_call_id);
+            move |url: Url| async move {
+                approve_network_access(session, turn, call_id, url.as_str())
+                    .await
+                    .map_err(|err| err.to_string())
+            }
+        };
+        let mut end = FetchUrlEndEvent {
+            call_id,
+            url: args.url.clone(),
+            final_url: None,
+            status: None,
+            title: None,
+            page: 0,
+            total_pages: 0,
+            cached: false,
+            error: None,
+        };
+        let result = match session
+            .services
+            .web_fetch
+            .fetch(&args.url, approve_hop)
+            .await
+        {
+            Ok((document, cached)) => {
+                end.final_url = Some(document.final_url.clone());
+                end.status = Some(document.status);
+                end.title = document.title.clone();
+                end.cached = cached;
+                end.total_pages = u32::try_from(document.pages.len()).unwrap_or(u32::MAX);
+                document.render_page(page)
+            }
+            Err(err) => Err(err),
+        };
+        match &result {
+            Ok(_) => end.page = u32::try_from(page).unwrap_or(u32::MAX),
+            Err(err) => end.error = Some(err.clone()),
+        }
+        session
+            .send_event(turn.as_ref(), EventMsg::FetchUrlEnd(end))
+            .await;
+
+        let content = result.map_err(FunctionCallError::RespondToModel)?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Requests run from the Codex process, outside the sandbox, so a sandbox
+/// without network access makes every fetch, and every redirect to another
+/// host, need the user's approval. Approving for the session covers later
+/// fetches from the same host.
+async fn approve_network_access(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    url: &str,
+) -> Result<(), FunctionCallError> {
+    if turn.sandbox_policy.has_full_network_access() {
+        return Ok(());
+    }
+    if turn.approval_policy == AskForApproval::Never {
+        return Err(FunctionCallError::RespondToModel(
+            "fetch_url needs network access, which the sandbox policy does not allow".to_string(),
+        ));
+    }
+    let host = Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| url.to_string());
+    let decision = with_cached_approval(
+        &session.services,
+        ("fetch_url".to_string(), host.clone()),
+        || async {
+            session
+                .request_command_approval(
+                    turn,
+                    call_id.to_string(),
+                    vec!["fetch_url".to_string(), url.to_string()],
+                    turn.cwd.clone(),
+                    Some(format!(
+                        "The sandbox does not allow network access. Fetch {host}?"
+                    )),
+                    None,
+                )
+                .await
+        },
+    )
+    .await;
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForSession => Ok(()),
+        ReviewDecision::Denied | ReviewDecision::Abort => Err(FunctionCallError::RespondToModel(
+            "the user declined the fetch".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,250 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::function_tool::FunctionCallError;
+use crate::lsp::Diagnostic;
+use crate::lsp::Range;
+use crate::lsp::char_column;
+use crate::lsp::display_path;
+use crate::lsp::file_uri;
+use crate::lsp::format_diagnostic;
+use crate::lsp::hover_text;
+use crate::lsp::parse_locations;
+use crate::lsp::utf16_column;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Handles `goto_definition`, `find_references`, `hover` and `diagnostics`.
+pub struct LspHandler;
+
+const MAX_LOCATIONS: usize = 100;
+
+#[derive(Deserialize)]
+struct PositionArgs {
+    path: String,
+    line: usize,
+    #[serde(default)]
+    column: Option<usize>,
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default)]
+    include_declaration: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticsArgs {
+    path: String,
+}
+
+#[async_trait]
+impl ToolHandler for LspHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{tool_name} handler received unsupported payload"
+                )));
+            }
+        };
+        let lsp = &session.services.lsp;
+
+        let content = if tool_name == "diagnostics" {
+            let args: DiagnosticsArgs = parse_arguments(&arguments)?;
+            let path = turn.resolve_path(Some(args.path));
+            let client = lsp
+                .client_for(&session, &turn, &call_id, &path)
+                .await
+                .map_err(respond)?;
+            match client
+                .diagnostics(&path, lsp.diagnostics_timeout())
+                .await
+                .map_err(respond)?
+            {
+                Some(diagnostics) => format_diagnostics(&turn.cwd, &path, &diagnostics),
+                None => format!(
+                    "The language server did not report diagnostics for {} in time; try again shortly.",
+                    display_path(&turn.cwd, &path)
+                ),
+            }
+        } else {
+            let args: PositionArgs = parse_arguments(&arguments)?;
+            let path = turn.resolve_path(Some(args.path.clone()));
+            let client = lsp
+                .client_for(&session, &turn, &call_id, &path)
+                .await
+                .map_err(respond)?;
+            let (text, _) = client.sync_document(&path).await.map_err(respond)?;
+            let (line, character) = resolve_position(&text, &args)?;
+            let mut params = json!({
+                "textDocument": { "uri": file_uri(&path).map_err(respond)? },
+                "position": { "line": line, "character": character },
+            });
+            let method = match tool_name.as_str() {
+                "goto_definition" => "textDocument/definition",
+                "find_references" => {
+                    params["context"] =
+                        json!({ "includeDeclaration": args.include_declaration.unwrap_or(true) });
+                    "textDocument/references"
+                }
+                "hover" => "textDocument/hover",
+                other => {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "unknown language server tool {other}"
+                    )));
+                }
+            };
+            let result = client.request(method, params).await.map_err(respond)?;
+            if method == "textDocument/hover" {
+                hover_text(&result)
+                    .unwrap_or_else(|| "No hover information at that position.".to_string())
+            } else {
+                let locations = parse_locations(&result);
+                let lines: Vec<String> = locations
+                    .iter()
+                    .take(MAX_LOCATIONS)
+                    .map(|location| format_location(&turn.cwd, &location.path, location.range))
+                    .collect();
+                match lines.len() {
+                    0 => "No results.".to_string(),
+                    shown if locations.len() > shown => format!(
+                        "{}\n... {} more not shown",
+                        lines.join("\n"),
+                        locations.len() - shown
+                    ),
+                    _ => lines.join("\n"),
+                }
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn parse_arguments<T: for<'de> Deserialize<'de>>(arguments: &str) -> Result<T, FunctionCallError> {
+    serde_json::from_str(arguments).map_err(|err| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err:?}"))
+    })
+}
+
+fn respond(err: String) -> FunctionCallError {
+    FunctionCallError::RespondToModel(err)
+}
+
+/// Turns the model's 1-based line plus column or symbol into a 0-based LSP
+/// position with a UTF-16 character offset.
+fn resolve_position(text: &str, args: &PositionArgs) -> Result<(u32, u32), FunctionCallError> {
+    let line_text = args
+        .line
+        .checked_sub(1)
+        .and_then(|index| text.lines().nth(index))
+        .ok_or_else(|| {
+            FunctionCallError::RespondToModel(format!(
+                "line {} is outside {} (lines are 1-based)",
+                args.line, args.path
+            ))
+        })?;
+    let column = match (&args.symbol, args.column) {
+        (Some(symbol), _) => {
+            let byte = line_text.find(symbol.as_str()).ok_or_else(|| {
+                FunctionCallError::RespondToModel(format!(
+                    "`{symbol}` does not appear on line {} of {}",
+                    args.line, args.path
+                ))
+            })?;
+            line_text[..byte].chars().count()
+        }
+        (None, Some(column)) => column.saturating_sub(1),
+        (None, None) => {
+            return Err(FunctionCallError::RespondToModel(
+                "provide either column or symbol".to_string(),
+            ));
+        }
+    };
+    Ok(((args.line - 1) as u32, utf16_column(line_text, column)))
+}
+
+fn format_location(cwd: &Path, path: &Path, range: Range) -> String {
+    let line_text = std::fs::read_to_string(path).ok().and_then(|text| {
+        text.lines()
+            .nth(range.start.line as usize)
+            .map(str::to_string)
+    });
+    let column = line_text
+        .as_deref()
+        .map_or(range.start.character as usize, |line| {
+            char_column(line, range.start.character)
+        });
+    let line_text = line_text.map(|line| line.trim().to_string());
+    let mut out = format!(
+        "{}:{}:{}",
+        display_path(cwd, path),
+        range.start.line + 1,
+        column + 1
+    );
+    if let Some(line_text) = line_text {
+        out.push_str(": ");
+        out.push_str(&line_text);
+    }
+    out
+}
+
+fn format_diagnostics(cwd: &Path, path: &Path, diagnostics: &[Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return format!("No diagnostics for {}.", display_path(cwd, path));
+    }
+    diagnostics
+        .iter()
+        .map(|diagnostic| format_diagnostic(cwd, path, diagnostic))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resolves_symbol_and_column_positions() {
+        let text = "fn main() {\n    let café = parse_config();\n}\n";
+        let mut args = PositionArgs {
+            path: "src/main.rs".to_string(),
+            line: 2,
+            column: None,
+            symbol: Some("parse_config".to_string()),
+            include_declaration: None,
+        };
+        assert_eq!(resolve_position(text, &args).unwrap(), (1, 15));
+
+        args.symbol = None;
+        args.column = Some(5);
+        assert_eq!(resolve_position(text, &args).unwrap(), (1, 4));
+
+        args.line = 9;
+        assert!(resolve_position(text, &args).is_err());
+    }
+}
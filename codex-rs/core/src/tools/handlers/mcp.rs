@@ -1,12 +1,19 @@
 use async_trait::async_trait;
 
+use crate::codex::Session;
+use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
 use crate::mcp_tool_call::handle_mcp_tool_call;
+use crate::output_store::spill_truncated_output;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use crate::truncate::truncate_text;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::TextContent;
 
 pub struct McpHandler;
 
@@ -53,6 +60,15 @@ impl ToolHandler for McpHandler {
 
         match response {
             codex_protocol::models::ResponseInputItem::McpToolCallOutput { result, .. } => {
+                let result = match result {
+                    Ok(result) => {
+                        Ok(
+                            spill_long_text(session.as_ref(), turn.as_ref(), &call_id, result)
+                                .await,
+                        )
+                    }
+                    Err(err) => Err(err),
+                };
                 Ok(ToolOutput::Mcp { result })
             }
             codex_protocol::models::ResponseInputItem::FunctionCallOutput { output, .. } => {
@@ -73,3 +89,46 @@ impl ToolHandler for McpHandler {
         }
     }
 }
+
+/// Replaces text content that would be truncated for the model with an
+/// excerpt and a pointer to the saved full text. Results with structured
+/// content are sent as JSON and left alone.
+async fn spill_long_text(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    mut result: CallToolResult,
+) -> CallToolResult {
+    if result
+        .structured_content
+        .as_ref()
+        .is_some_and(|content| !content.is_null())
+    {
+        return result;
+    }
+    let text = result
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::TextContent(TextContent { text, .. }) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let policy = turn.truncation_policy;
+    let Some(notice) = spill_truncated_output(session, turn, call_id, &text, policy).await else {
+        return result;
+    };
+    result
+        .content
+        .retain(|block| !matches!(block, ContentBlock::TextContent(_)));
+    result.content.insert(
+        0,
+        ContentBlock::TextContent(TextContent {
+            annotations: None,
+            text: format!("{}\n\n{notice}", truncate_text(&text, policy)),
+            r#type: "text".to_string(),
+        }),
+    );
+    result
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::MemoryScope;
+use codex_protocol::protocol::ReviewDecision;
+use serde::Deserialize;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::function_tool::FunctionCallError;
+use crate::memory::MemoryEdit;
+use crate::memory::MemoryStore;
+use crate::memory::entry_label;
+use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::apply_patch::run_apply_patch_action;
+use crate::tools::handlers::edit_file::create_file_patch;
+use crate::tools::handlers::edit_file::edit_file_patch;
+use crate::tools::handlers::edit_file::verify_patch;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::sandboxing::PLAN_MODE_REJECTION;
+
+/// Entries returned by one `recall`.
+const RECALL_MAX_ENTRIES: usize = 20;
+
+pub struct MemoryHandler;
+
+#[derive(Deserialize)]
+struct MemoryArgs {
+    action: MemoryAction,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    scope: Option<MemoryScope>,
+    #[serde(default)]
+    id: Option<u64>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum MemoryAction {
+    Remember,
+    Recall,
+    Forget,
+}
+
+fn parse_args(payload: &ToolPayload) -> Result<MemoryArgs, FunctionCallError> {
+    let ToolPayload::Function { arguments } = payload else {
+        return Err(FunctionCallError::RespondToModel(
+            "memory handler received unsupported payload".to_string(),
+        ));
+    };
+    serde_json::from_str(arguments).map_err(|e| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+    })
+}
+
+fn io_error(err: std::io::Error) -> FunctionCallError {
+    FunctionCallError::RespondToModel(format!("failed to access memory: {err}"))
+}
+
+#[async_trait]
+impl ToolHandler for MemoryHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        !parse_args(&invocation.payload).is_ok_and(|args| args.action == MemoryAction::Recall)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            payload,
+            call_id,
+            tool_name,
+        } = invocation;
+        let args = parse_args(&payload)?;
+        if turn.plan_mode && args.action != MemoryAction::Recall {
+            return Err(FunctionCallError::RespondToModel(
+                PLAN_MODE_REJECTION.to_string(),
+            ));
+        }
+        let store = MemoryStore::new(&turn.client.config().codex_home, &turn.cwd);
+        let scope = args.scope.unwrap_or(MemoryScope::Project);
+
+        let content = match args.action {
+            MemoryAction::Recall => {
+                let entries = store
+                    .recall(args.query.as_deref().unwrap_or_default())
+                    .map_err(io_error)?;
+                if entries.is_empty() {
+                    "No memories match.".to_string()
+                } else {
+                    entries
+                        .iter()
+                        .take(RECALL_MAX_ENTRIES)
+                        .map(|entry| format!("[{}] {}", entry_label(entry), entry.text))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            MemoryAction::Remember => {
+                let text = args
+                    .text
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|text| !text.is_empty())
+                    .ok_or_else(|| {
+                        FunctionCallError::RespondToModel(
+                            "`remember` requires a non-empty `text`".to_string(),
+                        )
+                    })?;
+                let edit = store.add(scope, text).map_err(io_error)?;
+                write_edit(&session, &turn, &tracker, call_id, &tool_name, &edit).await?;
+                format!("Remembered as {}.", entry_label(&edit.entry))
+            }
+            MemoryAction::Forget => {
+                let id = args.id.ok_or_else(|| {
+                    FunctionCallError::RespondToModel("`forget` requires an `id`".to_string())
+                })?;
+                let Some(edit) = store.forget(scope, id).map_err(io_error)? else {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "no memory entry {}#{id}",
+                        crate::memory::scope_name(scope)
+                    )));
+                };
+                write_edit(&session, &turn, &tracker, call_id, &tool_name, &edit).await?;
+                format!("Forgot {}.", entry_label(&edit.entry))
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Writes `edit`. Project memory lives in the workspace, so it goes through
+/// the `apply_patch` approval and sandbox path like any other file edit.
+async fn write_edit(
+    session: &Session,
+    turn: &TurnContext,
+    tracker: &SharedTurnDiffTracker,
+    call_id: String,
+    tool_name: &str,
+    edit: &MemoryEdit,
+) -> Result<(), FunctionCallError> {
+    match edit.entry.scope {
+        MemoryScope::Project => {
+            let patch = match &edit.before {
+                Some(before) => edit_file_patch(&edit.path, before, &edit.contents)?,
+                None => create_file_patch(&edit.path, &edit.contents)?,
+            };
+            let action = verify_patch(&edit.path, &patch, &turn.cwd)?;
+            run_apply_patch_action(session, turn, tracker, &call_id, tool_name, action)
+                .await
+                .map(|_| ())
+        }
+        MemoryScope::User => write_with_approval(session, turn, call_id, edit).await,
+    }
+}
+
+/// Writes a user memory edit, first asking the user to approve the file
+/// change unless the project is trusted.
+async fn write_with_approval(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: String,
+    edit: &MemoryEdit,
+) -> Result<(), FunctionCallError> {
+    if !turn.client.config().active_project.is_trusted() {
+        if turn.approval_policy == AskForApproval::Never {
+            return Err(FunctionCallError::RespondToModel(
+                "memory writes need approval in untrusted projects, and approvals are disabled"
+                    .to_string(),
+            ));
+        }
+        let changes = HashMap::from([(edit.path.clone(), edit.change.clone())]);
+        let decision = session
+            .request_patch_approval(
+                turn,
+                call_id,
+                changes,
+                Some("Update the memory kept across sessions?".to_string()),
+                None,
+            )
+            .await
+            .await
+            .unwrap_or_default();
+        match decision {
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+            | ReviewDecision::ApprovedForSession => {}
+            ReviewDecision::Denied | ReviewDecision::Abort => {
+                return Err(FunctionCallError::RespondToModel(
+                    "the user declined the memory update".to_string(),
+                ));
+            }
+        }
+    }
+    edit.apply().map_err(io_error)
+}
@@ -1,11 +1,19 @@
 pub mod apply_patch;
+mod custom_tool;
+pub mod edit_file;
+mod fetch_url;
 mod grep_files;
 mod list_dir;
+mod lsp;
 mod mcp;
 mod mcp_resource;
+mod memory;
 mod plan;
 mod read_file;
+mod read_output;
+mod search_code;
 mod shell;
+mod spawn_agent;
 mod test_sync;
 mod unified_exec;
 mod view_image;
@@ -13,14 +21,22 @@ mod view_image;
 pub use plan::PLAN_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use custom_tool::CustomToolHandler;
+pub use edit_file::EditFileHandler;
+pub use fetch_url::FetchUrlHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
+pub use lsp::LspHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
+pub use memory::MemoryHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use read_output::ReadOutputHandler;
+pub use search_code::SearchCodeHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub use spawn_agent::SpawnAgentHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
@@ -104,9 +104,18 @@ pub(crate) async fn handle_update_plan(
     _call_id: String,
 ) -> Result<String, FunctionCallError> {
     let args = parse_update_plan_arguments(&arguments)?;
+    if turn_context.plan_mode {
+        session.set_proposed_plan(args.clone()).await;
+    }
     session
         .send_event(turn_context, EventMsg::PlanUpdate(args))
         .await;
+    if turn_context.plan_mode {
+        return Ok(
+            "Plan recorded. End your turn with a short summary; the user will review the plan before you make changes."
+                .to_string(),
+        );
+    }
     Ok("Plan updated".to_string())
 }
 
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Pages through or greps tool output that was truncated for the model and
+/// saved by the session's output store.
+pub struct ReadOutputHandler;
+
+const MAX_LINE_LENGTH: usize = 500;
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 2000;
+
+#[derive(Deserialize)]
+struct ReadOutputArgs {
+    /// Output id from the truncation notice, e.g. `out-7f3a-3`.
+    id: String,
+    /// 1-indexed line number to start from; defaults to 1.
+    #[serde(default = "default_offset")]
+    offset: usize,
+    /// Maximum number of lines (or matches) to return.
+    #[serde(default = "default_limit")]
+    limit: usize,
+    /// Optional regular expression; only matching lines are returned.
+    #[serde(default)]
+    pattern: Option<String>,
+}
+
+fn default_offset() -> usize {
+    1
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[async_trait]
+impl ToolHandler for ReadOutputHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "read_output handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: ReadOutputArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+        if args.offset == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "offset must be a 1-indexed line number".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let pattern = args
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|err| FunctionCallError::RespondToModel(format!("invalid pattern: {err}")))?;
+
+        let content = session
+            .services
+            .output_store
+            .read(&args.id)
+            .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+
+        Ok(ToolOutput::Function {
+            content: render_page(
+                &args.id,
+                &content,
+                args.offset,
+                args.limit.min(MAX_LIMIT),
+                pattern.as_ref(),
+            ),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Renders up to `limit` lines starting at `offset` as `L<n>: <text>`,
+/// followed by a hint for the next call when more lines remain.
+fn render_page(
+    id: &str,
+    content: &str,
+    offset: usize,
+    limit: usize,
+    pattern: Option<&Regex>,
+) -> String {
+    let total_lines = content.lines().count();
+    let mut selected = content
+        .lines()
+        .enumerate()
+        .skip(offset - 1)
+        .filter(|(_, line)| pattern.is_none_or(|pattern| pattern.is_match(line)));
+    let page: Vec<(usize, &str)> = selected.by_ref().take(limit).collect();
+    let more = selected.next();
+
+    let mut out = match (pattern, page.last()) {
+        (Some(pattern), _) if page.is_empty() => {
+            return format!(
+                "No lines matching `{pattern}` in {id} from line {offset} ({total_lines} lines)."
+            );
+        }
+        (_, None) => {
+            return format!("{id} has {total_lines} lines; nothing at or after line {offset}.");
+        }
+        (Some(pattern), Some(_)) => format!(
+            "{id}: {} lines matching `{pattern}` ({total_lines} lines total)\n",
+            page.len()
+        ),
+        (None, Some((last, _))) => format!("{id}: lines {offset}-{} of {total_lines}\n", last + 1),
+    };
+    for (index, line) in &page {
+        let line = if line.len() > MAX_LINE_LENGTH {
+            take_bytes_at_char_boundary(line, MAX_LINE_LENGTH)
+        } else {
+            line
+        };
+        out.push_str(&format!("L{}: {line}\n", index + 1));
+    }
+    if let (Some((last, _)), Some(_)) = (page.last(), more) {
+        out.push_str(&format!(
+            "More lines remain; call read_output again with offset {}.",
+            last + 2
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const OUTPUT: &str = "running 3 tests\ntest a ... ok\ntest b ... FAILED\ntest c ... ok\n";
+
+    #[test]
+    fn pages_through_lines() {
+        assert_eq!(
+            render_page("out-1", OUTPUT, 2, 2, None),
+            "out-1: lines 2-3 of 4\nL2: test a ... ok\nL3: test b ... FAILED\nMore lines remain; call read_output again with offset 4."
+        );
+        assert_eq!(
+            render_page("out-1", OUTPUT, 9, 2, None),
+            "out-1 has 4 lines; nothing at or after line 9."
+        );
+    }
+
+    #[test]
+    fn greps_matching_lines() {
+        let pattern = Regex::new("FAILED|panicked").unwrap();
+        assert_eq!(
+            render_page("out-1", OUTPUT, 1, 10, Some(&pattern)),
+            "out-1: 1 lines matching `FAILED|panicked` (4 lines total)\nL3: test b ... FAILED"
+        );
+    }
+}
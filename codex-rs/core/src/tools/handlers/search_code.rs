@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::code_index::CodeSearch;
+use crate::code_index::RefreshStats;
+use crate::code_index::SearchHit;
+use crate::function_tool::FunctionCallError;
+use crate::git_info::get_git_repo_root;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SearchCodeHandler;
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct SearchCodeArgs {
+    query: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for SearchCodeHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "search_code handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SearchCodeArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let query = args.query.trim().to_string();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+
+        let root = get_git_repo_root(&turn.cwd).unwrap_or_else(|| turn.cwd.clone());
+        let prefix = match args.path {
+            Some(path) => {
+                let path = turn.resolve_path(Some(path));
+                let rel = path.strip_prefix(&root).map_err(|_| {
+                    FunctionCallError::RespondToModel(format!(
+                        "{} is outside the indexed project root {}",
+                        path.display(),
+                        root.display()
+                    ))
+                })?;
+                Some(rel.to_string_lossy().replace('\\', "/"))
+            }
+            None => None,
+        };
+
+        let search = session
+            .services
+            .code_index
+            .search(
+                root.clone(),
+                query.clone(),
+                args.limit.min(MAX_LIMIT),
+                prefix,
+            )
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("code search failed: {err}"))
+            })?;
+        let (hits, stats) = match search {
+            CodeSearch::Ready { hits, stats } => (hits, stats),
+            CodeSearch::Building => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "the code index for {} is still being built in the background; try again shortly or search with `rg` meanwhile",
+                    root.display()
+                )));
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content: format_hits(&query, &hits, stats),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn format_hits(query: &str, hits: &[SearchHit], stats: RefreshStats) -> String {
+    let mut sections = vec![format!(
+        "{} results for `{query}` ({} files indexed, {} re-indexed)",
+        hits.len(),
+        stats.indexed_files,
+        stats.updated_files
+    )];
+    for hit in hits {
+        let mut header = format!(
+            "{}:{}-{} (score {:.2})",
+            hit.path, hit.start_line, hit.end_line, hit.score
+        );
+        if !hit.symbols.is_empty() {
+            header.push_str(" · ");
+            header.push_str(&hit.symbols.join(", "));
+        }
+        sections.push(format!("{header}\n{}", hit.snippet));
+    }
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn formats_hits_with_symbols_and_snippets() {
+        let hits = vec![SearchHit {
+            path: "src/config.rs".to_string(),
+            start_line: 3,
+            end_line: 9,
+            score: 4.256,
+            symbols: vec!["fn parse_config".to_string()],
+            snippet: "L4: pub fn parse_config(path: &Path) -> Config {".to_string(),
+        }];
+        let stats = RefreshStats {
+            indexed_files: 120,
+            updated_files: 2,
+            removed_files: 0,
+        };
+        assert_eq!(
+            format_hits("parse config", &hits, stats),
+            "1 results for `parse config` (120 files indexed, 2 re-indexed)\n\nsrc/config.rs:3-9 (score 4.26) · fn parse_config\nL4: pub fn parse_config(path: &Path) -> Config {"
+        );
+    }
+}
@@ -0,0 +1,472 @@
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::SubAgentActivityEvent;
+use codex_protocol::protocol::SubAgentBeginEvent;
+use codex_protocol::protocol::SubAgentEndEvent;
+use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::protocol::SubAgentStatus;
+use codex_protocol::protocol::TokenCountEvent;
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+use crate::budget::SessionBudget;
+use crate::codex_delegate::run_codex_conversation_one_shot;
+use crate::config::Config;
+use crate::config::Constrained;
+use crate::config::types::BudgetConfig;
+use crate::features::Feature;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+const DEFAULT_MAX_CONCURRENT_AGENTS: usize = 3;
+
+const SUB_AGENT_INSTRUCTIONS: &str = "You are a sub-agent working on one part of a larger task for another agent. \
+Only your final message is returned to that agent, so end with a concise, self-contained report of what you found or changed.";
+
+/// Runs a child conversation for each `spawn_agent` call. Parallel calls in
+/// one response share the handler, so the semaphore caps how many children
+/// run at once. A call that opts out of concurrency takes every slot.
+#[derive(Default)]
+pub struct SpawnAgentHandler {
+    slots: OnceLock<(Arc<Semaphore>, u32)>,
+}
+
+#[derive(Deserialize)]
+struct SpawnAgentArgs {
+    prompt: String,
+    #[serde(default)]
+    role: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    sandbox: SpawnAgentSandbox,
+    /// Whether this child may run alongside other sub-agents. Defaults to
+    /// `true`.
+    #[serde(default)]
+    concurrent: Option<bool>,
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum SpawnAgentSandbox {
+    #[default]
+    ReadOnly,
+    WorkspaceWrite,
+}
+
+fn parse_args(payload: &ToolPayload) -> Result<SpawnAgentArgs, FunctionCallError> {
+    let ToolPayload::Function { arguments } = payload else {
+        return Err(FunctionCallError::RespondToModel(
+            "spawn_agent handler received unsupported payload".to_string(),
+        ));
+    };
+    let args: SpawnAgentArgs = serde_json::from_str(arguments).map_err(|e| {
+        FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+    })?;
+    if args.prompt.trim().is_empty() {
+        return Err(FunctionCallError::RespondToModel(
+            "spawn_agent requires a non-empty prompt".to_string(),
+        ));
+    }
+    Ok(args)
+}
+
+#[async_trait]
+impl ToolHandler for SpawnAgentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        parse_args(&invocation.payload)
+            .is_ok_and(|args| args.sandbox == SpawnAgentSandbox::WorkspaceWrite)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            call_id,
+            ..
+        } = invocation;
+        let args = parse_args(&payload)?;
+
+        let parent_config = turn.client.config();
+        let read_only = args.sandbox == SpawnAgentSandbox::ReadOnly;
+        let sandbox_policy = if read_only {
+            SandboxPolicy::new_read_only_policy()
+        } else if matches!(turn.sandbox_policy, SandboxPolicy::ReadOnly) {
+            return Err(FunctionCallError::RespondToModel(
+                "the current sandbox is read-only, so sub-agents cannot be given write access"
+                    .to_string(),
+            ));
+        } else {
+            turn.sandbox_policy.clone()
+        };
+        let model = args
+            .model
+            .clone()
+            .or_else(|| parent_config.agents.model.clone())
+            .unwrap_or_else(|| turn.client.get_model());
+        let source = SubAgentSource::SpawnAgent {
+            role: args.role.clone(),
+        };
+
+        let mut config = (*parent_config).clone();
+        config.model = Some(model.clone());
+        config.cwd = turn.cwd.clone();
+        config.approval_policy = Constrained::allow_any(turn.approval_policy);
+        config.sandbox_policy = Constrained::allow_any(sandbox_policy);
+        config.developer_instructions = Some(sub_agent_instructions(
+            args.role.as_deref(),
+            turn.developer_instructions.as_deref(),
+        ));
+        // Children never spawn grandchildren.
+        config.features.disable(Feature::SpawnAgent);
+        // Usage is charged to the parent's budget below instead of a fresh
+        // copy of the limits.
+        config.budget = BudgetConfig::default();
+
+        let (slots, permits) = self.slots(&parent_config);
+        let permits = if args.concurrent.unwrap_or(true) {
+            1
+        } else {
+            permits
+        };
+        let _permit = slots.acquire_many(permits).await.map_err(|_| {
+            FunctionCallError::RespondToModel("sub-agent slots are unavailable".to_string())
+        })?;
+
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::SubAgentBegin(SubAgentBeginEvent {
+                    call_id: call_id.clone(),
+                    source: source.clone(),
+                    model: model.clone(),
+                    prompt: args.prompt.clone(),
+                    read_only,
+                }),
+            )
+            .await;
+
+        // Dropping the tool future (e.g. when the turn is interrupted) stops
+        // the child as well.
+        let cancel = CancellationToken::new();
+        let _cancel_on_drop = cancel.clone().drop_guard();
+        let (status, message) =
+            match run_codex_conversation_one_shot(
+                config,
+                Arc::clone(&session.services.auth_manager),
+                Arc::clone(&session.services.models_manager),
+                vec![UserInput::Text {
+                    text: args.prompt.clone(),
+                }],
+                Arc::clone(&session),
+                Arc::clone(&turn),
+                cancel,
+                None,
+                source.clone(),
+            )
+            .await
+            {
+                Ok(child) => {
+                    let mut outcome = None;
+                    let mut last_error = None;
+                    let mut charged = ChargedUsage::default();
+                    while let Ok(Event { msg, .. }) = child.next_event().await {
+                        match msg {
+                            EventMsg::TokenCount(count) => {
+                                if charged.charge(&session.services.budget, &count)
+                                    && let Some(limit) = session.enforce_budget(turn.as_ref()).await
+                                {
+                                    outcome = Some((
+                                        SubAgentStatus::Failed,
+                                        Some(format!(
+                                            "sub-agent stopped: {}",
+                                            session.services.budget.exceeded_message(limit)
+                                        )),
+                                    ));
+                                    break;
+                                }
+                            }
+                            EventMsg::TaskComplete(complete) => {
+                                outcome =
+                                    Some((SubAgentStatus::Completed, complete.last_agent_message));
+                                break;
+                            }
+                            EventMsg::TurnAborted(_) => {
+                                outcome = Some((SubAgentStatus::Interrupted, None));
+                                break;
+                            }
+                            msg => {
+                                if let EventMsg::Error(error) = &msg {
+                                    last_error = Some(error.message.clone());
+                                }
+                                if forwards_to_parent(&msg) {
+                                    session
+                                        .send_event(
+                                            turn.as_ref(),
+                                            EventMsg::SubAgentActivity(SubAgentActivityEvent {
+                                                call_id: call_id.clone(),
+                                                source: source.clone(),
+                                                msg: Box::new(msg),
+                                            }),
+                                        )
+                                        .await;
+                                }
+                            }
+                        }
+                    }
+                    match outcome {
+                        Some((SubAgentStatus::Completed, message))
+                            if last_error.is_some() && message.is_none() =>
+                        {
+                            (SubAgentStatus::Failed, last_error)
+                        }
+                        Some(outcome) => outcome,
+                        None => (
+                            SubAgentStatus::Failed,
+                            Some(last_error.unwrap_or_else(|| {
+                                "sub-agent stopped without finishing".to_string()
+                            })),
+                        ),
+                    }
+                }
+                Err(err) => (
+                    SubAgentStatus::Failed,
+                    Some(format!("failed to start sub-agent: {err}")),
+                ),
+            };
+
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::SubAgentEnd(SubAgentEndEvent {
+                    call_id,
+                    source,
+                    model,
+                    prompt: args.prompt,
+                    read_only,
+                    status,
+                    message: message.clone(),
+                }),
+            )
+            .await;
+
+        match status {
+            SubAgentStatus::Completed => Ok(ToolOutput::Function {
+                content: message
+                    .unwrap_or_else(|| "sub-agent finished without a final message".to_string()),
+                content_items: None,
+                success: Some(true),
+            }),
+            SubAgentStatus::Failed => Err(FunctionCallError::RespondToModel(
+                message.unwrap_or_else(|| "sub-agent failed".to_string()),
+            )),
+            SubAgentStatus::Interrupted => Err(FunctionCallError::RespondToModel(
+                "sub-agent was interrupted before finishing".to_string(),
+            )),
+        }
+    }
+}
+
+impl SpawnAgentHandler {
+    fn slots(&self, config: &Config) -> (Arc<Semaphore>, u32) {
+        let (slots, permits) = self.slots.get_or_init(|| {
+            let permits = config
+                .agents
+                .max_concurrent
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_AGENTS)
+                .max(1);
+            let permits = u32::try_from(permits).unwrap_or(u32::MAX);
+            (Arc::new(Semaphore::new(permits as usize)), permits)
+        });
+        (Arc::clone(slots), *permits)
+    }
+}
+
+/// Child usage already charged to the parent's budget. Token counts carry
+/// running totals, so each event charges only the difference.
+#[derive(Default)]
+struct ChargedUsage {
+    tokens: TokenUsage,
+    cost_usd: f64,
+}
+
+impl ChargedUsage {
+    /// Returns whether the event added anything to the parent's budget.
+    fn charge(&mut self, budget: &SessionBudget, count: &TokenCountEvent) -> bool {
+        let mut charged = false;
+        if let Some(info) = &count.info {
+            let total = &info.total_token_usage;
+            let delta = TokenUsage {
+                input_tokens: (total.input_tokens - self.tokens.input_tokens).max(0),
+                output_tokens: (total.output_tokens - self.tokens.output_tokens).max(0),
+                ..Default::default()
+            };
+            if delta.input_tokens > 0 || delta.output_tokens > 0 {
+                budget.record_usage(&delta);
+                self.tokens = total.clone();
+                charged = true;
+            }
+        }
+        if let Some(cost) = &count.cost {
+            let delta = cost.session_usd - self.cost_usd;
+            if delta > 0.0 {
+                budget.add_sub_agent_cost_usd(delta);
+                self.cost_usd = cost.session_usd;
+                charged = true;
+            }
+        }
+        charged
+    }
+}
+
+fn sub_agent_instructions(role: Option<&str>, parent_instructions: Option<&str>) -> String {
+    let mut instructions = SUB_AGENT_INSTRUCTIONS.to_string();
+    if let Some(role) = role.map(str::trim).filter(|role| !role.is_empty()) {
+        instructions.push_str(&format!("\nYour role: {role}."));
+    }
+    if let Some(parent) = parent_instructions {
+        instructions.push_str("\n\n");
+        instructions.push_str(parent);
+    }
+    instructions
+}
+
+/// Child events worth showing under the parent's tool call. Streaming deltas
+/// and bookkeeping events stay with the child.
+fn forwards_to_parent(msg: &EventMsg) -> bool {
+    matches!(
+        msg,
+        EventMsg::ExecCommandBegin(_)
+            | EventMsg::ExecCommandEnd(_)
+            | EventMsg::PatchApplyBegin(_)
+            | EventMsg::PatchApplyEnd(_)
+            | EventMsg::McpToolCallBegin(_)
+            | EventMsg::McpToolCallEnd(_)
+            | EventMsg::WebSearchEnd(_)
+            | EventMsg::FetchUrlEnd(_)
+            | EventMsg::ViewImageToolCall(_)
+            | EventMsg::AgentMessage(_)
+            | EventMsg::Warning(_)
+            | EventMsg::Error(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::BudgetLimit;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_defaults_and_sandbox_modes() {
+        let args = parse_args(&ToolPayload::Function {
+            arguments: r#"{"prompt":"find the config loader"}"#.to_string(),
+        })
+        .unwrap();
+        assert!(args.sandbox == SpawnAgentSandbox::ReadOnly);
+        assert_eq!(args.role, None);
+
+        let args = parse_args(&ToolPayload::Function {
+            arguments: r#"{"prompt":"fix it","role":"editor","model":"gpt-5-mini","sandbox":"workspace-write"}"#
+                .to_string(),
+        })
+        .unwrap();
+        assert!(args.sandbox == SpawnAgentSandbox::WorkspaceWrite);
+        assert_eq!(args.model.as_deref(), Some("gpt-5-mini"));
+        assert_eq!(args.concurrent, None);
+
+        let args = parse_args(&ToolPayload::Function {
+            arguments: r#"{"prompt":"migrate the schema","concurrent":false}"#.to_string(),
+        })
+        .unwrap();
+        assert_eq!(args.concurrent, Some(false));
+
+        assert!(
+            parse_args(&ToolPayload::Function {
+                arguments: r#"{"prompt":"  "}"#.to_string(),
+            })
+            .is_err()
+        );
+        assert!(
+            parse_args(&ToolPayload::Function {
+                arguments: r#"{"prompt":"x","sandbox":"danger-full-access"}"#.to_string(),
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn charges_only_new_child_usage_to_the_parent_budget() {
+        use codex_protocol::protocol::TokenCost;
+        use codex_protocol::protocol::TokenUsageInfo;
+
+        let count = |input_tokens, output_tokens, session_usd| TokenCountEvent {
+            info: Some(TokenUsageInfo {
+                total_token_usage: TokenUsage {
+                    input_tokens,
+                    output_tokens,
+                    ..Default::default()
+                },
+                last_token_usage: TokenUsage::default(),
+                model_context_window: None,
+            }),
+            rate_limits: None,
+            cost: Some(TokenCost {
+                session_usd,
+                turn_usd: session_usd,
+            }),
+        };
+        let budget = SessionBudget::new(BudgetConfig {
+            max_input_tokens: Some(1_000),
+            max_cost_usd: Some(1.0),
+            ..Default::default()
+        });
+        let mut charged = ChargedUsage::default();
+
+        assert!(charged.charge(&budget, &count(400, 50, 0.25)));
+        // A repeated total, e.g. after a rate limit update, charges nothing.
+        assert!(!charged.charge(&budget, &count(400, 50, 0.25)));
+        assert!(charged.charge(&budget, &count(700, 80, 0.5)));
+
+        let used = |limit| {
+            budget
+                .status()
+                .entries
+                .into_iter()
+                .find(|entry| entry.limit == limit)
+                .map(|entry| entry.used)
+        };
+        assert_eq!(used(BudgetLimit::InputTokens), Some(700.0));
+        assert_eq!(used(BudgetLimit::Cost), Some(0.5));
+    }
+
+    #[test]
+    fn instructions_include_role_and_parent_instructions() {
+        let instructions = sub_agent_instructions(Some("explorer"), Some("Use pnpm."));
+        assert!(instructions.starts_with(SUB_AGENT_INSTRUCTIONS));
+        assert!(instructions.contains("\nYour role: explorer."));
+        assert!(instructions.ends_with("\n\nUse pnpm."));
+
+        assert_eq!(
+            sub_agent_instructions(Some(" "), None),
+            SUB_AGENT_INSTRUCTIONS
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
+use crate::output_store::spill_output;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandSource;
 use crate::protocol::TerminalInteractionEvent;
@@ -240,7 +241,13 @@ impl ToolHandler for UnifiedExecHandler {
             }
         };
 
-        let content = format_response(&response);
+        let raw_output = String::from_utf8_lossy(&response.raw_output);
+        let spill_notice = if response.output != raw_output {
+            spill_output(session.as_ref(), turn.as_ref(), &call_id, &raw_output).await
+        } else {
+            None
+        };
+        let content = format_response(&response, spill_notice.as_deref());
 
         Ok(ToolOutput::Function {
             content,
@@ -262,7 +269,7 @@ fn get_command(args: &ExecCommandArgs, session_shell: Arc<Shell>) -> Vec<String>
     shell.derive_exec_args(&args.cmd, args.login)
 }
 
-fn format_response(response: &UnifiedExecResponse) -> String {
+fn format_response(response: &UnifiedExecResponse, spill_notice: Option<&str>) -> String {
     let mut sections = Vec::new();
 
     if !response.chunk_id.is_empty() {
@@ -288,6 +295,10 @@ fn format_response(response: &UnifiedExecResponse) -> String {
     sections.push("Output:".to_string());
     sections.push(response.output.clone());
 
+    if let Some(notice) = spill_notice {
+        sections.push(notice.to_string());
+    }
+
     sections.join("\n")
 }
 
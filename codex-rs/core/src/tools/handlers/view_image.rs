@@ -11,12 +11,48 @@ use crate::tools::context::ToolPayload;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use codex_protocol::user_input::UserInput;
+use codex_utils_image::ImageDetail;
+use codex_utils_image::RenderOptions;
+use codex_utils_image::ResizeOptions;
+use codex_utils_image::error::ImageProcessingError;
+use codex_utils_image::render_for_model;
 
 pub struct ViewImageHandler;
 
 #[derive(Deserialize)]
 struct ViewImageArgs {
     path: String,
+    #[serde(default)]
+    detail: ViewImageDetail,
+    #[serde(default)]
+    max_dimension: Option<u32>,
+    #[serde(default)]
+    pages: Vec<u32>,
+    #[serde(default = "default_tile")]
+    tile: bool,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum ViewImageDetail {
+    Low,
+    #[default]
+    Auto,
+    High,
+}
+
+impl From<ViewImageDetail> for ImageDetail {
+    fn from(detail: ViewImageDetail) -> Self {
+        match detail {
+            ViewImageDetail::Low => ImageDetail::Low,
+            ViewImageDetail::Auto => ImageDetail::Auto,
+            ViewImageDetail::High => ImageDetail::High,
+        }
+    }
+}
+
+fn default_tile() -> bool {
+    true
 }
 
 #[async_trait]
@@ -64,14 +100,51 @@ impl ToolHandler for ViewImageHandler {
         }
         let event_path = abs_path.clone();
 
-        session
-            .inject_input(vec![UserInput::LocalImage { path: abs_path }])
-            .await
-            .map_err(|_| {
-                FunctionCallError::RespondToModel(
-                    "unable to attach image (no active task)".to_string(),
-                )
-            })?;
+        let options = RenderOptions {
+            resize: ResizeOptions {
+                detail: args.detail.into(),
+                max_dimension: args.max_dimension,
+            },
+            pages: args.pages,
+            tile: args.tile,
+        };
+        let (input, content) = match render_for_model(&abs_path, &options) {
+            Ok(images) => {
+                let labels: Vec<String> = images
+                    .iter()
+                    .filter_map(|image| image.label.clone())
+                    .collect();
+                let content = if labels.is_empty() {
+                    "attached local image path".to_string()
+                } else {
+                    format!("attached {}", labels.join(", "))
+                };
+                let input = images
+                    .into_iter()
+                    .map(|image| UserInput::Image {
+                        image_url: image.image.into_data_url(),
+                    })
+                    .collect();
+                (input, content)
+            }
+            Err(
+                err @ (ImageProcessingError::Svg { .. }
+                | ImageProcessingError::Pdf { .. }
+                | ImageProcessingError::PageOutOfRange { .. }),
+            ) => {
+                return Err(FunctionCallError::RespondToModel(err.to_string()));
+            }
+            // Let the regular local image conversion describe files that are
+            // not images at all.
+            Err(_) => (
+                vec![UserInput::LocalImage { path: abs_path }],
+                "attached local image path".to_string(),
+            ),
+        };
+
+        session.inject_input(input).await.map_err(|_| {
+            FunctionCallError::RespondToModel("unable to attach image (no active task)".to_string())
+        })?;
 
         session
             .send_event(
@@ -84,7 +157,7 @@ impl ToolHandler for ViewImageHandler {
             .await;
 
         Ok(ToolOutput::Function {
-            content: "attached local image path".to_string(),
+            content,
             content_items: None,
             success: Some(true),
         })
@@ -27,6 +27,7 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    spill_notice: Option<&str>,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
@@ -49,7 +50,11 @@ pub fn format_exec_output_for_model_structured(
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    let mut formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    if let Some(notice) = spill_notice {
+        formatted_output.push_str("\n\n");
+        formatted_output.push_str(notice);
+    }
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -63,9 +68,12 @@ pub fn format_exec_output_for_model_structured(
     serde_json::to_string(&payload).expect("serialize ExecOutput")
 }
 
+/// Format the combined exec output for sending back to the model as plain text.
+/// `spill_notice` points the model at the saved full output when it was cut.
 pub fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    spill_notice: Option<&str>,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
@@ -86,6 +94,9 @@ pub fn format_exec_output_for_model_freeform(
 
     sections.push("Output:".to_string());
     sections.push(formatted_output);
+    if let Some(notice) = spill_notice {
+        sections.push(notice.to_string());
+    }
 
     sections.join("\n")
 }
@@ -4,11 +4,17 @@ Module: orchestrator
 Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
 retry without sandbox on denial (no re‑approval thanks to caching).
+
+In plan mode, writes are rejected before approval and nothing is retried
+without the sandbox.
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::hooks::HookDecision;
+use crate::hooks::HookEvent;
 use crate::sandboxing::SandboxManager;
+use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
@@ -17,8 +23,10 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
+use crate::tools::sandboxing::plan_mode_requirement;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
+use serde_json::Value;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -54,6 +62,11 @@ impl ToolOrchestrator {
         let requirement = tool.exec_approval_requirement(req).unwrap_or_else(|| {
             default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
         });
+        let requirement = plan_mode_requirement(
+            turn_ctx.plan_mode,
+            tool.allowed_in_plan_mode(req),
+            requirement,
+        );
         match requirement {
             ExecApprovalRequirement::Skip { .. } => {
                 otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
@@ -62,6 +75,8 @@ impl ToolOrchestrator {
                 return Err(ToolError::Rejected(reason));
             }
             ExecApprovalRequirement::NeedsApproval { reason, .. } => {
+                run_approval_hooks(tool_ctx, approval_input(&*tool, req), reason.as_deref())
+                    .await?;
                 let approval_ctx = ApprovalCtx {
                     session: tool_ctx.session,
                     turn: turn_ctx,
@@ -85,7 +100,12 @@ impl ToolOrchestrator {
         }
 
         // 2) First attempt under the selected sandbox.
-        let initial_sandbox = match tool.sandbox_mode_for_first_attempt(req) {
+        let sandbox_override = if turn_ctx.plan_mode {
+            SandboxOverride::NoOverride
+        } else {
+            tool.sandbox_mode_for_first_attempt(req)
+        };
+        let initial_sandbox = match sandbox_override {
             SandboxOverride::BypassSandboxFirstAttempt => crate::exec::SandboxType::None,
             SandboxOverride::NoOverride => self
                 .sandbox
@@ -108,7 +128,7 @@ impl ToolOrchestrator {
                 Ok(out)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
-                if !tool.escalate_on_failure() {
+                if !tool.escalate_on_failure() || turn_ctx.plan_mode {
                     return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
                         output,
                     })));
@@ -122,8 +142,10 @@ impl ToolOrchestrator {
                 }
 
                 // Ask for approval before retrying without sandbox.
+                let reason_msg = build_denial_reason_from_output(output.as_ref());
                 if !tool.should_bypass_approval(approval_policy, already_approved) {
-                    let reason_msg = build_denial_reason_from_output(output.as_ref());
+                    run_approval_hooks(tool_ctx, approval_input(&*tool, req), Some(&reason_msg))
+                        .await?;
                     let approval_ctx = ApprovalCtx {
                         session: tool_ctx.session,
                         turn: turn_ctx,
@@ -160,6 +182,33 @@ impl ToolOrchestrator {
     }
 }
 
+/// Shows `approval_request` hooks what is about to be approved so they can
+/// reject it before the user is asked. Hooks cannot approve on the user's
+/// behalf; an `allow` answer still prompts.
+async fn run_approval_hooks(
+    tool_ctx: &ToolCtx<'_>,
+    tool_input: Value,
+    reason: Option<&str>,
+) -> Result<(), ToolError> {
+    let mut event = HookEvent::ApprovalRequest {
+        tool_name: tool_ctx.tool_name.clone(),
+        call_id: tool_ctx.call_id.clone(),
+        tool_input,
+        reason: reason.map(str::to_string),
+    };
+    match tool_ctx.session.run_hooks(tool_ctx.turn, &mut event).await {
+        HookDecision::Allow | HookDecision::Continue => Ok(()),
+        HookDecision::Deny { reason } => {
+            Err(ToolError::Rejected(format!("rejected by hook: {reason}")))
+        }
+    }
+}
+
+/// The approval key holds the command, patch or arguments being approved.
+fn approval_input<Rq, T: Approvable<Rq>>(tool: &T, req: &Rq) -> Value {
+    serde_json::to_value(tool.approval_key(req)).unwrap_or(Value::Null)
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
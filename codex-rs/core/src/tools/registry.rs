@@ -1,15 +1,20 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookDecision;
+use crate::hooks::HookEvent;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
 use async_trait::async_trait;
 use codex_protocol::models::ResponseInputItem;
+use codex_protocol::models::ShellToolCallParams;
 use codex_utils_readiness::Readiness;
+use serde_json::Value;
 use tracing::warn;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -60,13 +65,20 @@ impl ToolRegistry {
 
     pub async fn dispatch(
         &self,
-        invocation: ToolInvocation,
+        mut invocation: ToolInvocation,
     ) -> Result<ResponseInputItem, FunctionCallError> {
         let tool_name = invocation.tool_name.clone();
         let call_id_owned = invocation.call_id.clone();
         let otel = invocation.turn.client.get_otel_manager();
+        let hooks_enabled = !invocation.session.hooks().is_empty();
+        if hooks_enabled && self.handler(tool_name.as_ref()).is_some() {
+            run_pre_tool_use_hooks(&mut invocation).await?;
+        }
+        let session = Arc::clone(&invocation.session);
+        let turn = Arc::clone(&invocation.turn);
         let payload_for_response = invocation.payload.clone();
         let log_payload = payload_for_response.log_payload();
+        let referenced_paths = payload_for_response.referenced_paths();
 
         let handler = match self.handler(tool_name.as_ref()) {
             Some(handler) => handler,
@@ -98,6 +110,22 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        if let Err(limit) = session.services.budget.try_start_tool_call() {
+            let message = format!(
+                "{} The tool call was not run.",
+                session.services.budget.exceeded_message(limit)
+            );
+            otel.tool_result(
+                tool_name.as_ref(),
+                &call_id_owned,
+                log_payload.as_ref(),
+                Duration::ZERO,
+                false,
+                &message,
+            );
+            return Err(FunctionCallError::RespondToModel(message));
+        }
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -133,9 +161,50 @@ impl ToolRegistry {
         match result {
             Ok(_) => {
                 let mut guard = output_cell.lock().await;
-                let output = guard.take().ok_or_else(|| {
+                let mut output = guard.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                if !referenced_paths.is_empty() {
+                    let paths: Vec<PathBuf> = referenced_paths
+                        .into_iter()
+                        .map(|path| turn.resolve_path(Some(path)))
+                        .collect();
+                    session.load_nested_project_docs(&turn, &paths).await;
+                }
+                if hooks_enabled {
+                    let original_output = hook_tool_output(&output);
+                    let mut event = HookEvent::PostToolUse {
+                        tool_name: tool_name.clone(),
+                        call_id: call_id_owned.clone(),
+                        tool_input: hook_tool_input(&payload_for_response),
+                        tool_output: original_output.clone(),
+                        success: output.success_for_logging(),
+                    };
+                    match session.run_hooks(&turn, &mut event).await {
+                        HookDecision::Deny { reason } => {
+                            output = ToolOutput::Function {
+                                content: format!("output withheld by hook: {reason}"),
+                                content_items: None,
+                                success: Some(false),
+                            };
+                        }
+                        HookDecision::Continue | HookDecision::Allow => {
+                            if let HookEvent::PostToolUse {
+                                tool_output,
+                                success,
+                                ..
+                            } = event
+                                && tool_output != original_output
+                            {
+                                output = ToolOutput::Function {
+                                    content: tool_output,
+                                    content_items: None,
+                                    success: Some(success),
+                                };
+                            }
+                        }
+                    }
+                }
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
             Err(err) => Err(err),
@@ -184,6 +253,10 @@ impl ToolRegistryBuilder {
             .push(ConfiguredToolSpec::new(spec, supports_parallel_tool_calls));
     }
 
+    pub fn has_handler(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
     pub fn register_handler(&mut self, name: impl Into<String>, handler: Arc<dyn ToolHandler>) {
         let name = name.into();
         if self
@@ -219,6 +292,77 @@ impl ToolRegistryBuilder {
     }
 }
 
+/// Lets `pre_tool_use` hooks veto a call or rewrite its arguments before the
+/// handler sees them.
+async fn run_pre_tool_use_hooks(invocation: &mut ToolInvocation) -> Result<(), FunctionCallError> {
+    let original_input = hook_tool_input(&invocation.payload);
+    let mut event = HookEvent::PreToolUse {
+        tool_name: invocation.tool_name.clone(),
+        call_id: invocation.call_id.clone(),
+        tool_input: original_input.clone(),
+    };
+    let decision = invocation
+        .session
+        .run_hooks(&invocation.turn, &mut event)
+        .await;
+    if let HookDecision::Deny { reason } = decision {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "blocked by hook: {reason}"
+        )));
+    }
+    if let HookEvent::PreToolUse { tool_input, .. } = event
+        && tool_input != original_input
+    {
+        apply_hook_tool_input(&mut invocation.payload, tool_input)?;
+    }
+    Ok(())
+}
+
+/// JSON view of a tool call's input as shown to hooks: parsed arguments when
+/// they are JSON, the raw string otherwise.
+fn hook_tool_input(payload: &ToolPayload) -> Value {
+    let parse =
+        |raw: &str| serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    match payload {
+        ToolPayload::Function { arguments } => parse(arguments),
+        ToolPayload::Mcp { raw_arguments, .. } => parse(raw_arguments),
+        ToolPayload::Custom { input } => Value::String(input.clone()),
+        ToolPayload::LocalShell { params } => serde_json::to_value(params).unwrap_or(Value::Null),
+    }
+}
+
+fn apply_hook_tool_input(payload: &mut ToolPayload, input: Value) -> Result<(), FunctionCallError> {
+    let to_raw = |input: Value| match input {
+        Value::String(raw) => raw,
+        other => other.to_string(),
+    };
+    match payload {
+        ToolPayload::Function { arguments } => *arguments = to_raw(input),
+        ToolPayload::Mcp { raw_arguments, .. } => *raw_arguments = to_raw(input),
+        ToolPayload::Custom {
+            input: custom_input,
+        } => *custom_input = to_raw(input),
+        ToolPayload::LocalShell { params } => {
+            *params = serde_json::from_value::<ShellToolCallParams>(input).map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "hook returned invalid shell parameters: {err}"
+                ))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn hook_tool_output(output: &ToolOutput) -> String {
+    match output {
+        ToolOutput::Function { content, .. } => content.clone(),
+        ToolOutput::Mcp { result } => match result {
+            Ok(result) => serde_json::to_string(result).unwrap_or_default(),
+            Err(err) => err.clone(),
+        },
+    }
+}
+
 fn unsupported_tool_call_message(payload: &ToolPayload, tool_name: &str) -> String {
     match payload {
         ToolPayload::Custom { .. } => format!("unsupported custom tool call: {tool_name}"),
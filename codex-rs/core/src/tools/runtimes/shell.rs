@@ -6,6 +6,7 @@ builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
 use crate::exec::ExecToolCallOutput;
 use crate::features::Feature;
+use crate::is_safe_command::is_known_safe_command;
 use crate::powershell::prefix_powershell_script_with_utf8;
 use crate::sandboxing::SandboxPermissions;
 use crate::sandboxing::execute_env;
@@ -36,6 +37,9 @@ pub struct ShellRequest {
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub exec_approval_requirement: ExecApprovalRequirement,
+    /// Written to the command's stdin; config-defined tools pass their
+    /// arguments this way.
+    pub stdin: Option<String>,
 }
 
 #[derive(Default)]
@@ -46,6 +50,7 @@ pub(crate) struct ApprovalKey {
     command: Vec<String>,
     cwd: PathBuf,
     sandbox_permissions: SandboxPermissions,
+    stdin: Option<String>,
 }
 
 impl ShellRuntime {
@@ -79,6 +84,7 @@ impl Approvable<ShellRequest> for ShellRuntime {
             command: req.command.clone(),
             cwd: req.cwd.clone(),
             sandbox_permissions: req.sandbox_permissions,
+            stdin: req.stdin.clone(),
         }
     }
 
@@ -120,6 +126,10 @@ impl Approvable<ShellRequest> for ShellRuntime {
         Some(req.exec_approval_requirement.clone())
     }
 
+    fn allowed_in_plan_mode(&self, req: &ShellRequest) -> bool {
+        is_known_safe_command(&req.command)
+    }
+
     fn sandbox_mode_for_first_attempt(&self, req: &ShellRequest) -> SandboxOverride {
         if req.sandbox_permissions.requires_escalated_permissions()
             || matches!(
@@ -155,7 +165,7 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
             command
         };
 
-        let spec = build_command_spec(
+        let mut spec = build_command_spec(
             &command,
             &req.cwd,
             &req.env,
@@ -163,6 +173,7 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
             req.sandbox_permissions,
             req.justification.clone(),
         )?;
+        spec.stdin = req.stdin.clone();
         let env = attempt
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
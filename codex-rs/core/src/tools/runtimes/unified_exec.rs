@@ -2,12 +2,14 @@
 Runtime: unified exec
 
 Handles approval + sandbox orchestration for unified exec requests, delegating to
-the session manager to spawn PTYs once an ExecEnv is prepared.
+the session manager to spawn a PTY (or piped stdio for `tty: false`) once an
+ExecEnv is prepared.
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecExpiration;
 use crate::features::Feature;
+use crate::is_safe_command::is_known_safe_command;
 use crate::powershell::prefix_powershell_script_with_utf8;
 use crate::sandboxing::SandboxPermissions;
 use crate::shell::ShellType;
@@ -40,6 +42,9 @@ pub struct UnifiedExecRequest {
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub exec_approval_requirement: ExecApprovalRequirement,
+    /// Spawn under a PTY. Piped stdio is used for helper processes the
+    /// session talks to over a byte-exact protocol (language servers).
+    pub tty: bool,
 }
 
 #[derive(serde::Serialize, Clone, Debug, Eq, PartialEq, Hash)]
@@ -61,6 +66,7 @@ impl UnifiedExecRequest {
         sandbox_permissions: SandboxPermissions,
         justification: Option<String>,
         exec_approval_requirement: ExecApprovalRequirement,
+        tty: bool,
     ) -> Self {
         Self {
             command,
@@ -69,6 +75,7 @@ impl UnifiedExecRequest {
             sandbox_permissions,
             justification,
             exec_approval_requirement,
+            tty,
         }
     }
 }
@@ -141,6 +148,12 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         Some(req.exec_approval_requirement.clone())
     }
 
+    fn allowed_in_plan_mode(&self, req: &UnifiedExecRequest) -> bool {
+        // Piped sessions are started by the harness, not chosen by the model,
+        // and still run under the read-only sandbox in plan mode.
+        !req.tty || is_known_safe_command(&req.command)
+    }
+
     fn sandbox_mode_for_first_attempt(&self, req: &UnifiedExecRequest) -> SandboxOverride {
         if req.sandbox_permissions.requires_escalated_permissions()
             || matches!(
@@ -188,16 +201,20 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecSession> for UnifiedExecRunt
         let exec_env = attempt
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
-        self.manager
-            .open_session_with_exec_env(&exec_env)
-            .await
-            .map_err(|err| match err {
-                UnifiedExecError::SandboxDenied { output, .. } => {
-                    ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
-                        output: Box::new(output),
-                    }))
-                }
-                other => ToolError::Rejected(other.to_string()),
-            })
+        let session = if req.tty {
+            self.manager.open_session_with_exec_env(&exec_env).await
+        } else {
+            self.manager
+                .open_pipe_session_with_exec_env(&exec_env)
+                .await
+        };
+        session.map_err(|err| match err {
+            UnifiedExecError::SandboxDenied { output, .. } => {
+                ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
+                    output: Box::new(output),
+                }))
+            }
+            other => ToolError::Rejected(other.to_string()),
+        })
     }
 }
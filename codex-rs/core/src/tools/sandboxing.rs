@@ -152,6 +152,32 @@ pub(crate) fn default_exec_approval_requirement(
     }
 }
 
+/// Returned to the model when it attempts a write while plan mode is on.
+pub(crate) const PLAN_MODE_REJECTION: &str = "plan mode is on: only read-only tools and known-safe commands may run. Record your plan with update_plan and wait for the user to approve it before making changes.";
+
+/// Applies plan mode to `requirement`. In plan mode, requests that are not
+/// `allowed_in_plan_mode` are forbidden outright, and allowed ones run under
+/// the turn's read-only sandbox without prompting.
+pub(crate) fn plan_mode_requirement(
+    plan_mode: bool,
+    allowed_in_plan_mode: bool,
+    requirement: ExecApprovalRequirement,
+) -> ExecApprovalRequirement {
+    if !plan_mode {
+        return requirement;
+    }
+    match requirement {
+        ExecApprovalRequirement::Forbidden { .. } => requirement,
+        _ if !allowed_in_plan_mode => ExecApprovalRequirement::Forbidden {
+            reason: PLAN_MODE_REJECTION.to_string(),
+        },
+        _ => ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            proposed_execpolicy_amendment: None,
+        },
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum SandboxOverride {
     NoOverride,
@@ -184,6 +210,12 @@ pub(crate) trait Approvable<Req> {
         None
     }
 
+    /// Whether the request may run while plan mode is on. Only requests that
+    /// cannot modify anything qualify; the default rejects everything.
+    fn allowed_in_plan_mode(&self, _req: &Req) -> bool {
+        false
+    }
+
     /// Decide we can request an approval for no-sandbox execution.
     fn wants_no_sandbox_approval(&self, policy: AskForApproval) -> bool {
         !matches!(policy, AskForApproval::Never | AskForApproval::OnRequest)
@@ -289,4 +321,36 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn plan_mode_forbids_writes_and_runs_reads_without_prompting() {
+        let needs_approval = ExecApprovalRequirement::NeedsApproval {
+            reason: None,
+            proposed_execpolicy_amendment: None,
+        };
+        assert_eq!(
+            plan_mode_requirement(false, false, needs_approval.clone()),
+            needs_approval
+        );
+        assert_eq!(
+            plan_mode_requirement(true, false, needs_approval.clone()),
+            ExecApprovalRequirement::Forbidden {
+                reason: PLAN_MODE_REJECTION.to_string(),
+            }
+        );
+        assert_eq!(
+            plan_mode_requirement(true, true, needs_approval),
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: false,
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        let forbidden = ExecApprovalRequirement::Forbidden {
+            reason: "blocked by policy".to_string(),
+        };
+        assert_eq!(
+            plan_mode_requirement(true, true, forbidden.clone()),
+            forbidden
+        );
+    }
 }
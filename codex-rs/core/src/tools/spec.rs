@@ -1,11 +1,15 @@
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::custom_tools::CustomTool;
+use crate::custom_tools::parameters_schema;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::models_manager::model_family::ModelFamily;
 use crate::tools::handlers::PLAN_TOOL;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
+use crate::tools::handlers::edit_file::create_create_file_tool;
+use crate::tools::handlers::edit_file::create_edit_file_tool;
 use crate::tools::registry::ToolRegistryBuilder;
 use codex_protocol::openai_models::ApplyPatchToolType;
 use codex_protocol::openai_models::ConfigShellToolType;
@@ -22,6 +26,14 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_spawn_agent_tool: bool,
+    pub include_memory_tool: bool,
+    pub include_read_output_tool: bool,
+    pub include_search_code_tool: bool,
+    pub include_lsp_tools: bool,
+    pub include_fetch_url_tool: bool,
+    /// Config-defined command tools, filled in per session.
+    pub custom_tools: Vec<CustomTool>,
     pub experimental_supported_tools: Vec<String>,
 }
 
@@ -39,6 +51,12 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_spawn_agent_tool = features.enabled(Feature::SpawnAgent);
+        let include_memory_tool = features.enabled(Feature::Memory);
+        let include_read_output_tool = features.enabled(Feature::OutputSpill);
+        let include_search_code_tool = features.enabled(Feature::CodeIndex);
+        let include_lsp_tools = features.enabled(Feature::Lsp);
+        let include_fetch_url_tool = features.enabled(Feature::FetchUrl);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -56,6 +74,7 @@ impl ToolsConfig {
         let apply_patch_tool_type = match model_family.apply_patch_tool_type {
             Some(ApplyPatchToolType::Freeform) => Some(ApplyPatchToolType::Freeform),
             Some(ApplyPatchToolType::Function) => Some(ApplyPatchToolType::Function),
+            Some(ApplyPatchToolType::EditFile) => Some(ApplyPatchToolType::EditFile),
             None => {
                 if include_apply_patch_tool {
                     Some(ApplyPatchToolType::Freeform)
@@ -70,6 +89,13 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_spawn_agent_tool,
+            include_memory_tool,
+            include_read_output_tool,
+            include_search_code_tool,
+            include_lsp_tools,
+            include_fetch_url_tool,
+            custom_tools: Vec::new(),
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
         }
     }
@@ -398,15 +424,49 @@ fn create_view_image_tool() -> ToolSpec {
     properties.insert(
         "path".to_string(),
         JsonSchema::String {
-            description: Some("Local filesystem path to an image file".to_string()),
+            description: Some("Local filesystem path to an image, SVG or PDF file".to_string()),
+        },
+    );
+    properties.insert(
+        "detail".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Resolution to attach at: \"low\" (512px), \"auto\" (default) or \"high\" (2048px)"
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_dimension".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Optional cap, in pixels, on the longest side of each attached image".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "pages".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::Number { description: None }),
+            description: Some(
+                "1-based PDF pages to render (at most 8); defaults to the first page".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "tile".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Split images much taller than they are wide, such as full-page screenshots, into several overlapping tiles (default true)"
+                    .to_string(),
+            ),
         },
     );
 
     ToolSpec::Function(ResponsesApiTool {
         name: "view_image".to_string(),
-        description:
-            "Attach a local image (by filesystem path) to the conversation context for this turn."
-                .to_string(),
+        description: "Attach a local image (by filesystem path) to the conversation context for this turn. SVG files are rasterized and PDF pages are rendered as images."
+            .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
@@ -416,6 +476,320 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+fn create_memory_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "action".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "`remember` to store a new fact, `recall` to search stored facts, or `forget` to delete one."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "text".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "For `remember`: one self-contained fact worth knowing in later sessions, e.g. how to run the tests or a preference the user stated."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "For `recall`: words to search for. Omit to list every entry.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "scope".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "`project` (default) for facts about this project, or `user` for preferences that apply everywhere."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "id".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "For `forget`: the number of the entry, e.g. 3 for `project#3`.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "memory".to_string(),
+        description: "Read and update the memory kept across sessions. Remember durable facts (build commands, conventions, user preferences), not progress on the current task. Forget entries that turn out to be wrong."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["action".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_output_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "id".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Id of the saved output from a truncation notice, e.g. `out-7f3a-3`.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "offset".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "The line number to start reading from. Must be 1 or greater.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("The maximum number of lines to return (default 200).".to_string()),
+        },
+    );
+    properties.insert(
+        "pattern".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional regular expression; only lines matching it are returned.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_output".to_string(),
+        description: "Reads tool output that was truncated in an earlier result. Page through it with offset/limit or search it with a pattern."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_search_code_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Words or identifiers to look for, e.g. `retry backoff` or `parseConfig`."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional file or directory to restrict the search to. Defaults to the whole project."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("Maximum number of results to return (default 10).".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "search_code".to_string(),
+        description: "Searches a local index of the project and returns the best-matching code with paths, line ranges and the definitions they contain. Use it to find where a concept lives before reading files; use grep_files or rg for exact patterns."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn lsp_position_properties() -> BTreeMap<String, JsonSchema> {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some("File containing the symbol.".to_string()),
+        },
+    );
+    properties.insert(
+        "line".to_string(),
+        JsonSchema::Number {
+            description: Some("1-based line number.".to_string()),
+        },
+    );
+    properties.insert(
+        "column".to_string(),
+        JsonSchema::Number {
+            description: Some("1-based column. Use this or `symbol`.".to_string()),
+        },
+    );
+    properties.insert(
+        "symbol".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Identifier on that line to position on (its first occurrence). Use this or `column`."
+                    .to_string(),
+            ),
+        },
+    );
+    properties
+}
+
+fn create_lsp_position_tool(name: &str, description: &str) -> ToolSpec {
+    let mut properties = lsp_position_properties();
+    if name == "find_references" {
+        properties.insert(
+            "include_declaration".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Whether to include the declaration itself (default true).".to_string(),
+                ),
+            },
+        );
+    }
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: name.to_string(),
+        description: description.to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string(), "line".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_diagnostics_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some("File to check.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "diagnostics".to_string(),
+        description: "Returns the language server's errors, warnings and hints for a file as `path:line:col: severity: message`."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_fetch_url_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "url".to_string(),
+        JsonSchema::String {
+            description: Some("Absolute http or https URL to fetch.".to_string()),
+        },
+    );
+    properties.insert(
+        "page".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "1-based page of the converted document to return (default 1).".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "fetch_url".to_string(),
+        description: "Fetches a web page and returns it as markdown, split into pages for long documents. Use it to read documentation, issues or references the user points to; repeated fetches of the same URL are served from a cache."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["url".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_spawn_agent_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "prompt".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Task for the sub-agent. It cannot see this conversation, so include every detail it needs."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "role".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Short role for the sub-agent, e.g. `explorer` or `test-writer`.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "model".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Model or deployment to run the sub-agent with. Prefer a cheaper model for exploration. Defaults to the configured sub-agent model, or the current model."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "`read-only` (default) to only inspect the workspace, or `workspace-write` to let the sub-agent edit files."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "concurrent".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Whether the sub-agent may run alongside other sub-agents (default true). Set to false for work that must not overlap with other sub-agents, such as edits to shared files."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "spawn_agent".to_string(),
+        description: "Delegate a self-contained subtask to a sub-agent and return its final report. Call it several times in parallel to run independent subtasks concurrently."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["prompt".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -864,6 +1238,17 @@ pub(crate) fn mcp_tool_to_openai_tool(
     })
 }
 
+pub(crate) fn create_custom_tool(tool: &CustomTool) -> Result<ToolSpec, serde_json::Error> {
+    let mut parameters = parameters_schema(&tool.config);
+    sanitize_json_schema(&mut parameters);
+    Ok(ToolSpec::Function(ResponsesApiTool {
+        name: tool.name.clone(),
+        description: tool.config.description.clone(),
+        strict: false,
+        parameters: serde_json::from_value::<JsonSchema>(parameters)?,
+    }))
+}
+
 /// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
 /// JsonSchema enum. This function:
 /// - Ensures every schema object has a "type". If missing, infers it from
@@ -981,14 +1366,22 @@ pub(crate) fn build_specs(
     mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::CustomToolHandler;
+    use crate::tools::handlers::EditFileHandler;
+    use crate::tools::handlers::FetchUrlHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
+    use crate::tools::handlers::LspHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
+    use crate::tools::handlers::MemoryHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::ReadOutputHandler;
+    use crate::tools::handlers::SearchCodeHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SpawnAgentHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
@@ -1048,12 +1441,20 @@ pub(crate) fn build_specs(
         match apply_patch_tool_type {
             ApplyPatchToolType::Freeform => {
                 builder.push_spec(create_apply_patch_freeform_tool());
+                builder.register_handler("apply_patch", apply_patch_handler);
             }
             ApplyPatchToolType::Function => {
                 builder.push_spec(create_apply_patch_json_tool());
+                builder.register_handler("apply_patch", apply_patch_handler);
+            }
+            ApplyPatchToolType::EditFile => {
+                let edit_file_handler = Arc::new(EditFileHandler);
+                builder.push_spec(create_edit_file_tool());
+                builder.push_spec(create_create_file_tool());
+                builder.register_handler("edit_file", edit_file_handler.clone());
+                builder.register_handler("create_file", edit_file_handler);
             }
         }
-        builder.register_handler("apply_patch", apply_patch_handler);
     }
 
     if config
@@ -1102,6 +1503,82 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_spawn_agent_tool {
+        builder.push_spec_with_parallel_support(create_spawn_agent_tool(), true);
+        builder.register_handler("spawn_agent", Arc::new(SpawnAgentHandler::default()));
+    }
+
+    if config.include_memory_tool {
+        builder.push_spec(create_memory_tool());
+        builder.register_handler("memory", Arc::new(MemoryHandler));
+    }
+
+    if config.include_read_output_tool {
+        builder.push_spec_with_parallel_support(create_read_output_tool(), true);
+        builder.register_handler("read_output", Arc::new(ReadOutputHandler));
+    }
+
+    if config.include_search_code_tool {
+        builder.push_spec_with_parallel_support(create_search_code_tool(), true);
+        builder.register_handler("search_code", Arc::new(SearchCodeHandler));
+    }
+
+    if config.include_lsp_tools {
+        builder.push_spec_with_parallel_support(
+            create_lsp_position_tool(
+                "goto_definition",
+                "Asks the language server where the symbol at a position is defined. Returns `path:line:col` with the line text.",
+            ),
+            true,
+        );
+        builder.push_spec_with_parallel_support(
+            create_lsp_position_tool(
+                "find_references",
+                "Asks the language server for every reference to the symbol at a position. Returns `path:line:col` with the line text.",
+            ),
+            true,
+        );
+        builder.push_spec_with_parallel_support(
+            create_lsp_position_tool(
+                "hover",
+                "Returns the language server's hover text (type, signature and docs) for the symbol at a position.",
+            ),
+            true,
+        );
+        builder.push_spec_with_parallel_support(create_diagnostics_tool(), true);
+        let lsp_handler = Arc::new(LspHandler);
+        for name in ["goto_definition", "find_references", "hover", "diagnostics"] {
+            builder.register_handler(name, lsp_handler.clone());
+        }
+    }
+
+    if config.include_fetch_url_tool {
+        builder.push_spec_with_parallel_support(create_fetch_url_tool(), true);
+        builder.register_handler("fetch_url", Arc::new(FetchUrlHandler));
+    }
+
+    for tool in &config.custom_tools {
+        if builder.has_handler(&tool.name) {
+            tracing::warn!(
+                "custom tool `{}` conflicts with a built-in tool and was skipped",
+                tool.name
+            );
+            continue;
+        }
+        match create_custom_tool(tool) {
+            Ok(spec) => {
+                builder.push_spec(spec);
+                builder.register_handler(
+                    tool.name.clone(),
+                    Arc::new(CustomToolHandler::new(tool.clone())),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to convert custom tool {:?}: {e:?}", tool.name);
+            }
+        }
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -1349,6 +1826,230 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_specs_includes_spawn_agent_when_enabled() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::SpawnAgent);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+
+        let spawn_agent = find_tool(&tools, "spawn_agent");
+        assert!(spawn_agent.supports_parallel_tool_calls);
+        let ToolSpec::Function(ResponsesApiTool { parameters, .. }) = &spawn_agent.spec else {
+            panic!("spawn_agent should be a function tool");
+        };
+        let JsonSchema::Object { required, .. } = parameters else {
+            panic!("spawn_agent parameters should be an object");
+        };
+        assert_eq!(required, &Some(vec!["prompt".to_string()]));
+
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &Features::with_defaults(),
+            }),
+            None,
+        )
+        .build();
+        assert!(
+            !tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "spawn_agent")
+        );
+    }
+
+    #[test]
+    fn test_build_specs_claude_uses_edit_file_tools() {
+        let config = test_config();
+        let tool_names = |slug: &str| {
+            let model_family = ModelsManager::construct_model_family_offline(slug, &config);
+            let (tools, _) = build_specs(
+                &ToolsConfig::new(&ToolsConfigParams {
+                    model_family: &model_family,
+                    features: &Features::with_defaults(),
+                }),
+                None,
+            )
+            .build();
+            tools
+                .iter()
+                .map(|tool| tool_name(&tool.spec).to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let claude = tool_names("claude-sonnet-4-5");
+        assert!(claude.contains(&"edit_file".to_string()), "{claude:?}");
+        assert!(claude.contains(&"create_file".to_string()), "{claude:?}");
+        assert!(!claude.contains(&"apply_patch".to_string()), "{claude:?}");
+
+        let codex = tool_names("gpt-5-codex");
+        assert!(codex.contains(&"apply_patch".to_string()), "{codex:?}");
+        assert!(!codex.contains(&"edit_file".to_string()), "{codex:?}");
+    }
+
+    #[test]
+    fn test_build_specs_includes_memory_when_enabled() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::Memory);
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &features,
+            }),
+            None,
+        )
+        .build();
+        let memory = find_tool(&tools, "memory");
+        assert!(!memory.supports_parallel_tool_calls);
+
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &Features::with_defaults(),
+            }),
+            None,
+        )
+        .build();
+        assert!(!tools.iter().any(|tool| tool_name(&tool.spec) == "memory"));
+    }
+
+    #[test]
+    fn test_build_specs_includes_read_output_with_output_spill() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::OutputSpill);
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &features,
+            }),
+            None,
+        )
+        .build();
+        let read_output = find_tool(&tools, "read_output");
+        assert!(read_output.supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_includes_search_code_with_code_index() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::CodeIndex);
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &features,
+            }),
+            None,
+        )
+        .build();
+        let search_code = find_tool(&tools, "search_code");
+        assert!(search_code.supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_includes_lsp_tools_with_lsp() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::Lsp);
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &features,
+            }),
+            None,
+        )
+        .build();
+        for name in ["goto_definition", "find_references", "hover", "diagnostics"] {
+            assert!(find_tool(&tools, name).supports_parallel_tool_calls);
+        }
+    }
+
+    #[test]
+    fn test_build_specs_includes_fetch_url_with_feature() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::FetchUrl);
+        let (tools, _) = build_specs(
+            &ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features: &features,
+            }),
+            None,
+        )
+        .build();
+        let fetch_url = find_tool(&tools, "fetch_url");
+        assert!(fetch_url.supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_registers_custom_tools() {
+        let config = test_config();
+        let model_family = ModelsManager::construct_model_family_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let custom_tool = |name: &str| CustomTool {
+            name: name.to_string(),
+            config: crate::config::types::CustomToolConfig {
+                description: "Look up a table in the schema registry".to_string(),
+                command: vec!["schema-registry".to_string()],
+                parameters: Some(json!({
+                    "type": "object",
+                    "properties": { "table": { "type": "string" } },
+                    "required": ["table"],
+                })),
+                input: crate::config::types::CustomToolInput::Stdin,
+                cwd: None,
+                env: HashMap::new(),
+                timeout_ms: None,
+            },
+        };
+        tools_config.custom_tools = vec![custom_tool("schema_lookup"), custom_tool("update_plan")];
+        let (tools, registry) = build_specs(&tools_config, None).build();
+
+        let tool = find_tool(&tools, "schema_lookup");
+        assert!(!tool.supports_parallel_tool_calls);
+        assert_eq!(
+            tool.spec,
+            ToolSpec::Function(ResponsesApiTool {
+                name: "schema_lookup".to_string(),
+                description: "Look up a table in the schema registry".to_string(),
+                strict: false,
+                parameters: JsonSchema::Object {
+                    properties: BTreeMap::from([(
+                        "table".to_string(),
+                        JsonSchema::String { description: None },
+                    )]),
+                    required: Some(vec!["table".to_string()]),
+                    additional_properties: None,
+                },
+            })
+        );
+        assert!(registry.handler("schema_lookup").is_some());
+        // A custom tool cannot shadow a built-in one.
+        assert_eq!(
+            tools
+                .iter()
+                .filter(|tool| tool_name(&tool.spec) == "update_plan")
+                .count(),
+            1
+        );
+    }
+
     #[test]
     fn test_build_specs_gpt51_codex_unified_exec_web_search() {
         assert_model_tools(
@@ -40,6 +40,8 @@ pub struct TurnDiffTracker {
     temp_name_to_current_path: HashMap<String, PathBuf>,
     /// Cache of known git worktree roots to avoid repeated filesystem walks.
     git_root_cache: Vec<PathBuf>,
+    /// Number of patches seen so far.
+    patch_count: usize,
 }
 
 impl TurnDiffTracker {
@@ -52,6 +54,7 @@ impl TurnDiffTracker {
     /// - For additions, we intentionally do not create a baseline snapshot so that diffs are proper additions.
     /// - Also updates internal mappings for move/rename events.
     pub fn on_patch_begin(&mut self, changes: &HashMap<PathBuf, FileChange>) {
+        self.patch_count += 1;
         for (path, change) in changes.iter() {
             // Ensure a stable internal filename exists for this external path.
             if !self.external_to_temp_name.contains_key(path) {
@@ -127,6 +130,19 @@ impl TurnDiffTracker {
         }
     }
 
+    /// Number of patches applied so far, so callers can tell whether anything
+    /// changed since they last looked.
+    pub fn patch_count(&self) -> usize {
+        self.patch_count
+    }
+
+    /// Current paths of every file touched so far, following renames.
+    pub fn changed_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.temp_name_to_current_path.values().cloned().collect();
+        paths.sort();
+        paths
+    }
+
     fn get_path_for_internal(&self, internal: &str) -> Option<PathBuf> {
         self.temp_name_to_current_path
             .get(internal)
@@ -647,6 +663,34 @@ index {left_oid}..{right_oid}
         assert_eq!(out, expected);
     }
 
+    #[test]
+    fn changed_paths_follow_renames_and_count_patches() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dest = dir.path().join("dst.txt");
+        let added = dir.path().join("added.txt");
+        fs::write(&src, "line\n").unwrap();
+
+        let mut acc = TurnDiffTracker::new();
+        assert_eq!(acc.patch_count(), 0);
+        acc.on_patch_begin(&HashMap::from([(
+            src,
+            FileChange::Update {
+                unified_diff: "".to_owned(),
+                move_path: Some(dest.clone()),
+            },
+        )]));
+        acc.on_patch_begin(&HashMap::from([(
+            added.clone(),
+            FileChange::Add {
+                content: "new\n".to_string(),
+            },
+        )]));
+
+        assert_eq!(acc.patch_count(), 2);
+        assert_eq!(acc.changed_paths(), vec![added, dest]);
+    }
+
     #[test]
     fn move_without_1change_yields_no_diff() {
         let dir = tempdir().unwrap();
@@ -123,7 +123,7 @@ impl UnifiedExecSession {
         }
     }
 
-    pub(super) fn writer_sender(&self) -> mpsc::Sender<Vec<u8>> {
+    pub(crate) fn writer_sender(&self) -> mpsc::Sender<Vec<u8>> {
         self.session.writer_sender()
     }
 
@@ -135,7 +135,7 @@ impl UnifiedExecSession {
         }
     }
 
-    pub(super) fn output_receiver(&self) -> tokio::sync::broadcast::Receiver<Vec<u8>> {
+    pub(crate) fn output_receiver(&self) -> tokio::sync::broadcast::Receiver<Vec<u8>> {
         self.session.output_receiver()
     }
 
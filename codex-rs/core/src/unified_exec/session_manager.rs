@@ -476,6 +476,29 @@ impl UnifiedExecSessionManager {
         UnifiedExecSession::from_spawned(spawned, env.sandbox).await
     }
 
+    /// Spawns `env` with piped stdio instead of a PTY; see
+    /// `codex_utils_pty::spawn_pipe_process`.
+    pub(crate) async fn open_pipe_session_with_exec_env(
+        &self,
+        env: &ExecEnv,
+    ) -> Result<UnifiedExecSession, UnifiedExecError> {
+        let (program, args) = env
+            .command
+            .split_first()
+            .ok_or(UnifiedExecError::MissingCommandLine)?;
+
+        let spawned = codex_utils_pty::spawn_pipe_process(
+            program,
+            args,
+            env.cwd.as_path(),
+            &env.env,
+            &env.arg0,
+        )
+        .await
+        .map_err(|err| UnifiedExecError::create_session(err.to_string()))?;
+        UnifiedExecSession::from_spawned(spawned, env.sandbox).await
+    }
+
     pub(super) async fn open_session_with_sandbox(
         &self,
         command: &[String],
@@ -484,19 +507,67 @@ impl UnifiedExecSessionManager {
         justification: Option<String>,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecSession, UnifiedExecError> {
-        let env = apply_unified_exec_env(create_env(&context.turn.shell_environment_policy));
-        let features = context.session.features();
+        let tool_ctx = ToolCtx {
+            session: context.session.as_ref(),
+            turn: context.turn.as_ref(),
+            call_id: context.call_id.clone(),
+            tool_name: "exec_command".to_string(),
+        };
+        self.open_orchestrated_session(
+            command,
+            cwd,
+            sandbox_permissions,
+            justification,
+            true,
+            &tool_ctx,
+        )
+        .await
+    }
+
+    /// Starts a long-lived helper process with piped stdio (a language
+    /// server) under the same approval and sandbox orchestration as
+    /// `exec_command`. The session is owned by the caller and is not
+    /// registered in the session store, so the model cannot address it.
+    pub(crate) async fn open_stdio_session(
+        &self,
+        command: &[String],
+        cwd: PathBuf,
+        tool_ctx: &ToolCtx<'_>,
+    ) -> Result<UnifiedExecSession, UnifiedExecError> {
+        self.open_orchestrated_session(
+            command,
+            cwd,
+            SandboxPermissions::UseDefault,
+            None,
+            false,
+            tool_ctx,
+        )
+        .await
+    }
+
+    async fn open_orchestrated_session(
+        &self,
+        command: &[String],
+        cwd: PathBuf,
+        sandbox_permissions: SandboxPermissions,
+        justification: Option<String>,
+        tty: bool,
+        tool_ctx: &ToolCtx<'_>,
+    ) -> Result<UnifiedExecSession, UnifiedExecError> {
+        let turn = tool_ctx.turn;
+        let env = apply_unified_exec_env(create_env(&turn.shell_environment_policy));
+        let features = tool_ctx.session.features();
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
-        let exec_approval_requirement = context
+        let exec_approval_requirement = tool_ctx
             .session
             .services
             .exec_policy
             .create_exec_approval_requirement_for_command(
                 &features,
                 command,
-                context.turn.approval_policy,
-                &context.turn.sandbox_policy,
+                turn.approval_policy,
+                &turn.sandbox_policy,
                 sandbox_permissions,
             )
             .await;
@@ -507,21 +578,10 @@ impl UnifiedExecSessionManager {
             sandbox_permissions,
             justification,
             exec_approval_requirement,
+            tty,
         );
-        let tool_ctx = ToolCtx {
-            session: context.session.as_ref(),
-            turn: context.turn.as_ref(),
-            call_id: context.call_id.clone(),
-            tool_name: "exec_command".to_string(),
-        };
         orchestrator
-            .run(
-                &mut runtime,
-                &req,
-                &tool_ctx,
-                context.turn.as_ref(),
-                context.turn.approval_policy,
-            )
+            .run(&mut runtime, &req, tool_ctx, turn, turn.approval_policy)
             .await
             .map_err(|e| UnifiedExecError::create_session(format!("{e:?}")))
     }
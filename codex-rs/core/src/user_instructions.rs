@@ -2,6 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 
 pub const USER_INSTRUCTIONS_OPEN_TAG_LEGACY: &str = "<user_instructions>";
@@ -42,6 +43,30 @@ impl From<UserInstructions> for ResponseItem {
     }
 }
 
+/// Instructions from an `AGENTS.md` below the session's working directory,
+/// injected as a developer message the first time the agent works under that
+/// directory.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NestedInstructions {
+    pub directory: String,
+    pub text: String,
+}
+
+impl From<NestedInstructions> for ResponseInputItem {
+    fn from(ni: NestedInstructions) -> Self {
+        ResponseInputItem::Message {
+            role: "developer".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "{USER_INSTRUCTIONS_PREFIX}{directory}\n\n<INSTRUCTIONS>\n{contents}\n</INSTRUCTIONS>",
+                    directory = ni.directory,
+                    contents = ni.text
+                ),
+            }],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename = "skill_instructions", rename_all = "snake_case")]
 pub(crate) struct SkillInstructions {
@@ -151,6 +176,30 @@ mod tests {
         ]));
     }
 
+    #[test]
+    fn test_nested_instructions() {
+        let nested_instructions = NestedInstructions {
+            directory: "/repo/services/foo".to_string(),
+            text: "Run `make test`.".to_string(),
+        };
+        let input_item: ResponseInputItem = nested_instructions.into();
+
+        let ResponseInputItem::Message { role, content } = input_item else {
+            panic!("expected ResponseInputItem::Message");
+        };
+
+        assert_eq!(role, "developer");
+
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one InputText content item");
+        };
+
+        assert_eq!(
+            text,
+            "# AGENTS.md instructions for /repo/services/foo\n\n<INSTRUCTIONS>\nRun `make test`.\n</INSTRUCTIONS>",
+        );
+    }
+
     #[test]
     fn test_skill_instructions() {
         let skill_instructions = SkillInstructions {
@@ -0,0 +1,398 @@
+//! Project verification commands (`[verify]` in config.toml) that run once a
+//! turn's patches are applied. Results are recorded as `verify` tool calls so
+//! the model sees them, and failures send the task back to the model for a
+//! bounded number of fix attempts.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseItem;
+use serde_json::json;
+use uuid::Uuid;
+use wildmatch::WildMatchPattern;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::VerifyConfig;
+use crate::exec_env::create_env;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::ExecCommandSource;
+use crate::protocol::WarningEvent;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ToolCtx;
+
+/// Name of the synthetic tool call each verification command is recorded as.
+pub(crate) const VERIFY_TOOL_NAME: &str = "verify";
+
+const DEFAULT_MAX_FIX_ATTEMPTS: u32 = 2;
+const FILES_PLACEHOLDER: &str = "{files}";
+const CRATE_PLACEHOLDER: &str = "{crate}";
+
+struct VerifyRule {
+    pattern: WildMatchPattern<'*', '?'>,
+    /// Globs containing `/` match the cwd-relative path, others the file name.
+    match_path: bool,
+    commands: Vec<String>,
+}
+
+pub(crate) struct Verifier {
+    rules: Vec<VerifyRule>,
+    max_fix_attempts: u32,
+    timeout_ms: Option<u64>,
+}
+
+/// Per-task bookkeeping, so a fix turn that edits nothing does not re-run the
+/// same commands and repeated failures eventually stop.
+#[derive(Default)]
+pub(crate) struct VerifyState {
+    patches_verified: usize,
+    fix_attempts: u32,
+}
+
+impl Verifier {
+    pub(crate) fn new(config: &VerifyConfig) -> Self {
+        let rules = config
+            .commands
+            .iter()
+            .filter(|(_, commands)| !commands.is_empty())
+            .map(|(glob, commands)| VerifyRule {
+                pattern: WildMatchPattern::new(glob),
+                match_path: glob.contains('/'),
+                commands: commands.clone(),
+            })
+            .collect();
+        Self {
+            rules,
+            max_fix_attempts: config.max_fix_attempts.unwrap_or(DEFAULT_MAX_FIX_ATTEMPTS),
+            timeout_ms: config.timeout_ms,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Expands the commands whose globs match any of `changed`, in config
+    /// order and without duplicates. Files that no longer exist are ignored.
+    pub(crate) fn plan(&self, cwd: &Path, changed: &[PathBuf]) -> Vec<String> {
+        let files: Vec<(PathBuf, String)> = changed
+            .iter()
+            .filter(|path| path.is_file())
+            .map(|path| {
+                let relative = path.strip_prefix(cwd).unwrap_or(path).to_path_buf();
+                let display = relative.to_string_lossy().replace('\\', "/");
+                (path.clone(), display)
+            })
+            .collect();
+        let mut crate_names = HashMap::new();
+        let mut planned: Vec<String> = Vec::new();
+        for rule in &self.rules {
+            let matched: Vec<&(PathBuf, String)> = files
+                .iter()
+                .filter(|(path, display)| {
+                    if rule.match_path {
+                        rule.pattern.matches(display)
+                    } else {
+                        path.file_name()
+                            .is_some_and(|name| rule.pattern.matches(&name.to_string_lossy()))
+                    }
+                })
+                .collect();
+            if matched.is_empty() {
+                continue;
+            }
+            for template in &rule.commands {
+                let expanded = if template.contains(CRATE_PLACEHOLDER) {
+                    let mut by_crate: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+                    for (path, display) in &matched {
+                        if let Some(name) = crate_name_for(path, &mut crate_names) {
+                            by_crate.entry(name).or_default().push(display);
+                        }
+                    }
+                    by_crate
+                        .into_iter()
+                        .map(|(name, files)| {
+                            substitute_files(&template.replace(CRATE_PLACEHOLDER, &name), &files)
+                        })
+                        .collect()
+                } else {
+                    let files: Vec<&str> = matched.iter().map(|(_, d)| d.as_str()).collect();
+                    vec![substitute_files(template, &files)]
+                };
+                for command in expanded {
+                    if !planned.contains(&command) {
+                        planned.push(command);
+                    }
+                }
+            }
+        }
+        planned
+    }
+}
+
+fn substitute_files(template: &str, files: &[&str]) -> String {
+    if !template.contains(FILES_PLACEHOLDER) {
+        return template.to_string();
+    }
+    let quoted = shlex::try_join(files.iter().copied()).unwrap_or_default();
+    template.replace(FILES_PLACEHOLDER, &quoted)
+}
+
+/// Package name from the nearest `Cargo.toml` above `file` that has one.
+fn crate_name_for(file: &Path, cache: &mut HashMap<PathBuf, Option<String>>) -> Option<String> {
+    let mut visited = Vec::new();
+    let mut found = None;
+    for dir in file.ancestors().skip(1) {
+        if let Some(cached) = cache.get(dir) {
+            found = cached.clone();
+            break;
+        }
+        visited.push(dir.to_path_buf());
+        if let Some(name) = read_package_name(&dir.join("Cargo.toml")) {
+            found = Some(name);
+            break;
+        }
+    }
+    for dir in visited {
+        cache.insert(dir, found.clone());
+    }
+    found
+}
+
+fn read_package_name(manifest: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(manifest).ok()?;
+    let manifest: toml::Table = toml::from_str(&contents).ok()?;
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Runs the configured commands for the files this task has patched since the
+/// last verification. Returns `true` when a failure should go back to the
+/// model for another turn.
+pub(crate) async fn verify_turn_edits(
+    sess: &Arc<Session>,
+    turn: &Arc<TurnContext>,
+    tracker: &SharedTurnDiffTracker,
+    state: &mut VerifyState,
+) -> bool {
+    let verifier = &sess.services.verifier;
+    if verifier.is_empty() {
+        return false;
+    }
+    let (patch_count, changed) = {
+        let tracker = tracker.lock().await;
+        (tracker.patch_count(), tracker.changed_paths())
+    };
+    if patch_count == state.patches_verified {
+        return false;
+    }
+    state.patches_verified = patch_count;
+
+    let commands = verifier.plan(&turn.cwd, &changed);
+    let mut passed = true;
+    for command in &commands {
+        passed &= run_verify_command(sess, turn, command, verifier.timeout_ms).await;
+    }
+    if passed {
+        return false;
+    }
+    if state.fix_attempts < verifier.max_fix_attempts {
+        state.fix_attempts += 1;
+        return true;
+    }
+    let message = format!(
+        "Verification failed with no automatic fix attempts left (verify.max_fix_attempts = {}).",
+        verifier.max_fix_attempts
+    );
+    sess.send_event(turn.as_ref(), EventMsg::Warning(WarningEvent { message }))
+        .await;
+    false
+}
+
+/// Runs one command through the shell runtime, so it follows the turn's
+/// sandbox and approval policy, and records it as a `verify` call/output pair.
+async fn run_verify_command(
+    sess: &Arc<Session>,
+    turn: &Arc<TurnContext>,
+    command: &str,
+    timeout_ms: Option<u64>,
+) -> bool {
+    let call_id = format!("verify-{}", Uuid::new_v4());
+    let exec_args = sess.user_shell().derive_exec_args(command, true);
+    let cwd = turn.cwd.clone();
+
+    let emitter = ToolEmitter::shell(
+        exec_args.clone(),
+        cwd.clone(),
+        ExecCommandSource::Verification,
+        true,
+    );
+    let event_ctx = ToolEventCtx::new(sess.as_ref(), turn.as_ref(), &call_id, None);
+    emitter.begin(event_ctx).await;
+
+    let features = sess.features();
+    let exec_approval_requirement = sess
+        .services
+        .exec_policy
+        .create_exec_approval_requirement_for_command(
+            &features,
+            &exec_args,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            SandboxPermissions::UseDefault,
+        )
+        .await;
+    let req = ShellRequest {
+        command: exec_args,
+        cwd,
+        timeout_ms,
+        env: create_env(&turn.shell_environment_policy),
+        sandbox_permissions: SandboxPermissions::UseDefault,
+        justification: Some("project verification after edits".to_string()),
+        exec_approval_requirement,
+        stdin: None,
+    };
+    let mut orchestrator = ToolOrchestrator::new();
+    let mut runtime = ShellRuntime::new();
+    let tool_ctx = ToolCtx {
+        session: sess.as_ref(),
+        turn: turn.as_ref(),
+        call_id: call_id.clone(),
+        tool_name: VERIFY_TOOL_NAME.to_string(),
+    };
+    let out = orchestrator
+        .run(&mut runtime, &req, &tool_ctx, turn, turn.approval_policy)
+        .await;
+    let event_ctx = ToolEventCtx::new(sess.as_ref(), turn.as_ref(), &call_id, None);
+    let (content, success) = match emitter.finish(event_ctx, out).await {
+        Ok(content) => (content, true),
+        Err(FunctionCallError::RespondToModel(content)) => (content, false),
+        Err(err) => (err.to_string(), false),
+    };
+
+    let items = [
+        ResponseItem::FunctionCall {
+            id: None,
+            name: VERIFY_TOOL_NAME.to_string(),
+            arguments: json!({ "command": command }).to_string(),
+            call_id: call_id.clone(),
+        },
+        ResponseItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content,
+                content_items: None,
+                success: Some(success),
+            },
+        },
+    ];
+    sess.record_conversation_items(turn.as_ref(), &items).await;
+    success
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn verifier(config: &str) -> Verifier {
+        Verifier::new(&toml::from_str::<VerifyConfig>(config).unwrap())
+    }
+
+    #[test]
+    fn plan_expands_files_and_crates_for_matching_edits() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        for (path, contents) in [
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"alpha\", \"beta\"]\n",
+            ),
+            ("alpha/Cargo.toml", "[package]\nname = \"alpha\"\n"),
+            ("alpha/src/lib.rs", ""),
+            ("alpha/src/my file.rs", ""),
+            ("beta/Cargo.toml", "[package]\nname = \"beta\"\n"),
+            ("beta/src/main.rs", ""),
+            ("docs/guide.md", ""),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        let verifier = verifier(
+            r#"
+[commands]
+"*.rs" = ["cargo fmt", "cargo clippy -p {crate}"]
+"alpha/*" = ["cargo fmt", "rustfmt --check {files}"]
+"*.py" = ["ruff check {files}"]
+"#,
+        );
+
+        let changed = vec![
+            root.join("alpha/src/lib.rs"),
+            root.join("alpha/src/my file.rs"),
+            root.join("beta/src/main.rs"),
+            root.join("docs/guide.md"),
+            root.join("deleted.rs"),
+        ];
+        assert_eq!(
+            verifier.plan(root, &changed),
+            vec![
+                "cargo fmt".to_string(),
+                "cargo clippy -p alpha".to_string(),
+                "cargo clippy -p beta".to_string(),
+                "rustfmt --check alpha/src/lib.rs 'alpha/src/my file.rs'".to_string(),
+            ]
+        );
+        assert_eq!(
+            verifier.plan(root, &[root.join("docs/guide.md")]),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn plan_follows_config_order() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        let verifier = verifier(
+            r#"
+[commands]
+"src/*" = ["first"]
+"*.rs" = ["second"]
+"#,
+        );
+        assert_eq!(
+            verifier.plan(root, &[root.join("src/lib.rs")]),
+            vec!["first".to_string(), "second".to_string()]
+        );
+    }
+
+    #[test]
+    fn empty_rules_are_ignored_and_fix_attempts_default() {
+        let verifier = verifier(
+            r#"
+[commands]
+"*.rs" = []
+"#,
+        );
+        assert!(verifier.is_empty());
+        assert_eq!(verifier.max_fix_attempts, DEFAULT_MAX_FIX_ATTEMPTS);
+    }
+}
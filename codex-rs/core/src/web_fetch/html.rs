@@ -0,0 +1,625 @@
+//! Small HTML to markdown converter for `fetch_url`.
+//!
+//! This is not a conforming HTML parser. It walks the tag stream, drops
+//! scripts, styles and page chrome (navigation, footers, sidebars), and keeps
+//! the structure a model needs to read the page: headings, paragraphs, lists,
+//! links, emphasis and code blocks.
+
+use url::Url;
+
+/// Elements whose contents are never shown.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "nav", "footer", "aside", "noscript", "svg", "template", "iframe", "form", "button", "select",
+    "canvas", "dialog",
+];
+
+/// Elements whose contents are raw text and may contain a literal `<`.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "title", "textarea"];
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "table",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "details",
+    "summary",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Document {
+    pub(crate) title: Option<String>,
+    pub(crate) markdown: String,
+}
+
+/// Convert `html` to markdown. Relative links and image sources are resolved
+/// against `base`.
+pub(crate) fn html_to_markdown(html: &str, base: Option<&Url>) -> Document {
+    let mut converter = Converter {
+        base,
+        out: Writer::default(),
+        title: None,
+        lists: Vec::new(),
+        links: Vec::new(),
+        pre_depth: 0,
+        skip: None,
+    };
+    converter.run(html);
+    let title = converter
+        .title
+        .map(|title| collapse_whitespace(&decode_entities(&title)))
+        .filter(|title| !title.is_empty());
+    Document {
+        title,
+        markdown: converter.out.finish(),
+    }
+}
+
+struct Tag<'a> {
+    name: String,
+    attrs: &'a str,
+    closing: bool,
+    self_closing: bool,
+}
+
+impl Tag<'_> {
+    fn attr(&self, wanted: &str) -> Option<String> {
+        let mut rest = self.attrs;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+            if rest.is_empty() {
+                return None;
+            }
+            let name_end = rest
+                .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+                .unwrap_or(rest.len());
+            let name = &rest[..name_end];
+            rest = rest[name_end..].trim_start();
+            let value = if let Some(after_eq) = rest.strip_prefix('=') {
+                let after_eq = after_eq.trim_start();
+                let (value, remainder) = match after_eq.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let body = &after_eq[1..];
+                        match body.find(quote) {
+                            Some(end) => (&body[..end], &body[end + 1..]),
+                            None => (body, ""),
+                        }
+                    }
+                    _ => {
+                        let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                        (&after_eq[..end], &after_eq[end..])
+                    }
+                };
+                rest = remainder;
+                value
+            } else {
+                ""
+            };
+            if name.eq_ignore_ascii_case(wanted) {
+                return Some(decode_entities(value));
+            }
+        }
+    }
+}
+
+struct Converter<'a> {
+    base: Option<&'a Url>,
+    out: Writer,
+    title: Option<String>,
+    /// One entry per open list: `None` for `<ul>`, the next number for `<ol>`.
+    lists: Vec<Option<usize>>,
+    /// Target of each open `<a>`, `None` when it is not worth linking.
+    links: Vec<Option<String>>,
+    pre_depth: usize,
+    /// Name and nesting depth of the element being skipped.
+    skip: Option<(String, usize)>,
+}
+
+impl Converter<'_> {
+    fn run(&mut self, html: &str) {
+        let mut rest = html;
+        while !rest.is_empty() {
+            let Some(lt) = rest.find('<') else {
+                self.text(rest);
+                break;
+            };
+            if lt > 0 {
+                self.text(&rest[..lt]);
+            }
+            rest = &rest[lt..];
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment
+                    .find("-->")
+                    .map_or("", |end| &comment[end + "-->".len()..]);
+                continue;
+            }
+            if rest.starts_with("<!") || rest.starts_with("<?") {
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+                continue;
+            }
+            let Some((tag, after)) = parse_tag(rest) else {
+                // A stray `<` that does not open a tag.
+                self.text("<");
+                rest = &rest[1..];
+                continue;
+            };
+            rest = after;
+
+            if !tag.closing && !tag.self_closing && RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+                let (body, remainder) = split_raw_text(rest, &tag.name);
+                if tag.name == "title" && self.title.is_none() {
+                    self.title = Some(body.to_string());
+                } else if tag.name == "textarea" && self.skip.is_none() {
+                    self.text(body);
+                }
+                rest = remainder;
+                continue;
+            }
+            self.tag(&tag);
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag<'_>) {
+        let name = tag.name.as_str();
+        if let Some((skipped, depth)) = &mut self.skip {
+            if skipped.as_str() == name && !VOID_ELEMENTS.contains(&name) {
+                if tag.closing {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        self.skip = None;
+                    }
+                } else if !tag.self_closing {
+                    *depth += 1;
+                }
+            }
+            return;
+        }
+        if SKIPPED_ELEMENTS.contains(&name) {
+            if !tag.closing && !tag.self_closing {
+                self.skip = Some((name.to_string(), 1));
+            }
+            return;
+        }
+
+        match (name, tag.closing) {
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.out.block_break(2);
+                self.out.push(&format!("{} ", "#".repeat(level)));
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => self.out.block_break(2),
+            ("br", _) => self.out.line_break(),
+            ("hr", _) => {
+                self.out.block_break(2);
+                self.out.push("---");
+                self.out.block_break(2);
+            }
+            ("ul", false) => {
+                self.out
+                    .block_break(if self.lists.is_empty() { 2 } else { 1 });
+                self.lists.push(None);
+            }
+            ("ol", false) => {
+                self.out
+                    .block_break(if self.lists.is_empty() { 2 } else { 1 });
+                let start = tag
+                    .attr("start")
+                    .and_then(|start| start.trim().parse().ok())
+                    .unwrap_or(1);
+                self.lists.push(Some(start));
+            }
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                self.out
+                    .block_break(if self.lists.is_empty() { 2 } else { 1 });
+            }
+            ("li", false) => {
+                self.out.block_break(1);
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(next)) => {
+                        let marker = format!("{next}. ");
+                        *next += 1;
+                        marker
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push(&format!("{indent}{marker}"));
+            }
+            ("li", true) => self.out.block_break(1),
+            ("tr", _) => self.out.block_break(1),
+            ("td" | "th", false) => {
+                if !self.out.at_line_start() {
+                    self.out.push(" | ");
+                }
+            }
+            ("pre", false) => {
+                self.out.block_break(2);
+                self.out.push("```");
+                self.out.line_break();
+                self.pre_depth += 1;
+            }
+            ("pre", true) => {
+                if self.pre_depth > 0 {
+                    self.pre_depth -= 1;
+                    self.out.block_break(1);
+                    self.out.push("```");
+                    self.out.block_break(2);
+                }
+            }
+            ("code" | "kbd" | "samp", _) if self.pre_depth == 0 => {
+                self.out.inline_marker("`", tag.closing)
+            }
+            ("strong" | "b", _) => self.out.inline_marker("**", tag.closing),
+            ("em" | "i", _) => self.out.inline_marker("*", tag.closing),
+            ("a", false) => {
+                let target = tag
+                    .attr("href")
+                    .and_then(|href| self.resolve(&href))
+                    .filter(|target| !target.starts_with('#'));
+                if target.is_some() {
+                    self.out.inline_marker("[", false);
+                }
+                self.links.push(target);
+            }
+            ("a", true) => {
+                if let Some(Some(target)) = self.links.pop() {
+                    self.out.close_link(&target);
+                }
+            }
+            ("img", _) => {
+                let alt = tag.attr("alt").map(|alt| collapse_whitespace(&alt));
+                let src = tag.attr("src").and_then(|src| self.resolve(&src));
+                if let (Some(alt), Some(src)) = (alt, src)
+                    && !alt.is_empty()
+                {
+                    self.out.inline_marker(&format!("![{alt}]({src})"), false);
+                }
+            }
+            (name, _) if BLOCK_ELEMENTS.contains(&name) => self.out.block_break(2),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, raw: &str) {
+        if self.skip.is_some() {
+            return;
+        }
+        let decoded = decode_entities(raw);
+        if self.pre_depth > 0 {
+            self.out.push_preformatted(&decoded);
+        } else {
+            self.out.push_text(&decoded);
+        }
+    }
+
+    /// Absolute URL for a link target, or `None` for script links.
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.to_ascii_lowercase().starts_with("javascript:") {
+            return None;
+        }
+        if href.starts_with('#') {
+            return Some(href.to_string());
+        }
+        match self.base {
+            Some(base) => base.join(href).ok().map(String::from),
+            None => Some(href.to_string()),
+        }
+    }
+}
+
+/// Parse the tag at the start of `input` (which begins with `<`), returning
+/// it with the remaining input.
+fn parse_tag(input: &str) -> Option<(Tag<'_>, &str)> {
+    let body = &input[1..];
+    let (closing, body) = match body.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let name_len = body
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+        .unwrap_or(body.len());
+    if name_len == 0 || !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = body[..name_len].to_ascii_lowercase();
+    let after_name = &body[name_len..];
+
+    let mut quote = None;
+    let mut end = None;
+    for (idx, ch) in after_name.char_indices() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '>') => {
+                end = Some(idx);
+                break;
+            }
+            (None, _) => {}
+        }
+    }
+    let end = end.unwrap_or(after_name.len());
+    let attrs = &after_name[..end];
+    let self_closing = attrs.trim_end().ends_with('/') || VOID_ELEMENTS.contains(&name.as_str());
+    let rest = after_name.get(end + 1..).unwrap_or("");
+    Some((
+        Tag {
+            name,
+            attrs,
+            closing,
+            self_closing,
+        },
+        rest,
+    ))
+}
+
+/// Split raw element text at its closing tag.
+fn split_raw_text<'a>(input: &'a str, name: &str) -> (&'a str, &'a str) {
+    let needle = format!("</{name}");
+    let lower = input.to_ascii_lowercase();
+    match lower.find(&needle) {
+        Some(start) => {
+            let after = &input[start..];
+            let rest = after.find('>').map_or("", |end| &after[end + 1..]);
+            (&input[..start], rest)
+        }
+        None => (input, ""),
+    }
+}
+
+/// Accumulates markdown while keeping whitespace tidy: runs of whitespace in
+/// text collapse to one space and block breaks never stack beyond a blank
+/// line.
+#[derive(Default)]
+struct Writer {
+    out: String,
+    pending_space: bool,
+}
+
+impl Writer {
+    fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+        self.pending_space = false;
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if text.starts_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+        for word in text.split_whitespace() {
+            self.flush_space();
+            self.out.push_str(word);
+            self.pending_space = true;
+        }
+        if !text.ends_with(char::is_whitespace) {
+            self.pending_space = false;
+        }
+    }
+
+    fn push_preformatted(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.pending_space = false;
+    }
+
+    /// Emphasis and code markers hug the text they wrap.
+    fn inline_marker(&mut self, marker: &str, closing: bool) {
+        if closing {
+            let pending = self.pending_space;
+            self.out.push_str(marker);
+            self.pending_space = pending;
+        } else {
+            self.flush_space();
+            self.out.push_str(marker);
+        }
+    }
+
+    fn close_link(&mut self, target: &str) {
+        if self.out.ends_with('[') {
+            // Nothing inside the link worth keeping.
+            self.out.pop();
+            return;
+        }
+        let pending = self.pending_space;
+        self.out.push_str(&format!("]({target})"));
+        self.pending_space = pending;
+    }
+
+    fn flush_space(&mut self) {
+        if self.pending_space && !self.out.is_empty() && !self.out.ends_with([' ', '\n', '[']) {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn line_break(&mut self) {
+        self.trim_trailing_spaces();
+        self.out.push('\n');
+        self.pending_space = false;
+    }
+
+    /// Make sure the output ends with at least `newlines` line breaks.
+    fn block_break(&mut self, newlines: usize) {
+        self.trim_trailing_spaces();
+        self.pending_space = false;
+        if self.out.is_empty() {
+            return;
+        }
+        let existing = self.out.len() - self.out.trim_end_matches('\n').len();
+        for _ in existing..newlines {
+            self.out.push('\n');
+        }
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+    }
+
+    fn finish(self) -> String {
+        let mut result = String::with_capacity(self.out.len());
+        let mut blank_lines = 0;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_lines += 1;
+                if blank_lines > 1 {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+        result.trim().to_string()
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decode character references. Unknown named references are left as-is.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            decode_entity(entity).map(|ch| (ch, end + 1))
+        });
+        match decoded {
+            Some((ch, len)) => {
+                out.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(numeric) = entity.strip_prefix('#') {
+        let code = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => numeric.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    let ch = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "times" => '×',
+        "rarr" => '→',
+        "larr" => '←',
+        _ => return None,
+    };
+    Some(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn converts_structure_and_drops_page_chrome() {
+        let html = r#"<!doctype html>
+<html><head><title>Release &amp; notes</title>
+<style>body { color: red; }</style>
+<script>if (a < b) { document.write("<p>nope</p>"); }</script></head>
+<body>
+<nav><ul><li><a href="/">Home</a></li></ul></nav>
+<main>
+  <h1>Version   2.0</h1>
+  <p>Adds <strong>streaming</strong> and <em>retries</em>. See the
+     <a href="guide/setup.html">setup guide</a> or <a href="javascript:void(0)">this</a>.</p>
+  <ol><li>Install</li><li>Run <code>tool --init</code></li></ol>
+  <pre><code>fn main() {
+    println!("hi");
+}</code></pre>
+  <!-- hidden -->
+  <p>Footnote&nbsp;one &#x2192; two<br>next line</p>
+</main>
+<footer>Copyright</footer>
+</body></html>"#;
+        let base = Url::parse("https://example.com/docs/index.html").expect("url");
+        let doc = html_to_markdown(html, Some(&base));
+
+        assert_eq!(doc.title.as_deref(), Some("Release & notes"));
+        assert_eq!(
+            doc.markdown,
+            "# Version 2.0\n\n\
+             Adds **streaming** and *retries*. See the \
+             [setup guide](https://example.com/docs/guide/setup.html) or this.\n\n\
+             1. Install\n\
+             2. Run `tool --init`\n\n\
+             ```\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             Footnote one → two\n\
+             next line"
+        );
+    }
+
+    #[test]
+    fn nests_lists_and_keeps_image_alt_text() {
+        let html = r#"<ul><li>One<ul><li>Inner</li></ul></li><li>Two</li></ul>
+<p><img src="/logo.png" alt="Logo"><img src="/spacer.gif"></p>"#;
+        let base = Url::parse("https://example.com/a/").expect("url");
+        let doc = html_to_markdown(html, Some(&base));
+
+        assert_eq!(doc.title, None);
+        assert_eq!(
+            doc.markdown,
+            "- One\n  - Inner\n- Two\n\n![Logo](https://example.com/logo.png)"
+        );
+    }
+}
@@ -0,0 +1,693 @@
+//! Web page fetching behind the `fetch_url` tool.
+//!
+//! Requests are made from the Codex process rather than the sandbox, so the
+//! tool handler asks for approval when the sandbox has no network access,
+//! again for every redirect hop to a host not yet approved for the fetch, and
+//! the `[fetch_url]` domain policy is checked for the requested URL and again
+//! for every redirect hop. Host names are resolved through a resolver that
+//! refuses local, private and metadata addresses, so a public name cannot
+//! point a hop at an internal service. HTML is converted to markdown and
+//! split into pages, and converted documents are cached for the rest of the
+//! session so paging through a document does not refetch it.
+
+mod html;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::dns::Addrs;
+use reqwest::dns::Name;
+use reqwest::dns::Resolve;
+use reqwest::dns::Resolving;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::LOCATION;
+use url::Host;
+use url::Url;
+
+use crate::config::types::FetchUrlConfig;
+use crate::default_client::apply_network_settings;
+use crate::default_client::get_codex_user_agent;
+use crate::text_encoding::bytes_to_string_smart;
+
+const DEFAULT_PAGE_CHARS: usize = 20_000;
+const DEFAULT_MAX_BYTES: usize = 5 * 1024 * 1024;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_REDIRECTS: usize = 10;
+const MAX_CACHED_DOCUMENTS: usize = 32;
+
+/// A fetched and converted document, split into pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FetchedDocument {
+    pub(crate) final_url: String,
+    pub(crate) status: u16,
+    pub(crate) title: Option<String>,
+    pub(crate) pages: Vec<String>,
+}
+
+impl FetchedDocument {
+    /// Text returned to the model for the 1-based `page`.
+    pub(crate) fn render_page(&self, page: usize) -> Result<String, String> {
+        let total = self.pages.len();
+        let Some(content) = page.checked_sub(1).and_then(|idx| self.pages.get(idx)) else {
+            return Err(format!(
+                "page {page} is out of range; {} has {total} page(s)",
+                self.final_url
+            ));
+        };
+        let mut out = String::new();
+        if let Some(title) = &self.title {
+            out.push_str(&format!("Title: {title}\n"));
+        }
+        out.push_str(&format!(
+            "URL: {}\nPage {page} of {total}\n\n",
+            self.final_url
+        ));
+        if content.is_empty() {
+            out.push_str("(no readable content)");
+        } else {
+            out.push_str(content);
+        }
+        if page < total {
+            out.push_str(&format!(
+                "\n\n[Page {page} of {total}. Call fetch_url with page={} to continue.]",
+                page + 1
+            ));
+        }
+        Ok(out)
+    }
+}
+
+/// Per-session fetcher holding the domain policy and the document cache.
+pub(crate) struct WebFetcher {
+    policy: DomainPolicy,
+    page_chars: usize,
+    max_bytes: usize,
+    timeout: Duration,
+    cache: Mutex<DocumentCache>,
+}
+
+#[derive(Default)]
+struct DocumentCache {
+    documents: HashMap<String, Arc<FetchedDocument>>,
+    /// Keys in insertion order, oldest first.
+    order: VecDeque<String>,
+}
+
+impl WebFetcher {
+    pub(crate) fn new(config: &FetchUrlConfig) -> Self {
+        Self {
+            policy: DomainPolicy::new(config),
+            page_chars: config.page_chars.unwrap_or(DEFAULT_PAGE_CHARS).max(1),
+            max_bytes: config.max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+            timeout: config
+                .timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_TIMEOUT),
+            cache: Mutex::new(DocumentCache::default()),
+        }
+    }
+
+    /// Fetch `url`, or return the cached document from an earlier call. The
+    /// flag is true when the document came from the cache. The caller approves
+    /// the host of `url`; `approve_host` is asked before a redirect leads to
+    /// any other host.
+    pub(crate) async fn fetch<F, Fut>(
+        &self,
+        url: &str,
+        approve_host: F,
+    ) -> Result<(Arc<FetchedDocument>, bool), String>
+    where
+        F: Fn(Url) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        let mut url =
+            Url::parse(url.trim()).map_err(|err| format!("invalid URL `{url}`: {err}"))?;
+        url.set_fragment(None);
+        let key = url.to_string();
+        if let Some(document) = self.cached(&key) {
+            return Ok((document, true));
+        }
+
+        let document = Arc::new(self.fetch_uncached(url, approve_host).await?);
+        self.insert(key, Arc::clone(&document));
+        Ok((document, false))
+    }
+
+    fn cached(&self, key: &str) -> Option<Arc<FetchedDocument>> {
+        let cache = self.cache.lock().ok()?;
+        cache.documents.get(key).cloned()
+    }
+
+    fn insert(&self, key: String, document: Arc<FetchedDocument>) {
+        let Ok(mut cache) = self.cache.lock() else {
+            return;
+        };
+        if cache.documents.insert(key.clone(), document).is_none() {
+            cache.order.push_back(key);
+        }
+        while cache.order.len() > MAX_CACHED_DOCUMENTS {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.documents.remove(&oldest);
+            }
+        }
+    }
+
+    async fn fetch_uncached<F, Fut>(
+        &self,
+        mut url: Url,
+        approve_host: F,
+    ) -> Result<FetchedDocument, String>
+    where
+        F: Fn(Url) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        let resolver = Arc::new(PublicAddressResolver::default());
+        let builder = reqwest::Client::builder()
+            .user_agent(get_codex_user_agent())
+            .timeout(self.timeout)
+            .dns_resolver(Arc::clone(&resolver))
+            // Redirects are followed below so each hop goes through the policy.
+            .redirect(reqwest::redirect::Policy::none());
+        let client = apply_network_settings(builder)
+            .build()
+            .map_err(|err| format!("failed to build HTTP client: {err}"))?;
+
+        let mut approved_hosts: HashSet<String> = url
+            .host_str()
+            .map(str::to_ascii_lowercase)
+            .into_iter()
+            .collect();
+        let mut redirects = 0;
+        let mut response = loop {
+            self.policy.check(&url)?;
+            if let Some(host) = url.host_str().map(str::to_ascii_lowercase)
+                && !approved_hosts.contains(&host)
+            {
+                approve_host(url.clone()).await?;
+                approved_hosts.insert(host);
+            }
+            if let Some(Host::Domain(domain)) = url.host()
+                && !self.policy.is_allowlisted(domain)
+            {
+                resolver.guard(domain);
+            }
+            let response = client
+                .get(url.clone())
+                .header(
+                    reqwest::header::ACCEPT,
+                    "text/html, text/markdown, text/plain;q=0.9, */*;q=0.5",
+                )
+                .send()
+                .await
+                .map_err(|err| format!("failed to fetch {url}: {}", root_cause(&err)))?;
+            if !response.status().is_redirection() {
+                break response;
+            }
+            let Some(location) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+            else {
+                break response;
+            };
+            redirects += 1;
+            if redirects > MAX_REDIRECTS {
+                return Err(format!("too many redirects fetching {url}"));
+            }
+            url = url
+                .join(location)
+                .map_err(|err| format!("invalid redirect from {url} to `{location}`: {err}"))?;
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("{url} returned HTTP {}", describe_status(status)));
+        }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_ascii_lowercase);
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| format!("failed to read response from {url}: {err}"))?
+        {
+            let room = self.max_bytes.saturating_sub(body.len());
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let text = bytes_to_string_smart(&body);
+
+        let (title, mut markdown) = match ContentKind::detect(content_type.as_deref(), &text) {
+            ContentKind::Html => {
+                let document = html::html_to_markdown(&text, Some(&url));
+                (document.title, document.markdown)
+            }
+            ContentKind::Text => (None, text.trim().to_string()),
+            ContentKind::Unsupported(content_type) => {
+                return Err(format!(
+                    "{url} returned `{content_type}`; fetch_url only reads HTML and text"
+                ));
+            }
+        };
+        if truncated {
+            markdown.push_str(&format!(
+                "\n\n[Content truncated after {} bytes.]",
+                self.max_bytes
+            ));
+        }
+
+        Ok(FetchedDocument {
+            final_url: url.to_string(),
+            status: status.as_u16(),
+            title,
+            pages: paginate(&markdown, self.page_chars),
+        })
+    }
+}
+
+/// The innermost error, which names the actual failure (such as a refused
+/// address) rather than reqwest's generic "error sending request".
+fn root_cause(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut cause = err;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    cause.to_string()
+}
+
+fn describe_status(status: StatusCode) -> String {
+    match status.canonical_reason() {
+        Some(reason) => format!("{} {reason}", status.as_u16()),
+        None => status.as_u16().to_string(),
+    }
+}
+
+enum ContentKind {
+    Html,
+    Text,
+    Unsupported(String),
+}
+
+impl ContentKind {
+    fn detect(content_type: Option<&str>, body: &str) -> Self {
+        let Some(content_type) = content_type else {
+            return if body.trim_start().starts_with('<') {
+                ContentKind::Html
+            } else {
+                ContentKind::Text
+            };
+        };
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        if mime.contains("html") {
+            ContentKind::Html
+        } else if mime.starts_with("text/")
+            || mime.ends_with("json")
+            || mime.ends_with("xml")
+            || mime.ends_with("javascript")
+            || mime.ends_with("yaml")
+            || mime.ends_with("toml")
+        {
+            ContentKind::Text
+        } else {
+            ContentKind::Unsupported(mime.to_string())
+        }
+    }
+}
+
+/// Split `text` into pages of at most `page_chars` characters, preferring to
+/// break between paragraphs, then lines, then words.
+fn paginate(text: &str, page_chars: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut rest = text;
+    loop {
+        let Some((limit, _)) = rest.char_indices().nth(page_chars) else {
+            pages.push(rest.to_string());
+            break;
+        };
+        let window = &rest[..limit];
+        let min = limit / 2;
+        let cut = window
+            .rfind("\n\n")
+            .filter(|idx| *idx >= min)
+            .map(|idx| idx + 2)
+            .or_else(|| {
+                window
+                    .rfind('\n')
+                    .filter(|idx| *idx >= min)
+                    .map(|idx| idx + 1)
+            })
+            .or_else(|| {
+                window
+                    .rfind(' ')
+                    .filter(|idx| *idx >= min)
+                    .map(|idx| idx + 1)
+            })
+            .unwrap_or(limit);
+        pages.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start_matches('\n');
+        if rest.is_empty() {
+            break;
+        }
+    }
+    pages
+}
+
+/// The `[fetch_url]` allowlist and denylist.
+struct DomainPolicy {
+    allowed: Vec<String>,
+    denied: Vec<String>,
+}
+
+impl DomainPolicy {
+    fn new(config: &FetchUrlConfig) -> Self {
+        Self {
+            allowed: normalize_domains(&config.allowed_domains),
+            denied: normalize_domains(&config.denied_domains),
+        }
+    }
+
+    fn check(&self, url: &Url) -> Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!(
+                "cannot fetch `{url}`: only http and https URLs are supported"
+            ));
+        }
+        let host = url
+            .host()
+            .ok_or_else(|| format!("cannot fetch `{url}`: the URL has no host"))?;
+        let name = match &host {
+            Host::Domain(domain) => domain.trim_end_matches('.').to_ascii_lowercase(),
+            Host::Ipv4(ip) => ip.to_string(),
+            Host::Ipv6(ip) => ip.to_string(),
+        };
+
+        if domain_listed(&self.denied, &name) {
+            return Err(format!("{name} is blocked by fetch_url.denied_domains"));
+        }
+        let allowlisted = self.is_allowlisted(&name);
+        if !self.allowed.is_empty() && !allowlisted {
+            return Err(format!("{name} is not in fetch_url.allowed_domains"));
+        }
+        if !allowlisted && is_local_host(&host) {
+            return Err(format!(
+                "{name} is a local or private address; add it to fetch_url.allowed_domains to fetch it"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether `host` was allowlisted explicitly, which also allows it to
+    /// resolve to a local or private address.
+    fn is_allowlisted(&self, host: &str) -> bool {
+        domain_listed(&self.allowed, &normalize_host(host))
+    }
+}
+
+/// Resolves host names for `fetch_url` and refuses any that map to a local,
+/// private or cloud metadata address (169.254.169.254 is link-local). Only
+/// guarded names are checked: those are the hosts being fetched, while any
+/// other lookup is for the HTTP proxy, which resolves the target itself.
+#[derive(Default)]
+struct PublicAddressResolver {
+    guarded: Mutex<HashSet<String>>,
+}
+
+impl PublicAddressResolver {
+    fn guard(&self, host: &str) {
+        if let Ok(mut guarded) = self.guarded.lock() {
+            guarded.insert(normalize_host(host));
+        }
+    }
+
+    fn is_guarded(&self, host: &str) -> bool {
+        match self.guarded.lock() {
+            Ok(guarded) => guarded.contains(host),
+            Err(_) => true,
+        }
+    }
+}
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = normalize_host(name.as_str());
+        let guarded = self.is_guarded(&host);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if guarded && let Some(addr) = addrs.iter().find(|addr| is_local_ip(&addr.ip())) {
+                return Err(format!(
+                    "{host} resolves to {}, a local or private address; add it to fetch_url.allowed_domains to fetch it",
+                    addr.ip()
+                )
+                .into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn normalize_domains(domains: &[String]) -> Vec<String> {
+    domains
+        .iter()
+        .map(|domain| {
+            domain
+                .trim()
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+                .trim_end_matches('.')
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_ascii_lowercase()
+        })
+        .filter(|domain| !domain.is_empty())
+        .collect()
+}
+
+/// Whether `host` equals one of `domains` or is a subdomain of one.
+fn domain_listed(domains: &[String], host: &str) -> bool {
+    domains.iter().any(|domain| {
+        host == domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+fn is_local_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost") || domain.ends_with(".local")
+        }
+        Host::Ipv4(ip) => is_local_ipv4(*ip),
+        Host::Ipv6(ip) => is_local_ipv6(ip),
+    }
+}
+
+fn is_local_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_local_ipv4(*ip),
+        IpAddr::V6(ip) => is_local_ipv6(ip),
+    }
+}
+
+fn is_local_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // Carrier-grade NAT, 100.64.0.0/10.
+        || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+}
+
+fn is_local_ipv6(ip: &Ipv6Addr) -> bool {
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_local_ipv4(mapped);
+    }
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // Unique local, fc00::/7.
+        || (first & 0xfe00) == 0xfc00
+        // Link local, fe80::/10.
+        || (first & 0xffc0) == 0xfe80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn policy(allowed: &[&str], denied: &[&str]) -> DomainPolicy {
+        DomainPolicy::new(&FetchUrlConfig {
+            allowed_domains: allowed.iter().map(ToString::to_string).collect(),
+            denied_domains: denied.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        })
+    }
+
+    fn check(policy: &DomainPolicy, url: &str) -> Result<(), String> {
+        policy.check(&Url::parse(url).expect("valid url"))
+    }
+
+    #[test]
+    fn domain_policy_matches_subdomains_and_blocks_local_hosts() {
+        let open = policy(&[], &["*.tracker.example"]);
+        assert_eq!(check(&open, "https://docs.rs/serde"), Ok(()));
+        assert_eq!(
+            check(&open, "https://ads.tracker.example/pixel"),
+            Err("ads.tracker.example is blocked by fetch_url.denied_domains".to_string())
+        );
+        assert!(check(&open, "http://127.0.0.1:8080/").is_err());
+        assert!(check(&open, "http://192.168.1.10/").is_err());
+        assert!(check(&open, "http://[::1]/").is_err());
+        assert!(check(&open, "http://localhost/").is_err());
+        assert!(check(&open, "file:///etc/passwd").is_err());
+
+        let restricted = policy(&["example.com", "127.0.0.1"], &["private.example.com"]);
+        assert_eq!(check(&restricted, "https://www.example.com/a"), Ok(()));
+        assert_eq!(check(&restricted, "http://127.0.0.1:3000/"), Ok(()));
+        assert_eq!(
+            check(&restricted, "https://notexample.com/"),
+            Err("notexample.com is not in fetch_url.allowed_domains".to_string())
+        );
+        assert!(check(&restricted, "https://private.example.com/").is_err());
+    }
+
+    #[tokio::test]
+    async fn resolver_refuses_local_addresses_for_guarded_hosts() {
+        let resolver = PublicAddressResolver::default();
+        let name = |host: &str| host.parse::<Name>().expect("valid name");
+
+        assert!(resolver.resolve(name("localhost")).await.is_ok());
+
+        resolver.guard("LOCALHOST.");
+        let err = match resolver.resolve(name("localhost")).await {
+            Ok(_) => panic!("localhost should be refused"),
+            Err(err) => err.to_string(),
+        };
+        assert!(
+            err.starts_with("localhost resolves to "),
+            "unexpected error: {err}"
+        );
+        assert!(is_local_ip(&IpAddr::V4(Ipv4Addr::new(169, 254, 169, 254))));
+        assert!(!is_local_ip(&IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34))));
+    }
+
+    #[test]
+    fn paginate_prefers_paragraph_boundaries() {
+        let text = "first paragraph\n\nsecond paragraph is longer\n\nthird";
+        assert_eq!(
+            paginate(text, 30),
+            vec![
+                "first paragraph".to_string(),
+                "second paragraph is longer".to_string(),
+                "third".to_string(),
+            ]
+        );
+        assert_eq!(paginate("short", 30), vec!["short".to_string()]);
+        assert_eq!(paginate("", 30), vec![String::new()]);
+    }
+
+    #[test]
+    fn render_page_adds_header_and_continuation_hint() {
+        let document = FetchedDocument {
+            final_url: "https://example.com/".to_string(),
+            status: 200,
+            title: Some("Example".to_string()),
+            pages: vec!["one".to_string(), "two".to_string()],
+        };
+        assert_eq!(
+            document.render_page(1),
+            Ok(
+                "Title: Example\nURL: https://example.com/\nPage 1 of 2\n\none\n\n\
+                [Page 1 of 2. Call fetch_url with page=2 to continue.]"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            document.render_page(2),
+            Ok("Title: Example\nURL: https://example.com/\nPage 2 of 2\n\ntwo".to_string())
+        );
+        assert!(document.render_page(3).is_err());
+    }
+
+    #[tokio::test]
+    async fn redirects_to_another_host_need_approval() {
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::path;
+
+        let server = MockServer::start().await;
+        let port = server.address().port();
+        Mock::given(path("/start"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", format!("http://localhost:{port}/page")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/page"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("notes"))
+            .mount(&server)
+            .await;
+        let fetcher = WebFetcher::new(&FetchUrlConfig {
+            allowed_domains: vec!["127.0.0.1".to_string(), "localhost".to_string()],
+            ..Default::default()
+        });
+        let start = format!("http://127.0.0.1:{port}/start");
+
+        let approved = Mutex::new(Vec::new());
+        let (document, _) = fetcher
+            .fetch(&start, |url: Url| {
+                approved
+                    .lock()
+                    .expect("lock")
+                    .push(url.host_str().unwrap_or_default().to_string());
+                async { Ok(()) }
+            })
+            .await
+            .expect("fetch");
+        assert_eq!(document.final_url, format!("http://localhost:{port}/page"));
+        assert_eq!(
+            *approved.lock().expect("lock"),
+            vec!["localhost".to_string()]
+        );
+
+        let refused = WebFetcher::new(&FetchUrlConfig {
+            allowed_domains: vec!["127.0.0.1".to_string(), "localhost".to_string()],
+            ..Default::default()
+        })
+        .fetch(&start, |_| async {
+            Err("the user declined the fetch".to_string())
+        })
+        .await;
+        assert_eq!(refused, Err("the user declined the fetch".to_string()));
+    }
+}
@@ -63,6 +63,8 @@ async fn run_request(input: Vec<ResponseItem>) -> Value {
         auth_header_type: AuthHeaderType::Bearer,
         is_azure: false,
         skip_azure_detection: true,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let codex_home = match TempDir::new() {
@@ -62,6 +62,8 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         auth_header_type: AuthHeaderType::Bearer,
         is_azure: false,
         skip_azure_detection: true,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let codex_home = match TempDir::new() {
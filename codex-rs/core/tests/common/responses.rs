@@ -3,6 +3,7 @@ use std::sync::Mutex;
 
 use anyhow::Result;
 use base64::Engine;
+use codex_core::Cassette;
 use codex_protocol::openai_models::ModelsResponse;
 use serde_json::Value;
 use wiremock::BodyPrintLimit;
@@ -689,6 +690,57 @@ pub async fn mount_models_once_with_etag(
     models_mock
 }
 
+/// Mount every recorded response of a cassette (see `codex exec --record`)
+/// on `server`, in recording order. Requests are matched by method and final
+/// path segment so a cassette captured against any provider host can be
+/// replayed against the mock server; the returned mock captures the requests
+/// Codex sends so regression tests can assert on them.
+///
+/// Interactions that failed before any response arrived cannot be reproduced
+/// by wiremock and are skipped.
+pub async fn mount_cassette(server: &MockServer, cassette: &Cassette) -> ResponseMock {
+    let response_mock = ResponseMock::new();
+    for interaction in &cassette.interactions {
+        let Some(response) = &interaction.response else {
+            continue;
+        };
+        let url_path = interaction
+            .request
+            .url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default();
+        let last_segment = url_path.rsplit('/').next().unwrap_or_default();
+        let content_type = response
+            .headers
+            .get("content-type")
+            .cloned()
+            .unwrap_or_else(|| {
+                if response.streamed {
+                    "text/event-stream".to_string()
+                } else {
+                    "application/json".to_string()
+                }
+            });
+
+        Mock::given(method(interaction.request.method.as_str()))
+            .and(path_regex(format!(
+                "/{}$",
+                regex_lite::escape(last_segment)
+            )))
+            .and(response_mock.clone())
+            .respond_with(
+                ResponseTemplate::new(response.status)
+                    .insert_header("content-type", content_type.as_str())
+                    .set_body_raw(response.body.clone(), content_type.as_str()),
+            )
+            .up_to_n_times(1)
+            .mount(server)
+            .await;
+    }
+    response_mock
+}
+
 pub async fn start_mock_server() -> MockServer {
     let server = MockServer::builder()
         .body_print_limit(BodyPrintLimit::Limited(80_000))
@@ -56,6 +56,8 @@ async fn responses_stream_includes_subagent_header_on_review() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -154,6 +156,8 @@ async fn responses_stream_includes_subagent_header_on_other() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -248,6 +252,8 @@ async fn responses_respects_model_family_overrides_from_config() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
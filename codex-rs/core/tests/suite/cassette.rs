@@ -0,0 +1,165 @@
+//! Record-and-replay of model HTTP traffic through cassettes.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use codex_core::Cassette;
+use codex_core::CassetteMode;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_cassette;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+/// Streams are recorded when the client drops them, which can trail the
+/// `TaskComplete` event slightly.
+async fn wait_for_cassette(path: &Path, interactions: usize) -> Result<Cassette> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Ok(cassette) = Cassette::load(path)
+            && cassette.interactions.len() >= interactions
+        {
+            return Ok(cassette);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            anyhow::bail!(
+                "cassette {} never reached {interactions} interactions",
+                path.display()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+async fn record_session(dir: &TempDir) -> Result<(PathBuf, String)> {
+    let server = start_mock_server().await;
+    let body = sse(vec![
+        ev_response_created("resp-1"),
+        ev_assistant_message("msg-1", "recorded answer"),
+        ev_completed("resp-1"),
+    ]);
+    mount_sse_once(&server, body.clone()).await;
+
+    let path = dir.path().join("session.json");
+    let record_path = path.clone();
+    let test = test_codex()
+        .with_config(move |config| {
+            config.http_cassette = Some(CassetteMode::Record(record_path));
+        })
+        .build(&server)
+        .await?;
+    test.submit_turn("hello").await?;
+    wait_for_cassette(&path, 1).await?;
+    Ok((path, body))
+}
+
+async fn next_agent_message(test: &TestCodex) -> Result<String> {
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    let message = wait_for_event_match(&test.codex, |event| match event {
+        EventMsg::AgentMessage(message) => Some(message.message.clone()),
+        _ => None,
+    })
+    .await;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(message)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn records_session_with_redacted_credentials() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let dir = TempDir::new()?;
+    let (path, body) = record_session(&dir).await?;
+
+    let cassette = Cassette::load(&path)?;
+    assert_eq!(cassette.interactions.len(), 1);
+    let interaction = &cassette.interactions[0];
+    assert_eq!(interaction.request.method, "POST");
+    assert!(interaction.request.url.ends_with("/responses"));
+    assert_eq!(
+        interaction
+            .request
+            .headers
+            .get("authorization")
+            .map(String::as_str),
+        Some("[REDACTED]")
+    );
+    let response = interaction.response.as_ref().expect("recorded response");
+    assert_eq!(response.status, 200);
+    assert!(response.streamed);
+    assert_eq!(response.body, body);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replays_cassette_without_contacting_the_provider() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let dir = TempDir::new()?;
+    let (path, _) = record_session(&dir).await?;
+
+    // Nothing is mounted for `/responses`; only the cassette can answer.
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(move |config| {
+            config.http_cassette = Some(CassetteMode::Replay(path));
+        })
+        .build(&server)
+        .await?;
+
+    assert_eq!(next_agent_message(&test).await?, "recorded answer");
+    let requests = server.received_requests().await.unwrap_or_default();
+    assert!(
+        requests
+            .iter()
+            .all(|request| !request.url.path().ends_with("/responses")),
+        "replay should not hit the network"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mount_cassette_serves_recorded_responses() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let dir = TempDir::new()?;
+    let (path, _) = record_session(&dir).await?;
+    let cassette = Cassette::load(&path)?;
+
+    let server = start_mock_server().await;
+    let mock = mount_cassette(&server, &cassette).await;
+    let test = test_codex().build(&server).await?;
+
+    assert_eq!(next_agent_message(&test).await?, "recorded answer");
+    assert_eq!(
+        mock.single_request().message_input_texts("user").last(),
+        Some(&"hello".to_string())
+    );
+
+    Ok(())
+}
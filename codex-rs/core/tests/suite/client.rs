@@ -1,3 +1,6 @@
+use codex_core::APIM_DEFAULT_API_VERSION;
+use codex_core::APIM_SUBSCRIPTION_KEY_HEADER;
+use codex_core::ApimConfig;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
 use codex_core::ContentItem;
@@ -52,6 +55,7 @@ use wiremock::Mock;
 use wiremock::MockServer;
 use wiremock::ResponseTemplate;
 use wiremock::matchers::body_string_contains;
+use wiremock::matchers::header;
 use wiremock::matchers::header_regex;
 use wiremock::matchers::method;
 use wiremock::matchers::path;
@@ -854,6 +858,8 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
         is_azure: false,
         // Skip Azure detection to avoid requiring Azure auth in test
         skip_azure_detection: true,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let codex_home = TempDir::new().unwrap();
@@ -1356,6 +1362,8 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     // Init session
@@ -1441,6 +1449,8 @@ async fn env_var_overrides_loaded_auth() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     // Init session
@@ -1471,6 +1481,69 @@ async fn env_var_overrides_loaded_auth() {
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn apim_provider_sends_subscription_key_with_bearer_token() {
+    skip_if_no_network!();
+    let existing_env_var_with_random_value = if cfg!(windows) { "USERNAME" } else { "USER" };
+    let subscription_key = std::env::var(existing_env_var_with_random_value).unwrap();
+
+    let server = MockServer::start().await;
+    let first = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(sse_completed("resp1"), "text/event-stream");
+
+    // The APIM path prefix and default api-version are applied, and both
+    // credentials arrive on the same request.
+    Mock::given(method("POST"))
+        .and(path("/ai/openai/responses"))
+        .and(query_param("api-version", APIM_DEFAULT_API_VERSION))
+        .and(header("authorization", "Bearer entra-token"))
+        .and(header(APIM_SUBSCRIPTION_KEY_HEADER, subscription_key.as_str()))
+        .respond_with(first)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = ModelProviderInfo {
+        name: "apim".to_string(),
+        base_url: Some(server.uri()),
+        experimental_bearer_token: Some("entra-token".to_string()),
+        wire_api: WireApi::Responses,
+        apim: Some(ApimConfig {
+            path_prefix: Some("ai/openai".to_string()),
+            subscription_key_env_var: Some(existing_env_var_with_random_value.to_string()),
+            ..Default::default()
+        }),
+        ..built_in_model_providers()["openai"].clone()
+    };
+
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home).await;
+    config.model_provider = provider;
+
+    let conversation_manager = ConversationManager::with_models_provider_and_home(
+        create_dummy_codex_auth(),
+        config.model_provider.clone(),
+        config.codex_home.clone(),
+    );
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .expect("create new conversation")
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+}
+
 fn create_dummy_codex_auth() -> CodexAuth {
     CodexAuth::create_dummy_chatgpt_auth_for_testing()
 }
@@ -4,9 +4,11 @@ use codex_core::ConversationManager;
 use codex_core::ModelProviderInfo;
 use codex_core::NewConversation;
 use codex_core::built_in_model_providers;
+use codex_core::compact::STRUCTURED_SUMMARIZATION_PROMPT;
 use codex_core::compact::SUMMARIZATION_PROMPT;
 use codex_core::compact::SUMMARY_PREFIX;
 use codex_core::config::Config;
+use codex_core::config::types::CompactStrategy;
 use codex_core::features::Feature;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
@@ -2036,3 +2038,142 @@ async fn auto_compact_counts_encrypted_reasoning_before_last_user() {
         "third turn should include compaction summary item"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manual_compact_uses_compact_model_and_structured_prompt() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let responses_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("m1", SUMMARY_TEXT),
+            ev_completed("r1"),
+        ]),
+    )
+    .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home).await;
+    config.model_provider = non_openai_model_provider(&server);
+    config.compact_model = Some("gpt-5-mini".to_string());
+    config.compact_strategy = CompactStrategy::Structured;
+    let codex = ConversationManager::with_models_provider(
+        CodexAuth::from_api_key("dummy"),
+        config.model_provider.clone(),
+    )
+    .new_conversation(config)
+    .await
+    .unwrap()
+    .conversation;
+
+    codex.submit(Op::Compact).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let request = responses_mock.single_request();
+    assert_eq!(request.body_json()["model"], "gpt-5-mini");
+    assert!(
+        request
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text == STRUCTURED_SUMMARIZATION_PROMPT),
+        "structured strategy should use the structured summarization prompt"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rolling_compact_keeps_recent_turns_verbatim() {
+    skip_if_no_network!();
+
+    let first_user_message = "rolling first turn";
+    let second_user_message = "rolling second turn";
+    let final_user_message = "rolling follow-up";
+    let server = start_mock_server().await;
+    let responses_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_assistant_message("m1", FIRST_REPLY),
+                ev_completed("r1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", SECOND_LARGE_REPLY),
+                ev_completed("r2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m3", SUMMARY_TEXT),
+                ev_completed("r3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m4", FINAL_REPLY),
+                ev_completed("r4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home).await;
+    config.model_provider = non_openai_model_provider(&server);
+    config.compact_strategy = CompactStrategy::Rolling;
+    config.compact_keep_recent_turns = 1;
+    let codex = ConversationManager::with_models_provider(
+        CodexAuth::from_api_key("dummy"),
+        config.model_provider.clone(),
+    )
+    .new_conversation(config)
+    .await
+    .unwrap()
+    .conversation;
+
+    for text in [first_user_message, second_user_message] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text { text: text.into() }],
+            })
+            .await
+            .unwrap();
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    }
+    codex.submit(Op::Compact).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: final_user_message.into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses_mock.requests();
+    assert_eq!(requests.len(), 4);
+
+    let compact_user_texts = requests[2].message_input_texts("user");
+    assert!(compact_user_texts.iter().any(|t| t == first_user_message));
+    assert!(
+        !compact_user_texts.iter().any(|t| t == second_user_message),
+        "the kept turn should not be summarized"
+    );
+
+    let final_user_texts = requests[3].message_input_texts("user");
+    let summary_index = final_user_texts
+        .iter()
+        .position(|t| *t == summary_with_prefix(SUMMARY_TEXT))
+        .expect("summary in follow-up request");
+    let kept_index = final_user_texts
+        .iter()
+        .position(|t| t == second_user_message)
+        .expect("kept turn in follow-up request");
+    assert!(summary_index < kept_index, "kept turn follows the summary");
+    let final_body = requests[3].body_json().to_string();
+    assert!(
+        final_body.contains(SECOND_LARGE_REPLY),
+        "the kept turn's reply should stay verbatim"
+    );
+    assert!(
+        !final_body.contains(FIRST_REPLY),
+        "the summarized turn's reply should be dropped"
+    );
+}
@@ -0,0 +1,86 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::ev_shell_command_call;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn old_tool_outputs_are_pruned_before_compaction() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_shell_command_call("call-seq", "seq 1 3000"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "counted"),
+                ev_completed("resp-2"),
+            ]),
+            // Usage crosses the compaction limit; pruning the old output must
+            // free enough that no compaction request is made.
+            sse(vec![
+                ev_assistant_message("msg-2", "noted"),
+                ev_completed_with_tokens("resp-3", 10_200),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-3", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.model_auto_compact_token_limit = Some(10_000);
+            config.context_pruning.enabled = true;
+            config.context_pruning.keep_recent_turns = 1;
+            config.context_pruning.min_output_tokens = 100;
+        })
+        .build(&server)
+        .await?;
+    let codex = test.codex.clone();
+
+    for text in ["count to 3000", "remember the count", "what next?"] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text { text: text.into() }],
+            })
+            .await?;
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    }
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 4);
+    let before = requests[2]
+        .function_call_output_text("call-seq")
+        .expect("shell output before pruning");
+    assert!(before.contains("2999"));
+    let after = requests[3]
+        .function_call_output_text("call-seq")
+        .expect("shell output stub after pruning");
+    assert!(
+        after.starts_with("[Output pruned to save context"),
+        "unexpected output: {after}"
+    );
+    assert!(after.contains("shell_command"));
+    assert!(after.contains("seq 1 3000"));
+
+    Ok(())
+}
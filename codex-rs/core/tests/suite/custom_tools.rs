@@ -0,0 +1,100 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use codex_core::config::types::CustomToolConfig;
+use codex_core::config::types::CustomToolInput;
+use core_test_support::responses::mount_function_call_agent_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn tool(command: &[&str], input: CustomToolInput) -> CustomToolConfig {
+    CustomToolConfig {
+        description: "Look up a table in the schema registry".to_string(),
+        command: command.iter().map(ToString::to_string).collect(),
+        parameters: Some(json!({
+            "type": "object",
+            "properties": { "table": { "type": "string" } },
+            "required": ["table"],
+        })),
+        input,
+        cwd: None,
+        env: HashMap::new(),
+        timeout_ms: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_tool_receives_arguments_on_stdin() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.custom_tools.insert(
+                "schema_lookup".to_string(),
+                tool(
+                    &["/bin/sh", "-c", "printf 'input: '; cat"],
+                    CustomToolInput::Stdin,
+                ),
+            );
+        })
+        .build(&server)
+        .await?;
+
+    let call_id = "schema-lookup";
+    let arguments = json!({ "table": "users" }).to_string();
+    let mocks =
+        mount_function_call_agent_response(&server, call_id, &arguments, "schema_lookup").await;
+    test.submit_turn("describe the users table").await?;
+
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("function_call_output present");
+    let output = content.expect("output content present");
+    assert!(
+        output.contains(r#"input: {"table":"users"}"#),
+        "unexpected output: {output}"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_tool_rejects_invalid_arguments() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.custom_tools.insert(
+                "schema_lookup".to_string(),
+                tool(&["/bin/echo", "{{table}}"], CustomToolInput::Argv),
+            );
+        })
+        .build(&server)
+        .await?;
+
+    let call_id = "schema-lookup";
+    let arguments = json!({ "table": 7 }).to_string();
+    let mocks =
+        mount_function_call_agent_response(&server, call_id, &arguments, "schema_lookup").await;
+    test.submit_turn("describe table seven").await?;
+
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("function_call_output present");
+    assert_eq!(
+        content.as_deref(),
+        Some("argument `table` must be of type string")
+    );
+    Ok(())
+}
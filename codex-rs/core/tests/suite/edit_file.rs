@@ -0,0 +1,117 @@
+#![allow(clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::fs;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn edit_file_and_create_file_emit_patch_events() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = core_test_support::responses::start_mock_server().await;
+    let test = test_codex()
+        .with_model("claude-sonnet-4-5")
+        .build(&server)
+        .await?;
+    let target = test.workspace_path("lib.rs");
+    fs::write(&target, "fn a() {\n    x();\n}\n\nfn b() {\n    x();\n}\n")?;
+
+    let edit_args = json!({
+        "path": "lib.rs",
+        "old_string": "    x();",
+        "new_string": "    y();",
+        "occurrence": 2,
+    });
+    let create_args = json!({
+        "path": "notes/todo.md",
+        "content": "- ship it\n",
+    });
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("edit-1", "edit_file", &serde_json::to_string(&edit_args)?),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_function_call(
+                    "create-1",
+                    "create_file",
+                    &serde_json::to_string(&create_args)?,
+                ),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "rename the second call".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let mut patch_ends = Vec::new();
+    let mut turn_diff = None;
+    wait_for_event(&test.codex, |event| match event {
+        EventMsg::PatchApplyEnd(end) => {
+            patch_ends.push((end.call_id.clone(), end.success));
+            false
+        }
+        EventMsg::TurnDiff(ev) => {
+            turn_diff = Some(ev.unified_diff.clone());
+            false
+        }
+        EventMsg::TaskComplete(_) => true,
+        _ => false,
+    })
+    .await;
+
+    assert_eq!(
+        patch_ends,
+        vec![("edit-1".to_string(), true), ("create-1".to_string(), true)]
+    );
+    assert_eq!(
+        fs::read_to_string(&target)?,
+        "fn a() {\n    x();\n}\n\nfn b() {\n    y();\n}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(test.workspace_path("notes/todo.md"))?,
+        "- ship it\n"
+    );
+    let diff = turn_diff.expect("expected TurnDiff event");
+    assert!(diff.contains("+    y();"), "{diff}");
+    assert!(diff.contains("+- ship it"), "{diff}");
+    Ok(())
+}
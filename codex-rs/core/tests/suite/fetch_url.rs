@@ -0,0 +1,220 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::features::Feature;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_function_call_agent_response;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+const RELEASE_NOTES_HTML: &str = "<html><head><title>Release notes</title>\
+<script>track()</script></head><body><nav><a href=\"/\">Home</a></nav>\
+<h1>Release notes</h1><p>First paragraph about streaming.</p>\
+<p>Second paragraph about retries.</p></body></html>";
+
+fn fetch_call(call_id: &str, url: &str, page: u32) -> String {
+    let arguments = json!({ "url": url, "page": page }).to_string();
+    sse(vec![
+        ev_response_created(call_id),
+        ev_function_call(call_id, "fetch_url", &arguments),
+        ev_completed(call_id),
+    ])
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fetch_url_pages_html_and_reuses_cached_document() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let page_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/notes"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/html; charset=utf-8")
+                .set_body_string(RELEASE_NOTES_HTML),
+        )
+        .expect(1)
+        .mount(&page_server)
+        .await;
+    let url = format!("{}/notes", page_server.uri());
+
+    let server = start_mock_server().await;
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::FetchUrl);
+            config.fetch_url.allowed_domains = vec!["127.0.0.1".to_string()];
+            config.fetch_url.page_chars = Some(60);
+        })
+        .build(&server)
+        .await?;
+
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            fetch_call("fetch-1", &url, 1),
+            fetch_call("fetch-2", &url, 2),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "summarize the release notes".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let mut ends = Vec::new();
+    wait_for_event(&codex, |event| match event {
+        EventMsg::FetchUrlEnd(end) => {
+            ends.push(end.clone());
+            false
+        }
+        EventMsg::TaskComplete(_) => true,
+        _ => false,
+    })
+    .await;
+
+    assert_eq!(
+        ends.iter()
+            .map(|end| (end.call_id.as_str(), end.page, end.total_pages, end.cached))
+            .collect::<Vec<_>>(),
+        vec![("fetch-1", 1, 2, false), ("fetch-2", 2, 2, true)]
+    );
+    assert_eq!(ends[0].title.as_deref(), Some("Release notes"));
+    assert_eq!(ends[0].status, Some(200));
+
+    let first = mock
+        .function_call_output_text("fetch-1")
+        .expect("first fetch output");
+    assert_eq!(
+        first,
+        format!(
+            "Title: Release notes\nURL: {url}\nPage 1 of 2\n\n\
+             # Release notes\n\nFirst paragraph about streaming.\n\n\
+             [Page 1 of 2. Call fetch_url with page=2 to continue.]"
+        )
+    );
+    let second = mock
+        .function_call_output_text("fetch-2")
+        .expect("second fetch output");
+    assert!(
+        second.ends_with("Page 2 of 2\n\nSecond paragraph about retries."),
+        "unexpected output: {second}"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fetch_url_refuses_hosts_outside_allowlist() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let page_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("secret"))
+        .expect(0)
+        .mount(&page_server)
+        .await;
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::FetchUrl);
+            config.fetch_url.allowed_domains = vec!["docs.example.com".to_string()];
+        })
+        .build(&server)
+        .await?;
+
+    let call_id = "fetch-local";
+    let arguments = json!({ "url": page_server.uri() }).to_string();
+    let mocks = mount_function_call_agent_response(&server, call_id, &arguments, "fetch_url").await;
+    test.submit_turn("read the local page").await?;
+
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("function_call_output present");
+    assert_eq!(
+        content.as_deref(),
+        Some("127.0.0.1 is not in fetch_url.allowed_domains")
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fetch_url_is_refused_without_sandbox_network_access() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let page_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("notes"))
+        .expect(0)
+        .mount(&page_server)
+        .await;
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::FetchUrl);
+            config.fetch_url.allowed_domains = vec!["127.0.0.1".to_string()];
+        })
+        .build(&server)
+        .await?;
+
+    let call_id = "fetch-offline";
+    let arguments = json!({ "url": page_server.uri() }).to_string();
+    let mocks = mount_function_call_agent_response(&server, call_id, &arguments, "fetch_url").await;
+    test.submit_turn_with_policy("read the notes", SandboxPolicy::ReadOnly)
+        .await?;
+
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("function_call_output present");
+    assert_eq!(
+        content.as_deref(),
+        Some("fetch_url needs network access, which the sandbox policy does not allow")
+    );
+    Ok(())
+}
@@ -0,0 +1,125 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use codex_core::features::Feature;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ResponsesRequest;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::skip_if_sandbox;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+fn git(cwd: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("run git");
+    assert!(output.status.success(), "git {args:?} failed: {output:?}");
+}
+
+/// Environment context messages in the request that carry repository state.
+fn git_contexts(request: &ResponsesRequest) -> Vec<String> {
+    request
+        .message_input_texts("user")
+        .into_iter()
+        .filter(|text| text.starts_with("<environment_context>") && text.contains("<git>"))
+        .collect()
+}
+
+async fn submit_turn(test: &TestCodex, prompt: &str) -> Result<()> {
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: prompt.into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn git_context_is_sent_again_only_when_the_repository_changes() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+    skip_if_sandbox!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        (1..=3)
+            .map(|turn| {
+                let id = format!("resp-{turn}");
+                sse(vec![ev_assistant_message(&id, "ok"), ev_completed(&id)])
+            })
+            .collect(),
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::GitContext);
+        })
+        .build(&server)
+        .await?;
+    let repo = test.cwd.path();
+    git(repo, &["init"]);
+    git(repo, &["config", "user.name", "Test User"]);
+    git(repo, &["config", "user.email", "test@example.com"]);
+    std::fs::write(repo.join("README.md"), "hello")?;
+    git(repo, &["add", "-A"]);
+    git(repo, &["commit", "-m", "Initial commit"]);
+
+    submit_turn(&test, "first").await?;
+    submit_turn(&test, "second").await?;
+    std::fs::write(repo.join("new.txt"), "new")?;
+    submit_turn(&test, "third").await?;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    let first = git_contexts(&requests[0]);
+    assert_eq!(first.len(), 1);
+    assert!(
+        first[0].contains("<changes>clean</changes>") && first[0].contains(" Initial commit"),
+        "git context: {}",
+        first[0]
+    );
+    // Nothing changed, so the second turn only carries the earlier context.
+    assert_eq!(git_contexts(&requests[1]), first);
+
+    let third = git_contexts(&requests[2]);
+    assert_eq!(third.len(), 2);
+    assert!(
+        third[1].contains("<file>?? new.txt</file>"),
+        "git context: {}",
+        third[1]
+    );
+    Ok(())
+}
@@ -19,15 +19,21 @@ mod apply_patch_cli;
 #[cfg(not(target_os = "windows"))]
 mod approvals;
 mod auth_refresh;
+mod cassette;
 mod cli_stream;
 mod client;
 mod codex_delegate;
 mod compact;
 mod compact_resume_fork;
+mod context_pruning;
+mod custom_tools;
 mod deprecation_notice;
+mod edit_file;
 mod exec;
 mod exec_policy;
+mod fetch_url;
 mod fork_conversation;
+mod git_context;
 mod grep_files;
 mod items;
 mod json_result;
@@ -38,6 +44,8 @@ mod model_overrides;
 mod model_tools;
 mod models_etag_responses;
 mod otel;
+mod plan_mode;
+mod project_docs;
 mod prompt_caching;
 mod quota_exceeded;
 mod read_file;
@@ -46,6 +54,7 @@ mod resume;
 mod resume_warning;
 mod review;
 mod rmcp_client;
+mod rollback;
 mod rollout_list_find;
 mod seatbelt;
 mod shell_command;
@@ -64,4 +73,5 @@ mod undo;
 mod unified_exec;
 mod user_notification;
 mod user_shell_cmd;
+mod verify;
 mod view_image;
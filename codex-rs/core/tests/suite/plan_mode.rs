@@ -0,0 +1,217 @@
+use codex_core::protocol::CodexErrorInfo;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_apply_patch_function_call;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::ev_shell_command_call;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn plan_mode_rejects_writes_until_plan_is_approved() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let plan_args = json!({
+        "plan": [{ "step": "Create planned.txt", "status": "pending" }],
+    })
+    .to_string();
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_apply_patch_function_call(
+                    "patch-call",
+                    "*** Begin Patch\n*** Add File: planned.txt\n+hello\n*** End Patch",
+                ),
+                ev_shell_command_call("shell-call", "touch touched.txt"),
+                ev_function_call("plan-call", "update_plan", &plan_args),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "Plan: create planned.txt"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| config.include_apply_patch_tool = true)
+        .build(&server)
+        .await?;
+    let codex = test.codex.clone();
+
+    codex.submit(Op::SetPlanMode { enabled: true }).await?;
+    wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::PlanModeUpdated(ev) if ev.enabled),
+    )
+    .await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "create planned.txt".into(),
+            }],
+        })
+        .await?;
+    let approval = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::PlanApprovalRequest(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(approval.plan.plan[0].step, "Create planned.txt");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    assert!(!test.workspace_path("planned.txt").exists());
+    assert!(!test.workspace_path("touched.txt").exists());
+    let requests = responses.requests();
+    assert!(
+        requests[0]
+            .message_input_texts("developer")
+            .iter()
+            .any(|text| text.starts_with("Plan mode is on."))
+    );
+    for call_id in ["patch-call", "shell-call"] {
+        let output = requests[1]
+            .function_call_output_text(call_id)
+            .unwrap_or_default();
+        assert!(
+            output.contains("plan mode is on"),
+            "{call_id} was not rejected: {output}"
+        );
+    }
+
+    codex.submit(Op::ApprovePlan).await?;
+    wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::PlanModeUpdated(ev) if !ev.enabled),
+    )
+    .await;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(
+        requests[2]
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text.starts_with("The plan is approved."))
+    );
+    assert!(
+        requests[2]
+            .message_input_texts("developer")
+            .iter()
+            .any(|text| text.starts_with("Plan mode is off."))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approving_without_a_plan_is_rejected() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex().build(&server).await?;
+
+    test.codex.submit(Op::ApprovePlan).await?;
+    let error = wait_for_event_match(&test.codex, |ev| match ev {
+        EventMsg::Error(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(error.codex_error_info, Some(CodexErrorInfo::BadRequest));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resumed_session_keeps_plan_mode_and_pending_plan() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let plan_args = json!({
+        "plan": [{ "step": "Create planned.txt", "status": "pending" }],
+    })
+    .to_string();
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("plan-call", "update_plan", &plan_args),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "Plan: create planned.txt"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+    let mut builder = test_codex();
+    let initial = builder.build(&server).await?;
+    let codex = Arc::clone(&initial.codex);
+
+    codex.submit(Op::SetPlanMode { enabled: true }).await?;
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "create planned.txt".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::PlanApprovalRequest(_))).await;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let resumed = builder
+        .resume(
+            &server,
+            initial.home.clone(),
+            initial.session_configured.rollout_path.clone(),
+        )
+        .await?;
+    resumed.codex.submit(Op::ApprovePlan).await?;
+    let event = wait_for_event(&resumed.codex, |ev| {
+        matches!(ev, EventMsg::PlanModeUpdated(_) | EventMsg::Error(_))
+    })
+    .await;
+    assert!(
+        matches!(event, EventMsg::PlanModeUpdated(ref ev) if !ev.enabled),
+        "unexpected event after approving a resumed plan: {event:?}"
+    );
+    wait_for_event(&resumed.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(
+        requests[2]
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text.starts_with("The plan is approved."))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ResponsesRequest;
+use core_test_support::responses::ev_apply_patch_function_call;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+const ADD_NOTES: &str =
+    "*** Begin Patch\n*** Add File: services/foo/notes.txt\n+draft\n*** End Patch";
+const UPDATE_NOTES: &str =
+    "*** Begin Patch\n*** Update File: services/foo/notes.txt\n@@\n-draft\n+final\n*** End Patch";
+
+fn patch_turn(response_id: &str, call_id: &str, patch: &str) -> String {
+    sse(vec![
+        ev_response_created(response_id),
+        ev_apply_patch_function_call(call_id, patch),
+        ev_completed(response_id),
+    ])
+}
+
+/// Nested instruction messages in the request, in order.
+fn nested_instructions(request: &ResponsesRequest) -> Vec<String> {
+    request
+        .message_input_texts("developer")
+        .into_iter()
+        .filter(|text| text.starts_with("# AGENTS.md instructions for "))
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn nested_agents_md_is_loaded_once_when_the_agent_works_under_it() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            patch_turn("resp-1", "patch-1", ADD_NOTES),
+            patch_turn("resp-2", "patch-2", UPDATE_NOTES),
+            sse(vec![
+                ev_assistant_message("resp-3", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.include_apply_patch_tool = true;
+        })
+        .build(&server)
+        .await?;
+    std::fs::create_dir_all(test.workspace_path("services/foo"))?;
+    std::fs::write(
+        test.workspace_path("services/foo/AGENTS.md"),
+        "Run `make test` in this service.",
+    )?;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "write the notes".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    let mut loaded = Vec::new();
+    wait_for_event(&test.codex, |event| {
+        if let EventMsg::ProjectDocLoaded(event) = event {
+            loaded.push(event.path.clone());
+        }
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(nested_instructions(&requests[0]), Vec::<String>::new());
+    let injected = nested_instructions(&requests[1]);
+    assert_eq!(injected.len(), 1);
+    assert!(
+        injected[0].ends_with("<INSTRUCTIONS>\nRun `make test` in this service.\n</INSTRUCTIONS>"),
+        "instructions: {}",
+        injected[0]
+    );
+    // The second edit under the same directory does not inject it again.
+    assert_eq!(nested_instructions(&requests[2]), injected);
+
+    let expected = std::fs::canonicalize(test.workspace_path("services/foo/AGENTS.md"))?;
+    assert_eq!(loaded, vec![expected]);
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use codex_core::protocol::CodexErrorInfo;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+
+fn assistant_reply(id: &str, text: &str) -> String {
+    sse(vec![
+        ev_response_created(id),
+        ev_assistant_message(&format!("{id}-msg"), text),
+        ev_completed(id),
+    ])
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rollback_drops_last_turn_from_next_request() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            assistant_reply("resp-1", "one"),
+            assistant_reply("resp-2", "two"),
+            assistant_reply("resp-3", "three"),
+        ],
+    )
+    .await;
+    let test = test_codex().build(&server).await?;
+    let codex = test.codex.clone();
+
+    for text in ["first prompt", "second prompt"] {
+        codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text { text: text.into() }],
+            })
+            .await?;
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    }
+
+    codex
+        .submit(Op::Rollback {
+            num_turns: 1,
+            restore_worktree: false,
+        })
+        .await?;
+    let rolled_back = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::ThreadRolledBack(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(rolled_back.num_turns, 1);
+    assert!(!rolled_back.worktree_restored);
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "third prompt".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    let user_texts = requests[2].message_input_texts("user");
+    assert!(user_texts.iter().any(|text| text == "first prompt"));
+    assert!(user_texts.iter().any(|text| text == "third prompt"));
+    assert!(
+        !user_texts.iter().any(|text| text == "second prompt"),
+        "rolled back prompt was still sent: {user_texts:?}"
+    );
+    assert!(
+        !requests[2]
+            .message_input_texts("assistant")
+            .iter()
+            .any(|text| text == "two")
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rollback_past_start_of_history_is_rejected() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex().build(&server).await?;
+
+    test.codex
+        .submit(Op::Rollback {
+            num_turns: 1,
+            restore_worktree: false,
+        })
+        .await?;
+    let error = wait_for_event_match(&test.codex, |ev| match ev {
+        EventMsg::Error(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        error.codex_error_info,
+        Some(CodexErrorInfo::ThreadRollbackFailed)
+    );
+
+    Ok(())
+}
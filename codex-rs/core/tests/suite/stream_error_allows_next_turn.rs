@@ -76,6 +76,8 @@ async fn continue_after_stream_error() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let TestCodex { codex, .. } = test_codex()
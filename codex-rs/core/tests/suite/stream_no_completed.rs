@@ -84,6 +84,8 @@ async fn retries_on_early_close() {
         auth_header_type: Default::default(),
         is_azure: false,
         skip_azure_detection: false,
+        auth_headers: Vec::new(),
+        apim: None,
     };
 
     let TestCodex { codex, .. } = test_codex()
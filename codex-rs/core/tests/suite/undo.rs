@@ -2,14 +2,19 @@
 
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::bail;
 use codex_core::CodexConversation;
 use codex_core::features::Feature;
+use codex_core::protocol::Checkpoint;
+use codex_core::protocol::CheckpointRestoreScope;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::UndoCompletedEvent;
@@ -548,3 +553,133 @@ async fn undo_preserves_unrelated_staged_changes() -> Result<()> {
 
     Ok(())
 }
+
+async fn list_checkpoints(codex: &Arc<CodexConversation>) -> Result<Vec<Checkpoint>> {
+    codex.submit(Op::ListCheckpoints).await?;
+    let checkpoints = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::ListCheckpointsResponse(response) => Some(response.checkpoints.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(checkpoints)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn checkpoints_list_turn_files_and_external_changes() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    run_apply_patch_turn(
+        &harness,
+        "add first file\nwith details",
+        "checkpoint-first",
+        "*** Begin Patch\n*** Add File: first.txt\n+one\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    run_apply_patch_turn(
+        &harness,
+        "add second file",
+        "checkpoint-second",
+        "*** Begin Patch\n*** Add File: second.txt\n+two\n*** End Patch",
+        "ok",
+    )
+    .await?;
+
+    // Simulate an edit made after the last turn finished.
+    let notes = harness.path("notes.txt");
+    fs::write(&notes, "mine\n")?;
+    fs::File::options()
+        .write(true)
+        .open(&notes)?
+        .set_modified(SystemTime::now() + Duration::from_secs(60))?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let checkpoints = list_checkpoints(&codex).await?;
+    let summary: Vec<(u32, &str, Vec<PathBuf>, Vec<PathBuf>)> = checkpoints
+        .iter()
+        .map(|checkpoint| {
+            (
+                checkpoint.num_turns,
+                checkpoint.prompt.as_str(),
+                checkpoint.files.clone(),
+                checkpoint.external_changes.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                1,
+                "add second file",
+                vec![PathBuf::from("notes.txt"), PathBuf::from("second.txt")],
+                vec![PathBuf::from("notes.txt")],
+            ),
+            (
+                2,
+                "add first file",
+                vec![PathBuf::from("first.txt")],
+                vec![PathBuf::from("notes.txt")],
+            ),
+        ]
+    );
+    assert!(checkpoints[1].diff_preview.contains("+one"));
+    assert!(!checkpoints[1].diff_truncated);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restoring_checkpoint_worktree_keeps_conversation() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    run_apply_patch_turn(
+        &harness,
+        "add first file",
+        "restore-first",
+        "*** Begin Patch\n*** Add File: first.txt\n+one\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    run_apply_patch_turn(
+        &harness,
+        "add second file",
+        "restore-second",
+        "*** Begin Patch\n*** Add File: second.txt\n+two\n*** End Patch",
+        "ok",
+    )
+    .await?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    codex
+        .submit(Op::RestoreCheckpoint {
+            num_turns: 2,
+            scope: CheckpointRestoreScope::Worktree,
+        })
+        .await?;
+    let completed = wait_for_event_match(&codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert!(completed.success, "restore failed: {:?}", completed.message);
+    assert!(!harness.path("first.txt").exists());
+    assert!(!harness.path("second.txt").exists());
+
+    let checkpoints = list_checkpoints(&codex).await?;
+    assert_eq!(
+        checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.num_turns)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ResponsesRequest;
+use core_test_support::responses::ev_apply_patch_function_call;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+const ADD_NOTES: &str = "*** Begin Patch\n*** Add File: notes.txt\n+draft\n*** End Patch";
+const FIX_NOTES: &str =
+    "*** Begin Patch\n*** Update File: notes.txt\n@@\n-draft\n+final\n*** End Patch";
+
+fn patch_turn(response_id: &str, call_id: &str, patch: &str) -> String {
+    sse(vec![
+        ev_response_created(response_id),
+        ev_apply_patch_function_call(call_id, patch),
+        ev_completed(response_id),
+    ])
+}
+
+fn message_turn(response_id: &str, text: &str) -> String {
+    sse(vec![
+        ev_assistant_message(response_id, text),
+        ev_completed(response_id),
+    ])
+}
+
+/// Returns the arguments and output of every `verify` call in the request.
+fn verify_calls(request: &ResponsesRequest) -> Vec<(Value, String)> {
+    request
+        .inputs_of_type("function_call")
+        .into_iter()
+        .filter(|item| item["name"] == "verify")
+        .map(|item| {
+            let call_id = item["call_id"].as_str().expect("call_id");
+            let arguments: Value =
+                serde_json::from_str(item["arguments"].as_str().expect("arguments"))
+                    .expect("arguments are JSON");
+            let output = request
+                .function_call_output_text(call_id)
+                .expect("verify output recorded");
+            (arguments, output)
+        })
+        .collect()
+}
+
+async fn submit_and_collect(test: &TestCodex, prompt: &str) -> Result<Vec<EventMsg>> {
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: prompt.into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    let mut events = Vec::new();
+    wait_for_event(&test.codex, |event| {
+        events.push(event.clone());
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    Ok(events)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failing_verification_goes_back_to_the_model() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            patch_turn("resp-1", "patch-1", ADD_NOTES),
+            message_turn("resp-2", "wrote the notes"),
+            patch_turn("resp-3", "patch-2", FIX_NOTES),
+            message_turn("resp-4", "fixed the notes"),
+        ],
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.include_apply_patch_tool = true;
+            config.verify.commands = BTreeMap::from([(
+                "*.txt".to_string(),
+                vec!["grep -q final {files}".to_string()],
+            )]);
+        })
+        .build(&server)
+        .await?;
+
+    let events = submit_and_collect(&test, "write the notes").await?;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 4);
+    let calls = verify_calls(&requests[2]);
+    assert_eq!(calls.len(), 1);
+    let (arguments, output) = &calls[0];
+    assert_eq!(arguments["command"], "grep -q final notes.txt");
+    assert!(output.starts_with("Exit code: 1"), "output: {output}");
+    // The fix turn passed verification, so the task ended without sending
+    // that result back to the model.
+    assert_eq!(verify_calls(&requests[3]).len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(test.workspace_path("notes.txt"))?,
+        "final\n"
+    );
+
+    let exit_codes: Vec<i32> = events
+        .iter()
+        .filter_map(|event| match event {
+            EventMsg::ExecCommandEnd(end) if end.source == ExecCommandSource::Verification => {
+                Some(end.exit_code)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(exit_codes, vec![1, 0]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn verification_stops_after_max_fix_attempts() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            patch_turn("resp-1", "patch-1", ADD_NOTES),
+            message_turn("resp-2", "wrote the notes"),
+        ],
+    )
+    .await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.include_apply_patch_tool = true;
+            config.verify.max_fix_attempts = Some(0);
+            config.verify.commands = BTreeMap::from([(
+                "*.txt".to_string(),
+                vec!["grep -q final {files}".to_string()],
+            )]);
+        })
+        .build(&server)
+        .await?;
+
+    let events = submit_and_collect(&test, "write the notes").await?;
+
+    assert_eq!(responses.requests().len(), 2);
+    let warnings: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            EventMsg::Warning(warning) if warning.message.starts_with("Verification") => {
+                Some(warning.message.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "Verification failed with no automatic fix attempts left (verify.max_fix_attempts = 0)."
+        ]
+    );
+    Ok(())
+}
@@ -240,6 +240,90 @@ async fn view_image_tool_attaches_local_image() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn view_image_tool_tiles_tall_screenshots() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex().build(&server).await?;
+
+    let rel_path = "page.png";
+    let image = ImageBuffer::from_pixel(400, 3000, Rgba([255u8, 255, 255, 255]));
+    image.save(cwd.path().join(rel_path))?;
+
+    let call_id = "view-image-tiles";
+    let arguments = serde_json::json!({ "path": rel_path, "detail": "auto" }).to_string();
+
+    let first_response = sse(vec![
+        ev_response_created("resp-1"),
+        ev_function_call(call_id, "view_image", &arguments),
+        ev_completed("resp-1"),
+    ]);
+    responses::mount_sse_once(&server, first_response).await;
+
+    let second_response = sse(vec![
+        ev_assistant_message("msg-1", "done"),
+        ev_completed("resp-2"),
+    ]);
+    let mock = responses::mount_sse_once(&server, second_response).await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "look at the whole page".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let req = mock.single_request();
+    let output_text = req
+        .function_call_output_content_and_success(call_id)
+        .and_then(|(content, _)| content)
+        .expect("output text present");
+    assert_eq!(
+        output_text,
+        "attached tile 1 of 5, tile 2 of 5, tile 3 of 5, tile 4 of 5, tile 5 of 5"
+    );
+
+    let body = req.body_json();
+    let image_message =
+        find_image_message(&body).expect("pending input image message not included in request");
+    let tile_heights: Vec<u32> = image_message
+        .get("content")
+        .and_then(Value::as_array)
+        .expect("image message content")
+        .iter()
+        .filter_map(|span| span.get("image_url").and_then(Value::as_str))
+        .map(|image_url| {
+            let (_, encoded) = image_url
+                .split_once(',')
+                .expect("image url contains data prefix");
+            let decoded = BASE64_STANDARD
+                .decode(encoded)
+                .expect("image data decodes from base64 for request");
+            load_from_memory(&decoded).expect("load tile").height()
+        })
+        .collect();
+    assert_eq!(tile_heights, vec![768, 768, 768, 768, 184]);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn view_image_tool_errors_when_path_is_directory() -> anyhow::Result<()> {
     skip_if_no_network!(Ok(()));
@@ -1,5 +1,6 @@
 use clap::Parser;
 use clap::ValueEnum;
+use codex_common::BudgetCliArgs;
 use codex_common::CliConfigOverrides;
 use std::path::PathBuf;
 
@@ -73,6 +74,16 @@ pub struct Cli {
     #[arg(long = "color", value_enum, default_value_t = Color::Auto)]
     pub color: Color,
 
+    /// Record every model request and response of this session to a cassette
+    /// file. Credentials are redacted before they are written.
+    #[arg(long = "record", value_name = "CASSETTE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve model responses from a cassette written by `--record` instead of
+    /// contacting the provider.
+    #[arg(long = "replay", value_name = "CASSETTE")]
+    pub replay: Option<PathBuf>,
+
     /// Print events to stdout as JSONL.
     #[arg(long = "json", alias = "experimental-json", default_value_t = false)]
     pub json: bool,
@@ -103,6 +114,9 @@ pub struct Cli {
         requires = "loop_mode"
     )]
     pub completion_phrase: Option<String>,
+
+    #[clap(flatten)]
+    pub budget: BudgetCliArgs,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -18,6 +18,8 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentSource;
+use codex_core::protocol::SubAgentStatus;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
@@ -62,6 +64,7 @@ pub(crate) struct EventProcessorWithHumanOutput {
     show_raw_agent_reasoning: bool,
     last_message_path: Option<PathBuf>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsageInfo>,
+    last_cost: Option<codex_core::protocol::TokenCost>,
     final_message: Option<String>,
 }
 
@@ -88,6 +91,7 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_cost: None,
                 final_message: None,
             }
         } else {
@@ -105,6 +109,7 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_cost: None,
                 final_message: None,
             }
         }
@@ -262,6 +267,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             }
             EventMsg::TokenCount(ev) => {
                 self.last_total_token_usage = ev.info;
+                if ev.cost.is_some() {
+                    self.last_cost = ev.cost;
+                }
             }
 
             EventMsg::AgentReasoningSectionBreak(_) => {
@@ -365,6 +373,21 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::WebSearchEnd(WebSearchEndEvent { call_id: _, query }) => {
                 ts_msg!(self, "🌐 Searched: {query}");
             }
+            EventMsg::FetchUrlEnd(end) => match end.error {
+                Some(error) => ts_msg!(
+                    self,
+                    "{} {}: {error}",
+                    "fetch failed".style(self.red),
+                    end.url
+                ),
+                None => ts_msg!(
+                    self,
+                    "🌐 Fetched: {} (page {} of {})",
+                    end.final_url.as_deref().unwrap_or(&end.url),
+                    end.page,
+                    end.total_pages
+                ),
+            },
             EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
                 call_id,
                 auto_approved,
@@ -558,6 +581,39 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     view.path.display()
                 );
             }
+            EventMsg::ProjectDocLoaded(loaded) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "loaded instructions".style(self.dimmed),
+                    loaded.path.display()
+                );
+            }
+            EventMsg::SubAgentBegin(begin) => {
+                let access = if begin.read_only {
+                    "read-only"
+                } else {
+                    "workspace-write"
+                };
+                ts_msg!(
+                    self,
+                    "{} {} ({}, {access})",
+                    "spawned agent".style(self.magenta),
+                    sub_agent_label(&begin.source),
+                    begin.model
+                );
+            }
+            EventMsg::SubAgentEnd(end) => {
+                let status = match end.status {
+                    SubAgentStatus::Completed => "agent finished".style(self.green),
+                    SubAgentStatus::Failed => "agent failed".style(self.red),
+                    SubAgentStatus::Interrupted => "agent interrupted".style(self.red),
+                };
+                ts_msg!(self, "{status} {}", sub_agent_label(&end.source));
+                if let Some(message) = end.message {
+                    ts_msg!(self, "{}", message.style(self.dimmed));
+                }
+            }
             EventMsg::TurnAborted(abort_reason) => match abort_reason.reason {
                 TurnAbortReason::Interrupted => {
                     ts_msg!(self, "task interrupted");
@@ -572,8 +628,17 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ContextCompacted(_) => {
                 ts_msg!(self, "context compacted");
             }
+            EventMsg::ThreadRolledBack(rollback) => {
+                ts_msg!(self, "rolled back {} turn(s)", rollback.num_turns);
+            }
+            EventMsg::BudgetExceeded(exceeded) => {
+                ts_msg!(self, "{}", exceeded.message.style(self.red));
+            }
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::WebSearchBegin(_)
+            | EventMsg::FetchUrlBegin(_)
+            | EventMsg::PlanModeUpdated(_)
+            | EventMsg::PlanApprovalRequest(_)
             | EventMsg::ExecApprovalRequest(_)
             | EventMsg::ApplyPatchApprovalRequest(_)
             | EventMsg::TerminalInteraction(_)
@@ -582,6 +647,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
             | EventMsg::ListSkillsResponse(_)
+            | EventMsg::ListCheckpointsResponse(_)
+            | EventMsg::ListMemoriesResponse(_)
+            | EventMsg::ToolOutputSpilled(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -596,7 +664,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::SkillsUpdateAvailable
             | EventMsg::UndoCompleted(_)
-            | EventMsg::UndoStarted(_) => {}
+            | EventMsg::UndoStarted(_)
+            | EventMsg::SubAgentActivity(_)
+            | EventMsg::BudgetUpdated(_) => {}
         }
         CodexStatus::Running
     }
@@ -609,6 +679,13 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 format_with_separators(usage_info.total_token_usage.blended_total())
             );
         }
+        if let Some(cost) = &self.last_cost {
+            eprintln!(
+                "{}\n${:.4}",
+                "estimated cost".style(self.magenta).style(self.italic),
+                cost.session_usd
+            );
+        }
 
         // If the user has not piped the final message to a file, they will see
         // it twice: once written to stderr as part of the normal event
@@ -659,3 +736,10 @@ fn format_mcp_invocation(invocation: &McpInvocation) -> String {
         format!("{fq_tool_name}({args_str})")
     }
 }
+
+fn sub_agent_label(source: &SubAgentSource) -> &str {
+    match source {
+        SubAgentSource::SpawnAgent { role: Some(role) } => role,
+        _ => "sub-agent",
+    }
+}
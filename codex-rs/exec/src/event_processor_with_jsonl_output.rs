@@ -65,6 +65,7 @@ pub struct EventProcessorWithJsonOutput {
     // Tracks the todo list for the current turn (at most one per turn).
     running_todo_list: Option<RunningTodoList>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsage>,
+    last_session_cost_usd: Option<f64>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
 }
@@ -99,6 +100,7 @@ impl EventProcessorWithJsonOutput {
             running_patch_applies: HashMap::new(),
             running_todo_list: None,
             last_total_token_usage: None,
+            last_session_cost_usd: None,
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
         }
@@ -125,6 +127,9 @@ impl EventProcessorWithJsonOutput {
                 if let Some(info) = &ev.info {
                     self.last_total_token_usage = Some(info.total_token_usage.clone());
                 }
+                if let Some(cost) = &ev.cost {
+                    self.last_session_cost_usd = Some(cost.session_usd);
+                }
                 Vec::new()
             }
             EventMsg::TaskStarted(ev) => self.handle_task_started(ev),
@@ -470,6 +475,7 @@ impl EventProcessorWithJsonOutput {
                 input_tokens: u.input_tokens,
                 cached_input_tokens: u.cached_input_tokens,
                 output_tokens: u.output_tokens,
+                cost_usd: self.last_session_cost_usd,
             }
         } else {
             Usage::default()
@@ -16,6 +16,8 @@ pub use cli::ReviewArgs;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
 use codex_core::AuthManager;
+use codex_core::Cassette;
+use codex_core::CassetteMode;
 use codex_core::ConversationManager;
 use codex_core::LMSTUDIO_OSS_PROVIDER_ID;
 use codex_core::NewConversation;
@@ -57,6 +59,10 @@ use crate::event_processor::EventProcessor;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_conversation_path_by_id_str;
 
+/// Exit status used when the session stops because a `[budget]` limit was
+/// reached.
+pub const BUDGET_EXCEEDED_EXIT_CODE: i32 = 3;
+
 enum InitialOperation {
     UserTurn {
         items: Vec<UserInput>,
@@ -85,15 +91,18 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         skip_git_repo_check,
         add_dir,
         color,
+        record,
+        replay,
         last_message_file,
         json: json_mode,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
-        config_overrides,
+        mut config_overrides,
         loop_mode,
         max_iterations,
         completion_phrase,
+        budget,
     } = cli;
 
     let (stdout_with_ansi, stderr_with_ansi) = match color {
@@ -126,6 +135,11 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode_cli_arg.map(Into::<SandboxMode>::into)
     };
 
+    // Budget flags are shorthands for `-c budget.*` overrides.
+    config_overrides
+        .raw_overrides
+        .extend(budget.to_raw_overrides());
+
     // Parse `-c` overrides from the CLI.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
         Ok(v) => v,
@@ -218,9 +232,21 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         additional_writable_roots: add_dir,
     };
 
-    let config =
+    let mut config =
         Config::load_with_cli_overrides_and_harness_overrides(cli_kv_overrides, overrides).await?;
 
+    if let Some(path) = replay {
+        // Fail fast on a missing or malformed cassette rather than on the
+        // first model request.
+        if let Err(err) = Cassette::load(&path) {
+            eprintln!("Failed to load cassette {}: {err}", path.display());
+            std::process::exit(1);
+        }
+        config.http_cassette = Some(CassetteMode::Replay(path));
+    } else if let Some(path) = record {
+        config.http_cassette = Some(CassetteMode::Record(path));
+    }
+
     if let Err(err) = enforce_login_restrictions(&config).await {
         eprintln!("{err}");
         std::process::exit(1);
@@ -457,6 +483,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    // A budget stop ends loop mode and exits with its own status code.
+    let mut budget_exceeded = false;
     let mut loop_iteration: u32 = 1;
     let loop_prompt = prompt_summary.clone();
     let mut last_agent_message: Option<String> = None;
@@ -486,6 +514,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if matches!(event.msg, EventMsg::BudgetExceeded(_)) {
+            budget_exceeded = true;
+        }
         // Capture agent messages for completion phrase detection in loop mode
         if let EventMsg::AgentMessage(ref agent_event) = event.msg {
             last_agent_message = Some(agent_event.message.clone());
@@ -495,7 +526,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             CodexStatus::Running => continue,
             CodexStatus::InitiateShutdown => {
                 // Task completed - check if we should continue looping
-                if loop_mode {
+                if loop_mode && !budget_exceeded {
                     // Check if we've reached max iterations
                     if max_iterations > 0 && loop_iteration >= max_iterations {
                         eprintln!(
@@ -556,6 +587,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
+    if budget_exceeded {
+        std::process::exit(BUDGET_EXCEEDED_EXIT_CODE);
+    }
     if error_seen {
         std::process::exit(1);
     }
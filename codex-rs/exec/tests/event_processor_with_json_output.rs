@@ -1054,6 +1054,10 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            cost: Some(codex_core::protocol::TokenCost {
+                session_usd: 0.0123,
+                turn_usd: 0.0045,
+            }),
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
@@ -1073,6 +1077,7 @@ fn task_complete_produces_turn_completed_with_usage() {
                 input_tokens: 1200,
                 cached_input_tokens: 200,
                 output_tokens: 345,
+                cost_usd: Some(0.0123),
             },
         })]
     );
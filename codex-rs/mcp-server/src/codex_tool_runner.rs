@@ -280,6 +280,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
                     | EventMsg::ListSkillsResponse(_)
+                    | EventMsg::ListCheckpointsResponse(_)
+                    | EventMsg::ListMemoriesResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
                     | EventMsg::ExecCommandOutputDelta(_)
@@ -291,12 +293,19 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::TurnDiff(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
+                    | EventMsg::FetchUrlBegin(_)
+                    | EventMsg::FetchUrlEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
                     | EventMsg::TurnAborted(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
+                    | EventMsg::ProjectDocLoaded(_)
+                    | EventMsg::ToolOutputSpilled(_)
+                    | EventMsg::SubAgentBegin(_)
+                    | EventMsg::SubAgentActivity(_)
+                    | EventMsg::SubAgentEnd(_)
                     | EventMsg::RawResponseItem(_)
                     | EventMsg::EnteredReviewMode(_)
                     | EventMsg::ItemStarted(_)
@@ -307,6 +316,11 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::SkillsUpdateAvailable
                     | EventMsg::UndoStarted(_)
                     | EventMsg::UndoCompleted(_)
+                    | EventMsg::ThreadRolledBack(_)
+                    | EventMsg::BudgetUpdated(_)
+                    | EventMsg::BudgetExceeded(_)
+                    | EventMsg::PlanModeUpdated(_)
+                    | EventMsg::PlanApprovalRequest(_)
                     | EventMsg::ExitedReviewMode(_)
                     | EventMsg::ContextCompacted(_)
                     | EventMsg::DeprecationNotice(_) => {
@@ -127,6 +127,14 @@ impl ReasoningItem {
                     },
                 ));
             }
+        } else if self.summary_text.is_empty() {
+            // Chat Completions and Anthropic providers never produce summaries;
+            // their raw reasoning is the only reasoning there is, so surface it
+            // like a summary and let `hide_agent_reasoning` decide.
+            let text = self.raw_content.concat();
+            if !text.trim().is_empty() {
+                events.push(EventMsg::AgentReasoning(AgentReasoningEvent { text }));
+            }
         }
 
         events
@@ -161,3 +169,55 @@ impl TurnItem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn reasoning_item(summary_text: Vec<&str>, raw_content: Vec<&str>) -> ReasoningItem {
+        ReasoningItem {
+            id: "r1".to_string(),
+            summary_text: summary_text.into_iter().map(str::to_string).collect(),
+            raw_content: raw_content.into_iter().map(str::to_string).collect(),
+        }
+    }
+
+    fn event_texts(events: &[EventMsg]) -> Vec<(&'static str, String)> {
+        events
+            .iter()
+            .map(|event| match event {
+                EventMsg::AgentReasoning(ev) => ("summary", ev.text.clone()),
+                EventMsg::AgentReasoningRawContent(ev) => ("raw", ev.text.clone()),
+                other => panic!("unexpected event: {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn raw_only_reasoning_is_surfaced_as_agent_reasoning() {
+        let item = reasoning_item(Vec::new(), vec!["step one", ", step two"]);
+
+        assert_eq!(
+            event_texts(&item.as_legacy_events(false)),
+            vec![("summary", "step one, step two".to_string())]
+        );
+        assert_eq!(
+            event_texts(&item.as_legacy_events(true)),
+            vec![
+                ("raw", "step one".to_string()),
+                ("raw", ", step two".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn summaries_take_precedence_over_raw_reasoning() {
+        let item = reasoning_item(vec!["summary"], vec!["raw"]);
+
+        assert_eq!(
+            event_texts(&item.as_legacy_events(false)),
+            vec![("summary", "summary".to_string())]
+        );
+    }
+}
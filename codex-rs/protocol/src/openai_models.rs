@@ -128,6 +128,9 @@ pub enum ConfigShellToolType {
 pub enum ApplyPatchToolType {
     Freeform,
     Function,
+    /// Exact string replacement through the `edit_file` and `create_file`
+    /// tools, for models that struggle with the patch format.
+    EditFile,
 }
 
 /// Server-provided truncation policy metadata for a model.
@@ -207,6 +207,56 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Drop the last `num_turns` user turns from the conversation history.
+    /// The rollback is recorded in the rollout so that resuming the session
+    /// replays it. Must not be sent while a turn is running.
+    Rollback {
+        num_turns: u32,
+        /// Also restore the working tree from the ghost snapshot taken at the
+        /// start of the earliest dropped turn.
+        #[serde(default)]
+        restore_worktree: bool,
+    },
+
+    /// Request the undo checkpoints of the session: one per turn that has a
+    /// ghost snapshot, newest first. Reply is delivered via
+    /// `EventMsg::ListCheckpointsResponse`.
+    ListCheckpoints,
+
+    /// Return to the checkpoint taken at the start of the `num_turns`-th most
+    /// recent turn. Must not be sent while a turn is running.
+    RestoreCheckpoint {
+        num_turns: u32,
+        scope: CheckpointRestoreScope,
+    },
+
+    /// Request the stored memory entries for the user and the current
+    /// project. Reply is delivered via `EventMsg::ListMemoriesResponse`.
+    ListMemories,
+
+    /// Store a new memory entry.
+    AddMemory { scope: MemoryScope, text: String },
+
+    /// Replace the text of a memory entry.
+    UpdateMemory {
+        scope: MemoryScope,
+        id: u64,
+        text: String,
+    },
+
+    /// Delete a memory entry.
+    ForgetMemory { scope: MemoryScope, id: u64 },
+
+    /// Turn plan mode on or off for subsequent turns. While plan mode is on
+    /// the agent may read, search and run known-safe commands, but writes are
+    /// rejected until the plan is approved with [`Op::ApprovePlan`].
+    SetPlanMode { enabled: bool },
+
+    /// Approve the plan produced in plan mode: leave plan mode, restoring the
+    /// configured sandbox and approval policies, and start a turn that
+    /// implements the plan.
+    ApprovePlan,
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -588,6 +638,12 @@ pub enum EventMsg {
 
     WebSearchEnd(WebSearchEndEvent),
 
+    /// The agent started fetching a page with the `fetch_url` tool.
+    FetchUrlBegin(FetchUrlBeginEvent),
+
+    /// A `fetch_url` call finished, successfully or not.
+    FetchUrlEnd(FetchUrlEndEvent),
+
     /// Notification that the server is about to execute a command.
     ExecCommandBegin(ExecCommandBeginEvent),
 
@@ -602,6 +658,23 @@ pub enum EventMsg {
     /// Notification that the agent attached a local image via the view_image tool.
     ViewImageToolCall(ViewImageToolCallEvent),
 
+    /// An instruction file (`AGENTS.md`) below the session's working
+    /// directory was loaded because the agent worked under its directory.
+    ProjectDocLoaded(ProjectDocLoadedEvent),
+
+    /// A tool output was truncated for the model and its full text was saved
+    /// to a scratch file the model can page through with `read_output`.
+    ToolOutputSpilled(ToolOutputSpilledEvent),
+
+    /// A `spawn_agent` tool call started a child conversation.
+    SubAgentBegin(SubAgentBeginEvent),
+
+    /// Progress reported by a child conversation started by `spawn_agent`.
+    SubAgentActivity(SubAgentActivityEvent),
+
+    /// A child conversation started by `spawn_agent` finished.
+    SubAgentEnd(SubAgentEndEvent),
+
     ExecApprovalRequest(ExecApprovalRequestEvent),
 
     ElicitationRequest(ElicitationRequestEvent),
@@ -618,6 +691,24 @@ pub enum EventMsg {
 
     UndoCompleted(UndoCompletedEvent),
 
+    /// The last turns were dropped from history in response to
+    /// `Op::Rollback`. Persisted so resumed sessions drop them as well.
+    ThreadRolledBack(ThreadRolledBackEvent),
+
+    /// Usage against the configured session budget changed.
+    BudgetUpdated(BudgetUpdatedEvent),
+
+    /// A session budget limit was reached; the current turn stops and new
+    /// turns are refused.
+    BudgetExceeded(BudgetExceededEvent),
+
+    /// Plan mode was turned on or off.
+    PlanModeUpdated(PlanModeUpdatedEvent),
+
+    /// A turn in plan mode finished with a plan that is waiting for the
+    /// user's approval (`Op::ApprovePlan`).
+    PlanApprovalRequest(PlanApprovalRequestEvent),
+
     /// Notification that a model stream experienced an error or disconnect
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
@@ -643,6 +734,12 @@ pub enum EventMsg {
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
+    /// Undo checkpoints available in the session.
+    ListCheckpointsResponse(ListCheckpointsResponseEvent),
+
+    /// Memory entries stored for the user and the current project.
+    ListMemoriesResponse(ListMemoriesResponseEvent),
+
     /// Notification that skill data may have been updated and clients may want to reload.
     SkillsUpdateAvailable,
 
@@ -695,6 +792,8 @@ pub enum CodexErrorInfo {
     ResponseTooManyFailedAttempts {
         http_status_code: Option<u16>,
     },
+    /// An `Op::Rollback` could not be applied; history is unchanged.
+    ThreadRollbackFailed,
     Other,
 }
 
@@ -925,6 +1024,18 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Estimated cost, present when pricing is known for the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<TokenCost>,
+}
+
+/// Estimated spend in USD, computed from the pricing catalog.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TokenCost {
+    /// Cost of every model request in the session so far.
+    pub session_usd: f64,
+    /// Cost of the model requests in the current turn.
+    pub turn_usd: f64,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -1147,6 +1258,31 @@ pub struct WebSearchEndEvent {
     pub query: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct FetchUrlBeginEvent {
+    pub call_id: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct FetchUrlEndEvent {
+    pub call_id: String,
+    /// URL the model asked for.
+    pub url: String,
+    /// URL the page was served from after redirects.
+    pub final_url: Option<String>,
+    /// HTTP status of the final response.
+    pub status: Option<u16>,
+    pub title: Option<String>,
+    /// 1-based page of the converted document returned to the model.
+    pub page: u32,
+    pub total_pages: u32,
+    /// Whether the page came from this session's cache.
+    pub cached: bool,
+    /// Why the fetch failed or was refused.
+    pub error: Option<String>,
+}
+
 /// Response payload for `Op::GetHistory` containing the current session's
 /// in-memory transcript.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1224,6 +1360,10 @@ pub enum SessionSource {
 pub enum SubAgentSource {
     Review,
     Compact,
+    /// Started by the model through the `spawn_agent` tool.
+    SpawnAgent {
+        role: Option<String>,
+    },
     Other(String),
 }
 
@@ -1245,6 +1385,7 @@ impl fmt::Display for SubAgentSource {
         match self {
             SubAgentSource::Review => f.write_str("review"),
             SubAgentSource::Compact => f.write_str("compact"),
+            SubAgentSource::SpawnAgent { .. } => f.write_str("spawn_agent"),
             SubAgentSource::Other(other) => f.write_str(other),
         }
     }
@@ -1459,6 +1600,8 @@ pub enum ExecCommandSource {
     UserShell,
     UnifiedExecStartup,
     UnifiedExecInteraction,
+    /// A project verification command run after the turn edited files.
+    Verification,
 }
 
 impl Default for ExecCommandSource {
@@ -1538,6 +1681,67 @@ pub struct ViewImageToolCallEvent {
     pub path: PathBuf,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProjectDocLoadedEvent {
+    /// Absolute path of the instruction file that was added to the context.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolOutputSpilledEvent {
+    /// Identifier for the tool call whose output was truncated.
+    pub call_id: String,
+    /// Handle the model passes to `read_output`, e.g. `out-3`.
+    pub output_id: String,
+    /// Scratch file holding the full output.
+    pub path: PathBuf,
+    pub total_lines: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentBeginEvent {
+    /// Identifier for the originating `spawn_agent` tool call.
+    pub call_id: String,
+    pub source: SubAgentSource,
+    /// Model (or deployment) the child runs with.
+    pub model: String,
+    /// Task handed to the child.
+    pub prompt: String,
+    /// Whether the child is limited to a read-only sandbox.
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentActivityEvent {
+    /// Identifier for the originating `spawn_agent` tool call.
+    pub call_id: String,
+    pub source: SubAgentSource,
+    /// Event emitted by the child conversation.
+    pub msg: Box<EventMsg>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentEndEvent {
+    /// Identifier for the originating `spawn_agent` tool call.
+    pub call_id: String,
+    pub source: SubAgentSource,
+    pub model: String,
+    pub prompt: String,
+    pub read_only: bool,
+    pub status: SubAgentStatus,
+    /// Final message of the child, or the reason it failed.
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SubAgentStatus {
+    Completed,
+    Failed,
+    Interrupted,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecOutputStream {
@@ -1597,6 +1801,85 @@ pub struct UndoCompletedEvent {
     pub message: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ThreadRolledBackEvent {
+    pub num_turns: u32,
+    /// Whether the working tree was restored from a ghost snapshot.
+    #[serde(default)]
+    pub worktree_restored: bool,
+}
+
+/// A limit that can be configured under `[budget]`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    InputTokens,
+    OutputTokens,
+    Cost,
+    ToolCalls,
+    Turns,
+    Duration,
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            BudgetLimit::InputTokens => "input tokens",
+            BudgetLimit::OutputTokens => "output tokens",
+            BudgetLimit::Cost => "cost",
+            BudgetLimit::ToolCalls => "tool calls",
+            BudgetLimit::Turns => "turns",
+            BudgetLimit::Duration => "duration",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Usage of a single configured limit. Cost is in USD and duration in seconds.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BudgetEntry {
+    pub limit: BudgetLimit,
+    pub used: f64,
+    pub max: f64,
+}
+
+impl BudgetEntry {
+    pub fn remaining(&self) -> f64 {
+        (self.max - self.used).max(0.0)
+    }
+}
+
+/// Snapshot of the session budget. Only configured limits have entries.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BudgetStatus {
+    pub entries: Vec<BudgetEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exceeded: Option<BudgetLimit>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct BudgetUpdatedEvent {
+    pub status: BudgetStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct BudgetExceededEvent {
+    pub limit: BudgetLimit,
+    pub message: String,
+    pub status: BudgetStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PlanModeUpdatedEvent {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PlanApprovalRequestEvent {
+    /// The latest plan recorded with `update_plan`.
+    pub plan: UpdatePlanArgs,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -1732,6 +2015,76 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// What `Op::RestoreCheckpoint` brings back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum CheckpointRestoreScope {
+    /// Restore the working tree from the snapshot and keep the conversation.
+    Worktree,
+    /// Drop the turns from the conversation and leave the working tree as is.
+    Conversation,
+    /// Restore the working tree and drop the turns from the conversation.
+    Both,
+}
+
+/// Ghost snapshot taken at the start of a turn.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct Checkpoint {
+    /// Number of trailing turns `Op::RestoreCheckpoint` drops to return here.
+    pub num_turns: u32,
+    pub commit_id: String,
+    /// First line of the user message that started the turn.
+    pub prompt: String,
+    /// Files changed between this checkpoint and the next one (or the current
+    /// working tree for the latest turn), relative to the session cwd.
+    pub files: Vec<PathBuf>,
+    /// Unified diff of `files`, cut to a preview.
+    pub diff_preview: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub diff_truncated: bool,
+    /// Files that restoring this checkpoint would overwrite and that were last
+    /// modified while no Codex turn was running.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_changes: Vec<PathBuf>,
+}
+
+/// Response payload for `Op::ListCheckpoints`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListCheckpointsResponseEvent {
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+/// Where a memory entry is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// Applies to every project of this user; stored under `CODEX_HOME`.
+    User,
+    /// Applies to the current project; stored in its `.codex/` directory.
+    Project,
+}
+
+/// A fact remembered across sessions.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct MemoryEntry {
+    pub scope: MemoryScope,
+    /// Identifier, unique within `scope`.
+    pub id: u64,
+    pub text: String,
+}
+
+/// Response payload for `Op::ListMemories`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListMemoriesResponseEvent {
+    pub entries: Vec<MemoryEntry>,
+    /// File holding the user memories.
+    pub user_path: PathBuf,
+    /// File holding the project memories.
+    pub project_path: PathBuf,
+}
+
 /// Response payload for `Op::ListSkills`.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListSkillsResponseEvent {
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use anyhow::Error;
 use anyhow::Result;
+use codex_client::NetworkSettings;
 use codex_protocol::protocol::McpAuthStatus;
 use reqwest::Client;
 use reqwest::StatusCode;
@@ -28,6 +29,7 @@ pub async fn determine_streamable_http_auth_status(
     http_headers: Option<HashMap<String, String>>,
     env_http_headers: Option<HashMap<String, String>>,
     store_mode: OAuthCredentialsStoreMode,
+    network: &NetworkSettings,
 ) -> Result<McpAuthStatus> {
     if bearer_token_env_var.is_some() {
         return Ok(McpAuthStatus::BearerToken);
@@ -39,7 +41,7 @@ pub async fn determine_streamable_http_auth_status(
 
     let default_headers = build_default_headers(http_headers, env_http_headers)?;
 
-    match supports_oauth_login_with_headers(url, &default_headers).await {
+    match supports_oauth_login_with_headers(url, &default_headers, network).await {
         Ok(true) => Ok(McpAuthStatus::NotLoggedIn),
         Ok(false) => Ok(McpAuthStatus::Unsupported),
         Err(error) => {
@@ -52,17 +54,25 @@ pub async fn determine_streamable_http_auth_status(
 }
 
 /// Attempt to determine whether a streamable HTTP MCP server advertises OAuth login.
-pub async fn supports_oauth_login(url: &str) -> Result<bool> {
-    supports_oauth_login_with_headers(url, &HeaderMap::new()).await
+pub async fn supports_oauth_login(url: &str, network: &NetworkSettings) -> Result<bool> {
+    supports_oauth_login_with_headers(url, &HeaderMap::new(), network).await
 }
 
-async fn supports_oauth_login_with_headers(url: &str, default_headers: &HeaderMap) -> Result<bool> {
+async fn supports_oauth_login_with_headers(
+    url: &str,
+    default_headers: &HeaderMap,
+    network: &NetworkSettings,
+) -> Result<bool> {
     let base_url = Url::parse(url)?;
 
     // Use no_proxy to avoid a bug in the system-configuration crate that
-    // can result in a panic. See #8912.
+    // can result in a panic. See #8912. An explicitly configured proxy still
+    // applies.
     let builder = Client::builder().timeout(DISCOVERY_TIMEOUT).no_proxy();
-    let client = apply_default_headers(builder, default_headers).build()?;
+    let client = network
+        .load()?
+        .apply(apply_default_headers(builder, default_headers))
+        .build()?;
 
     let mut last_error: Option<Error> = None;
     for candidate_path in discovery_paths(base_url.path()) {
@@ -6,6 +6,7 @@ use std::time::Duration;
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
+use codex_client::NetworkSettings;
 use reqwest::ClientBuilder;
 use rmcp::transport::auth::OAuthState;
 use tiny_http::Response;
@@ -25,6 +26,7 @@ use crate::utils::build_default_headers;
 struct OauthHeaders {
     http_headers: Option<HashMap<String, String>>,
     env_http_headers: Option<HashMap<String, String>>,
+    network: NetworkSettings,
 }
 
 struct CallbackServerGuard {
@@ -43,11 +45,13 @@ pub async fn perform_oauth_login(
     store_mode: OAuthCredentialsStoreMode,
     http_headers: Option<HashMap<String, String>>,
     env_http_headers: Option<HashMap<String, String>>,
+    network: &NetworkSettings,
     scopes: &[String],
 ) -> Result<()> {
     let headers = OauthHeaders {
         http_headers,
         env_http_headers,
+        network: network.clone(),
     };
     OauthLoginFlow::new(
         server_name,
@@ -63,18 +67,21 @@ pub async fn perform_oauth_login(
     .await
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn perform_oauth_login_return_url(
     server_name: &str,
     server_url: &str,
     store_mode: OAuthCredentialsStoreMode,
     http_headers: Option<HashMap<String, String>>,
     env_http_headers: Option<HashMap<String, String>>,
+    network: &NetworkSettings,
     scopes: &[String],
     timeout_secs: Option<i64>,
 ) -> Result<OauthLoginHandle> {
     let headers = OauthHeaders {
         http_headers,
         env_http_headers,
+        network: network.clone(),
     };
     let flow = OauthLoginFlow::new(
         server_name,
@@ -226,9 +233,16 @@ impl OauthLoginFlow {
         let OauthHeaders {
             http_headers,
             env_http_headers,
+            network,
         } = headers;
         let default_headers = build_default_headers(http_headers, env_http_headers)?;
-        let http_client = apply_default_headers(ClientBuilder::new(), &default_headers).build()?;
+        let http_client = network
+            .load()?
+            .apply(apply_default_headers(
+                ClientBuilder::new(),
+                &default_headers,
+            ))
+            .build()?;
 
         let mut oauth_state = OAuthState::new(server_url, Some(http_client)).await?;
         let scope_refs: Vec<&str> = scopes.iter().map(String::as_str).collect();
@@ -8,6 +8,8 @@ use std::time::Duration;
 
 use anyhow::Result;
 use anyhow::anyhow;
+use codex_client::HttpNetwork;
+use codex_client::NetworkSettings;
 use futures::FutureExt;
 use futures::future::BoxFuture;
 use mcp_types::CallToolRequestParams;
@@ -166,8 +168,10 @@ impl RmcpClient {
         http_headers: Option<HashMap<String, String>>,
         env_http_headers: Option<HashMap<String, String>>,
         store_mode: OAuthCredentialsStoreMode,
+        network: &NetworkSettings,
     ) -> Result<Self> {
         let default_headers = build_default_headers(http_headers, env_http_headers)?;
+        let network = network.load()?;
 
         let initial_oauth_tokens = match bearer_token {
             Some(_) => None,
@@ -187,6 +191,7 @@ impl RmcpClient {
                 initial_tokens,
                 store_mode,
                 default_headers.clone(),
+                &network,
             )
             .await?;
             PendingTransport::StreamableHttpWithOAuth {
@@ -199,8 +204,12 @@ impl RmcpClient {
                 http_config = http_config.auth_header(bearer_token);
             }
 
-            let http_client =
-                apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+            let http_client = network
+                .apply(apply_default_headers(
+                    reqwest::Client::builder(),
+                    &default_headers,
+                ))
+                .build()?;
 
             let transport = StreamableHttpClientTransport::with_client(http_client, http_config);
             PendingTransport::StreamableHttp { transport }
@@ -441,12 +450,17 @@ async fn create_oauth_transport_and_runtime(
     initial_tokens: StoredOAuthTokens,
     credentials_store: OAuthCredentialsStoreMode,
     default_headers: HeaderMap,
+    network: &HttpNetwork,
 ) -> Result<(
     StreamableHttpClientTransport<AuthClient<reqwest::Client>>,
     OAuthPersistor,
 )> {
-    let http_client =
-        apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+    let http_client = network
+        .apply(apply_default_headers(
+            reqwest::Client::builder(),
+            &default_headers,
+        ))
+        .build()?;
     let mut oauth_state = OAuthState::new(url.to_string(), Some(http_client.clone())).await?;
 
     oauth_state
@@ -2018,6 +2018,32 @@ impl App {
             AppEvent::OpenApprovalsPopup => {
                 self.chat_widget.open_approvals_popup();
             }
+            AppEvent::OpenCheckpointRestore(checkpoint) => {
+                self.chat_widget.open_checkpoint_restore_popup(checkpoint);
+            }
+            AppEvent::OpenMemoryEntry(entry) => {
+                self.chat_widget.open_memory_entry_popup(entry);
+            }
+            AppEvent::OpenMemoryEditor { scope, id, text } => {
+                self.chat_widget.open_memory_editor(scope, id, text);
+            }
+            AppEvent::OpenSpilledOutput { output_id, path } => {
+                let pager_lines: Vec<ratatui::text::Line<'static>> =
+                    match std::fs::read_to_string(&path) {
+                        Ok(text) => text.lines().map(ansi_escape_line).collect(),
+                        Err(err) => vec![
+                            format!("Failed to read {}: {err}", path.display())
+                                .italic()
+                                .into(),
+                        ],
+                    };
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_static_with_lines(
+                    pager_lines,
+                    format!("O U T P U T · {output_id}"),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::OpenReviewBranchPicker(cwd, auto_fix) => {
                 self.chat_widget
                     .show_review_branch_picker(&cwd, auto_fix)
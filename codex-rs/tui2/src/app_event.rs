@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
 use codex_common::approval_presets::ApprovalPreset;
+use codex_core::protocol::Checkpoint;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::MemoryEntry;
+use codex_core::protocol::MemoryScope;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_file_search::FileMatch;
 use codex_protocol::openai_models::ModelPreset;
@@ -167,6 +170,25 @@ pub(crate) enum AppEvent {
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt(bool),
 
+    /// Open the restore options for a checkpoint picked in `/undo`.
+    OpenCheckpointRestore(Checkpoint),
+
+    /// Open the actions for an entry picked in `/memory`.
+    OpenMemoryEntry(MemoryEntry),
+
+    /// Open the editor for a new (`id: None`) or existing memory entry.
+    OpenMemoryEditor {
+        scope: MemoryScope,
+        id: Option<u64>,
+        text: String,
+    },
+
+    /// Open a saved tool output picked in `/output` in the pager.
+    OpenSpilledOutput {
+        output_id: String,
+        path: PathBuf,
+    },
+
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
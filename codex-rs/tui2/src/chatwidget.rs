@@ -25,6 +25,10 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BudgetExceededEvent;
+use codex_core::protocol::BudgetStatus;
+use codex_core::protocol::Checkpoint;
+use codex_core::protocol::CheckpointRestoreScope;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -35,7 +39,11 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::FetchUrlBeginEvent;
+use codex_core::protocol::FetchUrlEndEvent;
+use codex_core::protocol::ListCheckpointsResponseEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::ListMemoriesResponseEvent;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
@@ -43,17 +51,28 @@ use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::MemoryEntry;
+use codex_core::protocol::MemoryScope;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PlanApprovalRequestEvent;
+use codex_core::protocol::PlanModeUpdatedEvent;
+use codex_core::protocol::ProjectDocLoadedEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SkillsListEntry;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TerminalInteractionEvent;
+use codex_core::protocol::ThreadRolledBackEvent;
+use codex_core::protocol::TokenCost;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::ToolOutputSpilledEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::UndoCompletedEvent;
@@ -314,6 +333,14 @@ pub(crate) struct ChatWidget {
     model_state: SharedModelState,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    // Latest session budget usage, shown in /status
+    budget_status: Option<BudgetStatus>,
+    // Latest estimated spend, shown in /status
+    token_cost: Option<TokenCost>,
+    // Nested AGENTS.md files loaded during the session, shown in /status
+    nested_project_docs: Vec<PathBuf>,
+    // Whether the session is in plan mode; toggled with /plan
+    plan_mode: bool,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     plan_type: Option<PlanType>,
     rate_limit_warnings: RateLimitWarningState,
@@ -326,6 +353,8 @@ pub(crate) struct ChatWidget {
     last_unified_wait: Option<UnifiedExecWaitState>,
     /// Active background terminal sessions (UnifiedExec) for /ps display.
     unified_exec_sessions: Vec<UnifiedExecSessionSummary>,
+    /// Tool outputs truncated for the model and saved to disk, for /output.
+    spilled_outputs: Vec<ToolOutputSpilledEvent>,
     task_complete_pending: bool,
     mcp_startup_status: Option<HashMap<String, McpStartupStatus>>,
     // Queue of interruptive UI events deferred during an active write cycle
@@ -977,6 +1006,96 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_project_doc_loaded(&mut self, event: ProjectDocLoadedEvent) {
+        let display = event
+            .path
+            .strip_prefix(&self.config.cwd)
+            .unwrap_or(&event.path)
+            .display()
+            .to_string();
+        self.add_info_message(format!("Loaded instructions from {display}"), None);
+        if !self.nested_project_docs.contains(&event.path) {
+            self.nested_project_docs.push(event.path);
+        }
+    }
+
+    fn on_tool_output_spilled(&mut self, event: ToolOutputSpilledEvent) {
+        self.add_info_message(
+            format!(
+                "Output truncated for the model; saved as {} ({} lines).",
+                event.output_id, event.total_lines
+            ),
+            Some("Use /output to open it in the pager.".to_string()),
+        );
+        self.spilled_outputs.push(event);
+    }
+
+    fn open_spilled_outputs_popup(&mut self) {
+        if self.spilled_outputs.is_empty() {
+            self.add_info_message(
+                "No truncated tool output in this session.".to_string(),
+                Some(
+                    "Enable the `output_spill` feature to keep the full text of long tool results."
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+        let items = self
+            .spilled_outputs
+            .iter()
+            .rev()
+            .map(|event| {
+                let output_id = event.output_id.clone();
+                let path = event.path.clone();
+                SelectionItem {
+                    name: event.output_id.clone(),
+                    description: Some(format!(
+                        "{} lines · {}",
+                        event.total_lines,
+                        event.path.display()
+                    )),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::OpenSpilledOutput {
+                            output_id: output_id.clone(),
+                            path: path.clone(),
+                        });
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Truncated output".to_string()),
+            subtitle: Some("Open the full text of a tool result in the pager".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
+    fn on_sub_agent_begin(&mut self, event: SubAgentBeginEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_sub_agent_begin(&event));
+        self.request_redraw();
+    }
+
+    fn on_sub_agent_activity(&mut self, event: SubAgentActivityEvent) {
+        if let Some(cell) = history_cell::new_sub_agent_activity(&event) {
+            self.flush_answer_stream_with_separator();
+            self.add_to_history(cell);
+            self.request_redraw();
+        }
+    }
+
+    fn on_sub_agent_end(&mut self, event: SubAgentEndEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_sub_agent_end(&event));
+        self.request_redraw();
+    }
+
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
         let ev2 = event.clone();
         self.defer_or_handle(
@@ -1009,6 +1128,15 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_web_search_call(ev.query));
     }
 
+    fn on_fetch_url_begin(&mut self, _ev: FetchUrlBeginEvent) {
+        self.flush_answer_stream_with_separator();
+    }
+
+    fn on_fetch_url_end(&mut self, ev: FetchUrlEndEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_fetch_url_call(&ev));
+    }
+
     fn on_get_history_entry_response(
         &mut self,
         event: codex_core::protocol::GetHistoryEntryResponseEvent,
@@ -1069,6 +1197,320 @@ impl ChatWidget {
         }
     }
 
+    fn on_thread_rolled_back(&mut self, event: ThreadRolledBackEvent) {
+        let turns = if event.num_turns == 1 {
+            "turn"
+        } else {
+            "turns"
+        };
+        let mut message = format!("Rolled back {} {turns}", event.num_turns);
+        if event.worktree_restored {
+            message.push_str(" and restored the working tree");
+        }
+        message.push('.');
+        self.add_info_message(message, None);
+    }
+
+    fn on_list_checkpoints(&mut self, event: ListCheckpointsResponseEvent) {
+        if event.checkpoints.is_empty() {
+            self.add_info_message(
+                "No checkpoints to restore yet.".to_string(),
+                Some("Codex snapshots the working tree at the start of each turn in a git repository.".to_string()),
+            );
+            return;
+        }
+        let items = event
+            .checkpoints
+            .into_iter()
+            .map(|checkpoint| {
+                let turns = if checkpoint.num_turns == 1 {
+                    "turn"
+                } else {
+                    "turns"
+                };
+                let name = format!(
+                    "{} {turns} ago · {}",
+                    checkpoint.num_turns, checkpoint.prompt
+                );
+                let mut description = summarize_paths(&checkpoint.files, "file", "changed");
+                if !checkpoint.external_changes.is_empty() {
+                    description.push_str(" · ⚠ ");
+                    description.push_str(&summarize_paths(
+                        &checkpoint.external_changes,
+                        "file",
+                        "changed outside Codex",
+                    ));
+                }
+                SelectionItem {
+                    name,
+                    description: Some(description),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::OpenCheckpointRestore(checkpoint.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Restore a checkpoint".to_string()),
+            subtitle: Some("Snapshots taken at the start of each turn, newest first".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
+    pub(crate) fn open_checkpoint_restore_popup(&mut self, checkpoint: Checkpoint) {
+        let short_id: String = checkpoint.commit_id.chars().take(7).collect();
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from(format!("Checkpoint {short_id} · {}", checkpoint.prompt)).bold());
+        if !checkpoint.external_changes.is_empty() {
+            let files = checkpoint
+                .external_changes
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            header.push(
+                Paragraph::new(vec![Line::from(format!(
+                    "⚠ Changed outside Codex since this checkpoint: {files}. Restoring the code discards these edits."
+                ))
+                .red()])
+                .wrap(Wrap { trim: false }),
+            );
+        }
+        header.push(Paragraph::new(checkpoint_diff_lines(&checkpoint)));
+
+        let num_turns = checkpoint.num_turns;
+        let restore = move |scope: CheckpointRestoreScope| -> Vec<SelectionAction> {
+            vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::RestoreCheckpoint {
+                    num_turns,
+                    scope,
+                }));
+            })]
+        };
+        let items = vec![
+            SelectionItem {
+                name: "Restore code and conversation".to_string(),
+                description: Some(
+                    "Reset the working tree and drop the later turns from the conversation"
+                        .to_string(),
+                ),
+                actions: restore(CheckpointRestoreScope::Both),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Restore code only".to_string(),
+                description: Some("Reset the working tree and keep the conversation".to_string()),
+                actions: restore(CheckpointRestoreScope::Worktree),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Restore conversation only".to_string(),
+                description: Some(
+                    "Drop the later turns and leave the working tree as it is".to_string(),
+                ),
+                actions: restore(CheckpointRestoreScope::Conversation),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Back".to_string(),
+                description: Some("Return to the checkpoint list".to_string()),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::ListCheckpoints));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(header),
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
+    fn on_list_memories(&mut self, event: ListMemoriesResponseEvent) {
+        let mut items: Vec<SelectionItem> = event
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let label = format!("{}#{}", memory_scope_name(entry.scope), entry.id);
+                SelectionItem {
+                    name: entry.text.clone(),
+                    description: Some(label),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::OpenMemoryEntry(entry.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        for scope in [MemoryScope::Project, MemoryScope::User] {
+            items.push(SelectionItem {
+                name: format!("Add a {} memory", memory_scope_name(scope)),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::OpenMemoryEditor {
+                        scope,
+                        id: None,
+                        text: String::new(),
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Memory".to_string()),
+            subtitle: Some(format!(
+                "User: {} · Project: {}",
+                event.user_path.display(),
+                event.project_path.display()
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
+    pub(crate) fn open_memory_entry_popup(&mut self, entry: MemoryEntry) {
+        let MemoryEntry { scope, id, text } = entry;
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from(format!("{}#{id}", memory_scope_name(scope))).bold());
+        header.push(Paragraph::new(text.clone()).wrap(Wrap { trim: false }));
+        let items = vec![
+            SelectionItem {
+                name: "Edit".to_string(),
+                description: Some("Rewrite this memory".to_string()),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::OpenMemoryEditor {
+                        scope,
+                        id: Some(id),
+                        text: text.clone(),
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Forget".to_string(),
+                description: Some("Remove this memory".to_string()),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::ForgetMemory { scope, id }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Back".to_string(),
+                description: Some("Return to the memory list".to_string()),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::ListMemories));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(header),
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
+    pub(crate) fn open_memory_editor(&mut self, scope: MemoryScope, id: Option<u64>, text: String) {
+        let tx = self.app_event_tx.clone();
+        let title = match id {
+            Some(id) => format!("Edit {}#{id}", memory_scope_name(scope)),
+            None => format!("New {} memory", memory_scope_name(scope)),
+        };
+        let view = CustomPromptView::new(
+            title,
+            "Type a fact for Codex to remember and press Enter".to_string(),
+            None,
+            Box::new(move |input: String| {
+                let text = input.trim().to_string();
+                if text.is_empty() {
+                    return;
+                }
+                let op = match id {
+                    Some(id) => Op::UpdateMemory { scope, id, text },
+                    None => Op::AddMemory { scope, text },
+                };
+                tx.send(AppEvent::CodexOp(op));
+            }),
+        )
+        .with_initial_text(&text);
+        self.bottom_pane.show_view(Box::new(view));
+        self.request_redraw();
+    }
+
+    fn on_budget_exceeded(&mut self, event: BudgetExceededEvent) {
+        self.budget_status = Some(event.status);
+        self.cancel_loop_with_reason("session budget exceeded");
+        self.add_to_history(history_cell::new_error_event(event.message));
+        self.request_redraw();
+    }
+
+    fn on_plan_mode_updated(&mut self, event: PlanModeUpdatedEvent) {
+        self.plan_mode = event.enabled;
+        if event.enabled {
+            self.add_info_message(
+                "Plan mode on. Codex will explore read-only and propose a plan for you to approve."
+                    .to_string(),
+                Some("Run /plan again to leave plan mode without approving.".to_string()),
+            );
+        } else {
+            self.add_info_message("Plan mode off.".to_string(), None);
+        }
+    }
+
+    fn on_plan_approval_request(&mut self, event: PlanApprovalRequestEvent) {
+        let steps = event.plan.plan.len();
+        let items = vec![
+            SelectionItem {
+                name: "Yes, implement the plan".to_string(),
+                description: Some("Leave plan mode and let Codex make the changes".to_string()),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::ApprovePlan));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "No, keep planning".to_string(),
+                description: Some(
+                    "Stay in plan mode and refine the plan with your next message".to_string(),
+                ),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Implement this plan?".to_string()),
+            subtitle: Some(format!(
+                "{steps} step{} proposed in plan mode",
+                if steps == 1 { "" } else { "s" }
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+        self.request_redraw();
+    }
+
     fn on_stream_error(&mut self, message: String, additional_details: Option<String>) {
         if self.retry_status_header.is_none() {
             self.retry_status_header = Some(self.current_status_header.clone());
@@ -1453,6 +1895,10 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            budget_status: None,
+            token_cost: None,
+            nested_project_docs: Vec::new(),
+            plan_mode: false,
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -1463,6 +1909,7 @@ impl ChatWidget {
             suppressed_exec_calls: HashSet::new(),
             last_unified_wait: None,
             unified_exec_sessions: Vec::new(),
+            spilled_outputs: Vec::new(),
             task_complete_pending: false,
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
@@ -1546,6 +1993,10 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            budget_status: None,
+            token_cost: None,
+            nested_project_docs: Vec::new(),
+            plan_mode: false,
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -1556,6 +2007,7 @@ impl ChatWidget {
             suppressed_exec_calls: HashSet::new(),
             last_unified_wait: None,
             unified_exec_sessions: Vec::new(),
+            spilled_outputs: Vec::new(),
             task_complete_pending: false,
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
@@ -1751,6 +2203,11 @@ impl ChatWidget {
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
+            SlashCommand::Plan => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::SetPlanMode {
+                    enabled: !self.plan_mode,
+                }));
+            }
             SlashCommand::Quit | SlashCommand::Exit => {
                 self.request_exit();
             }
@@ -1763,9 +2220,16 @@ impl ChatWidget {
                 }
                 self.request_exit();
             }
-            // SlashCommand::Undo => {
-            //     self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
-            // }
+            SlashCommand::Undo => {
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::ListCheckpoints));
+            }
+            SlashCommand::Memory => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::ListMemories));
+            }
+            SlashCommand::Output => {
+                self.open_spilled_outputs_popup();
+            }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
@@ -2038,6 +2502,15 @@ impl ChatWidget {
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                 self.on_agent_message_delta(delta)
             }
+            EventMsg::AgentReasoningDelta(_)
+            | EventMsg::AgentReasoningRawContentDelta(_)
+            | EventMsg::AgentReasoning(_)
+            | EventMsg::AgentReasoningRawContent(_)
+            | EventMsg::AgentReasoningSectionBreak(_)
+                if self.config.hide_agent_reasoning =>
+            {
+                self.mark_first_token();
+            }
             EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent { delta })
             | EventMsg::AgentReasoningRawContentDelta(AgentReasoningRawContentDeltaEvent {
                 delta,
@@ -2053,6 +2526,9 @@ impl ChatWidget {
                 self.on_task_complete(last_agent_message)
             }
             EventMsg::TokenCount(ev) => {
+                if ev.cost.is_some() {
+                    self.token_cost = ev.cost;
+                }
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
@@ -2089,14 +2565,23 @@ impl ChatWidget {
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
+            EventMsg::ProjectDocLoaded(ev) => self.on_project_doc_loaded(ev),
+            EventMsg::ToolOutputSpilled(ev) => self.on_tool_output_spilled(ev),
+            EventMsg::SubAgentBegin(ev) => self.on_sub_agent_begin(ev),
+            EventMsg::SubAgentActivity(ev) => self.on_sub_agent_activity(ev),
+            EventMsg::SubAgentEnd(ev) => self.on_sub_agent_end(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
+            EventMsg::FetchUrlBegin(ev) => self.on_fetch_url_begin(ev),
+            EventMsg::FetchUrlEnd(ev) => self.on_fetch_url_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
+            EventMsg::ListCheckpointsResponse(ev) => self.on_list_checkpoints(ev),
+            EventMsg::ListMemoriesResponse(ev) => self.on_list_memories(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
                     cwds: Vec::new(),
@@ -2111,6 +2596,11 @@ impl ChatWidget {
             }
             EventMsg::UndoStarted(ev) => self.on_undo_started(ev),
             EventMsg::UndoCompleted(ev) => self.on_undo_completed(ev),
+            EventMsg::ThreadRolledBack(ev) => self.on_thread_rolled_back(ev),
+            EventMsg::BudgetUpdated(ev) => self.budget_status = Some(ev.status),
+            EventMsg::BudgetExceeded(ev) => self.on_budget_exceeded(ev),
+            EventMsg::PlanModeUpdated(ev) => self.on_plan_mode_updated(ev),
+            EventMsg::PlanApprovalRequest(ev) => self.on_plan_approval_request(ev),
             EventMsg::StreamError(StreamErrorEvent {
                 message,
                 additional_details,
@@ -2326,6 +2816,9 @@ impl ChatWidget {
             self.last_request_metrics.clone(),
             &self.conversation_id,
             self.rate_limit_snapshot.as_ref(),
+            self.budget_status.as_ref(),
+            self.token_cost.as_ref(),
+            &self.nested_project_docs,
             self.plan_type,
             Local::now(),
             self.model_family.get_model_slug(),
@@ -4316,6 +4809,68 @@ pub(crate) fn show_review_commit_picker_with_entries(
     });
 }
 
+/// Diff lines shown above the restore options of a checkpoint.
+const CHECKPOINT_DIFF_PREVIEW_LINES: usize = 12;
+
+/// Summarize `paths` as e.g. "3 files changed: a.rs, b.rs, +1 more".
+fn memory_scope_name(scope: MemoryScope) -> &'static str {
+    match scope {
+        MemoryScope::User => "user",
+        MemoryScope::Project => "project",
+    }
+}
+
+fn summarize_paths(paths: &[PathBuf], noun: &str, verb: &str) -> String {
+    const SHOWN: usize = 2;
+    if paths.is_empty() {
+        return format!("no {noun}s {verb}");
+    }
+    let plural = if paths.len() == 1 { "" } else { "s" };
+    let mut names = paths
+        .iter()
+        .take(SHOWN)
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    if paths.len() > SHOWN {
+        names.push(format!("+{} more", paths.len() - SHOWN));
+    }
+    format!(
+        "{} {noun}{plural} {verb}: {}",
+        paths.len(),
+        names.join(", ")
+    )
+}
+
+fn checkpoint_diff_lines(checkpoint: &Checkpoint) -> Vec<Line<'static>> {
+    if checkpoint.diff_preview.is_empty() {
+        return vec![Line::from("No changes since this checkpoint.").dim()];
+    }
+    let total = checkpoint.diff_preview.lines().count();
+    let mut lines: Vec<Line<'static>> = checkpoint
+        .diff_preview
+        .lines()
+        .take(CHECKPOINT_DIFF_PREVIEW_LINES)
+        .map(|line| {
+            let styled = Line::from(line.to_string());
+            if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+                styled.bold()
+            } else if line.starts_with('+') {
+                styled.green()
+            } else if line.starts_with('-') {
+                styled.red()
+            } else if line.starts_with("@@") {
+                styled.cyan()
+            } else {
+                styled.dim()
+            }
+        })
+        .collect();
+    if total > CHECKPOINT_DIFF_PREVIEW_LINES || checkpoint.diff_truncated {
+        lines.push(Line::from("… diff continues").dim());
+    }
+    lines
+}
+
 fn find_skill_mentions(text: &str, skills: &[SkillMetadata]) -> Vec<SkillMetadata> {
     let mut seen: HashSet<String> = HashSet::new();
     let mut matches: Vec<SkillMetadata> = Vec::new();
@@ -206,6 +206,7 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_review_tokens, context_window)),
             rate_limits: None,
+            cost: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -226,6 +227,7 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(review_tokens, context_window)),
             rate_limits: None,
+            cost: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(97));
@@ -255,6 +257,7 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
             rate_limits: None,
+            cost: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -264,6 +267,7 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: None,
             rate_limits: None,
+            cost: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
@@ -294,6 +298,7 @@ async fn context_indicator_shows_used_tokens_when_window_unknown() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(token_info),
             rate_limits: None,
+            cost: None,
         }),
     });
 
@@ -383,6 +388,10 @@ async fn make_chatwidget_manual(
         ),
         initial_user_message: None,
         token_info: None,
+        budget_status: None,
+        token_cost: None,
+        nested_project_docs: Vec::new(),
+        plan_mode: false,
         rate_limit_snapshot: None,
         plan_type: None,
         rate_limit_warnings: RateLimitWarningState::default(),
@@ -393,6 +402,7 @@ async fn make_chatwidget_manual(
         suppressed_exec_calls: HashSet::new(),
         last_unified_wait: None,
         unified_exec_sessions: Vec::new(),
+        spilled_outputs: Vec::new(),
         task_complete_pending: false,
         mcp_startup_status: None,
         interrupts: InterruptManager::new(),
@@ -1571,6 +1581,339 @@ async fn view_image_tool_call_adds_history_cell() {
     assert_snapshot!("local_image_attachment_history_snapshot", combined);
 }
 
+#[tokio::test]
+async fn fetch_url_events_add_history_cells() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "sub-fetch".into(),
+        msg: EventMsg::FetchUrlBegin(codex_core::protocol::FetchUrlBeginEvent {
+            call_id: "call-fetch".into(),
+            url: "https://docs.rs/serde".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-fetch".into(),
+        msg: EventMsg::FetchUrlEnd(codex_core::protocol::FetchUrlEndEvent {
+            call_id: "call-fetch".into(),
+            url: "https://docs.rs/serde".into(),
+            final_url: Some("https://docs.rs/serde/latest/serde/".into()),
+            status: Some(200),
+            title: Some("serde - Rust".into()),
+            page: 1,
+            total_pages: 3,
+            cached: false,
+            error: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-fetch".into(),
+        msg: EventMsg::FetchUrlEnd(codex_core::protocol::FetchUrlEndEvent {
+            call_id: "call-denied".into(),
+            url: "https://example.com/".into(),
+            final_url: None,
+            status: None,
+            title: None,
+            page: 0,
+            total_pages: 0,
+            cached: false,
+            error: Some("example.com is not in the fetch_url allowlist".into()),
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 2, "expected one cell per finished fetch");
+    let fetched = lines_to_single_string(&cells[0]);
+    assert!(fetched.contains("Fetched https://docs.rs/serde/latest/serde/"));
+    assert!(fetched.contains("serde - Rust · page 1 of 3"));
+    let denied = lines_to_single_string(&cells[1]);
+    assert!(denied.contains("Fetch failed https://example.com/"));
+    assert!(denied.contains("not in the fetch_url allowlist"));
+}
+
+#[tokio::test]
+async fn sub_agent_events_add_history_cells() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    let source = codex_core::protocol::SubAgentSource::SpawnAgent {
+        role: Some("explorer".to_string()),
+    };
+
+    chat.handle_codex_event(Event {
+        id: "sub-agent".into(),
+        msg: EventMsg::SubAgentBegin(codex_core::protocol::SubAgentBeginEvent {
+            call_id: "call-agent".into(),
+            source: source.clone(),
+            model: "gpt-5-mini".into(),
+            prompt: "Find where config is loaded".into(),
+            read_only: true,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-agent".into(),
+        msg: EventMsg::SubAgentActivity(codex_core::protocol::SubAgentActivityEvent {
+            call_id: "call-agent".into(),
+            source: source.clone(),
+            msg: Box::new(EventMsg::WebSearchEnd(
+                codex_core::protocol::WebSearchEndEvent {
+                    call_id: "search".into(),
+                    query: "toml loader".into(),
+                },
+            )),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-agent".into(),
+        msg: EventMsg::SubAgentEnd(codex_core::protocol::SubAgentEndEvent {
+            call_id: "call-agent".into(),
+            source,
+            model: "gpt-5-mini".into(),
+            prompt: "Find where config is loaded".into(),
+            read_only: true,
+            status: codex_core::protocol::SubAgentStatus::Completed,
+            message: Some("Config is loaded in config/mod.rs".into()),
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 3, "expected begin, activity and end cells");
+    let begin = lines_to_single_string(&cells[0]);
+    assert!(begin.contains("Spawned agent explorer (gpt-5-mini, read-only)"));
+    assert!(begin.contains("Find where config is loaded"));
+    assert!(lines_to_single_string(&cells[1]).contains("explorer searched toml loader"));
+    let end = lines_to_single_string(&cells[2]);
+    assert!(end.contains("Agent explorer finished"));
+    assert!(end.contains("Config is loaded in config/mod.rs"));
+}
+
+#[tokio::test]
+async fn budget_exceeded_cancels_loop_and_shows_error() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.start_loop_mode(5, None);
+    chat.loop_state.prompt = "keep going".to_string();
+    chat.loop_state.current_iteration = 2;
+    drain_insert_history(&mut rx);
+
+    let status = codex_core::protocol::BudgetStatus {
+        entries: vec![codex_core::protocol::BudgetEntry {
+            limit: codex_core::protocol::BudgetLimit::Turns,
+            used: 2.0,
+            max: 2.0,
+        }],
+        exceeded: Some(codex_core::protocol::BudgetLimit::Turns),
+    };
+    chat.handle_codex_event(Event {
+        id: "budget".into(),
+        msg: EventMsg::BudgetExceeded(codex_core::protocol::BudgetExceededEvent {
+            limit: codex_core::protocol::BudgetLimit::Turns,
+            message: "Session budget exceeded: reached the limit of 2 turns.".into(),
+            status: status.clone(),
+        }),
+    });
+
+    assert!(!chat.loop_state.active, "loop should be cancelled");
+    assert_eq!(chat.budget_status, Some(status));
+    let blob = drain_insert_history(&mut rx)
+        .iter()
+        .map(|cell| lines_to_single_string(cell))
+        .collect::<String>();
+    assert!(blob.contains("Loop cancelled after 2 iteration(s): session budget exceeded."));
+    assert!(blob.contains("Session budget exceeded: reached the limit of 2 turns."));
+}
+
+#[tokio::test]
+async fn plan_command_toggles_plan_mode_and_approval_sends_op() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Plan);
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::SetPlanMode { enabled: true })) => {}
+        other => panic!("expected SetPlanMode {{ enabled: true }}, got {other:?}"),
+    }
+    chat.handle_codex_event(Event {
+        id: "plan-mode".into(),
+        msg: EventMsg::PlanModeUpdated(codex_core::protocol::PlanModeUpdatedEvent {
+            enabled: true,
+        }),
+    });
+    assert!(chat.plan_mode);
+    let blob = drain_insert_history(&mut rx)
+        .iter()
+        .map(|cell| lines_to_single_string(cell))
+        .collect::<String>();
+    assert!(blob.contains("Plan mode on."));
+
+    chat.handle_codex_event(Event {
+        id: "plan".into(),
+        msg: EventMsg::PlanApprovalRequest(codex_core::protocol::PlanApprovalRequestEvent {
+            plan: UpdatePlanArgs {
+                explanation: None,
+                plan: vec![PlanItemArg {
+                    step: "Add the migration".into(),
+                    status: StepStatus::Pending,
+                }],
+            },
+        }),
+    });
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::ApprovePlan)) => {}
+        other => panic!("expected ApprovePlan, got {other:?}"),
+    }
+
+    chat.dispatch_command(SlashCommand::Plan);
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::SetPlanMode { enabled: false })) => {}
+        other => panic!("expected SetPlanMode {{ enabled: false }}, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn undo_command_browses_checkpoints_and_restores() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Undo);
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::ListCheckpoints)) => {}
+        other => panic!("expected ListCheckpoints, got {other:?}"),
+    }
+
+    let checkpoint = codex_core::protocol::Checkpoint {
+        num_turns: 2,
+        commit_id: "0123456789abcdef".into(),
+        prompt: "add the parser".into(),
+        files: vec![PathBuf::from("src/parser.rs")],
+        diff_preview: "+fn parse() {}\n".into(),
+        diff_truncated: false,
+        external_changes: vec![PathBuf::from("notes.md")],
+    };
+    chat.handle_codex_event(Event {
+        id: "checkpoints".into(),
+        msg: EventMsg::ListCheckpointsResponse(
+            codex_core::protocol::ListCheckpointsResponseEvent {
+                checkpoints: vec![checkpoint.clone()],
+            },
+        ),
+    });
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(popup.contains("2 turns ago · add the parser"), "{popup}");
+    assert!(
+        popup.contains("1 file changed outside Codex: notes.md"),
+        "{popup}"
+    );
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::OpenCheckpointRestore(selected)) => assert_eq!(selected, checkpoint),
+        other => panic!("expected OpenCheckpointRestore, got {other:?}"),
+    }
+
+    chat.open_checkpoint_restore_popup(checkpoint);
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(
+        popup.contains("Checkpoint 0123456 · add the parser"),
+        "{popup}"
+    );
+    assert!(
+        popup.contains("Changed outside Codex since this checkpoint: notes.md"),
+        "{popup}"
+    );
+    assert!(popup.contains("+fn parse() {}"), "{popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::RestoreCheckpoint {
+            num_turns: 2,
+            scope: CheckpointRestoreScope::Worktree,
+        })) => {}
+        other => panic!("expected RestoreCheckpoint, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn memory_command_lists_and_edits_entries() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Memory);
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::ListMemories)) => {}
+        other => panic!("expected ListMemories, got {other:?}"),
+    }
+
+    let entry = codex_core::protocol::MemoryEntry {
+        scope: codex_core::protocol::MemoryScope::Project,
+        id: 3,
+        text: "Run tests with just test".into(),
+    };
+    chat.handle_codex_event(Event {
+        id: "memories".into(),
+        msg: EventMsg::ListMemoriesResponse(codex_core::protocol::ListMemoriesResponseEvent {
+            entries: vec![entry.clone()],
+            user_path: PathBuf::from("/home/me/.codex/memory.json"),
+            project_path: PathBuf::from("/repo/.codex/memory.json"),
+        }),
+    });
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(popup.contains("Run tests with just test"), "{popup}");
+    assert!(popup.contains("project#3"), "{popup}");
+    assert!(popup.contains("Add a user memory"), "{popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::OpenMemoryEntry(selected)) => assert_eq!(selected, entry),
+        other => panic!("expected OpenMemoryEntry, got {other:?}"),
+    }
+
+    chat.open_memory_entry_popup(entry);
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::ForgetMemory {
+            scope: codex_core::protocol::MemoryScope::Project,
+            id: 3,
+        })) => {}
+        other => panic!("expected ForgetMemory, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn output_command_opens_spilled_output() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Output);
+    let cells = drain_insert_history(&mut rx);
+    let text = lines_to_single_string(&cells.concat());
+    assert!(text.contains("No truncated tool output"), "{text}");
+
+    chat.handle_codex_event(Event {
+        id: "spill".into(),
+        msg: EventMsg::ToolOutputSpilled(codex_core::protocol::ToolOutputSpilledEvent {
+            call_id: "call-1".into(),
+            output_id: "out-1".into(),
+            path: PathBuf::from("/tmp/outputs/out-1.txt"),
+            total_lines: 1200,
+            total_bytes: 48_000,
+        }),
+    });
+    let cells = drain_insert_history(&mut rx);
+    let text = lines_to_single_string(&cells.concat());
+    assert!(text.contains("saved as out-1 (1200 lines)"), "{text}");
+
+    chat.dispatch_command(SlashCommand::Output);
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(popup.contains("out-1"), "{popup}");
+    assert!(popup.contains("1200 lines"), "{popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::OpenSpilledOutput { output_id, path }) => {
+            assert_eq!(output_id, "out-1");
+            assert_eq!(path, PathBuf::from("/tmp/outputs/out-1.txt"));
+        }
+        other => panic!("expected OpenSpilledOutput, got {other:?}"),
+    }
+}
+
 // Snapshot test: interrupting a running exec finalizes the active cell with a red ✗
 // marker (replacing the spinner) and flushes it into history.
 #[tokio::test]
@@ -3354,3 +3697,38 @@ async fn chatwidget_tall() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[tokio::test]
+async fn hide_agent_reasoning_suppresses_reasoning_cells() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.hide_agent_reasoning = true;
+
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "**Planning** the change".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentReasoning(AgentReasoningEvent {
+            text: "**Planning** the change".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentMessage(AgentMessageEvent {
+            message: "Done.".into(),
+        }),
+    });
+
+    let combined = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        !combined.contains("Planning"),
+        "reasoning leaked: {combined}"
+    );
+    assert!(combined.contains("Done."), "missing answer: {combined}");
+}
@@ -1,6 +1,7 @@
 use clap::Parser;
 use clap::ValueHint;
 use codex_common::ApprovalModeCliArg;
+use codex_common::BudgetCliArgs;
 use codex_common::CliConfigOverrides;
 use std::path::PathBuf;
 
@@ -85,6 +86,9 @@ pub struct Cli {
     #[arg(long = "add-dir", value_name = "DIR", value_hint = ValueHint::DirPath)]
     pub add_dir: Vec<PathBuf>,
 
+    #[clap(flatten)]
+    pub budget: BudgetCliArgs,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
@@ -57,7 +57,9 @@ impl ExecCell {
             duration: None,
             interaction_input,
         };
-        if self.is_exploring_cell() && Self::is_exploring_call(&call) {
+        let joins_cell = (self.is_exploring_cell() && Self::is_exploring_call(&call))
+            || (self.is_verification_cell() && call.is_verification());
+        if joins_cell {
             Some(Self {
                 calls: [self.calls.clone(), vec![call]].concat(),
                 animations_enabled: self.animations_enabled,
@@ -81,7 +83,9 @@ impl ExecCell {
     }
 
     pub(crate) fn should_flush(&self) -> bool {
-        !self.is_exploring_cell() && self.calls.iter().all(|c| c.output.is_some())
+        !self.is_exploring_cell()
+            && !self.is_verification_cell()
+            && self.calls.iter().all(|c| c.output.is_some())
     }
 
     pub(crate) fn mark_failed(&mut self) {
@@ -106,6 +110,12 @@ impl ExecCell {
         self.calls.iter().all(Self::is_exploring_call)
     }
 
+    /// Verification commands run back to back after a turn's edits, so they
+    /// share one cell the way exploring calls do.
+    pub(crate) fn is_verification_cell(&self) -> bool {
+        self.calls.iter().all(ExecCall::is_verification)
+    }
+
     pub(crate) fn is_active(&self) -> bool {
         self.calls.iter().any(|c| c.output.is_none())
     }
@@ -126,8 +136,10 @@ impl ExecCell {
     }
 
     pub(super) fn is_exploring_call(call: &ExecCall) -> bool {
-        !matches!(call.source, ExecCommandSource::UserShell)
-            && !call.parsed.is_empty()
+        !matches!(
+            call.source,
+            ExecCommandSource::UserShell | ExecCommandSource::Verification
+        ) && !call.parsed.is_empty()
             && call.parsed.iter().all(|p| {
                 matches!(
                     p,
@@ -147,4 +159,8 @@ impl ExecCall {
     pub(crate) fn is_unified_exec_interaction(&self) -> bool {
         matches!(self.source, ExecCommandSource::UnifiedExecInteraction)
     }
+
+    pub(crate) fn is_verification(&self) -> bool {
+        matches!(self.source, ExecCommandSource::Verification)
+    }
 }
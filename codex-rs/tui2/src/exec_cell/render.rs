@@ -197,7 +197,9 @@ pub(crate) fn spinner(start_time: Option<Instant>, animations_enabled: bool) ->
 
 impl HistoryCell for ExecCell {
     fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
-        if self.is_exploring_cell() {
+        if self.is_verification_cell() {
+            self.verification_display_lines(width)
+        } else if self.is_exploring_cell() {
             self.exploring_display_lines(width)
         } else {
             self.command_display_lines(width)
@@ -438,6 +440,98 @@ impl ExecCell {
         out
     }
 
+    fn verification_display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let failed = self
+            .calls
+            .iter()
+            .any(|call| call.output.as_ref().is_some_and(|o| o.exit_code != 0));
+        let (action, label) = if self.is_active() {
+            (theme::ToolAction::Running, "Verifying")
+        } else if failed {
+            (theme::ToolAction::Failed, "Verification failed")
+        } else {
+            (theme::ToolAction::Completed, "Verified")
+        };
+
+        let header = if self.is_active() {
+            Line::from(vec![
+                spinner(self.active_start_time(), self.animations_enabled()),
+                " ".into(),
+                theme::header_span(label),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", action.icon()),
+                    Style::default().fg(action.color()).dim(),
+                ),
+                Span::styled(label.to_string(), Style::default().fg(action.color()).dim()),
+            ])
+        };
+        let mut out = vec![header];
+
+        // Each command gets one status line; failures also show the tail of
+        // their output so the problem is visible without opening the transcript.
+        let body_width = usize::from(width).saturating_sub(4).max(1);
+        let mut body: Vec<Line<'static>> = Vec::new();
+        for call in &self.calls {
+            let status = match call.output.as_ref() {
+                None => spinner(call.start_time, self.animations_enabled()),
+                Some(output) if output.exit_code == 0 => theme::checkmark(),
+                Some(_) => theme::crossmark(),
+            };
+            let mut spans = vec![
+                status,
+                " ".into(),
+                Span::from(strip_bash_lc_and_escape(&call.command)),
+            ];
+            if let Some(output) = call.output.as_ref()
+                && output.exit_code != 0
+            {
+                spans.push(format!(" (exit {})", output.exit_code).dim());
+            }
+            if let Some(duration) = call.duration {
+                spans.push(format!(" • {}", format_duration(duration)).dim());
+            }
+            let wrapped = word_wrap_line(
+                &Line::from(spans),
+                RtOptions::new(body_width).subsequent_indent("  ".into()),
+            );
+            push_owned_lines(&wrapped, &mut body);
+
+            let Some(output) = call.output.as_ref().filter(|o| o.exit_code != 0) else {
+                continue;
+            };
+            let raw_output = output_lines(
+                Some(output),
+                OutputLinesParams {
+                    line_limit: TOOL_CALL_MAX_LINES,
+                    only_err: false,
+                    include_angle_pipe: false,
+                    include_prefix: false,
+                },
+            );
+            let output_opts = RtOptions::new(body_width.saturating_sub(4).max(1))
+                .word_splitter(WordSplitter::NoHyphenation);
+            let mut wrapped_output: Vec<Line<'static>> = Vec::new();
+            for line in &raw_output.lines {
+                push_owned_lines(
+                    &word_wrap_line(line, output_opts.clone()),
+                    &mut wrapped_output,
+                );
+            }
+            let trimmed = Self::truncate_lines_middle(
+                &wrapped_output,
+                TOOL_CALL_MAX_LINES,
+                raw_output.omitted,
+            );
+            body.extend(prefix_lines(trimmed, "  │ ".dim(), "  │ ".dim()));
+        }
+
+        out.extend(prefix_lines(body, "  ╰ ".dim(), "    ".into()));
+        out
+    }
+
     fn command_display_lines(&self, width: u16) -> Vec<Line<'static>> {
         let [call] = &self.calls.as_slice() else {
             panic!("Expected exactly one call in a command display cell");
@@ -734,6 +828,79 @@ const EXEC_DISPLAY_LAYOUT: ExecDisplayLayout = ExecDisplayLayout::new(
 mod tests {
     use super::*;
     use codex_core::protocol::ExecCommandSource;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn bash(script: &str) -> Vec<String> {
+        vec!["bash".into(), "-lc".into(), script.into()]
+    }
+
+    #[test]
+    fn verification_calls_render_as_one_group() {
+        let cell = new_active_exec_command(
+            "verify-1".to_string(),
+            bash("cargo fmt"),
+            Vec::new(),
+            ExecCommandSource::Verification,
+            None,
+            false,
+        );
+        let mut cell = cell
+            .with_added_call(
+                "verify-2".to_string(),
+                bash("cargo clippy -p alpha"),
+                Vec::new(),
+                ExecCommandSource::Verification,
+                None,
+            )
+            .expect("verification calls share a cell");
+        assert!(
+            cell.with_added_call(
+                "call-3".to_string(),
+                bash("cargo fmt"),
+                Vec::new(),
+                ExecCommandSource::Agent,
+                None,
+            )
+            .is_none()
+        );
+
+        cell.complete_call(
+            "verify-1",
+            CommandOutput::default(),
+            Duration::from_millis(300),
+        );
+        cell.complete_call(
+            "verify-2",
+            CommandOutput {
+                exit_code: 101,
+                aggregated_output: "error: unused variable".to_string(),
+                formatted_output: String::new(),
+            },
+            Duration::from_secs(2),
+        );
+        assert!(!cell.should_flush());
+
+        let rendered: Vec<String> = cell
+            .display_lines(80)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "● Verification failed",
+                "  ╰ ✓ cargo fmt • 300ms",
+                "    ✗ cargo clippy -p alpha (exit 101) • 2.00s",
+                "      │ error: unused variable",
+            ]
+        );
+    }
 
     #[test]
     fn user_shell_output_is_limited_by_screen_lines() {
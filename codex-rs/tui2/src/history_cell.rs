@@ -25,10 +25,17 @@ use base64::Engine;
 use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::FetchUrlEndEvent;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
+use codex_core::protocol::SubAgentSource;
+use codex_core::protocol::SubAgentStatus;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
@@ -1318,6 +1325,37 @@ pub(crate) fn new_web_search_call(query: String) -> PrefixedWrappedHistoryCell {
     PrefixedWrappedHistoryCell::new(text, "• ".dim(), "  ")
 }
 
+pub(crate) fn new_fetch_url_call(event: &FetchUrlEndEvent) -> PlainHistoryCell {
+    let url = event.final_url.as_deref().unwrap_or(&event.url).to_string();
+    let heading = if event.error.is_some() {
+        "Fetch failed "
+    } else {
+        "Fetched "
+    };
+    let detail: Vec<Span<'static>> = match &event.error {
+        Some(error) => vec![error.clone().red()],
+        None => {
+            let mut spans = Vec::new();
+            if let Some(title) = &event.title {
+                spans.push(format!("{title} · ").dim());
+            }
+            spans.push(format!("page {} of {}", event.page, event.total_pages).dim());
+            if event.cached {
+                spans.push(" (cached)".dim());
+            }
+            spans
+        }
+    };
+    let mut detail_line = vec!["  └ ".dim()];
+    detail_line.extend(detail);
+    PlainHistoryCell {
+        lines: vec![
+            vec!["• ".dim(), heading.bold(), url.into()].into(),
+            detail_line.into(),
+        ],
+    }
+}
+
 /// If the first content is an image, return a new cell with the image.
 /// TODO(rgwood-dd): Handle images properly even if they're not the first result.
 fn try_new_completed_mcp_tool_call_with_image_output(
@@ -1791,6 +1829,130 @@ pub(crate) fn new_view_image_tool_call(path: PathBuf, cwd: &Path) -> PlainHistor
     PlainHistoryCell { lines }
 }
 
+const SUB_AGENT_PROMPT_MAX_GRAPHEMES: usize = 120;
+const SUB_AGENT_RESULT_MAX_LINES: usize = 4;
+
+fn sub_agent_label(source: &SubAgentSource) -> String {
+    match source {
+        SubAgentSource::SpawnAgent { role: Some(role) } => role.clone(),
+        _ => "sub-agent".to_string(),
+    }
+}
+
+pub(crate) fn new_sub_agent_begin(event: &SubAgentBeginEvent) -> PlainHistoryCell {
+    let access = if event.read_only {
+        "read-only"
+    } else {
+        "workspace-write"
+    };
+    let prompt = event.prompt.lines().next().unwrap_or_default();
+    let lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".dim(),
+            "Spawned agent ".bold(),
+            sub_agent_label(&event.source).cyan(),
+            format!(" ({}, {access})", event.model).dim(),
+        ]
+        .into(),
+        vec![
+            "  └ ".dim(),
+            truncate_text(prompt, SUB_AGENT_PROMPT_MAX_GRAPHEMES).dim(),
+        ]
+        .into(),
+    ];
+    PlainHistoryCell { lines }
+}
+
+/// One-line summary of something a sub-agent did, or `None` for events that
+/// are not worth a line of their own.
+pub(crate) fn new_sub_agent_activity(event: &SubAgentActivityEvent) -> Option<PlainHistoryCell> {
+    let summary: Vec<Span<'static>> = match event.msg.as_ref() {
+        EventMsg::ExecCommandEnd(end) => {
+            let command = truncate_text(
+                &strip_bash_lc_and_escape(&end.command),
+                SUB_AGENT_PROMPT_MAX_GRAPHEMES,
+            );
+            let mut spans = vec!["ran ".into(), command.into()];
+            if end.exit_code != 0 {
+                spans.push(format!(" (exit {})", end.exit_code).red());
+            }
+            spans
+        }
+        EventMsg::PatchApplyEnd(end) => {
+            let noun = if end.changes.len() == 1 {
+                "file"
+            } else {
+                "files"
+            };
+            let mut spans = vec![format!("edited {} {noun}", end.changes.len()).into()];
+            if !end.success {
+                spans.push(" (failed)".red());
+            }
+            spans
+        }
+        EventMsg::McpToolCallEnd(end) => vec![
+            "called ".into(),
+            format!("{}.{}", end.invocation.server, end.invocation.tool).into(),
+        ],
+        EventMsg::WebSearchEnd(end) => vec!["searched ".into(), end.query.clone().into()],
+        EventMsg::FetchUrlEnd(end) => vec!["fetched ".into(), end.url.clone().into()],
+        EventMsg::Error(error) => vec![error.message.clone().red()],
+        _ => return None,
+    };
+    let mut spans = vec![
+        "  ↳ ".dim(),
+        sub_agent_label(&event.source).cyan(),
+        " ".into(),
+    ];
+    spans.extend(summary);
+    Some(PlainHistoryCell {
+        lines: vec![spans.into()],
+    })
+}
+
+pub(crate) fn new_sub_agent_end(event: &SubAgentEndEvent) -> PlainHistoryCell {
+    let outcome = match event.status {
+        SubAgentStatus::Completed => "finished".green(),
+        SubAgentStatus::Failed => "failed".red(),
+        SubAgentStatus::Interrupted => "interrupted".red(),
+    };
+    let mut lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".dim(),
+            "Agent ".bold(),
+            sub_agent_label(&event.source).cyan(),
+            " ".into(),
+            outcome,
+        ]
+        .into(),
+    ];
+    if let Some(message) = event.message.as_deref() {
+        let result_lines: Vec<&str> = message.lines().filter(|l| !l.trim().is_empty()).collect();
+        for (idx, line) in result_lines
+            .iter()
+            .take(SUB_AGENT_RESULT_MAX_LINES)
+            .enumerate()
+        {
+            let prefix = if idx == 0 { "  └ " } else { "    " };
+            lines.push(vec![prefix.dim(), line.to_string().dim()].into());
+        }
+        if result_lines.len() > SUB_AGENT_RESULT_MAX_LINES {
+            lines.push(
+                vec![
+                    "    ".into(),
+                    format!(
+                        "… +{} lines",
+                        result_lines.len() - SUB_AGENT_RESULT_MAX_LINES
+                    )
+                    .dim(),
+                ]
+                .into(),
+            );
+        }
+    }
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_reasoning_summary_block(full_reasoning_buffer: String) -> Box<dyn HistoryCell> {
     // Experimental format is following:
     // ** header **
@@ -145,6 +145,9 @@ pub async fn run_main(
             .raw_overrides
             .push("features.web_search_request=true".to_string());
     }
+    // Budget flags are shorthands for `-c budget.*` overrides.
+    let budget_overrides = cli.budget.to_raw_overrides();
+    cli.config_overrides.raw_overrides.extend(budget_overrides);
 
     // When using `--oss`, let the bootstrapper pick the model (defaulting to
     // gpt-oss:20b) and ensure it is present locally. Also, force the built‑in
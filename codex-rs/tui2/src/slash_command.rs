@@ -15,6 +15,7 @@ pub enum SlashCommand {
     Model,
     Endpoint,
     Approvals,
+    Plan,
     Skills,
     Review,
     ReviewFix,
@@ -24,7 +25,9 @@ pub enum SlashCommand {
     Resume,
     Init,
     Compact,
-    // Undo,
+    Undo,
+    Memory,
+    Output,
     Diff,
     Mention,
     Status,
@@ -53,7 +56,9 @@ impl SlashCommand {
             SlashCommand::Loop => "run a task in an autonomous loop until completion",
             SlashCommand::CancelLoop => "stop the current autonomous loop",
             SlashCommand::Resume => "resume a saved chat",
-            // SlashCommand::Undo => "ask Codex to undo a turn",
+            SlashCommand::Undo => "browse checkpoints and restore an earlier turn",
+            SlashCommand::Memory => "view and edit what Codex remembers across sessions",
+            SlashCommand::Output => "open truncated tool output in the pager",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
@@ -63,6 +68,7 @@ impl SlashCommand {
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Endpoint => "show or change the Azure OpenAI endpoint",
             SlashCommand::Approvals => "choose what Codex can do without approval",
+            SlashCommand::Plan => "toggle plan mode: explore read-only and approve a plan first",
             SlashCommand::Mcp => "list configured MCP tools",
             SlashCommand::Theme => "change the color theme",
             SlashCommand::ToggleMouseMode => "toggle mouse capture for native text selection",
@@ -85,15 +91,18 @@ impl SlashCommand {
             | SlashCommand::Resume
             | SlashCommand::Init
             | SlashCommand::Compact
-            // | SlashCommand::Undo
+            | SlashCommand::Undo
             | SlashCommand::Model
             | SlashCommand::Endpoint
             | SlashCommand::Approvals
+            | SlashCommand::Plan
             | SlashCommand::Review
             | SlashCommand::ReviewFix
             | SlashCommand::Loop
             | SlashCommand::Logout => false,
             SlashCommand::Diff
+            | SlashCommand::Memory
+            | SlashCommand::Output
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
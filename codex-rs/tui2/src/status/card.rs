@@ -9,8 +9,12 @@ use codex_common::create_config_summary_entries;
 use codex_common::elapsed::format_duration;
 use codex_core::config::Config;
 use codex_core::models_manager::model_family::ModelFamily;
+use codex_core::protocol::BudgetEntry;
+use codex_core::protocol::BudgetLimit;
+use codex_core::protocol::BudgetStatus;
 use codex_core::protocol::NetworkAccess;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::TokenCost;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::ConversationId;
 use codex_protocol::account::PlanType;
@@ -74,6 +78,8 @@ struct StatusHistoryCell {
     token_usage: StatusTokenUsageData,
     last_request: Option<StatusRequestMetrics>,
     rate_limits: StatusRateLimitData,
+    budget: Option<BudgetStatus>,
+    cost: Option<TokenCost>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -86,6 +92,9 @@ pub(crate) fn new_status_output(
     last_request: Option<StatusRequestMetrics>,
     session_id: &Option<ConversationId>,
     rate_limits: Option<&RateLimitSnapshotDisplay>,
+    budget: Option<&BudgetStatus>,
+    cost: Option<&TokenCost>,
+    nested_agents: &[PathBuf],
     plan_type: Option<PlanType>,
     now: DateTime<Local>,
     model_name: &str,
@@ -100,6 +109,9 @@ pub(crate) fn new_status_output(
         last_request,
         session_id,
         rate_limits,
+        budget,
+        cost,
+        nested_agents,
         plan_type,
         now,
         model_name,
@@ -119,6 +131,9 @@ impl StatusHistoryCell {
         last_request: Option<StatusRequestMetrics>,
         session_id: &Option<ConversationId>,
         rate_limits: Option<&RateLimitSnapshotDisplay>,
+        budget: Option<&BudgetStatus>,
+        cost: Option<&TokenCost>,
+        nested_agents: &[PathBuf],
         plan_type: Option<PlanType>,
         now: DateTime<Local>,
         model_name: &str,
@@ -142,7 +157,7 @@ impl StatusHistoryCell {
                 }
             }
         };
-        let agents_summary = compose_agents_summary(config);
+        let agents_summary = compose_agents_summary(config, nested_agents);
         let account = compose_account_display(auth_manager, plan_type);
         let session_id = session_id.as_ref().map(std::string::ToString::to_string);
         let context_window = model_family.context_window.and_then(|window| {
@@ -173,6 +188,8 @@ impl StatusHistoryCell {
             token_usage,
             last_request,
             rate_limits,
+            budget: budget.filter(|budget| !budget.entries.is_empty()).cloned(),
+            cost: cost.copied(),
         }
     }
 
@@ -194,6 +211,15 @@ impl StatusHistoryCell {
         ]
     }
 
+    fn cost_spans(&self) -> Option<Vec<Span<'static>>> {
+        let cost = self.cost.as_ref()?;
+        Some(vec![
+            Span::from(format!("${:.2}", cost.session_usd)),
+            Span::from(" this session").dim(),
+            Span::from(format!(" (${:.2} this turn)", cost.turn_usd)).dim(),
+        ])
+    }
+
     fn last_request_spans(&self) -> Option<Vec<Span<'static>>> {
         let metrics = self.last_request.as_ref()?;
         let mut spans = Vec::new();
@@ -243,6 +269,25 @@ impl StatusHistoryCell {
         ])
     }
 
+    fn budget_lines(&self, formatter: &FieldFormatter) -> Vec<Line<'static>> {
+        let Some(budget) = self.budget.as_ref() else {
+            return Vec::new();
+        };
+        let mut lines = Vec::with_capacity(budget.entries.len());
+        for entry in &budget.entries {
+            let mut spans = vec![Span::from(format_budget_remaining(entry))];
+            if budget.exceeded == Some(entry.limit) {
+                spans.push(Span::from(" (limit reached)").red());
+            }
+            if lines.is_empty() {
+                lines.push(formatter.line("Budget", spans));
+            } else {
+                lines.push(formatter.continuation(spans));
+            }
+        }
+        lines
+    }
+
     fn rate_limit_lines(
         &self,
         available_inner_width: usize,
@@ -390,6 +435,11 @@ impl HistoryCell for StatusHistoryCell {
             push_label(&mut labels, &mut seen, "Session");
         }
         push_label(&mut labels, &mut seen, "Token usage");
+        if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. }))
+            && self.cost.is_some()
+        {
+            push_label(&mut labels, &mut seen, "Cost");
+        }
         if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. }))
             && self.last_request_spans().is_some()
         {
@@ -398,6 +448,9 @@ impl HistoryCell for StatusHistoryCell {
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
+        if self.budget.is_some() {
+            push_label(&mut labels, &mut seen, "Budget");
+        }
         self.collect_rate_limit_labels(&mut seen, &mut labels);
 
         let formatter = FieldFormatter::from_labels(labels.iter().map(String::as_str));
@@ -433,6 +486,9 @@ impl HistoryCell for StatusHistoryCell {
         // Hide token usage only for ChatGPT subscribers
         if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. })) {
             lines.push(formatter.line("Token usage", self.token_usage_spans()));
+            if let Some(spans) = self.cost_spans() {
+                lines.push(formatter.line("Cost", spans));
+            }
         }
 
         if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. }))
@@ -445,6 +501,8 @@ impl HistoryCell for StatusHistoryCell {
             lines.push(formatter.line("Context window", spans));
         }
 
+        lines.extend(self.budget_lines(&formatter));
+
         lines.extend(self.rate_limit_lines(available_inner_width, &formatter));
 
         let content_width = lines.iter().map(line_display_width).max().unwrap_or(0);
@@ -457,3 +515,27 @@ impl HistoryCell for StatusHistoryCell {
         with_border_with_inner_width(truncated_lines, inner_width)
     }
 }
+
+fn format_budget_remaining(entry: &BudgetEntry) -> String {
+    let remaining = entry.remaining();
+    match entry.limit {
+        BudgetLimit::InputTokens | BudgetLimit::OutputTokens => format!(
+            "{} of {} {} left",
+            format_tokens_compact(remaining as i64),
+            format_tokens_compact(entry.max as i64),
+            entry.limit
+        ),
+        BudgetLimit::Cost => format!("${remaining:.2} of ${:.2} left", entry.max),
+        BudgetLimit::ToolCalls | BudgetLimit::Turns => {
+            format!(
+                "{} of {} {} left",
+                remaining as u64, entry.max as u64, entry.limit
+            )
+        }
+        BudgetLimit::Duration => format!(
+            "{} of {} left",
+            format_duration(Duration::from_secs(remaining as u64)),
+            format_duration(Duration::from_secs(entry.max as u64))
+        ),
+    }
+}
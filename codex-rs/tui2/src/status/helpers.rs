@@ -8,6 +8,7 @@ use codex_core::config::Config;
 use codex_core::project_doc::discover_project_doc_paths;
 use codex_protocol::account::PlanType;
 use std::path::Path;
+use std::path::PathBuf;
 use unicode_width::UnicodeWidthStr;
 
 use super::account::StatusAccountDisplay;
@@ -36,11 +37,13 @@ pub(crate) fn compose_model_display(
     (model_name.to_string(), details)
 }
 
-pub(crate) fn compose_agents_summary(config: &Config) -> String {
+/// Lists the AGENTS.md files loaded at session start followed by the nested
+/// ones loaded since, relative to the working directory where possible.
+pub(crate) fn compose_agents_summary(config: &Config, nested: &[PathBuf]) -> String {
     match discover_project_doc_paths(config) {
         Ok(paths) => {
             let mut rels: Vec<String> = Vec::new();
-            for p in paths {
+            for p in paths.into_iter().chain(nested.iter().cloned()) {
                 let file_name = p
                     .file_name()
                     .map(|name| name.to_string_lossy().to_string())
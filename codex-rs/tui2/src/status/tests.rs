@@ -9,10 +9,14 @@ use codex_core::config::Config;
 use codex_core::config::ConfigBuilder;
 use codex_core::models_manager::manager::ModelsManager;
 use codex_core::models_manager::model_family::ModelFamily;
+use codex_core::protocol::BudgetEntry;
+use codex_core::protocol::BudgetLimit;
+use codex_core::protocol::BudgetStatus;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::TokenCost;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::openai_models::ReasoningEffort;
@@ -144,6 +148,9 @@ async fn status_snapshot_includes_reasoning_details() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -202,6 +209,9 @@ async fn status_snapshot_includes_monthly_limit() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -248,6 +258,9 @@ async fn status_snapshot_shows_unlimited_credits() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -293,6 +306,9 @@ async fn status_snapshot_shows_positive_credits() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -338,6 +354,9 @@ async fn status_snapshot_hides_zero_credits() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -381,6 +400,9 @@ async fn status_snapshot_hides_when_has_no_credits_flag() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -424,6 +446,9 @@ async fn status_card_token_usage_excludes_cached_tokens() {
         &None,
         None,
         None,
+        None,
+        &[],
+        None,
         now,
         &model_slug,
     );
@@ -482,6 +507,9 @@ async fn status_snapshot_truncates_in_narrow_terminal() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -529,6 +557,9 @@ async fn status_snapshot_shows_missing_limits_message() {
         &None,
         None,
         None,
+        None,
+        &[],
+        None,
         now,
         &model_slug,
     );
@@ -594,6 +625,9 @@ async fn status_snapshot_includes_credits_and_limits() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -647,6 +681,9 @@ async fn status_snapshot_shows_empty_limits_message() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         captured_at,
         &model_slug,
     );
@@ -709,6 +746,9 @@ async fn status_snapshot_shows_stale_limits_message() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         now,
         &model_slug,
     );
@@ -775,6 +815,9 @@ async fn status_snapshot_cached_limits_hide_credits_without_flag() {
         &None,
         Some(&rate_display),
         None,
+        None,
+        &[],
+        None,
         now,
         &model_slug,
     );
@@ -827,6 +870,9 @@ async fn status_context_window_uses_last_usage() {
         &None,
         None,
         None,
+        None,
+        &[],
+        None,
         now,
         &model_slug,
     );
@@ -845,3 +891,77 @@ async fn status_context_window_uses_last_usage() {
         "context line should not use total aggregated tokens, got: {context_line}"
     );
 }
+
+#[tokio::test]
+async fn status_shows_cost_and_remaining_budget() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home).await;
+    config.cwd = PathBuf::from("/workspace/tests");
+    let auth_manager = test_auth_manager(&config);
+    let usage = TokenUsage::default();
+    let budget = BudgetStatus {
+        entries: vec![
+            BudgetEntry {
+                limit: BudgetLimit::Turns,
+                used: 3.0,
+                max: 10.0,
+            },
+            BudgetEntry {
+                limit: BudgetLimit::Cost,
+                used: 5.5,
+                max: 5.0,
+            },
+        ],
+        exceeded: Some(BudgetLimit::Cost),
+    };
+    let cost = TokenCost {
+        session_usd: 5.5,
+        turn_usd: 0.75,
+    };
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 2, 3, 4, 5, 6)
+        .single()
+        .expect("timestamp");
+
+    let model_slug = ModelsManager::get_model_offline(config.model.as_deref());
+    let model_family = test_model_family(&model_slug, &config);
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        &model_family,
+        &usage,
+        Some(&usage),
+        None,
+        &None,
+        None,
+        Some(&budget),
+        Some(&cost),
+        &[],
+        None,
+        now,
+        &model_slug,
+    );
+    let rendered_lines = render_lines(&composite.display_lines(80));
+    assert!(
+        rendered_lines
+            .iter()
+            .any(|line| line.contains("Cost:")
+                && line.contains("$5.50 this session ($0.75 this turn)")),
+        "expected cost line, got: {rendered_lines:?}"
+    );
+    let budget_index = rendered_lines
+        .iter()
+        .position(|line| line.contains("Budget:"))
+        .expect("budget line");
+
+    assert!(
+        rendered_lines[budget_index].contains("7 of 10 turns left"),
+        "unexpected budget line: {}",
+        rendered_lines[budget_index]
+    );
+    assert!(
+        rendered_lines[budget_index + 1].contains("$0.00 of $5.00 left (limit reached)"),
+        "unexpected budget line: {}",
+        rendered_lines[budget_index + 1]
+    );
+}
@@ -462,6 +462,59 @@ pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToo
     restore_to_commit_inner(repo_root.as_path(), repo_prefix.as_deref(), commit_id)
 }
 
+/// Changes between two snapshot commits, limited to `repo_path`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GhostCommitDiff {
+    /// Changed paths relative to `repo_path`.
+    pub files: Vec<PathBuf>,
+    /// Unified diff of `files`.
+    pub patch: String,
+}
+
+/// Diff two commits (typically ghost snapshots) for the part of the
+/// repository under `repo_path`.
+pub fn diff_ghost_commits(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+) -> Result<GhostCommitDiff, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    // `--relative` scopes the diff to `repo_path` and reports paths relative to it.
+    let names = run_git_for_stdout_all(
+        repo_path,
+        [
+            "diff",
+            "--name-only",
+            "-z",
+            "--no-renames",
+            "--relative",
+            from,
+            to,
+        ],
+        None,
+    )?;
+    let files = names
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    let patch = run_git_for_stdout_all(
+        repo_path,
+        [
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            "--relative",
+            from,
+            to,
+        ],
+        None,
+    )?;
+    Ok(GhostCommitDiff { files, patch })
+}
+
 /// Restores the working tree and index to the given commit using `git restore`.
 /// The repository root and optional repository-relative prefix limit the restore scope.
 fn restore_to_commit_inner(
@@ -946,6 +999,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    /// Diffing two snapshots lists the changed files relative to the subdirectory.
+    fn diff_ghost_commits_reports_changes_under_repo_path() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        let workspace = repo.join("workspace");
+        std::fs::create_dir_all(&workspace)?;
+        std::fs::write(workspace.join("kept.txt"), "one\n")?;
+        std::fs::write(workspace.join("edited.txt"), "before\n")?;
+        std::fs::write(repo.join("outside.txt"), "outside\n")?;
+        let first = create_ghost_commit(&CreateGhostCommitOptions::new(&workspace))?;
+
+        std::fs::write(workspace.join("edited.txt"), "after\n")?;
+        std::fs::write(workspace.join("added.txt"), "new\n")?;
+        std::fs::write(repo.join("outside.txt"), "changed outside\n")?;
+        let second = create_ghost_commit(&CreateGhostCommitOptions::new(&workspace))?;
+
+        let diff = diff_ghost_commits(&workspace, first.id(), second.id())?;
+        assert_eq!(
+            diff.files,
+            vec![PathBuf::from("added.txt"), PathBuf::from("edited.txt")]
+        );
+        assert!(diff.patch.contains("-before"));
+        assert!(diff.patch.contains("+after"));
+        assert!(!diff.patch.contains("outside"));
+        Ok(())
+    }
+
     #[test]
     /// Verifies a ghost commit can be created and restored end to end.
     fn create_and_restore_roundtrip() -> Result<(), GitToolingError> {
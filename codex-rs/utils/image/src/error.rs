@@ -23,6 +23,16 @@ pub enum ImageProcessingError {
         #[source]
         source: image::ImageError,
     },
+    #[error("failed to render SVG at {path}: {message}")]
+    Svg { path: PathBuf, message: String },
+    #[error("failed to render PDF at {path}: {message}")]
+    Pdf { path: PathBuf, message: String },
+    #[error("page {page} is out of range: {path} has {total} page(s)")]
+    PageOutOfRange {
+        path: PathBuf,
+        page: u32,
+        total: usize,
+    },
 }
 
 impl ImageProcessingError {
@@ -20,7 +20,20 @@ pub const MAX_WIDTH: u32 = 2048;
 /// Maximum height used when resizing images before uploading.
 pub const MAX_HEIGHT: u32 = 768;
 
+/// Longest side used for documents (SVGs and PDF pages) and for
+/// `ImageDetail::High`; documents are rarely legible at `MAX_HEIGHT`.
+pub const MAX_DOCUMENT_DIMENSION: u32 = 2048;
+/// Longest side used for `ImageDetail::Low`.
+pub const LOW_DETAIL_DIMENSION: u32 = 512;
+/// Maximum number of tiles a tall image is split into.
+pub const MAX_TILES: usize = 8;
+/// Rows shared by consecutive tiles so that text cut by a tile edge is
+/// readable in at least one of them.
+const TILE_OVERLAP: u32 = 64;
+
 pub mod error;
+mod pdf;
+mod svg;
 
 #[derive(Debug, Clone)]
 pub struct EncodedImage {
@@ -37,15 +50,79 @@ impl EncodedImage {
     }
 }
 
-static IMAGE_CACHE: LazyLock<BlockingLruCache<[u8; 20], EncodedImage>> =
+/// How much detail the model asked for when attaching an image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ImageDetail {
+    Low,
+    #[default]
+    Auto,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ResizeOptions {
+    pub detail: ImageDetail,
+    /// Additional cap on the longest side, applied on top of `detail`.
+    pub max_dimension: Option<u32>,
+}
+
+impl ResizeOptions {
+    /// Returns the `(width, height)` box an image must fit in.
+    fn bounds(self, document: bool) -> (u32, u32) {
+        let (width, height) = match (self.detail, document) {
+            (ImageDetail::Low, _) => (LOW_DETAIL_DIMENSION, LOW_DETAIL_DIMENSION),
+            (ImageDetail::Auto, false) => (MAX_WIDTH, MAX_HEIGHT),
+            (ImageDetail::Auto, true) | (ImageDetail::High, _) => {
+                (MAX_DOCUMENT_DIMENSION, MAX_DOCUMENT_DIMENSION)
+            }
+        };
+        match self.max_dimension {
+            Some(max) => {
+                let max = max.max(1);
+                (width.min(max), height.min(max))
+            }
+            None => (width, height),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub resize: ResizeOptions,
+    /// 1-based PDF pages to render; the first page when empty. Ignored for
+    /// other formats.
+    pub pages: Vec<u32>,
+    /// Split images much taller than they are wide into several tiles
+    /// instead of shrinking them until they are unreadable.
+    pub tile: bool,
+}
+
+/// One image produced by [`render_for_model`].
+#[derive(Debug, Clone)]
+pub struct RenderedImage {
+    pub image: EncodedImage,
+    /// Which part of the source this is (e.g. "page 2 of 5" or "tile 1 of 3");
+    /// `None` when the file produced a single image.
+    pub label: Option<String>,
+}
+
+static IMAGE_CACHE: LazyLock<BlockingLruCache<([u8; 20], ResizeOptions), EncodedImage>> =
     LazyLock::new(|| BlockingLruCache::new(NonZeroUsize::new(32).unwrap_or(NonZeroUsize::MIN)));
 
 pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessingError> {
+    load_and_resize_with_options(path, ResizeOptions::default())
+}
+
+pub fn load_and_resize_with_options(
+    path: &Path,
+    options: ResizeOptions,
+) -> Result<EncodedImage, ImageProcessingError> {
     let path_buf = path.to_path_buf();
 
     let file_bytes = read_file_bytes(path, &path_buf)?;
 
-    let key = sha1_digest(&file_bytes);
+    let key = (sha1_digest(&file_bytes), options);
+    let (max_width, max_height) = options.bounds(false);
 
     IMAGE_CACHE.get_or_try_insert_with(key, move || {
         let format = match image::guess_format(&file_bytes) {
@@ -63,7 +140,7 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
 
         let (width, height) = dynamic.dimensions();
 
-        let encoded = if width <= MAX_WIDTH && height <= MAX_HEIGHT {
+        let encoded = if width <= max_width && height <= max_height {
             if let Some(format) = format {
                 let mime = format_to_mime(format);
                 EncodedImage {
@@ -83,7 +160,7 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
                 }
             }
         } else {
-            let resized = dynamic.resize(MAX_WIDTH, MAX_HEIGHT, FilterType::Triangle);
+            let resized = dynamic.resize(max_width, max_height, FilterType::Triangle);
             let target_format = format.unwrap_or(ImageFormat::Png);
             let (bytes, output_format) = encode_image(&resized, target_format)?;
             let mime = format_to_mime(output_format);
@@ -99,6 +176,147 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
     })
 }
 
+/// Turns the file at `path` into one or more images for the model.
+///
+/// Raster images are resized like [`load_and_resize_with_options`], SVGs are
+/// rasterized, and the requested PDF pages are rendered one image per page.
+/// With `RenderOptions::tile`, images much taller than they are wide (such as
+/// full-page screenshots) are split into overlapping tiles instead of being
+/// shrunk until they are unreadable.
+pub fn render_for_model(
+    path: &Path,
+    options: &RenderOptions,
+) -> Result<Vec<RenderedImage>, ImageProcessingError> {
+    let file_bytes = read_file_bytes(path, path)?;
+    let resize = options.resize;
+
+    if pdf::is_pdf(&file_bytes) {
+        let (max_width, max_height) = resize.bounds(true);
+        let (pages, total) =
+            pdf::render_pages(path, file_bytes, &options.pages, max_width, max_height)?;
+        return pages
+            .into_iter()
+            .map(|page| {
+                Ok(RenderedImage {
+                    image: encode_dynamic(&page.image)?,
+                    label: Some(format!("page {} of {total}", page.number)),
+                })
+            })
+            .collect();
+    }
+
+    if svg::is_svg(path, &file_bytes) {
+        let (max_width, max_height) = resize.bounds(true);
+        // Leave room for tiling so that tall diagrams keep their width.
+        let render_height = if options.tile {
+            max_height.saturating_mul(MAX_TILES as u32)
+        } else {
+            max_height
+        };
+        let image = svg::rasterize(path, &file_bytes, max_width, render_height)?;
+        return fit_or_tile(&image, (max_width, max_height), options.tile);
+    }
+
+    let bounds = resize.bounds(false);
+    if options.tile {
+        let dynamic = image::load_from_memory(&file_bytes).map_err(|source| {
+            ImageProcessingError::Decode {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        if let Some(tiles) = tile_tall_image(&dynamic, bounds) {
+            return encode_tiles(tiles);
+        }
+    }
+
+    let image = load_and_resize_with_options(path, resize)?;
+    Ok(vec![RenderedImage { image, label: None }])
+}
+
+fn fit_or_tile(
+    image: &DynamicImage,
+    (max_width, max_height): (u32, u32),
+    tile: bool,
+) -> Result<Vec<RenderedImage>, ImageProcessingError> {
+    if tile && let Some(tiles) = tile_tall_image(image, (max_width, max_height)) {
+        return encode_tiles(tiles);
+    }
+    let image = if image.width() > max_width || image.height() > max_height {
+        encode_dynamic(&image.resize(max_width, max_height, FilterType::Triangle))?
+    } else {
+        encode_dynamic(image)?
+    };
+    Ok(vec![RenderedImage { image, label: None }])
+}
+
+fn encode_tiles(tiles: Vec<DynamicImage>) -> Result<Vec<RenderedImage>, ImageProcessingError> {
+    let count = tiles.len();
+    tiles
+        .iter()
+        .enumerate()
+        .map(|(index, tile)| {
+            Ok(RenderedImage {
+                image: encode_dynamic(tile)?,
+                label: Some(format!("tile {} of {count}", index + 1)),
+            })
+        })
+        .collect()
+}
+
+/// Splits an image at least twice as tall as it is wide into overlapping
+/// tiles that fit `max_width` x `max_height`, scaling it down first when it
+/// is too wide or would need more than `MAX_TILES` tiles. Returns `None`
+/// when the image is not tall or already fits in a single tile.
+fn tile_tall_image(
+    image: &DynamicImage,
+    (max_width, max_height): (u32, u32),
+) -> Option<Vec<DynamicImage>> {
+    let (width, height) = image.dimensions();
+    if height < width.saturating_mul(2) {
+        return None;
+    }
+
+    let mut scaled = if width > max_width {
+        image.resize(max_width, u32::MAX, FilterType::Triangle)
+    } else {
+        image.clone()
+    };
+    let tile_height = max_height;
+    if scaled.height() <= tile_height {
+        return None;
+    }
+
+    let overlap = TILE_OVERLAP.min(tile_height / 4);
+    let stride = tile_height - overlap;
+    let max_span = tile_height + stride * (MAX_TILES as u32 - 1);
+    if scaled.height() > max_span {
+        scaled = scaled.resize(scaled.width(), max_span, FilterType::Triangle);
+    }
+
+    let scaled_height = scaled.height();
+    let count = 1 + (scaled_height - tile_height).div_ceil(stride);
+    Some(
+        (0..count)
+            .map(|index| {
+                let top = index * stride;
+                let rows = (scaled_height - top).min(tile_height);
+                scaled.crop_imm(0, top, scaled.width(), rows)
+            })
+            .collect(),
+    )
+}
+
+fn encode_dynamic(image: &DynamicImage) -> Result<EncodedImage, ImageProcessingError> {
+    let (bytes, format) = encode_image(image, ImageFormat::Png)?;
+    Ok(EncodedImage {
+        bytes,
+        mime: format_to_mime(format),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
 fn read_file_bytes(path: &Path, path_for_error: &Path) -> Result<Vec<u8>, ImageProcessingError> {
     match tokio::runtime::Handle::try_current() {
         // If we're inside a Tokio runtime, avoid block_on (it panics on worker threads).
@@ -249,4 +467,107 @@ mod tests {
         assert_eq!(second.height, 48);
         assert_ne!(second.bytes, first.bytes);
     }
+
+    #[test]
+    fn low_detail_and_max_dimension_shrink_bounds() {
+        let low = ResizeOptions {
+            detail: ImageDetail::Low,
+            max_dimension: None,
+        };
+        let capped = ResizeOptions {
+            detail: ImageDetail::High,
+            max_dimension: Some(1000),
+        };
+
+        assert_eq!(
+            ResizeOptions::default().bounds(false),
+            (MAX_WIDTH, MAX_HEIGHT)
+        );
+        assert_eq!(low.bounds(true), (512, 512));
+        assert_eq!(capped.bounds(false), (1000, 1000));
+    }
+
+    #[test]
+    fn tiles_tall_images_with_overlap() {
+        let image = DynamicImage::new_rgba8(400, 3000);
+
+        let tiles = tile_tall_image(&image, (MAX_WIDTH, MAX_HEIGHT)).expect("tall image is tiled");
+
+        let heights: Vec<u32> = tiles.iter().map(DynamicImage::height).collect();
+        assert_eq!(heights, vec![768, 768, 768, 768, 184]);
+        assert!(tiles.iter().all(|tile| tile.width() == 400));
+        assert!(
+            tile_tall_image(&DynamicImage::new_rgba8(400, 700), (MAX_WIDTH, MAX_HEIGHT)).is_none()
+        );
+        assert!(
+            tile_tall_image(
+                &DynamicImage::new_rgba8(1000, 1900),
+                (MAX_WIDTH, MAX_HEIGHT)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn very_tall_images_are_capped_at_max_tiles() {
+        let image = DynamicImage::new_rgba8(400, 20_000);
+
+        let tiles = tile_tall_image(&image, (MAX_WIDTH, MAX_HEIGHT)).expect("tall image is tiled");
+
+        assert_eq!(tiles.len(), MAX_TILES);
+        assert!(tiles.iter().all(|tile| tile.height() <= MAX_HEIGHT));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn renders_tall_screenshot_as_labelled_tiles() {
+        let temp_file = NamedTempFile::new().expect("temp file");
+        let image = ImageBuffer::from_pixel(400, 3000, Rgba([255u8, 255, 255, 255]));
+        image
+            .save_with_format(temp_file.path(), ImageFormat::Png)
+            .expect("write png to temp file");
+        let options = RenderOptions {
+            tile: true,
+            ..RenderOptions::default()
+        };
+
+        let rendered = render_for_model(temp_file.path(), &options).expect("render screenshot");
+
+        let labels: Vec<&str> = rendered
+            .iter()
+            .filter_map(|image| image.label.as_deref())
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "tile 1 of 5",
+                "tile 2 of 5",
+                "tile 3 of 5",
+                "tile 4 of 5",
+                "tile 5 of 5"
+            ]
+        );
+        assert!(rendered.iter().all(|image| image.image.mime == "image/png"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rasterizes_svg() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let path = temp_dir.path().join("diagram.svg");
+        std::fs::write(
+            &path,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="100"><rect width="200" height="100" fill="red"/></svg>"#,
+        )
+        .expect("write svg");
+
+        let rendered = render_for_model(&path, &RenderOptions::default()).expect("render svg");
+
+        assert_eq!(rendered.len(), 1);
+        let image = &rendered[0].image;
+        assert_eq!((image.width, image.height), (800, 400));
+        let decoded = image::load_from_memory(&image.bytes).expect("decode rendered svg");
+        assert_eq!(
+            decoded.to_rgba8().get_pixel(10, 10),
+            &Rgba([255, 0, 0, 255])
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use hayro::InterpreterSettings;
+use hayro::Pdf;
+use hayro::RenderSettings;
+use image::DynamicImage;
+use image::Rgba;
+
+use crate::error::ImageProcessingError;
+
+/// Maximum number of pages rendered from a single PDF.
+pub(crate) const MAX_PAGES: usize = 8;
+
+/// Returns true when `bytes` start with the PDF file header.
+pub(crate) fn is_pdf(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"%PDF-")
+}
+
+/// A rendered page together with its 1-based page number.
+pub(crate) struct RenderedPage {
+    pub number: u32,
+    pub image: DynamicImage,
+}
+
+/// Renders the requested 1-based `pages` (the first page when empty) so that
+/// each fits within `max_width` x `max_height`. Returns the rendered pages
+/// and the total page count.
+pub(crate) fn render_pages(
+    path: &Path,
+    bytes: Vec<u8>,
+    pages: &[u32],
+    max_width: u32,
+    max_height: u32,
+) -> Result<(Vec<RenderedPage>, usize), ImageProcessingError> {
+    let pdf = Pdf::new(Arc::new(bytes)).map_err(|err| ImageProcessingError::Pdf {
+        path: path.to_path_buf(),
+        message: format!("{err:?}"),
+    })?;
+    let all_pages = pdf.pages();
+    let total = all_pages.len();
+
+    let mut requested: Vec<u32> = Vec::new();
+    for &page in pages {
+        if !requested.contains(&page) {
+            requested.push(page);
+        }
+    }
+    if requested.is_empty() {
+        requested.push(1);
+    }
+    if requested.len() > MAX_PAGES {
+        return Err(ImageProcessingError::Pdf {
+            path: path.to_path_buf(),
+            message: format!(
+                "{} pages requested; at most {MAX_PAGES} can be rendered at once",
+                requested.len()
+            ),
+        });
+    }
+
+    let interpreter_settings = InterpreterSettings::default();
+    let mut rendered = Vec::with_capacity(requested.len());
+    for number in requested {
+        let Some(page) = (number as usize)
+            .checked_sub(1)
+            .and_then(|index| all_pages.get(index))
+        else {
+            return Err(ImageProcessingError::PageOutOfRange {
+                path: path.to_path_buf(),
+                page: number,
+                total,
+            });
+        };
+
+        let (width, height) = page.render_dimensions();
+        let scale = (max_width as f32 / width.max(1.0)).min(max_height as f32 / height.max(1.0));
+        let render_settings = RenderSettings {
+            x_scale: scale,
+            y_scale: scale,
+            ..RenderSettings::default()
+        };
+        let png = hayro::render(page, &interpreter_settings, &render_settings).take_png();
+        let image =
+            image::load_from_memory(&png).map_err(|source| ImageProcessingError::Decode {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        rendered.push(RenderedPage {
+            number,
+            image: flatten_onto_white(image),
+        });
+    }
+
+    Ok((rendered, total))
+}
+
+/// Pages without a painted background render as transparent; composite them
+/// onto white so that text stays legible.
+fn flatten_onto_white(image: DynamicImage) -> DynamicImage {
+    let mut rgba = image.into_rgba8();
+    for Rgba([r, g, b, a]) in rgba.pixels_mut() {
+        let alpha = u16::from(*a);
+        let blend = |channel: u8| ((u16::from(channel) * alpha + 255 * (255 - alpha)) / 255) as u8;
+        *r = blend(*r);
+        *g = blend(*g);
+        *b = blend(*b);
+        *a = 255;
+    }
+    DynamicImage::ImageRgba8(rgba)
+}
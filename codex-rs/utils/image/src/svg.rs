@@ -0,0 +1,77 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use image::DynamicImage;
+use image::RgbaImage;
+use resvg::tiny_skia;
+use resvg::usvg;
+
+use crate::error::ImageProcessingError;
+
+/// Small SVGs (icons, diagrams with a tiny viewBox) are scaled up so that the
+/// model sees more than a few pixels, but never by more than this factor.
+const MAX_UPSCALE: f32 = 4.0;
+
+static FONT_DB: LazyLock<Arc<usvg::fontdb::Database>> = LazyLock::new(|| {
+    let mut db = usvg::fontdb::Database::new();
+    db.load_system_fonts();
+    Arc::new(db)
+});
+
+/// Returns true when `bytes` (read from `path`) look like an SVG document.
+pub(crate) fn is_svg(path: &Path, bytes: &[u8]) -> bool {
+    let by_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg") || ext.eq_ignore_ascii_case("svgz"));
+    if by_extension {
+        return true;
+    }
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    (head.starts_with("<?xml") || head.starts_with("<svg") || head.starts_with("<!DOCTYPE svg"))
+        && head.contains("<svg")
+}
+
+/// Rasterizes an SVG onto a white background so that it fits within
+/// `max_width` x `max_height`.
+pub(crate) fn rasterize(
+    path: &Path,
+    bytes: &[u8],
+    max_width: u32,
+    max_height: u32,
+) -> Result<DynamicImage, ImageProcessingError> {
+    let error = |message: String| ImageProcessingError::Svg {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let options = usvg::Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        fontdb: FONT_DB.clone(),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_data(bytes, &options).map_err(|err| error(err.to_string()))?;
+
+    let size = tree.size();
+    let scale = (max_width as f32 / size.width())
+        .min(max_height as f32 / size.height())
+        .min(MAX_UPSCALE);
+    let width = ((size.width() * scale).round() as u32).clamp(1, max_width);
+    let height = ((size.height() * scale).round() as u32).clamp(1, max_height);
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| error(format!("invalid render size {width}x{height}")))?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    // The background is opaque, so premultiplied and straight alpha agree.
+    RgbaImage::from_raw(width, height, pixmap.take())
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| error("rendered pixel buffer has an unexpected size".to_string()))
+}
@@ -17,10 +17,14 @@ use portable_pty::CommandBuilder;
 use portable_pty::MasterPty;
 use portable_pty::PtySize;
 use portable_pty::SlavePty;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::sync::Mutex as TokioMutex;
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 
 pub struct PtyPairWrapper {
@@ -39,8 +43,8 @@ pub struct ExecCommandSession {
     exit_status: Arc<AtomicBool>,
     exit_code: Arc<StdMutex<Option<i32>>>,
     // PtyPair must be preserved because the process will receive Control+C if the
-    // slave is closed
-    _pair: StdMutex<PtyPairWrapper>,
+    // slave is closed. `None` for processes spawned with piped stdio.
+    _pair: StdMutex<Option<PtyPairWrapper>>,
 }
 
 impl fmt::Debug for PtyPairWrapper {
@@ -61,7 +65,7 @@ impl ExecCommandSession {
         wait_handle: JoinHandle<()>,
         exit_status: Arc<AtomicBool>,
         exit_code: Arc<StdMutex<Option<i32>>>,
-        pair: PtyPairWrapper,
+        pair: Option<PtyPairWrapper>,
     ) -> (Self, broadcast::Receiver<Vec<u8>>) {
         (
             Self {
@@ -260,7 +264,129 @@ pub async fn spawn_pty_process(
         wait_handle,
         exit_status,
         exit_code,
-        pair,
+        Some(pair),
+    );
+
+    Ok(SpawnedPty {
+        session,
+        output_rx,
+        exit_rx,
+    })
+}
+
+/// Kills a piped child by waking its wait task, which owns the `Child`.
+#[derive(Debug)]
+struct PipeChildKiller {
+    kill: Arc<Notify>,
+}
+
+impl portable_pty::ChildKiller for PipeChildKiller {
+    fn kill(&mut self) -> std::io::Result<()> {
+        self.kill.notify_one();
+        Ok(())
+    }
+
+    fn clone_killer(&self) -> Box<dyn portable_pty::ChildKiller + Send + Sync> {
+        Box::new(PipeChildKiller {
+            kill: Arc::clone(&self.kill),
+        })
+    }
+}
+
+/// Spawns a process with piped stdin/stdout instead of a PTY, for callers
+/// that speak a byte-exact protocol over stdio. Output carries stdout only;
+/// stderr is discarded so it cannot interleave with the protocol stream.
+pub async fn spawn_pipe_process(
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    #[cfg_attr(not(unix), allow(unused_variables))] arg0: &Option<String>,
+) -> Result<SpawnedPty> {
+    if program.is_empty() {
+        anyhow::bail!("missing program for pipe spawn");
+    }
+
+    let mut command = Command::new(program);
+    #[cfg(unix)]
+    command.arg0(arg0.as_deref().unwrap_or(program));
+    command
+        .args(args)
+        .current_dir(cwd)
+        .env_clear()
+        .envs(env)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let mut child = command.spawn()?;
+    let (Some(mut stdin), Some(mut stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        anyhow::bail!("stdio was not piped");
+    };
+
+    let (writer_tx, mut writer_rx) = mpsc::channel::<Vec<u8>>(128);
+    let (output_tx, _) = broadcast::channel::<Vec<u8>>(256);
+    let initial_output_rx = output_tx.subscribe();
+
+    let output_tx_clone = output_tx.clone();
+    let reader_handle: JoinHandle<()> = tokio::spawn(async move {
+        let mut buf = [0u8; 8_192];
+        loop {
+            match stdout.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => {
+                    let _ = output_tx_clone.send(buf[..n].to_vec());
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+    });
+
+    let writer_handle: JoinHandle<()> = tokio::spawn(async move {
+        while let Some(bytes) = writer_rx.recv().await {
+            if stdin.write_all(&bytes).await.is_err() || stdin.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let kill = Arc::new(Notify::new());
+    let killer = Box::new(PipeChildKiller {
+        kill: Arc::clone(&kill),
+    });
+    let (exit_tx, exit_rx) = oneshot::channel::<i32>();
+    let exit_status = Arc::new(AtomicBool::new(false));
+    let wait_exit_status = Arc::clone(&exit_status);
+    let exit_code = Arc::new(StdMutex::new(None));
+    let wait_exit_code = Arc::clone(&exit_code);
+    let wait_handle: JoinHandle<()> = tokio::spawn(async move {
+        let status = tokio::select! {
+            status = child.wait() => status,
+            _ = kill.notified() => {
+                let _ = child.start_kill();
+                child.wait().await
+            }
+        };
+        let code = status.ok().and_then(|status| status.code()).unwrap_or(-1);
+        wait_exit_status.store(true, std::sync::atomic::Ordering::SeqCst);
+        if let Ok(mut guard) = wait_exit_code.lock() {
+            *guard = Some(code);
+        }
+        let _ = exit_tx.send(code);
+    });
+
+    let (session, output_rx) = ExecCommandSession::new(
+        writer_tx,
+        output_tx,
+        initial_output_rx,
+        killer,
+        reader_handle,
+        writer_handle,
+        wait_handle,
+        exit_status,
+        exit_code,
+        None,
     );
 
     Ok(SpawnedPty {